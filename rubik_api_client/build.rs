@@ -0,0 +1,203 @@
+//! Generates the client out of the OpenAPI document of the server, the one
+//! it serves at `/openapi.json`, checked in as `openapi.json`. The schemas
+//! of its components become the types of the `types` module, and every
+//! operation a method of the client taking and answering those types. The
+//! server tests that the document checked in is the one it serves.
+
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use schemars::schema::Schema;
+use serde_json::{Map, Value};
+use typify::{TypeSpace, TypeSpaceSettings};
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "box", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern", "false",
+    "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return", "static",
+    "struct", "trait", "true", "type", "unsafe", "use", "where", "while", "yield",
+];
+
+/// Name of the argument of a parameter, escaped if it is a keyword.
+fn argument(name: &str) -> String {
+    if KEYWORDS.contains(&name) {
+        format!("r#{}", name)
+    } else {
+        name.to_string()
+    }
+}
+
+/// Rewrites a schema of OpenAPI 3.0 as JSON Schema, which has no
+/// `nullable` but a `null` type.
+fn json_schema(schema: &Value) -> Value {
+    match schema {
+        Value::Object(object) => {
+            let mut object: Map<String, Value> =
+                object.iter().map(|(key, value)| (key.clone(), json_schema(value))).collect();
+            // A default of null is the absence of the field.
+            if object.get("default") == Some(&Value::Null) {
+                object.remove("default");
+            }
+            if object.remove("nullable") != Some(Value::Bool(true)) {
+                return Value::Object(object);
+            }
+            if object.get("enum") == Some(&serde_json::json!([null])) {
+                return serde_json::json!({"type": "null"});
+            }
+            match object.remove("type") {
+                Some(Value::String(type_)) => {
+                    object.insert("type".to_string(), serde_json::json!([type_, "null"]));
+                    Value::Object(object)
+                }
+                _ => serde_json::json!({"anyOf": [object, {"type": "null"}]}),
+            }
+        }
+        Value::Array(items) => Value::Array(items.iter().map(json_schema).collect()),
+        value => value.clone(),
+    }
+}
+
+/// Rust type of a schema, generated in the `types` module if it is not
+/// one of the standard types.
+fn rust_type(types: &mut TypeSpace, schema: &Value) -> String {
+    let schema: Schema = serde_json::from_value(json_schema(schema)).expect("the schemas of the document are valid");
+    let id = types.add_type(&schema).expect("the schemas of the document can be generated");
+    types.get_type(&id).expect("generated types can be found").ident().to_string()
+}
+
+/// Rust type of a parameter. Strings are borrowed, and enumerations are
+/// sent as the name of their variant.
+fn parameter_type(types: &mut TypeSpace, schema: &Value) -> String {
+    match schema["type"].as_str() {
+        Some("string") => "&str".to_string(),
+        Some("boolean") => "bool".to_string(),
+        Some("number") => "f64".to_string(),
+        Some("integer") if schema["minimum"].as_i64() == Some(0) => "u64".to_string(),
+        Some("integer") => "i64".to_string(),
+        _ => rust_type(types, schema),
+    }
+}
+
+/// Writes the method of an operation.
+fn method(code: &mut String, types: &mut TypeSpace, method: &str, path: &str, operation: &Map<String, Value>) {
+    let name = operation["operationId"].as_str().expect("operations have an ID");
+    let mut arguments = Vec::new();
+    let mut route = path.to_string();
+    let mut segments = Vec::new();
+    let mut query = String::new();
+    for param in operation["parameters"].as_array().into_iter().flatten() {
+        let param_name = param["name"].as_str().expect("parameters have a name");
+        let required = param["required"].as_bool().unwrap_or(false);
+        let type_ = parameter_type(types, &param["schema"]);
+        let argument = argument(param_name);
+        if required {
+            arguments.push(format!("{}: {}", argument, type_));
+        } else {
+            arguments.push(format!("{}: Option<{}>", argument, type_));
+        }
+        if param["in"] == "path" {
+            route = route.replace(&format!("{{{}}}", param_name), "{}");
+            if type_ == "&str" {
+                segments.push(format!("segment({})", argument));
+            } else {
+                segments.push(format!("segment(&{}.to_string())", argument));
+            }
+        } else if required {
+            writeln!(query, "query.push(({:?}, {}.to_string()));", param_name, argument).unwrap();
+        } else {
+            writeln!(query, "if let Some(value) = {} {{ query.push(({:?}, value.to_string())); }}", argument, param_name)
+                .unwrap();
+        }
+    }
+
+    let mut body_doc = None;
+    let body = match operation.get("requestBody").and_then(|body| body["content"].as_object()) {
+        Some(content) if content.contains_key("application/json") => {
+            let type_ = rust_type(types, &content["application/json"]["schema"]);
+            arguments.push(format!("body: &{}", type_));
+            body_doc = Some(type_.replace(' ', ""));
+            "Body::Json(serde_json::to_vec(body)?)".to_string()
+        }
+        Some(content) if content.contains_key("multipart/form-data") => {
+            let schema = &content["multipart/form-data"]["schema"];
+            let mut parts = Vec::new();
+            for field in schema["properties"].as_object().into_iter().flat_map(|properties| properties.keys()) {
+                arguments.push(format!("{}: Vec<u8>", argument(field)));
+                parts.push(format!("({:?}, {})", field, argument(field)));
+            }
+            format!("Body::Multipart(vec![{}])", parts.join(", "))
+        }
+        Some(content) => {
+            let content_type = content.keys().next().expect("bodies have a content type");
+            arguments.push("body: Vec<u8>, content_type: &str".to_string());
+            body_doc = Some(format!("{} file", content_type));
+            "Body::Raw(content_type.to_string(), body)".to_string()
+        }
+        None => "Body::Empty".to_string(),
+    };
+
+    let response = &operation["responses"]["200"];
+    let json = response["content"].get("application/json").map(|content| rust_type(types, &content["schema"]));
+    let description = response["description"].as_str().unwrap_or_default();
+
+    writeln!(code, "/// `{} {}`, answering with `{}`.", method.to_uppercase(), path, description).unwrap();
+    if let Some(body) = body_doc {
+        writeln!(code, "///\n/// The body is a `{}`.", body).unwrap();
+    }
+    writeln!(
+        code,
+        "pub async fn {}(&self{}) -> Result<Response<{}>, Error> {{",
+        name,
+        arguments.iter().map(|argument| format!(", {}", argument)).collect::<String>(),
+        json.as_deref().unwrap_or("Vec<u8>")
+    )
+    .unwrap();
+    if segments.is_empty() {
+        writeln!(code, "let path = {:?}.to_string();", route).unwrap();
+    } else {
+        writeln!(code, "let path = format!({:?}, {});", route, segments.join(", ")).unwrap();
+    }
+    writeln!(code, "#[allow(unused_mut)]\nlet mut query: Vec<(&str, String)> = Vec::new();\n{}", query).unwrap();
+    let call = if json.is_some() { "json" } else { "send" };
+    writeln!(code, "self.{}(Method::{}, path, query, {}).await\n}}\n", call, method.to_uppercase(), body).unwrap();
+}
+
+fn main() {
+    let source = Path::new(&env::var("CARGO_MANIFEST_DIR").expect("cargo sets CARGO_MANIFEST_DIR")).join("openapi.json");
+    let document: Value =
+        serde_json::from_slice(&fs::read(&source).expect("the OpenAPI document can be read")).expect("it is JSON");
+
+    let mut settings = TypeSpaceSettings::default();
+    settings.with_type_mod("types").with_struct_builder(false);
+    let mut types = TypeSpace::new(&settings);
+    let schemas = document["components"]["schemas"].as_object().expect("the document has schemas");
+    types
+        .add_ref_types(schemas.iter().map(|(name, schema)| {
+            (name.clone(), serde_json::from_value(json_schema(schema)).expect("the schemas of the document are valid"))
+        }))
+        .expect("the schemas of the document can be generated");
+
+    let mut methods = String::new();
+    for (path, operations) in document["paths"].as_object().expect("the document has paths") {
+        for (verb, operation) in operations.as_object().expect("paths have operations") {
+            method(&mut methods, &mut types, verb, path, operation.as_object().expect("operations are objects"));
+        }
+    }
+
+    let base = document["servers"][0]["url"].as_str().expect("the document has a server");
+    let mut code = String::new();
+    writeln!(code, "/// Prefix of the version of the API the client was generated for.").unwrap();
+    writeln!(code, "pub const BASE: &str = {:?};", base).unwrap();
+    writeln!(code, "/// Models of the API, as the schemas of its OpenAPI document.").unwrap();
+    writeln!(code, "#[allow(clippy::all, rustdoc::all)]\npub mod types {{ {} }}", types.to_stream()).unwrap();
+    // The methods take every parameter of their route, as many as it has.
+    writeln!(code, "#[allow(clippy::too_many_arguments)]\nimpl Client {{ {} }}", methods).unwrap();
+
+    let file = syn::parse_file(&code).expect("the generated client parses");
+    let out = env::var("OUT_DIR").expect("cargo sets OUT_DIR");
+    fs::write(Path::new(&out).join("client.rs"), prettyplease::unparse(&file))
+        .expect("the generated client can be written");
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=openapi.json");
+}
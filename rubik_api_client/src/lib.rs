@@ -0,0 +1,224 @@
+//! Typed client of the Rubik API. It is generated from the OpenAPI document
+//! of the server, the one served at `/openapi.json`: the models of the API
+//! are the types of [`types`], and there is one method per endpoint, named
+//! after it, taking its parameters and body and answering its model.
+//!
+//! Responses that are not JSON, such as exports or images, are answered as
+//! their raw bytes. Every response carries the `ETag` the server sent, to
+//! send back with [`Client::with_if_match`] or
+//! [`Client::with_if_none_match`].
+//!
+//! ```no_run
+//! # async fn run() -> Result<(), rubik_api_client::Error> {
+//! let client = rubik_api_client::Client::new("https://localhost:8000").with_token("...");
+//! let cube = client.get_cube_by_name("3x3x3").await?;
+//! println!("{} has {} pieces", cube.body.name, cube.body.pieces);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use reqwest::header::{AUTHORIZATION, CONTENT_TYPE, ETAG, IF_MATCH, IF_NONE_MATCH};
+use reqwest::multipart::{Form, Part};
+use reqwest::{Method, StatusCode};
+use serde::de::DeserializeOwned;
+
+/// Why a request failed.
+#[derive(Debug)]
+pub enum Error {
+    /// The server could not be reached or the response could not be read.
+    Http(reqwest::Error),
+    /// The resource did not change since the tag sent in `If-None-Match`.
+    NotModified,
+    /// The server answered with an error status, with the body it sent.
+    Status(u16, String),
+    /// The body could not be encoded, or the response decoded.
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Http(error) => write!(f, "{}", error),
+            Error::NotModified => write!(f, "not modified"),
+            Error::Status(status, body) => write!(f, "the server answered {}: {}", status, body),
+            Error::Json(error) => write!(f, "invalid JSON: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<reqwest::Error> for Error {
+    fn from(error: reqwest::Error) -> Self {
+        Error::Http(error)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(error: serde_json::Error) -> Self {
+        Error::Json(error)
+    }
+}
+
+/// Response of the server to a request.
+#[derive(Debug, Clone)]
+pub struct Response<T> {
+    /// Body of the response, decoded into the model of the route.
+    pub body: T,
+    /// `ETag` of the response, sent for single cubes.
+    pub etag: Option<String>,
+}
+
+/// Body of a request, as sent by the generated methods.
+enum Body {
+    Empty,
+    Json(Vec<u8>),
+    Raw(String, Vec<u8>),
+    Multipart(Vec<(&'static str, Vec<u8>)>),
+}
+
+/// Encodes a path parameter as a single segment.
+fn segment(value: &str) -> String {
+    utf8_percent_encode(value, NON_ALPHANUMERIC).to_string()
+}
+
+/// Client of a server of the Rubik API, over HTTP or HTTPS.
+#[derive(Clone)]
+pub struct Client {
+    base_url: String,
+    http: reqwest::Client,
+    token: Option<String>,
+    api_key: Option<String>,
+    sandbox: bool,
+    if_match: Option<String>,
+    if_none_match: Option<String>,
+}
+
+impl Client {
+    /// Builds a client of the server at the given URL.
+    ///
+    /// ## Arguments
+    /// * `base_url` - scheme, host and port of the server, such as
+    ///   `https://localhost:8000`.
+    ///
+    /// ## Returns
+    /// * A client sending anonymous requests.
+    pub fn new(base_url: &str) -> Self {
+        Client {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            http: reqwest::Client::new(),
+            token: None,
+            api_key: None,
+            sandbox: false,
+            if_match: None,
+            if_none_match: None,
+        }
+    }
+
+    /// Sends the requests with a bearer token, as returned by `login`.
+    pub fn with_token(mut self, token: &str) -> Self {
+        self.token = Some(token.to_string());
+        self
+    }
+
+    /// Sends the requests with an API key, in the `X-Api-Key` header.
+    pub fn with_api_key(mut self, api_key: &str) -> Self {
+        self.api_key = Some(api_key.to_string());
+        self
+    }
+
+    /// Sends the requests to the sandbox, with `X-Sandbox: true`. Live
+    /// servers redirect them to their sandbox, which the client follows.
+    pub fn with_sandbox(mut self) -> Self {
+        self.sandbox = true;
+        self
+    }
+
+    /// Only applies the writes to a cube still tagged as given, in
+    /// `If-Match`. Other writes are refused with a 412.
+    pub fn with_if_match(mut self, etag: &str) -> Self {
+        self.if_match = Some(etag.to_string());
+        self
+    }
+
+    /// Only answers the reads of a cube no longer tagged as given, in
+    /// `If-None-Match`. Others fail with [`Error::NotModified`].
+    pub fn with_if_none_match(mut self, etag: &str) -> Self {
+        self.if_none_match = Some(etag.to_string());
+        self
+    }
+
+    /// Sends a request to a route of the current version of the API.
+    ///
+    /// ## Arguments
+    /// * `method` - method of the route.
+    /// * `path` - path of the route, with its parameters filled in.
+    /// * `query` - query parameters given.
+    /// * `body` - body of the request.
+    ///
+    /// ## Returns
+    /// * The body of the response with its tag, or why the request failed.
+    async fn send(
+        &self,
+        method: Method,
+        path: String,
+        query: Vec<(&str, String)>,
+        body: Body,
+    ) -> Result<Response<Vec<u8>>, Error> {
+        let mut request = self.http.request(method, format!("{}{}{}", self.base_url, BASE, path)).query(&query);
+        if let Some(token) = &self.token {
+            request = request.header(AUTHORIZATION, format!("Bearer {}", token));
+        }
+        if let Some(api_key) = &self.api_key {
+            request = request.header("X-Api-Key", api_key);
+        }
+        if self.sandbox {
+            request = request.header("X-Sandbox", "true");
+        }
+        if let Some(etag) = &self.if_match {
+            request = request.header(IF_MATCH, etag);
+        }
+        if let Some(etag) = &self.if_none_match {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        request = match body {
+            Body::Empty => request,
+            Body::Json(body) => request.header(CONTENT_TYPE, "application/json").body(body),
+            Body::Raw(content_type, body) => request.header(CONTENT_TYPE, content_type).body(body),
+            Body::Multipart(parts) => request.multipart(
+                parts
+                    .into_iter()
+                    .fold(Form::new(), |form, (name, bytes)| form.part(name, Part::bytes(bytes).file_name(name))),
+            ),
+        };
+
+        let response = request.send().await?;
+        let status = response.status();
+        let etag = response.headers().get(ETAG).and_then(|etag| etag.to_str().ok()).map(str::to_string);
+        let body = response.bytes().await?.to_vec();
+        if status == StatusCode::NOT_MODIFIED {
+            return Err(Error::NotModified);
+        }
+        if status.is_client_error() || status.is_server_error() {
+            return Err(Error::Status(status.as_u16(), String::from_utf8_lossy(&body).into_owned()));
+        }
+        Ok(Response { body, etag })
+    }
+
+    /// Sends a request to a route answering with JSON.
+    async fn json<R: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: String,
+        query: Vec<(&str, String)>,
+        body: Body,
+    ) -> Result<Response<R>, Error> {
+        let Response { body, etag } = self.send(method, path, query, body).await?;
+        Ok(Response { body: serde_json::from_slice(&body)?, etag })
+    }
+}
+
+include!(concat!(env!("OUT_DIR"), "/client.rs"));
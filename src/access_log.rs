@@ -0,0 +1,129 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Instant;
+
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::Header;
+use rocket::request::{FromRequest, Outcome};
+use rocket::{Data, Request, Response};
+
+use crate::proxy::TrustedProxies;
+
+/// Header with the ID of a request, sent back on its response. An ID given
+/// by the client or a proxy is kept, so that logs can be followed across
+/// services.
+pub const REQUEST_ID_HEADER: &str = "X-Request-Id";
+/// Longest request ID kept from a client, longer ones are replaced.
+const MAX_REQUEST_ID: usize = 64;
+
+/// ID of a request, to find its log line from a bug report. Handlers can
+/// ask for it as a request guard.
+#[derive(Clone)]
+pub struct RequestId(pub String);
+
+impl RequestId {
+    /// Keeps the ID sent by the client if it is short and made of safe
+    /// characters, or makes a random one.
+    fn of(request: &Request<'_>) -> Self {
+        let given = request.headers().get_one(REQUEST_ID_HEADER).filter(|id| {
+            !id.is_empty()
+                && id.len() <= MAX_REQUEST_ID
+                && id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        });
+        match given {
+            Some(id) => RequestId(id.to_string()),
+            None => RequestId(format!("{:032x}", rand::random::<u128>())),
+        }
+    }
+}
+
+impl<'r> FromRequest<'r> for RequestId {
+    type Error = ();
+
+    fn from_request<'life0, 'async_trait>(
+        request: &'r Request<'life0>
+    ) -> Pin<Box<dyn Future<Output = Outcome<Self, Self::Error>> + Send + 'async_trait>>
+    where
+        'r: 'async_trait,
+        'life0: 'async_trait,
+        Self: 'async_trait,
+    {
+        let id = request.local_cache(|| RequestId::of(request)).clone();
+        Box::pin(async move { Outcome::Success(id) })
+    }
+}
+
+/// When the server started handling a request.
+struct RequestStarted(Instant);
+
+/// Gives every request an ID, returned in [`REQUEST_ID_HEADER`], and logs a
+/// JSON line for every response with the ID, the address of the real client,
+/// resolved through the trusted proxies, the method, path, status and
+/// duration.
+pub struct AccessLogFairing {
+    proxies: TrustedProxies,
+}
+
+impl AccessLogFairing {
+    pub fn new(proxies: TrustedProxies) -> Self {
+        AccessLogFairing { proxies }
+    }
+}
+
+impl Fairing for AccessLogFairing {
+    fn info(&self) -> Info {
+        Info {
+            name: "Access Log Fairing",
+            kind: Kind::Request | Kind::Response,
+        }
+    }
+
+    fn on_request<'life0, 'life1, 'life2, 'life3, 'life4, 'async_trait>(
+        &'life0 self,
+        request: &'life1 mut Request<'life2>,
+        _data: &'life3 mut Data<'life4>
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'async_trait>>
+    where
+        Self: 'async_trait,
+        'life0: 'async_trait,
+        'life1: 'async_trait,
+        'life2: 'async_trait,
+        'life3: 'async_trait,
+        'life4: 'async_trait,
+    {
+        request.local_cache(|| RequestStarted(Instant::now()));
+        request.local_cache(|| RequestId::of(request));
+        Box::pin(async move {})
+    }
+
+    fn on_response<'r, 'life0, 'life1, 'life2, 'async_trait>(
+        &'life0 self,
+        request: &'r Request<'life1>,
+        response: &'life2 mut Response<'r>
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'async_trait>>
+    where
+        Self: 'async_trait,
+        'r: 'async_trait,
+        'life0: 'async_trait,
+        'life1: 'async_trait,
+        'life2: 'async_trait,
+    {
+        let started = request.local_cache(|| RequestStarted(Instant::now()));
+        let id = request.local_cache(|| RequestId::of(request));
+        let client = self
+            .proxies
+            .client_ip(request)
+            .map_or_else(|| "-".to_string(), |ip| ip.to_string());
+        tracing::info!(
+            target: "rubik_api::access",
+            request_id = id.0.as_str(),
+            client = client.as_str(),
+            method = request.method().as_str(),
+            path = request.uri().path().as_str(),
+            status = response.status().code,
+            duration_ms = started.0.elapsed().as_micros() as f64 / 1000.0,
+        );
+        response.set_header(Header::new(REQUEST_ID_HEADER, id.0.clone()));
+        Box::pin(async {})
+    }
+}
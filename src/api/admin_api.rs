@@ -0,0 +1,147 @@
+use crate::{
+    auth::{Admin, Editor},
+    models::{
+        consistency_report_model::ConsistencyReport,
+        data_quality_model::{DataQuality, DataQualityEntry},
+        query_analytics_model::QueryStat,
+        retention_model::{RetentionReport, RetentionStatus},
+    },
+    pagination::PageSize,
+    repository::{analytics_repo::AnalyticsRepo, cube_repository::CubeRepository},
+    retention::RetentionPolicy,
+};
+use rocket::{http::Status, serde::json::Json, State};
+
+/// POST endpoint which scans the database looking for inconsistent data:
+/// documents that do not match the cube schema, cubes whose names collide
+/// once normalized and leftovers of older writes.
+/// 
+/// ## Arguments
+/// * `db` - instance of the mongo database.
+/// * `_admin` - the admin making the change.
+/// * `fix` - if `true`, the issues that are safe to repair are fixed.
+/// 
+/// ## Returns
+/// * A report with all the issues found and how many were fixed.
+#[post("/admin/check_consistency?<fix>")]
+pub async fn check_consistency(
+    db: &State<Box<dyn CubeRepository>>, _admin: Admin, fix: Option<bool>
+) -> Result<Json<ConsistencyReport>, Status> {
+    let report = db.check_consistency(fix.unwrap_or(false)).await;
+    match report {
+        Ok(report) => Ok(Json(report)),
+        Err(error) => Err(error.into()),
+    }
+}
+
+/// GET endpoint which allows to get the cubes with the least complete data,
+/// so that editors know what to fix first. Scores are computed again from
+/// the stored data, so cubes written before scoring existed are included.
+/// 
+/// ## Arguments
+/// * `db` - instance of the mongo database.
+/// * `_editor` - the editor asking for the cubes.
+/// * `limit` - number of cubes to return, from the `limit` query parameter.
+/// 
+/// ## Returns
+/// * The cubes with their score and missing fields, the lowest score first.
+#[get("/admin/data_quality")]
+pub async fn get_data_quality(
+    db: &State<Box<dyn CubeRepository>>, _editor: Editor, limit: PageSize
+) -> Result<Json<Vec<DataQualityEntry>>, Status> {
+    let cubes = match db.get_all_cubes().await {
+        Ok(cubes) => cubes,
+        Err(error) => return Err(error.into()),
+    };
+    let mut entries: Vec<DataQualityEntry> = cubes
+        .iter()
+        .map(|cube| DataQualityEntry {
+            cube_id: cube.id,
+            name: cube.name.to_owned(),
+            quality: DataQuality::of(cube),
+        })
+        .collect();
+    entries.sort_by(|a, b| a.quality.score.cmp(&b.quality.score).then_with(|| a.name.cmp(&b.name)));
+    entries.truncate(limit.0 as usize);
+    Ok(Json(entries))
+}
+
+/// GET endpoint which allows to get the cube names and types looked up the
+/// most.
+/// 
+/// ## Arguments
+/// * `analytics` - instance of the query analytics repository.
+/// * `_admin` - the admin asking for the terms.
+/// * `limit` - number of terms to return, from the `limit` query parameter.
+/// 
+/// ## Returns
+/// * The terms with their estimated number of lookups.
+#[get("/admin/analytics/top_queries")]
+pub async fn get_top_queries(
+    analytics: &State<AnalyticsRepo>, _admin: Admin, limit: PageSize
+) -> Result<Json<Vec<QueryStat>>, Status> {
+    match analytics.most_searched(limit.0 as i64).await {
+        Ok(stats) => Ok(Json(stats)),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
+
+/// GET endpoint which allows to get the lookups that found nothing, to
+/// guide what the catalog is missing.
+/// 
+/// ## Arguments
+/// * `analytics` - instance of the query analytics repository.
+/// * `_admin` - the admin asking for the terms.
+/// * `limit` - number of terms to return, from the `limit` query parameter.
+/// 
+/// ## Returns
+/// * The terms with their estimated number of lookups.
+#[get("/admin/analytics/zero_results")]
+pub async fn get_zero_result_queries(
+    analytics: &State<AnalyticsRepo>, _admin: Admin, limit: PageSize
+) -> Result<Json<Vec<QueryStat>>, Status> {
+    match analytics.zero_results(limit.0 as i64).await {
+        Ok(stats) => Ok(Json(stats)),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
+
+/// GET endpoint which allows to get the size of the telemetry collections,
+/// with the retention policy applied to them.
+/// 
+/// ## Arguments
+/// * `analytics` - instance of the query analytics repository.
+/// * `policy` - retention policy of the server.
+/// * `_admin` - the admin asking for the collections.
+/// 
+/// ## Returns
+/// * The size of each collection and of its archive.
+#[get("/admin/retention")]
+pub async fn get_retention(
+    analytics: &State<AnalyticsRepo>, policy: &State<RetentionPolicy>, _admin: Admin
+) -> Result<Json<Vec<RetentionStatus>>, Status> {
+    match policy.status(analytics).await {
+        Ok(status) => Ok(Json(status)),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
+
+/// POST endpoint which applies the retention policy right away, instead of
+/// waiting for its next run.
+/// 
+/// ## Arguments
+/// * `analytics` - instance of the query analytics repository.
+/// * `policy` - retention policy of the server.
+/// * `_admin` - the admin making the change.
+/// 
+/// ## Returns
+/// * What was expired from each collection.
+#[post("/admin/retention/run")]
+pub async fn run_retention(
+    analytics: &State<AnalyticsRepo>, policy: &State<RetentionPolicy>, _admin: Admin
+) -> Result<Json<Vec<RetentionReport>>, Status> {
+    match policy.apply(analytics).await {
+        Ok(reports) => Ok(Json(reports)),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
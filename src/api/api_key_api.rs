@@ -0,0 +1,88 @@
+use crate::{
+    api::{error_api::LocalizedError, parse_id},
+    auth::{generate_api_key, hash_api_key, Admin},
+    i18n::Lang,
+    models::api_key_model::{ApiKey, IssuedApiKey, NewApiKey},
+    repository::api_key_repo::ApiKeyRepo,
+    validation::FieldError,
+};
+use rocket::{http::Status, serde::json::Json, State};
+
+/// POST endpoint which allows admins to issue an API key for a machine
+/// client.
+///
+/// ## Arguments
+/// * `keys` - instance of the API keys repository.
+/// * `admin` - the admin issuing the key.
+/// * `lang` - languages accepted by the client.
+/// * `new_key` - name of the client and scope of the key.
+///
+/// ## Returns
+/// * The new key, which is not shown again.
+#[post("/admin/api_keys", data = "<new_key>")]
+pub async fn insert_api_key(
+    keys: &State<ApiKeyRepo>,
+    admin: Admin,
+    lang: Lang,
+    new_key: Json<NewApiKey>,
+) -> Result<Json<IssuedApiKey>, (Status, LocalizedError)> {
+    let name = new_key.name.trim().to_string();
+    if name.is_empty() {
+        let error = FieldError { field: "name".to_string(), message: "name must not be empty".to_string() };
+        return Err(LocalizedError::invalid(&lang, vec![error]));
+    };
+
+    let key = generate_api_key();
+    let data = ApiKey {
+        id: None,
+        name: name.clone(),
+        scope: new_key.scope,
+        key_hash: hash_api_key(&key),
+        created_by: admin.0.username,
+        revoked: false,
+    };
+    match keys.insert_key(data).await {
+        Ok(inserted) => match inserted.inserted_id.as_object_id() {
+            Some(id) => Ok(Json(IssuedApiKey { id, name, scope: new_key.scope, key })),
+            None => Err(LocalizedError::of(Status::InternalServerError, &lang)),
+        },
+        Err(_) => Err(LocalizedError::of(Status::InternalServerError, &lang)),
+    }
+}
+
+/// GET endpoint which allows to get every API key issued, revoked ones
+/// included, without the keys themselves.
+///
+/// ## Arguments
+/// * `keys` - instance of the API keys repository.
+/// * `_admin` - the admin asking for the keys.
+///
+/// ## Returns
+/// * The list of API keys on json format.
+#[get("/admin/api_keys")]
+pub async fn get_api_keys(keys: &State<ApiKeyRepo>, _admin: Admin) -> Result<Json<Vec<ApiKey>>, Status> {
+    match keys.get_keys().await {
+        Ok(keys) => Ok(Json(keys)),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
+
+/// DELETE endpoint which allows to revoke an API key, refusing it from
+/// then on.
+///
+/// ## Arguments
+/// * `keys` - instance of the API keys repository.
+/// * `_admin` - the admin revoking the key.
+/// * `id` - id of the API key.
+///
+/// ## Returns
+/// * The revoked API key.
+#[delete("/admin/api_keys/<id>")]
+pub async fn revoke_api_key(keys: &State<ApiKeyRepo>, _admin: Admin, id: &str) -> Result<Json<ApiKey>, Status> {
+    let key_id = parse_id(id)?;
+    match keys.revoke_key(&key_id).await {
+        Ok(Some(revoked)) => Ok(Json(revoked)),
+        Ok(None) => Err(Status::NotFound),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
@@ -0,0 +1,135 @@
+use crate::{
+    api::error_api::LocalizedError,
+    auth::{hash_password, verify_password, Admin, AuthKeys},
+    compute::ComputeSlot,
+    i18n::Lang,
+    models::user_model::{Credentials, Role, RoleChange, TokenResponse, User},
+    repository::user_repo::UserRepo,
+    validation::validate_credentials,
+};
+use mongodb::results::InsertOneResult;
+use rocket::{http::Status, serde::json::Json, State};
+
+/// POST endpoint which allows to register a new user, as a viewer until an
+/// admin grants another role.
+///
+/// ## Arguments
+/// * `users` - instance of the users repository.
+/// * `slot` - place in the compute pool, to hash the password.
+/// * `lang` - languages accepted by the client.
+/// * `credentials` - username and password of the new user.
+///
+/// ## Returns
+/// * The id of the new user, or a conflict if the username is taken.
+#[post("/auth/register", data = "<credentials>")]
+pub async fn register(
+    users: &State<UserRepo>,
+    slot: ComputeSlot,
+    lang: Lang,
+    credentials: Json<Credentials>,
+) -> Result<Json<InsertOneResult>, (Status, LocalizedError)> {
+    if let Err(errors) = validate_credentials(&credentials) {
+        return Err(LocalizedError::invalid(&lang, errors));
+    };
+    let Credentials { username, password } = credentials.into_inner();
+    let username = username.trim().to_lowercase();
+
+    let password_hash = slot
+        .run(move || hash_password(&password))
+        .await
+        .map_err(|status| LocalizedError::of(status, &lang))?;
+    let data = User { id: None, username: username.clone(), password_hash, role: Role::Viewer };
+    match users.insert_user(data).await {
+        Ok(Some(user)) => Ok(Json(user)),
+        Ok(None) => {
+            let message = lang.message("error.username_taken").replace("{name}", &username);
+            Err(LocalizedError::new(Status::Conflict, &lang, message))
+        }
+        Err(_) => Err(LocalizedError::of(Status::InternalServerError, &lang)),
+    }
+}
+
+/// POST endpoint which allows a user to log in, trading the username and
+/// the password for a bearer token.
+///
+/// ## Arguments
+/// * `users` - instance of the users repository.
+/// * `keys` - keys signing the tokens.
+/// * `slot` - place in the compute pool, to check the password.
+/// * `lang` - languages accepted by the client.
+/// * `credentials` - username and password of the user.
+///
+/// ## Returns
+/// * The token, or a 401 if the username or the password are wrong.
+#[post("/auth/login", data = "<credentials>")]
+pub async fn login(
+    users: &State<UserRepo>,
+    keys: &State<AuthKeys>,
+    slot: ComputeSlot,
+    lang: Lang,
+    credentials: Json<Credentials>,
+) -> Result<Json<TokenResponse>, (Status, LocalizedError)> {
+    let Credentials { username, password } = credentials.into_inner();
+    let user = match users.get_user(&username.trim().to_lowercase()).await {
+        Ok(Some(user)) => user,
+        Ok(None) => return Err(LocalizedError::new(Status::Unauthorized, &lang, lang.message("error.credentials"))),
+        Err(_) => return Err(LocalizedError::of(Status::InternalServerError, &lang)),
+    };
+
+    let stored = user.password_hash.clone();
+    let valid = slot
+        .run(move || verify_password(&password, &stored))
+        .await
+        .map_err(|status| LocalizedError::of(status, &lang))?;
+    if !valid {
+        return Err(LocalizedError::new(Status::Unauthorized, &lang, lang.message("error.credentials")));
+    }
+    let (access_token, expires_in) = keys.issue(&user);
+    Ok(Json(TokenResponse { access_token, token_type: "Bearer", expires_in }))
+}
+
+/// GET endpoint which allows admins to get every user with its role.
+///
+/// ## Arguments
+/// * `users` - instance of the users repository.
+/// * `_admin` - the admin asking for the users.
+///
+/// ## Returns
+/// * The list of users on json format, without their password hashes.
+#[get("/admin/users")]
+pub async fn get_users(users: &State<UserRepo>, _admin: Admin) -> Result<Json<Vec<User>>, Status> {
+    match users.get_users().await {
+        Ok(users) => Ok(Json(users)),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
+
+/// PUT endpoint which allows admins to change the role of a user, from
+/// the next time the user logs in. Admins cannot change their own role, so
+/// that there is always one left.
+///
+/// ## Arguments
+/// * `users` - instance of the users repository.
+/// * `admin` - the admin changing the role.
+/// * `username` - name of the user.
+/// * `change` - new role of the user.
+///
+/// ## Returns
+/// * The updated user, without its password hash.
+#[put("/admin/users/<username>/role", data = "<change>")]
+pub async fn set_user_role(
+    users: &State<UserRepo>,
+    admin: Admin,
+    username: &str,
+    change: Json<RoleChange>,
+) -> Result<Json<User>, Status> {
+    let username = username.trim().to_lowercase();
+    if username == admin.0.username {
+        return Err(Status::Conflict);
+    };
+    match users.set_role(&username, change.role).await {
+        Ok(Some(user)) => Ok(Json(user)),
+        Ok(None) => Err(Status::NotFound),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
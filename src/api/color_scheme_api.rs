@@ -0,0 +1,110 @@
+use crate::{
+    auth::Editor,
+    models::color_scheme_model::{ColorScheme, FaceColors},
+    repository::color_scheme_repo::ColorSchemeRepo,
+};
+use mongodb::results::InsertOneResult;
+use rocket::{http::Status, serde::json::Json, State};
+
+/// POST endpoint which allows to store a named color scheme, so that it
+/// can be used by name when rendering or simulating cubes.
+/// 
+/// ## Arguments
+/// * `schemes` - instance of the color schemes repository.
+/// * `_editor` - the editor making the change.
+/// * `new_scheme` - the name and the color of every face.
+/// 
+/// ## Returns
+/// * The id of the stored scheme, or a conflict if the name is taken.
+#[post("/color_schemes", data = "<new_scheme>")]
+pub async fn insert_color_scheme(
+    schemes: &State<ColorSchemeRepo>,
+    _editor: Editor,
+    new_scheme: Json<ColorScheme>,
+) -> Result<Json<InsertOneResult>, Status> {
+    if new_scheme.validate().is_err() {
+        return Err(Status::UnprocessableEntity);
+    };
+    let name = new_scheme.name.trim().to_string();
+    match schemes.get_scheme(&name).await {
+        Ok(Some(_)) => return Err(Status::Conflict),
+        Ok(None) => {}
+        Err(_) => return Err(Status::InternalServerError),
+    }
+
+    let data = ColorScheme {
+        id: None,
+        name,
+        colors: new_scheme.colors.clone(),
+    };
+    match schemes.insert_scheme(data).await {
+        Ok(scheme) => Ok(Json(scheme)),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
+
+/// GET endpoint which allows to get every color scheme, the built-in
+/// `western` and `japanese` ones included.
+/// 
+/// ## Arguments
+/// * `schemes` - instance of the color schemes repository.
+/// 
+/// ## Returns
+/// * The list of color schemes on json format.
+#[get("/color_schemes")]
+pub async fn get_color_schemes(schemes: &State<ColorSchemeRepo>) -> Result<Json<Vec<ColorScheme>>, Status> {
+    match schemes.get_schemes().await {
+        Ok(schemes) => Ok(Json(schemes)),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
+
+/// GET endpoint which allows to get a color scheme by its name.
+/// 
+/// ## Arguments
+/// * `schemes` - instance of the color schemes repository.
+/// * `name` - name of the color scheme.
+/// 
+/// ## Returns
+/// * The color scheme on json format.
+#[get("/color_schemes/<name>")]
+pub async fn get_color_scheme(
+    schemes: &State<ColorSchemeRepo>, name: &str
+) -> Result<Json<ColorScheme>, Status> {
+    match schemes.get_scheme(name).await {
+        Ok(Some(scheme)) => Ok(Json(scheme)),
+        Ok(None) => Err(Status::NotFound),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
+
+/// Gets the colors to render a cube with: the inline colors of a request
+/// or, failing that, the colors of a named scheme.
+/// 
+/// ## Arguments
+/// * `schemes` - instance of the color schemes repository.
+/// * `name` - name of the scheme, if any.
+/// * `colors` - inline colors, if any.
+/// 
+/// ## Returns
+/// * The colors to use, none if neither was given.
+pub async fn resolve_colors(
+    schemes: &ColorSchemeRepo,
+    name: Option<&str>,
+    colors: Option<&FaceColors>,
+) -> Result<Option<FaceColors>, Status> {
+    if let Some(colors) = colors {
+        return match colors.validate() {
+            Ok(()) => Ok(Some(colors.clone())),
+            Err(_) => Err(Status::UnprocessableEntity),
+        };
+    }
+    let Some(name) = name else {
+        return Ok(None);
+    };
+    match schemes.get_scheme(name).await {
+        Ok(Some(scheme)) => Ok(Some(scheme.colors)),
+        Ok(None) => Err(Status::NotFound),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
@@ -0,0 +1,47 @@
+use crate::{
+    models::contribution_model::{ContributionKind, Contributions},
+    pagination::PageSize,
+    repository::contribution_repo::ContributionRepo,
+};
+use rocket::{http::Status, serde::json::Json, State};
+
+/// GET endpoint which allows to get the users who contributed the most to
+/// the catalog.
+/// 
+/// ## Arguments
+/// * `contributions` - instance of the contributions repository.
+/// * `by` - kind of contributions the users are ranked by, all of them by
+///   default.
+/// * `limit` - number of users to return, from the `limit` query parameter.
+/// 
+/// ## Returns
+/// * The contributions of the top users, the first being the top one.
+#[get("/contributors/top?<by>")]
+pub async fn get_top_contributors(
+    contributions: &State<ContributionRepo>, by: Option<ContributionKind>, limit: PageSize
+) -> Result<Json<Vec<Contributions>>, Status> {
+    match contributions.top_contributors(by.unwrap_or(ContributionKind::Total), limit.0 as i64).await {
+        Ok(top) => Ok(Json(top)),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
+
+/// GET endpoint which allows to get the contributions of a user to the
+/// catalog.
+/// 
+/// ## Arguments
+/// * `contributions` - instance of the contributions repository.
+/// * `username` - name of the contributor.
+/// 
+/// ## Returns
+/// * The contributions of the user, or a 404 if the user made none.
+#[get("/contributors/<username>")]
+pub async fn get_contributor(
+    contributions: &State<ContributionRepo>, username: &str
+) -> Result<Json<Contributions>, Status> {
+    match contributions.get_contributions(username).await {
+        Ok(Some(contributor)) => Ok(Json(contributor)),
+        Ok(None) => Err(Status::NotFound),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
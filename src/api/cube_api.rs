@@ -1,75 +1,349 @@
-use crate::{models::cube_model::Cube, repository::mongodb_repo::MongoRepo};
-use mongodb::{bson::oid::ObjectId, results::InsertOneResult};
-use rocket::{http::Status, serde::json::Json, State};
+use crate::{
+    api::error_api::{near_duplicate_message, LocalizedError},
+    auth::{Admin, Editor},
+    duplicates,
+    events::{ApiEvent, EventBus},
+    export::{self, CsvExport},
+    import,
+    i18n::Lang,
+    pagination::{self, PageSize},
+    precondition::{entity_tag, IfMatch, Tagged},
+    validation::{validate_cube, validate_world_record, FieldError},
+    models::{
+        bulk_insert_model::{BulkInsertReport, CubeUpload, InsertedCube, InsertedItem, RejectedItem},
+        contribution_model::ContributionKind,
+        cube_filter_model::{BulkDelete, BulkDeleteReport, CubeFilter},
+        cube_model::Cube,
+        cube_type_model::CubeType,
+        page_model::Page,
+        sort_model::Sort,
+        world_record_model::WorldRecord,
+        performance_model::PerformanceFilter,
+        puzzle_shape_model::ShapeKind,
+        query_analytics_model::QueryKind,
+        search_model::SearchHit,
+    },
+    repository::{
+        analytics_repo::AnalyticsRepo,
+        contribution_repo::ContributionRepo,
+        cube_repository::{CubeRepository, InsertedCubes},
+        repo_error::RepoError,
+    },
+};
+use futures::stream::{self, StreamExt};
+use mongodb::bson::{oid::ObjectId, Document};
+use rocket::{form::Form, http::Status, serde::json::Json, tokio::io::AsyncReadExt, State};
+
+/// Explains a failure of the repository to the client, naming the cube
+/// whose name is already taken or the limit a refused query breaks.
+fn repo_failure(error: RepoError, lang: &Lang) -> (Status, LocalizedError) {
+    match error {
+        RepoError::DuplicateName(name) => {
+            let message = lang.message("error.duplicate_name").replace("{name}", &name);
+            LocalizedError::new(Status::Conflict, lang, message)
+        }
+        RepoError::TooExpensive(limit) => LocalizedError::new(Status::BadRequest, lang, limit.message(lang)),
+        error => LocalizedError::of(error.into(), lang),
+    }
+}
 
 /// POST endpoint which allows to add a new cube to the database,
-/// given the body of a new cube object.
+/// given the body of a new cube object. Names are unique, and a cube named
+/// like an existing one is refused with a 409, as is, unless forced, a cube
+/// that looks like cubes of the catalog.
 /// 
 /// ## Arguments
 /// * `db` - instance of the mongo database.
+/// * `events` - listeners notified of the new cube.
+/// * `contributions` - counts of the contributions of the users.
+/// * `editor` - the editor making the change.
+/// * `lang` - languages accepted by the client.
+/// * `force` - if `true`, the cube is added even if it looks like others.
 /// * `new_cube` - new cube object to be inserted.
 /// 
 /// ## Returns
-/// * The id of the inserted object.
-#[post("/add_cube", data = "<new_cube>")]
-pub fn insert_cube(
-    db: &State<MongoRepo>, new_cube: Json<Cube>
-) -> Result<Json<InsertOneResult>, Status> {
+/// * The id of the inserted object, or a 409 with the cubes it may
+///   duplicate.
+#[post("/add_cube?<force>", data = "<new_cube>")]
+pub async fn insert_cube(
+    db: &State<Box<dyn CubeRepository>>,
+    events: &State<EventBus>,
+    contributions: &State<ContributionRepo>,
+    editor: Editor,
+    lang: Lang,
+    force: Option<bool>,
+    new_cube: Json<Cube>,
+) -> Result<Json<InsertedCube>, (Status, LocalizedError)> {
+    if let Err(errors) = validate_cube(&new_cube) {
+        return Err(LocalizedError::invalid(&lang, errors));
+    };
+
     let data = Cube {
         id: None,
         name: new_cube.name.to_owned(),
-        type_: new_cube.type_.clone(),
+        aliases: new_cube.aliases.clone(),
+        type_: new_cube.type_,
         pieces: new_cube.pieces,
         faces: new_cube.faces,
         stickers: new_cube.stickers,
         year_created: new_cube.year_created,
         wr: new_cube.wr.clone(),
+        performance: new_cube.performance.clone(),
+        adjustability: new_cube.adjustability.clone(),
+        electronics: new_cube.electronics,
+        shape: new_cube.shape.clone(),
+        quality: None,
+        created_at: None,
+        updated_at: None,
+        version: 0,
+        extra: Document::new(),
     };
-    let cube_detail = db.insert_cube(data);
+    if !force.unwrap_or(false) {
+        let candidates = duplicates::near_duplicates(db.as_ref(), &data)
+            .await
+            .map_err(|error| repo_failure(error, &lang))?;
+        if !candidates.is_empty() {
+            return Err(LocalizedError::near_duplicate(&lang, &data.name, candidates));
+        }
+    }
+    let cube_detail = db.insert_cube(data).await;
     match cube_detail {
-        Ok(cube) => Ok(Json(cube)),
-        Err(_) => Err(Status::InternalServerError),
+        Ok(inserted_id) => {
+            events.emit(ApiEvent::CubeCreated { id: inserted_id.as_object_id() });
+            contributions.record(&editor.0.username, ContributionKind::CubesAdded, 1).await;
+            Ok(Json(InsertedCube { inserted_id }))
+        }
+        Err(error) => Err(repo_failure(error, &lang)),
     }
 }
 
-/// GET endpoint which allows to get a cube instance by its ID.
+/// POST endpoint which allows to add several cubes to the database at
+/// once, given a list of new cube objects. Cubes with invalid specs, named
+/// like an earlier cube of the list or an existing one, and unless forced
+/// those that look like cubes of the catalog, are left out and reported,
+/// and the rest are inserted.
+/// 
+/// ## Arguments
+/// * `db` - instance of the mongo database.
+/// * `events` - listeners notified of the new cubes.
+/// * `contributions` - counts of the contributions of the users.
+/// * `editor` - the editor making the change.
+/// * `lang` - languages accepted by the client.
+/// * `force` - if `true`, cubes are added even if they look like others.
+/// * `new_cubes` - new cube objects to be inserted.
+/// 
+/// ## Returns
+/// * The IDs of the inserted cubes and the errors of the rejected ones, by
+///   their position in the list.
+#[post("/add_cubes?<force>", data = "<new_cubes>")]
+pub async fn insert_cubes(
+    db: &State<Box<dyn CubeRepository>>,
+    events: &State<EventBus>,
+    contributions: &State<ContributionRepo>,
+    editor: Editor,
+    lang: Lang,
+    force: Option<bool>,
+    new_cubes: Json<Vec<Cube>>,
+) -> Result<Json<BulkInsertReport>, (Status, LocalizedError)> {
+    let duplicate_name = |name: &str| {
+        let message = lang.message("error.duplicate_name").replace("{name}", name);
+        vec![FieldError::new("name", message)]
+    };
+    let mut indexes = Vec::new();
+    let mut valid: Vec<Cube> = Vec::new();
+    let mut rejected = Vec::new();
+    for (index, cube) in new_cubes.into_inner().into_iter().enumerate() {
+        if let Err(errors) = validate_cube(&cube) {
+            rejected.push(RejectedItem { index, errors });
+            continue;
+        }
+        if valid.iter().any(|earlier| earlier.name == cube.name) {
+            rejected.push(RejectedItem { index, errors: duplicate_name(&cube.name) });
+            continue;
+        }
+        if !force.unwrap_or(false) {
+            let candidates = duplicates::near_duplicates(db.as_ref(), &cube)
+                .await
+                .map_err(|error| repo_failure(error, &lang))?;
+            if !candidates.is_empty() {
+                let message = near_duplicate_message(&lang, &cube.name, &candidates);
+                rejected.push(RejectedItem { index, errors: vec![FieldError::new("name", message)] });
+                continue;
+            }
+        }
+        indexes.push(index);
+        valid.push(cube);
+    }
+    if valid.is_empty() {
+        return Ok(Json(BulkInsertReport { inserted: Vec::new(), rejected }));
+    }
+
+    let InsertedCubes { inserted, failed } = db.insert_cubes(valid).await.map_err(|error| repo_failure(error, &lang))?;
+    let mut inserted: Vec<InsertedItem> = inserted
+        .into_iter()
+        .map(|(position, id)| InsertedItem { index: indexes[position], id })
+        .collect();
+    inserted.sort_by_key(|item| item.index);
+    for item in &inserted {
+        events.emit(ApiEvent::CubeCreated { id: item.id.as_object_id() });
+    }
+    contributions.record(&editor.0.username, ContributionKind::CubesAdded, inserted.len() as u64).await;
+    for (position, error) in failed {
+        let errors = match error {
+            RepoError::DuplicateName(name) => duplicate_name(&name),
+            RepoError::Refused(reason) => vec![FieldError::new("cube", reason)],
+            error => vec![FieldError::new("cube", error.to_string())],
+        };
+        rejected.push(RejectedItem { index: indexes[position], errors });
+    }
+    rejected.sort_by_key(|item| item.index);
+    Ok(Json(BulkInsertReport { inserted, rejected }))
+}
+
+/// POST endpoint which allows to import a catalog from a CSV or a JSON
+/// file, sent as the `file` field of a multipart form. CSV files have the
+/// columns of the CSV export and JSON ones an array of cubes. Every record
+/// is checked and inserted on its own, so that invalid ones, those named
+/// like an existing cube and, unless forced, those that look like cubes of
+/// the catalog are reported without stopping the rest.
+/// 
+/// ## Arguments
+/// * `db` - instance of the mongo database.
+/// * `events` - listeners notified of the new cubes.
+/// * `contributions` - counts of the contributions of the users.
+/// * `editor` - the editor making the import.
+/// * `lang` - languages accepted by the client.
+/// * `force` - if `true`, cubes are added even if they look like others.
+/// * `upload` - the form with the file.
+/// 
+/// ## Returns
+/// * The IDs of the inserted cubes and the errors of the rejected ones, by
+///   their position in the file, or a 422 if the file cannot be read.
+#[post("/cubes/import?<force>", data = "<upload>")]
+pub async fn import_cubes(
+    db: &State<Box<dyn CubeRepository>>,
+    events: &State<EventBus>,
+    contributions: &State<ContributionRepo>,
+    editor: Editor,
+    lang: Lang,
+    force: Option<bool>,
+    upload: Form<CubeUpload<'_>>,
+) -> Result<Json<BulkInsertReport>, (Status, LocalizedError)> {
+    let unreadable = |message: String| LocalizedError::invalid(&lang, vec![FieldError::new("file", message)]);
+    let file = &upload.file;
+    let named = |extension: &str| {
+        file.raw_name()
+            .is_some_and(|name| name.dangerous_unsafe_unsanitized_raw().as_str().to_lowercase().ends_with(extension))
+    };
+    let is_csv = file.content_type().is_some_and(|kind| kind.is_csv()) || named(".csv");
+    let is_json = file.content_type().is_some_and(|kind| kind.is_json()) || named(".json");
+
+    let mut text = String::new();
+    let mut reader = file.open().await.map_err(|_| LocalizedError::of(Status::InternalServerError, &lang))?;
+    reader
+        .read_to_string(&mut text)
+        .await
+        .map_err(|_| unreadable("the file is not UTF-8 text".to_string()))?;
+    let rows = match (is_csv, is_json) {
+        (true, _) => import::read_csv(&text),
+        (false, true) => import::read_json(&text),
+        (false, false) => Err("the file must be a CSV or a JSON one".to_string()),
+    }
+    .map_err(unreadable)?;
+
+    let mut inserted = Vec::new();
+    let mut rejected = Vec::new();
+    for (index, row) in rows.into_iter().enumerate() {
+        let cube = match row.and_then(|cube| validate_cube(&cube).map(|()| cube)) {
+            Ok(cube) => Cube { id: None, quality: None, created_at: None, updated_at: None, version: 0, ..cube },
+            Err(errors) => {
+                rejected.push(RejectedItem { index, errors });
+                continue;
+            }
+        };
+        if !force.unwrap_or(false) {
+            let candidates = duplicates::near_duplicates(db.as_ref(), &cube)
+                .await
+                .map_err(|error| LocalizedError::of(error.into(), &lang))?;
+            if !candidates.is_empty() {
+                let message = near_duplicate_message(&lang, &cube.name, &candidates);
+                rejected.push(RejectedItem { index, errors: vec![FieldError::new("name", message)] });
+                continue;
+            }
+        }
+        match db.insert_cube(cube).await {
+            Ok(id) => {
+                events.emit(ApiEvent::CubeCreated { id: id.as_object_id() });
+                contributions.record(&editor.0.username, ContributionKind::CubesAdded, 1).await;
+                inserted.push(InsertedItem { index, id });
+            }
+            Err(RepoError::DuplicateName(name)) => {
+                let message = lang.message("error.duplicate_name").replace("{name}", &name);
+                rejected.push(RejectedItem { index, errors: vec![FieldError::new("name", message)] });
+            }
+            Err(error) => return Err(LocalizedError::of(error.into(), &lang)),
+        }
+    }
+    Ok(Json(BulkInsertReport { inserted, rejected }))
+}
+
+/// GET endpoint which allows to get a cube instance by its ID, tagged
+/// with its ID and its version.
 /// 
 /// ## Arguments
 /// * `db` - instance of the mongo database.
 /// * `id` - id of the cube to get.
 /// 
 /// ## Returns
-/// * The cube instance on json format.
+/// * The cube instance on json format, or a 304 if the client has that
+///   version already.
 #[get("/cube_by_id?<id>")]
-pub fn get_cube(db: &State<MongoRepo>, id: String) -> Result<Json<Cube>, Status> {
+pub async fn get_cube(db: &State<Box<dyn CubeRepository>>, id: String) -> Result<Tagged<Json<Cube>>, Status> {
     if id.is_empty() {
         return Err(Status::BadRequest);
     };
 
-    let cube_detail = db.get_cube(&id);
+    let cube_detail = db.get_cube(&id).await;
     match cube_detail {
-        Ok(cube) => Ok(Json(cube)),
-        Err(_) => Err(Status::InternalServerError),
+        Ok(cube) => {
+            let tag = entity_tag(&cube);
+            Ok(Tagged(Json(cube), tag))
+        }
+        Err(error) => Err(error.into()),
     }
 }
 
-/// GET endpoint which allows to get a cube instance by its name
+/// GET endpoint which allows to get a cube instance by its name, tagged
+/// with its ID and its version.
 ///
 /// ## Arguments
 /// * `db` - instance of the mongo database.
-/// * `name` - name of the cube to get.
+/// * `analytics` - instance of the query analytics repository.
+/// * `name` - name of the cube to get, or one of its aliases.
 /// 
 /// ## Returns
-/// * The cube instance on json format.
+/// * The cube instance on json format, or a 304 if the client has that
+///   version already.
 #[get("/cube_by_name?<name>")]
-pub fn get_cube_by_name(db: &State<MongoRepo>, name: String) -> Result<Json<Cube>, Status> {
+pub async fn get_cube_by_name(
+    db: &State<Box<dyn CubeRepository>>,
+    analytics: &State<AnalyticsRepo>,
+    name: String,
+) -> Result<Tagged<Json<Cube>>, Status> {
     if name.is_empty() {
         return Err(Status::BadRequest);
     };
-    let cube_detail = db.get_cube_by_name(&name);
+    let cube_detail = db.find_cube_by_name(&name).await;
+    if let Ok(found) = &cube_detail {
+        analytics.record(QueryKind::Name, &name, found.is_some() as u64);
+    }
     match cube_detail {
-        Ok(cube) => Ok(Json(cube)),
-        Err(_) => Err(Status::InternalServerError)
+        Ok(Some(cube)) => {
+            let tag = entity_tag(&cube);
+            Ok(Tagged(Json(cube), tag))
+        }
+        Ok(None) => Err(Status::NotFound),
+        Err(error) => Err(error.into())
     }
 }
 
@@ -77,83 +351,270 @@ pub fn get_cube_by_name(db: &State<MongoRepo>, name: String) -> Result<Json<Cube
 /// 
 /// ## Arguments
 /// * `db` - instance of the mongo database.
-/// * `type_` - type of the cubes to get.
+/// * `analytics` - instance of the query analytics repository.
+/// * `type_` - type of the cubes to get: `cubic`, `cuboid`, `minx` or
+///   `other`. Any other type is refused with a 400.
+/// * `sort_by` - field to sort the cubes by, if any.
+/// * `order` - `asc` (the default) or `desc`.
+/// * `lang` - languages accepted by the client.
 /// 
 /// ## Returns
 /// * A vector that contains the cubes with the specified type.
-#[get("/cube_by_type?<type_>")]
-pub fn get_cube_by_type(db: &State<MongoRepo>, type_: String) -> Result<Json<Vec<Cube>>, Status> {
-    if type_.is_empty() {
+#[get("/cube_by_type?<type_>&<sort_by>&<order>")]
+pub async fn get_cube_by_type(
+    db: &State<Box<dyn CubeRepository>>,
+    analytics: &State<AnalyticsRepo>,
+    type_: &str,
+    sort_by: Option<&str>,
+    order: Option<&str>,
+    lang: Lang,
+) -> Result<Json<Vec<Cube>>, (Status, LocalizedError)> {
+    let cube_type = type_.parse::<CubeType>().map_err(|_| LocalizedError::of(Status::BadRequest, &lang))?;
+    let sort = Sort::parse(sort_by, order).map_err(|_| LocalizedError::of(Status::BadRequest, &lang))?;
+    let cubes_detail = db.get_cube_by_type(&cube_type, sort).await;
+    match &cubes_detail {
+        Ok(cubes) => {
+            analytics.record(QueryKind::Type, type_, cubes.len() as u64);
+        }
+        Err(RepoError::NotFound) => {
+            analytics.record(QueryKind::Type, type_, 0);
+        }
+        Err(_) => {}
+    }
+    match cubes_detail {
+        Ok(cubes) => Ok(Json(cubes)),
+        Err(error) => Err(repo_failure(error, &lang)),
+    }
+}
+
+/// GET endpoint which allows to search the cubes by the words of their
+/// name, aliases or type, for catalog browsers that cannot rely on exact names.
+/// 
+/// ## Arguments
+/// * `db` - instance of the mongo database.
+/// * `q` - words to search for.
+/// * `limit` - maximum number of cubes to return, from the `limit` query
+///   parameter.
+/// 
+/// ## Returns
+/// * The matching cubes with their relevance score, the most relevant first.
+#[get("/search?<q>")]
+pub async fn search_cubes(
+    db: &State<Box<dyn CubeRepository>>, q: &str, limit: PageSize
+) -> Result<Json<Vec<SearchHit>>, Status> {
+    if q.trim().is_empty() {
         return Err(Status::BadRequest);
     };
-    let cubes_detail = db.get_cube_by_type(&type_);
+    match db.search_cubes(q, limit.0 as i64).await {
+        Ok(hits) => Ok(Json(hits)),
+        Err(error) => Err(error.into()),
+    }
+}
+
+/// GET endpoint which allows to get the cubes that reach the given
+/// performance characteristics.
+/// 
+/// ## Arguments
+/// * `db` - instance of the mongo database.
+/// * `filter` - minimum corner cutting, stability and speed, and whether
+///   the cube must be suited for one-handed solving.
+/// * `lang` - languages accepted by the client.
+/// 
+/// ## Returns
+/// * A vector that contains the cubes that matched the filter.
+#[get("/cube_by_performance?<filter..>")]
+pub async fn get_cube_by_performance(
+    db: &State<Box<dyn CubeRepository>>, filter: PerformanceFilter, lang: Lang
+) -> Result<Json<Vec<Cube>>, (Status, LocalizedError)> {
+    let cubes_detail = db.get_cubes_by_performance(&filter).await;
     match cubes_detail {
         Ok(cubes) => Ok(Json(cubes)),
-        Err(_) => Err(Status::InternalServerError)
+        Err(error) => Err(repo_failure(error, &lang)),
     }
 }
 
-/// GET endpoint which allows to gets all the cubes from the database
+/// GET endpoint which allows to get the puzzles of a given shape, such as
+/// megaminx or square-1.
+/// 
+/// ## Arguments
+/// * `db` - instance of the mongo database.
+/// * `shape` - shape of the puzzles to get.
+/// * `lang` - languages accepted by the client.
+/// 
+/// ## Returns
+/// * A vector that contains the puzzles with the specified shape.
+#[get("/cube_by_shape?<shape>")]
+pub async fn get_cube_by_shape(
+    db: &State<Box<dyn CubeRepository>>, shape: ShapeKind, lang: Lang
+) -> Result<Json<Vec<Cube>>, (Status, LocalizedError)> {
+    let cubes_detail = db.get_cubes_by_shape(&shape).await;
+    match cubes_detail {
+        Ok(cubes) => Ok(Json(cubes)),
+        Err(error) => Err(repo_failure(error, &lang)),
+    }
+}
+
+/// GET endpoint which allows to get the cubes whose world record is held
+/// by a person.
 ///
 /// ## Arguments
 /// * `db` - instance of the mongo database.
+/// * `holder` - name of the record holder.
+/// * `lang` - languages accepted by the client.
 /// 
 /// ## Returns
-/// * A vector of cubes which contains all the cubes available in the database.
-#[get("/cubes")]
-pub fn get_all_cubes(db: &State<MongoRepo>) -> Result<Json<Vec<Cube>>, Status> {
-    let cubes = db.get_all_cubes();
-    match cubes {
+/// * A vector that contains the cubes whose record the person holds.
+#[get("/cube_by_holder?<holder>")]
+pub async fn get_cube_by_holder(
+    db: &State<Box<dyn CubeRepository>>, holder: String, lang: Lang
+) -> Result<Json<Vec<Cube>>, (Status, LocalizedError)> {
+    if holder.trim().is_empty() {
+        return Err(LocalizedError::of(Status::BadRequest, &lang));
+    };
+    let cubes_detail = db.get_cubes_by_holder(holder.trim()).await;
+    match cubes_detail {
         Ok(cubes) => Ok(Json(cubes)),
-        Err(_) => Err(Status::InternalServerError),
+        Err(error) => Err(repo_failure(error, &lang)),
+    }
+}
+
+/// GET endpoint which allows to gets the cubes from the database, one page
+/// at a time, optionally filtered by any combination of type, release year
+/// and number of pieces.
+///
+/// ## Arguments
+/// * `db` - instance of the mongo database.
+/// * `page` - number of the page, starting at 1. The first one by default.
+/// * `per_page` - number of cubes per page, from the `per_page` query
+///   parameter.
+/// * `sort_by` - field to sort the cubes by, if any.
+/// * `order` - `asc` (the default) or `desc`.
+/// * `filter` - criteria the cubes must match, if any.
+/// * `lang` - languages accepted by the client.
+/// 
+/// ## Returns
+/// * The cubes of the page, with the total number of matching cubes and
+///   pages.
+#[get("/cubes?<page>&<sort_by>&<order>&<filter..>")]
+pub async fn get_all_cubes(
+    db: &State<Box<dyn CubeRepository>>,
+    page: Option<u64>,
+    per_page: PageSize,
+    sort_by: Option<&str>,
+    order: Option<&str>,
+    filter: CubeFilter,
+    lang: Lang,
+) -> Result<Json<Page<Cube>>, (Status, LocalizedError)> {
+    let page = page.unwrap_or(1);
+    let per_page = per_page.0;
+    let Some(skip) = pagination::skip(page, per_page) else {
+        return Err(LocalizedError::of(Status::BadRequest, &lang));
+    };
+    let sort = Sort::parse(sort_by, order).map_err(|_| LocalizedError::of(Status::BadRequest, &lang))?;
+
+    let cubes = db.get_cubes_page(&filter, sort, skip, per_page as i64).await;
+    match cubes {
+        Ok((cubes, total)) => Ok(Json(Page::new(cubes, page, per_page, total))),
+        Err(error) => Err(repo_failure(error, &lang)),
     }
 }
 
+/// GET endpoint which allows to download the whole catalog as a CSV file,
+/// one cube per row, streamed from the database as it is read. A failure
+/// halfway through ends the file early.
+/// 
+/// ## Arguments
+/// * `db` - instance of the mongo database.
+/// * `format` - format of the export, only `csv` for now.
+/// 
+/// ## Returns
+/// * The CSV file, downloaded as `cubes.csv`.
+#[get("/cubes/export?<format>")]
+pub async fn export_cubes<'r>(
+    db: &'r State<Box<dyn CubeRepository>>, format: &str
+) -> Result<CsvExport<'r>, Status> {
+    if !format.eq_ignore_ascii_case("csv") {
+        return Err(Status::UnprocessableEntity);
+    };
+    let cubes = db.stream_cubes().await.map_err(Status::from)?;
+    let rows = cubes
+        .take_while(|cube| {
+            if let Err(error) = cube {
+                error!("CSV export interrupted: {}", error);
+            }
+            std::future::ready(cube.is_ok())
+        })
+        .filter_map(|cube| std::future::ready(cube.ok().map(|cube| export::csv_row(&cube))));
+    let header = stream::once(std::future::ready(format!("{}\r\n", export::CSV_HEADER)));
+    Ok(CsvExport::new(header.chain(rows).boxed(), "cubes.csv"))
+}
+
 /// PUT endpoint which allows to update a cube with its ID and the
 /// body of the new definition of the cube.
 /// 
 /// ## Arguments
 /// * `db` - instance of the mongo database.
+/// * `events` - listeners notified of the change.
+/// * `contributions` - counts of the contributions of the users.
+/// * `editor` - the editor making the change.
+/// * `lang` - languages accepted by the client.
 /// * `id` - id of the cube to be updated.
 /// * `new_cube` - new cube object definition.
 /// 
 /// ## Returns
 /// * The definition of the updated cube.
 #[put("/update_cube?<id>", data = "<new_cube>")]
-pub fn update_cube(
-    db: &State<MongoRepo>, 
+pub async fn update_cube(
+    db: &State<Box<dyn CubeRepository>>, 
+    events: &State<EventBus>,
+    contributions: &State<ContributionRepo>,
+    editor: Editor,
+    lang: Lang,
     id: String, 
     new_cube: Json<Cube>, 
-) -> Result<Json<Cube>, Status> {
+) -> Result<Json<Cube>, (Status, LocalizedError)> {
     if id.is_empty() {
-        return Err(Status::BadRequest);
+        return Err(LocalizedError::of(Status::BadRequest, &lang));
+    };
+    if let Err(errors) = validate_cube(&new_cube) {
+        return Err(LocalizedError::invalid(&lang, errors));
     };
 
     let data = Cube {
-        id: Some(ObjectId::parse_str(&id).unwrap()),
+        id: ObjectId::parse_str(&id).ok(),
         name: new_cube.name.to_owned(),
-        type_: new_cube.type_.clone(),
+        aliases: new_cube.aliases.clone(),
+        type_: new_cube.type_,
         pieces: new_cube.pieces,
         faces: new_cube.faces,
         stickers: new_cube.stickers,
         year_created: new_cube.year_created,
         wr: new_cube.wr.clone(),
+        performance: new_cube.performance.clone(),
+        adjustability: new_cube.adjustability.clone(),
+        electronics: new_cube.electronics,
+        shape: new_cube.shape.clone(),
+        quality: None,
+        created_at: None,
+        updated_at: None,
+        version: 0,
+        extra: Document::new(),
     };
     
-    let update_result = db.edit_cube(&id, data);
+    let update_result = db.edit_cube(&id, data).await;
     match update_result { 
-        Ok(update) => {
-            if update.matched_count == 1 {
-                let updated_cube_info = db.get_cube(&id);
-                match updated_cube_info {
-                    Ok(cube) => Ok(Json(cube)),
-                    Err(_) => Err(Status::InternalServerError),
+        Ok(()) => {
+            let updated_cube_info = db.get_cube(&id).await;
+            match updated_cube_info {
+                Ok(cube) => {
+                    events.emit(ApiEvent::CubeUpdated { id: cube.id });
+                    contributions.record(&editor.0.username, ContributionKind::Edits, 1).await;
+                    Ok(Json(cube))
                 }
-            } else {
-                Err(Status::NotFound)
+                Err(error) => Err(LocalizedError::of(error.into(), &lang)),
             }
         }
-        Err(_) => Err(Status::InternalServerError),
+        Err(error) => Err(repo_failure(error, &lang)),
     }
 }
 
@@ -162,71 +623,379 @@ pub fn update_cube(
 /// 
 /// ## Arguments
 /// * `db` - instance of the mongo database.
+/// * `events` - listeners notified of the change.
+/// * `contributions` - counts of the contributions of the users.
+/// * `editor` - the editor making the change.
+/// * `lang` - languages accepted by the client.
 /// * `name` - name of the cube to be updated.
 /// * `new_cube` -  new cube object definition.
 /// 
 /// ## Returns
 /// The definition of the updated cube.
 #[put("/update_by_name?<name>", data= "<new_cube>")]
-pub fn update_cube_by_name(
-    db: &State<MongoRepo>,
+pub async fn update_cube_by_name(
+    db: &State<Box<dyn CubeRepository>>,
+    events: &State<EventBus>,
+    contributions: &State<ContributionRepo>,
+    editor: Editor,
+    lang: Lang,
     name: String,
     new_cube: Json<Cube>,
-) -> Result<Json<Cube>, Status> {
+) -> Result<Json<Cube>, (Status, LocalizedError)> {
     if name.is_empty() {
-        return Err(Status::BadRequest);
+        return Err(LocalizedError::of(Status::BadRequest, &lang));
+    };
+    if let Err(errors) = validate_cube(&new_cube) {
+        return Err(LocalizedError::invalid(&lang, errors));
     };
 
     let data = Cube {
         id: new_cube.id,
         name: new_cube.name.to_owned(),
-        type_: new_cube.type_.clone(),
+        aliases: new_cube.aliases.clone(),
+        type_: new_cube.type_,
         pieces: new_cube.pieces,
         faces: new_cube.faces,
         stickers: new_cube.stickers,
         year_created: new_cube.year_created,
         wr: new_cube.wr.clone(),
+        performance: new_cube.performance.clone(),
+        adjustability: new_cube.adjustability.clone(),
+        electronics: new_cube.electronics,
+        shape: new_cube.shape.clone(),
+        quality: None,
+        created_at: None,
+        updated_at: None,
+        version: 0,
+        extra: Document::new(),
     };
 
-    let update_result = db.edit_cube_by_name(&name, data);
+    let update_result = db.edit_cube_by_name(&name, data).await;
     match update_result {
-        Ok(update) => {
-            if update.matched_count == 1 {
-                let updated_cube_info = db.get_cube_by_name(&name);
-                match updated_cube_info {
-                    Ok(cube) => Ok(Json(cube)),
-                    Err(_) => Err(Status::InternalServerError),
+        Ok(()) => {
+            let updated_cube_info = db.get_cube_by_name(&name).await;
+            match updated_cube_info {
+                Ok(cube) => {
+                    events.emit(ApiEvent::CubeUpdated { id: cube.id });
+                    contributions.record(&editor.0.username, ContributionKind::Edits, 1).await;
+                    Ok(Json(cube))
                 }
-            } else {
-                Err(Status::NotFound)
+                Err(error) => Err(LocalizedError::of(error.into(), &lang)),
             }
         }
-        Err(_) => Err(Status::InternalServerError),
+        Err(error) => Err(repo_failure(error, &lang)),
     }
 }
 
-/// DELETE endpoint which allows to delete a cube by its ID.
+/// PUT endpoint that allows to replace only the world record of a cube,
+/// leaving the rest of its definition untouched.
+/// 
+/// ## Arguments
+/// * `db` - instance of the mongo database.
+/// * `events` - listeners notified of the change.
+/// * `contributions` - counts of the contributions of the users.
+/// * `editor` - the editor making the change.
+/// * `lang` - languages accepted by the client.
+/// * `id` - ID of the cube.
+/// * `wr` - new world record of the cube.
+/// 
+/// ## Returns
+/// The definition of the updated cube.
+#[put("/cube/<id>/wr", data = "<wr>")]
+pub async fn update_world_record(
+    db: &State<Box<dyn CubeRepository>>,
+    events: &State<EventBus>,
+    contributions: &State<ContributionRepo>,
+    editor: Editor,
+    lang: Lang,
+    id: &str,
+    wr: Json<WorldRecord>,
+) -> Result<Json<Cube>, (Status, LocalizedError)> {
+    if let Err(errors) = validate_world_record(&wr) {
+        return Err(LocalizedError::invalid(&lang, errors));
+    };
+
+    match db.set_world_record(id, wr.into_inner()).await {
+        Ok(cube) => {
+            events.emit(ApiEvent::CubeUpdated { id: cube.id });
+            contributions.record(&editor.0.username, ContributionKind::Edits, 1).await;
+            Ok(Json(cube))
+        }
+        Err(error) => Err(LocalizedError::of(error.into(), &lang)),
+    }
+}
+
+/// DELETE endpoint which allows to delete a cube by its ID. With an
+/// `If-Match` tag, the cube is only deleted if nobody has changed it
+/// since the client read it. The cube can be brought back with
+/// [`restore_cube`] until an admin purges it.
 /// 
 /// ## Arguments
 /// * `db` - instance of the mongo repo.
+/// * `events` - listeners notified of the deletion.
+/// * `_admin` - the admin making the change.
+/// * `lang` - languages accepted by the client.
+/// * `if_match` - tag of the cube as the client last read it, if any.
 /// * `id` - ID of the cube to be deleted.
 /// 
 /// ## Returns
 /// * A message with the operation status.
 #[delete("/delete_cube?<id>")]
-pub fn delete_cube(db: &State<MongoRepo>, id: String) -> Result<Json<&str>, Status> {
+pub async fn delete_cube(
+    db: &State<Box<dyn CubeRepository>>, events: &State<EventBus>, _admin: Admin, lang: Lang, if_match: IfMatch, id: String
+) -> Result<Json<String>, Status> {
     if id.is_empty() {
         return Err(Status::BadRequest);
     };
-    let result = db.delete_cube(&id);
+    let expected = if_match.version_for(&id)?;
+    let result = db.delete_cube(&id, expected).await;
     match result {
-        Ok(res) => {
-            if res.deleted_count == 1 {
-                Ok(Json("Cube successfully deleted!"))
-            } else {
-                Err(Status::InternalServerError)
+        Ok(()) => {
+            if let Ok(id) = ObjectId::parse_str(&id) {
+                events.emit(ApiEvent::CubeDeleted { id });
             }
+            Ok(Json(lang.message("cube_deleted")))
         },
-        Err(_) => Err(Status::InternalServerError),
+        Err(RepoError::NotFound) if expected.is_some() && db.get_cube(&id).await.is_ok() => {
+            Err(Status::PreconditionFailed)
+        }
+        Err(error) => Err(error.into()),
     }
-}
\ No newline at end of file
+}
+
+/// POST endpoint which allows to restore a deleted cube by its ID, with
+/// its name, data and a new version.
+/// 
+/// ## Arguments
+/// * `db` - instance of the mongo repo.
+/// * `events` - listeners notified of the restoration.
+/// * `_admin` - the admin making the change.
+/// * `id` - ID of the cube to be restored.
+/// 
+/// ## Returns
+/// * The restored cube, or a 404 if no deleted cube has the ID.
+#[post("/cube/<id>/restore")]
+pub async fn restore_cube(
+    db: &State<Box<dyn CubeRepository>>, events: &State<EventBus>, _admin: Admin, id: &str
+) -> Result<Json<Cube>, Status> {
+    match db.restore_cube(id).await {
+        Ok(cube) => {
+            if let Some(id) = cube.id {
+                events.emit(ApiEvent::CubeRestored { id });
+            }
+            Ok(Json(cube))
+        }
+        Err(error) => Err(error.into()),
+    }
+}
+
+/// DELETE endpoint which allows to remove a cube for good by its ID,
+/// whether it was deleted before or not. Its name is free afterwards.
+/// 
+/// ## Arguments
+/// * `db` - instance of the mongo repo.
+/// * `events` - listeners notified of the deletion.
+/// * `_admin` - the admin making the change.
+/// * `lang` - languages accepted by the client.
+/// * `id` - ID of the cube to be removed.
+/// 
+/// ## Returns
+/// * A message with the operation status.
+#[delete("/admin/cubes/<id>")]
+pub async fn purge_cube(
+    db: &State<Box<dyn CubeRepository>>, events: &State<EventBus>, _admin: Admin, lang: Lang, id: &str
+) -> Result<Json<String>, Status> {
+    let obj_id = ObjectId::parse_str(id).map_err(|_| Status::BadRequest)?;
+    // Listeners already heard of the deletion of a deleted cube.
+    let was_live = db.cube_exists(&obj_id).await.map_err(Status::from)?;
+    match db.purge_cube(id).await {
+        Ok(()) => {
+            if was_live {
+                events.emit(ApiEvent::CubeDeleted { id: obj_id });
+            }
+            Ok(Json(lang.message("cube_deleted")))
+        }
+        Err(error) => Err(error.into()),
+    }
+}
+
+/// DELETE endpoint which allows to delete every cube matching a filter,
+/// such as a type or a range of years or pieces. A filter without criteria is
+/// refused, so that the catalog is never emptied by mistake. If any of the
+/// cubes with an expected version has changed since, or any cube changes
+/// while they are deleted, nothing is deleted and a 412 is answered.
+/// 
+/// ## Arguments
+/// * `db` - instance of the mongo repo.
+/// * `events` - listeners notified of the deletions.
+/// * `_admin` - the admin making the change.
+/// * `lang` - languages accepted by the client.
+/// * `request` - criteria of the cubes to be deleted and their expected
+///   versions.
+/// 
+/// ## Returns
+/// * The number of deleted cubes.
+#[delete("/cubes", data = "<request>")]
+pub async fn delete_cubes(
+    db: &State<Box<dyn CubeRepository>>,
+    events: &State<EventBus>,
+    _admin: Admin,
+    lang: Lang,
+    request: Json<BulkDelete>,
+) -> Result<Json<BulkDeleteReport>, (Status, LocalizedError)> {
+    if request.filter.is_empty() {
+        return Err(LocalizedError::of(Status::BadRequest, &lang));
+    };
+    let versions = match db.get_versions(&request.filter).await {
+        Ok(versions) => versions,
+        Err(error) => return Err(repo_failure(error, &lang)),
+    };
+    let changed = versions.iter().any(|(id, version)| {
+        request
+            .expected_versions
+            .get(&id.to_hex())
+            .is_some_and(|expected| expected != version)
+    });
+    if changed {
+        return Err(LocalizedError::of(Status::PreconditionFailed, &lang));
+    };
+
+    match db.delete_versions(&versions).await {
+        Ok(ids) => {
+            let deleted = ids.len() as u64;
+            for id in ids {
+                events.emit(ApiEvent::CubeDeleted { id });
+            }
+            Ok(Json(BulkDeleteReport { deleted }))
+        }
+        Err(error) => Err(repo_failure(error, &lang)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Once};
+
+    use rocket::http::Header;
+    use rocket::local::asynchronous::Client;
+    use rocket::serde::json::{serde_json, Value};
+
+    use crate::auth::AuthKeys;
+    use crate::models::user_model::{Role, User};
+    use crate::repository::memory_repo::MemoryRepo;
+    use crate::server::RubikApi;
+
+    use super::*;
+
+    const BASE: &str = "/api/v1";
+
+    fn cube(name: &str) -> Cube {
+        serde_json::from_value(serde_json::json!({
+            "name": name,
+            "type_": "cubic",
+            "pieces": 26,
+            "faces": 6,
+            "stickers": 54,
+            "year_created": 1974,
+            "wr": {"time_ms": 3130, "holder": "Max Park", "date": "2023-06-11"}
+        }))
+        .unwrap()
+    }
+
+    /// Builds the server on the given cubes. MongoDB is never reached: the
+    /// other repositories fail fast, which the routes tested do not need.
+    async fn client(cubes: &[&str]) -> (Client, Vec<String>) {
+        static ENV: Once = Once::new();
+        ENV.call_once(|| std::env::set_var("MONGOURI", "mongodb://127.0.0.1:1/?serverSelectionTimeoutMS=200"));
+        let repo = Arc::new(MemoryRepo::default());
+        let mut ids = Vec::new();
+        for name in cubes {
+            ids.push(repo.insert_cube(cube(name)).await.unwrap().as_object_id().unwrap().to_hex());
+        }
+        let rocket = RubikApi::builder().cube_repository(repo).build().await;
+        (Client::tracked(rocket).await.unwrap(), ids)
+    }
+
+    fn bearer(client: &Client, role: Role) -> Header<'static> {
+        let user = User { id: Some(ObjectId::new()), username: "ana".to_string(), password_hash: String::new(), role };
+        let (token, _) = client.rocket().state::<AuthKeys>().unwrap().issue(&user);
+        Header::new("Authorization", format!("Bearer {}", token))
+    }
+
+    #[rocket::async_test]
+    async fn writes_need_a_user_with_the_role() {
+        let (client, ids) = client(&["3x3x3"]).await;
+        let delete = || client.delete(format!("{}/delete_cube?id={}", BASE, ids[0]));
+        assert_eq!(delete().dispatch().await.status(), Status::Unauthorized);
+        assert_eq!(delete().header(Header::new("Authorization", "Bearer forged")).dispatch().await.status(), Status::Unauthorized);
+        assert_eq!(delete().header(bearer(&client, Role::Viewer)).dispatch().await.status(), Status::Forbidden);
+        assert_eq!(delete().header(bearer(&client, Role::Editor)).dispatch().await.status(), Status::Forbidden);
+        assert_eq!(delete().header(bearer(&client, Role::Admin)).dispatch().await.status(), Status::Ok);
+
+        let insert = || client.post(format!("{}/add_cube", BASE)).json(&cube("2x2x2"));
+        assert_eq!(insert().dispatch().await.status(), Status::Unauthorized);
+        assert_eq!(insert().header(bearer(&client, Role::Viewer)).dispatch().await.status(), Status::Forbidden);
+        assert_eq!(insert().header(bearer(&client, Role::Editor)).dispatch().await.status(), Status::Ok);
+    }
+
+    #[rocket::async_test]
+    async fn cubes_are_tagged_with_their_version() {
+        let (client, ids) = client(&["3x3x3"]).await;
+        let get = || client.get(format!("{}/cube_by_id?id={}", BASE, ids[0]));
+        let response = get().dispatch().await;
+        assert_eq!(response.status(), Status::Ok);
+        let tag = response.headers().get_one("ETag").unwrap().to_string();
+        assert_eq!(tag, format!("\"{}-1\"", ids[0]));
+
+        let unchanged = get().header(Header::new("If-None-Match", tag.clone())).dispatch().await;
+        assert_eq!(unchanged.status(), Status::NotModified);
+        assert_eq!(unchanged.headers().get_one("ETag"), Some(tag.as_str()));
+        let weak = get().header(Header::new("If-None-Match", format!("\"other\", W/{}", tag))).dispatch().await;
+        assert_eq!(weak.status(), Status::NotModified);
+        let changed = get().header(Header::new("If-None-Match", format!("\"{}-0\"", ids[0]))).dispatch().await;
+        assert_eq!(changed.status(), Status::Ok);
+    }
+
+    #[rocket::async_test]
+    async fn deletes_check_the_tag_they_are_sent() {
+        let (client, ids) = client(&["3x3x3", "4x4x4"]).await;
+        let delete = |if_match: String| {
+            client
+                .delete(format!("{}/delete_cube?id={}", BASE, ids[0]))
+                .header(bearer(&client, Role::Admin))
+                .header(Header::new("If-Match", if_match))
+        };
+        assert_eq!(delete(format!("\"{}-0\"", ids[0])).dispatch().await.status(), Status::PreconditionFailed);
+        assert_eq!(delete(format!("\"{}-1\"", ids[1])).dispatch().await.status(), Status::PreconditionFailed);
+        assert_eq!(delete("\"not-a-version\"".to_string()).dispatch().await.status(), Status::BadRequest);
+        assert_eq!(delete(format!("\"{}-1\"", ids[0])).dispatch().await.status(), Status::Ok);
+        assert_eq!(delete(format!("\"{}-1\"", ids[0])).dispatch().await.status(), Status::NotFound);
+
+        let get = client.get(format!("{}/cube_by_id?id={}", BASE, ids[0])).dispatch().await;
+        assert_eq!(get.status(), Status::NotFound);
+    }
+
+    #[rocket::async_test]
+    async fn listings_are_paged() {
+        let (client, _) = client(&["2x2x2", "3x3x3", "4x4x4", "5x5x5", "6x6x6"]).await;
+        let page = |query: &str| client.get(format!("{}/cubes?{}", BASE, query)).dispatch();
+
+        let response = page("per_page=2&page=3").await;
+        assert_eq!(response.status(), Status::Ok);
+        let body: Value = response.into_json().await.unwrap();
+        assert_eq!(body["items"].as_array().unwrap().len(), 1);
+        assert_eq!(body["items"][0]["name"], "6x6x6");
+        assert_eq!((body["page"].as_u64(), body["per_page"].as_u64()), (Some(3), Some(2)));
+        assert_eq!((body["total"].as_u64(), body["pages"].as_u64()), (Some(5), Some(3)));
+
+        let body: Value = page("").await.into_json().await.unwrap();
+        assert_eq!((body["per_page"].as_u64(), body["items"].as_array().unwrap().len()), (Some(20), 5));
+        let body: Value = page("page=2").await.into_json().await.unwrap();
+        assert!(body["items"].as_array().unwrap().is_empty());
+
+        for query in ["per_page=0", "per_page=101", "per_page=two", "page=0", &format!("page={}&per_page=100", u64::MAX)] {
+            assert_eq!(page(query).await.status(), Status::BadRequest, "{}", query);
+        }
+        assert_eq!(page("per_page=100").await.status(), Status::Ok);
+    }
+}
@@ -0,0 +1,143 @@
+use crate::i18n::Lang;
+use crate::models::duplicate_model::DuplicateCandidate;
+use crate::repository::repo_error::RepoError;
+use crate::validation::FieldError;
+use rocket::{
+    http::{Header, Status},
+    serde::{json::Json, Serialize},
+    Request, Responder,
+};
+
+#[derive(Debug, Serialize)]
+pub struct ErrorBody {
+    pub status: u16,
+    pub message: String,
+    /// Problems of the payload by field, for invalid payloads.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<FieldError>,
+    /// Cubes of the catalog a refused new cube may duplicate.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub candidates: Vec<DuplicateCandidate>,
+}
+
+/// Explanation of an error more precise than the default message of its
+/// status, left in the request-local cache by the guard that failed.
+pub struct ErrorDetail(pub Option<String>);
+
+impl From<RepoError> for Status {
+    fn from(error: RepoError) -> Self {
+        match error {
+            RepoError::NotFound => Status::NotFound,
+            RepoError::InvalidId => Status::BadRequest,
+            RepoError::DuplicateName(_) => Status::Conflict,
+            RepoError::Refused(_) => Status::UnprocessableEntity,
+            RepoError::Changed => Status::PreconditionFailed,
+            RepoError::TooExpensive(_) => Status::BadRequest,
+            RepoError::Db(_) => Status::InternalServerError,
+        }
+    }
+}
+
+/// Error response in the language of the client. The body is boxed, so
+/// that handlers failing with it keep small results.
+#[derive(Responder)]
+pub struct LocalizedError {
+    body: Json<Box<ErrorBody>>,
+    language: Header<'static>,
+}
+
+impl LocalizedError {
+    /// Builds an error response with the given explanation, for handlers
+    /// that know better than the default message of the status.
+    /// 
+    /// ## Arguments
+    /// * `status` - status of the response.
+    /// * `lang` - languages accepted by the client.
+    /// * `message` - explanation of the error, already localized.
+    /// 
+    /// ## Returns
+    /// * The status and the message, on json format.
+    pub fn new(status: Status, lang: &Lang, message: String) -> (Status, LocalizedError) {
+        let error = LocalizedError {
+            body: Json(Box::new(ErrorBody { status: status.code, message, errors: Vec::new(), candidates: Vec::new() })),
+            language: Header::new("Content-Language", lang.language().to_string()),
+        };
+        (status, error)
+    }
+
+    /// Builds the 422 response of an invalid payload, listing its problems.
+    /// 
+    /// ## Arguments
+    /// * `lang` - languages accepted by the client.
+    /// * `errors` - problems of the payload, by field.
+    /// 
+    /// ## Returns
+    /// * The status, its localized message and the problems, on json format.
+    pub fn invalid(lang: &Lang, errors: Vec<FieldError>) -> (Status, LocalizedError) {
+        let (status, mut error) = LocalizedError::of(Status::UnprocessableEntity, lang);
+        error.body.errors = errors;
+        (status, error)
+    }
+
+    /// Builds the 409 response of a new cube that looks like cubes of the
+    /// catalog, listing them.
+    /// 
+    /// ## Arguments
+    /// * `lang` - languages accepted by the client.
+    /// * `name` - name of the new cube.
+    /// * `candidates` - cubes the new one may duplicate.
+    /// 
+    /// ## Returns
+    /// * The status, the localized warning and the candidates, on json
+    ///   format.
+    pub fn near_duplicate(lang: &Lang, name: &str, candidates: Vec<DuplicateCandidate>) -> (Status, LocalizedError) {
+        let message = near_duplicate_message(lang, name, &candidates);
+        let (status, mut error) = LocalizedError::new(Status::Conflict, lang, message);
+        error.body.candidates = candidates;
+        (status, error)
+    }
+
+    /// Builds an error response with the default message of its status.
+    /// 
+    /// ## Arguments
+    /// * `status` - status of the response.
+    /// * `lang` - languages accepted by the client.
+    /// 
+    /// ## Returns
+    /// * The status and its localized message, on json format.
+    pub fn of(status: Status, lang: &Lang) -> (Status, LocalizedError) {
+        let key = format!("error.{}", status.code);
+        let message = lang.message(&key);
+        if message == key {
+            return LocalizedError::new(status, lang, lang.message("error.default"));
+        }
+        LocalizedError::new(status, lang, message)
+    }
+}
+
+/// Explains in the language of the client which cubes a new one looks like.
+pub fn near_duplicate_message(lang: &Lang, name: &str, candidates: &[DuplicateCandidate]) -> String {
+    let names: Vec<String> = candidates.iter().map(|candidate| format!("`{}`", candidate.name)).collect();
+    lang.message("error.near_duplicate")
+        .replace("{name}", name)
+        .replace("{candidates}", &names.join(", "))
+}
+
+/// Catcher of every error response, which explains the error in the
+/// language asked for with `Accept-Language`. Errors with a detail of
+/// their own are explained by it.
+/// 
+/// ## Arguments
+/// * `status` - status of the response.
+/// * `request` - the request that failed.
+/// 
+/// ## Returns
+/// * The status and a localized message, on json format.
+#[catch(default)]
+pub fn localized_error(status: Status, request: &Request) -> (Status, LocalizedError) {
+    let lang = Lang::from_header(request.headers().get_one("Accept-Language"));
+    match &request.local_cache(|| ErrorDetail(None)).0 {
+        Some(detail) => LocalizedError::new(status, &lang, detail.clone()),
+        None => LocalizedError::of(status, &lang),
+    }
+}
@@ -0,0 +1,472 @@
+use std::sync::Arc;
+
+use crate::{
+    api::error_api::near_duplicate_message,
+    auth::AuthUser,
+    duplicates,
+    events::{ApiEvent, EventBus},
+    i18n::Lang,
+    models::{
+        contribution_model::ContributionKind,
+        cube_filter_model::CubeFilter,
+        cube_model::Cube,
+        page_model::Page,
+        sort_model::Sort,
+        user_model::Role,
+    },
+    pagination::{self, PagePolicy},
+    repository::{contribution_repo::ContributionRepo, cube_repository::CubeRepository, repo_error::RepoError},
+    validation::{validate_cube, FieldError},
+};
+use async_graphql::{Context, EmptySubscription, Error, ErrorExtensions, Guard, Object, Schema, ID};
+use async_graphql_rocket::{GraphQLRequest, GraphQLResponse};
+use mongodb::bson::oid::ObjectId;
+use rocket::{
+    http::Status,
+    serde::{
+        json::{serde_json::Map, Value},
+        Serialize,
+    },
+    State,
+};
+use schemars::JsonSchema;
+
+/// Deepest nesting of selections, lists and input objects a document may
+/// have, so that a deeply nested query can exhaust neither the stack of
+/// the parser nor the database.
+pub const MAX_DEPTH: usize = 32;
+
+/// Schema of `/graphql`, built once by [`schema`] and managed as state.
+pub type CubeSchema = Schema<Query, Mutation, EmptySubscription>;
+
+/// Body of a GraphQL request, as documented in the manifest. The body is
+/// read by async-graphql, so this only describes it.
+#[allow(dead_code)]
+#[derive(Debug, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(rename = "GraphQLRequest")]
+pub struct GraphqlRequest {
+    pub query: String,
+    pub operation_name: Option<String>,
+    #[serde(default)]
+    pub variables: Option<Map<String, Value>>,
+}
+
+/// Error of a GraphQL request, with the path of the field that failed.
+/// Its `status` extension is the one the REST endpoints would answer, and
+/// the `errors` one lists the problems of an invalid cube, by field.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GraphqlError {
+    pub message: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub path: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extensions: Option<GraphqlErrorExtensions>,
+}
+
+/// Extensions of a [`GraphqlError`].
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GraphqlErrorExtensions {
+    pub status: u16,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<FieldError>,
+}
+
+/// Response of a GraphQL request, as documented in the manifest. The data
+/// is missing when the request could not be run.
+#[derive(Debug, Serialize, JsonSchema)]
+#[schemars(rename = "GraphQLResponse")]
+pub struct GraphqlResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<GraphqlError>,
+}
+
+/// Explains a failed status in the language of the client, with the
+/// status in the `status` extension of the error.
+fn failure(lang: &Lang, status: Status) -> Error {
+    let key = format!("error.{}", status.code);
+    let message = match lang.message(&key) {
+        message if message == key => lang.message("error.default"),
+        message => message,
+    };
+    failure_with(message, status)
+}
+
+/// Fails with the given message and status.
+fn failure_with(message: String, status: Status) -> Error {
+    Error::new(message).extend_with(|_, extensions| extensions.set("status", status.code))
+}
+
+/// Explains a failure of the repository, as the REST endpoints do.
+fn repo_failure(lang: &Lang, error: RepoError) -> Error {
+    match error {
+        RepoError::DuplicateName(name) => {
+            failure_with(lang.message("error.duplicate_name").replace("{name}", &name), Status::Conflict)
+        }
+        RepoError::TooExpensive(limit) => failure_with(limit.message(lang), Status::BadRequest),
+        error => failure(lang, error.into()),
+    }
+}
+
+/// Checks a cube sent in a mutation, as the REST endpoints do.
+fn valid_cube(lang: &Lang, cube: Cube) -> Result<Cube, Error> {
+    validate_cube(&cube).map_err(|errors| {
+        failure(lang, Status::UnprocessableEntity).extend_with(|_, extensions| {
+            extensions.set("errors", async_graphql::to_value(&errors).unwrap_or_default())
+        })
+    })?;
+    Ok(cube)
+}
+
+/// Guard of the fields needing at least a role, as the routes asking for
+/// an [`crate::Editor`] or an [`crate::Admin`].
+struct RoleGuard(Role);
+
+impl Guard for RoleGuard {
+    async fn check(&self, ctx: &Context<'_>) -> async_graphql::Result<()> {
+        let lang = ctx.data_unchecked::<Lang>();
+        match ctx.data_unchecked::<Option<AuthUser>>() {
+            None => Err(failure(lang, Status::Unauthorized)),
+            Some(user) if user.role < self.0 => Err(failure(lang, Status::Forbidden)),
+            Some(_) => Ok(()),
+        }
+    }
+}
+
+/// Counts a contribution of the caller.
+async fn contributed(ctx: &Context<'_>, kind: ContributionKind) {
+    if let Some(user) = ctx.data_unchecked::<Option<AuthUser>>() {
+        ctx.data_unchecked::<ContributionRepo>().record(&user.username, kind, 1).await;
+    }
+}
+
+/// Root of the queries, open to everyone.
+pub struct Query;
+
+#[Object]
+impl Query {
+    /// Cube with the given ID, or null if there is none.
+    async fn cube(&self, ctx: &Context<'_>, id: ID) -> async_graphql::Result<Option<Cube>> {
+        let db = ctx.data_unchecked::<Arc<dyn CubeRepository>>();
+        match db.get_cube(&id).await {
+            Ok(cube) => Ok(Some(cube)),
+            Err(RepoError::NotFound) => Ok(None),
+            Err(error) => Err(repo_failure(ctx.data_unchecked::<Lang>(), error)),
+        }
+    }
+
+    /// Page of the cubes matching a filter, sorted by a field of the cubes
+    /// in `asc` (the default) or `desc` order.
+    async fn cubes(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<CubeFilter>,
+        #[graphql(default = 1)] page: u64,
+        per_page: Option<u64>,
+        sort_by: Option<String>,
+        order: Option<String>,
+    ) -> async_graphql::Result<Page<Cube>> {
+        let lang = ctx.data_unchecked::<Lang>();
+        let db = ctx.data_unchecked::<Arc<dyn CubeRepository>>();
+        let per_page = ctx
+            .data_unchecked::<PagePolicy>()
+            .limit(per_page, lang)
+            .map_err(|message| failure_with(message, Status::BadRequest))?;
+        let sort = Sort::parse(sort_by.as_deref(), order.as_deref()).map_err(|_| failure(lang, Status::BadRequest))?;
+        let skip = pagination::skip(page, per_page).ok_or_else(|| failure(lang, Status::BadRequest))?;
+
+        let filter = filter.unwrap_or_default();
+        match db.get_cubes_page(&filter, sort, skip, per_page as i64).await {
+            Ok((cubes, total)) => Ok(Page::new(cubes, page, per_page, total)),
+            Err(error) => Err(repo_failure(lang, error)),
+        }
+    }
+}
+
+/// Root of the mutations, needing the same roles as the REST endpoints.
+pub struct Mutation;
+
+#[Object]
+impl Mutation {
+    /// Inserts a cube, refused if it looks like a cube of the catalog
+    /// unless forced.
+    #[graphql(guard = "RoleGuard(Role::Editor)")]
+    async fn create_cube(
+        &self,
+        ctx: &Context<'_>,
+        cube: Cube,
+        #[graphql(default)] force: bool,
+    ) -> async_graphql::Result<Cube> {
+        let lang = ctx.data_unchecked::<Lang>();
+        let db = ctx.data_unchecked::<Arc<dyn CubeRepository>>();
+        let cube = valid_cube(lang, cube)?;
+        if !force {
+            let candidates =
+                duplicates::near_duplicates(db.as_ref(), &cube).await.map_err(|error| repo_failure(lang, error))?;
+            if !candidates.is_empty() {
+                let message = near_duplicate_message(lang, &cube.name, &candidates);
+                return Err(failure_with(message, Status::Conflict));
+            }
+        }
+        let inserted_id = db.insert_cube(cube).await.map_err(|error| repo_failure(lang, error))?;
+        let id = inserted_id.as_object_id();
+        ctx.data_unchecked::<EventBus>().emit(ApiEvent::CubeCreated { id });
+        contributed(ctx, ContributionKind::CubesAdded).await;
+        let id = id.map(|id| id.to_hex()).unwrap_or_default();
+        db.get_cube(&id).await.map_err(|error| repo_failure(lang, error))
+    }
+
+    /// Replaces the data of a cube.
+    #[graphql(guard = "RoleGuard(Role::Editor)")]
+    async fn update_cube(&self, ctx: &Context<'_>, id: ID, cube: Cube) -> async_graphql::Result<Cube> {
+        let lang = ctx.data_unchecked::<Lang>();
+        let db = ctx.data_unchecked::<Arc<dyn CubeRepository>>();
+        let mut cube = valid_cube(lang, cube)?;
+        cube.id = ObjectId::parse_str(id.as_str()).ok();
+        db.edit_cube(&id, cube).await.map_err(|error| repo_failure(lang, error))?;
+        let cube = db.get_cube(&id).await.map_err(|error| repo_failure(lang, error))?;
+        ctx.data_unchecked::<EventBus>().emit(ApiEvent::CubeUpdated { id: cube.id });
+        contributed(ctx, ContributionKind::Edits).await;
+        Ok(cube)
+    }
+
+    /// Deletes a cube, only at the given version if any.
+    #[graphql(guard = "RoleGuard(Role::Admin)")]
+    async fn delete_cube(&self, ctx: &Context<'_>, id: ID, version: Option<u64>) -> async_graphql::Result<bool> {
+        let lang = ctx.data_unchecked::<Lang>();
+        let db = ctx.data_unchecked::<Arc<dyn CubeRepository>>();
+        match db.delete_cube(&id, version).await {
+            Ok(()) => {
+                if let Ok(id) = ObjectId::parse_str(id.as_str()) {
+                    ctx.data_unchecked::<EventBus>().emit(ApiEvent::CubeDeleted { id });
+                }
+                Ok(true)
+            }
+            Err(RepoError::NotFound) if version.is_some() && db.get_cube(&id).await.is_ok() => {
+                Err(failure(lang, Status::PreconditionFailed))
+            }
+            Err(error) => Err(repo_failure(lang, error)),
+        }
+    }
+}
+
+/// Builds the schema of `/graphql` on top of the state of the server.
+///
+/// ## Arguments
+/// * `db` - repository of the cubes.
+/// * `events` - listeners notified of the changes.
+/// * `contributions` - counts of the contributions of the users.
+/// * `policy` - page sizes allowed on the listings.
+///
+/// ## Returns
+/// * The schema, to be managed as state.
+pub fn schema(
+    db: Arc<dyn CubeRepository>,
+    events: EventBus,
+    contributions: ContributionRepo,
+    policy: PagePolicy,
+) -> CubeSchema {
+    Schema::build(Query, Mutation, EmptySubscription)
+        .data(db)
+        .data(events)
+        .data(contributions)
+        .data(policy)
+        .limit_depth(MAX_DEPTH)
+        .limit_recursive_depth(MAX_DEPTH)
+        .finish()
+}
+
+/// Status of a GraphQL response. Requests that resolve at least one root
+/// field get a 200 with the errors of the others, those that resolve none
+/// the status of their first error, and those that could not run at all,
+/// such as invalid documents, a 400.
+///
+/// ## Arguments
+/// * `response` - the response of the schema.
+///
+/// ## Returns
+/// * The status to answer with.
+fn status_of(response: &async_graphql::Response) -> Status {
+    let Some(error) = response.errors.first() else {
+        return Status::Ok;
+    };
+    let resolved = match &response.data {
+        async_graphql::Value::Object(fields) => fields.values().any(|value| *value != async_graphql::Value::Null),
+        _ => false,
+    };
+    if resolved {
+        return Status::Ok;
+    }
+    error
+        .extensions
+        .as_ref()
+        .and_then(|extensions| match extensions.get("status") {
+            Some(async_graphql::Value::Number(code)) => code.as_u64(),
+            _ => None,
+        })
+        .and_then(|code| u16::try_from(code).ok())
+        .and_then(Status::from_code)
+        .unwrap_or(Status::BadRequest)
+}
+
+/// POST endpoint which runs a GraphQL query or mutation over the cubes,
+/// so that clients get only the fields they ask for. The schema can be
+/// introspected, queries are open to everyone, and mutations need the
+/// same roles as the REST endpoints.
+///
+/// ## Arguments
+/// * `schema` - the schema of the cubes.
+/// * `user` - the caller, if authenticated.
+/// * `lang` - languages accepted by the client.
+/// * `request` - the GraphQL document, with its variables and the name of
+///   the operation to run.
+///
+/// ## Returns
+/// * The data asked for and the errors found, on json format, with the
+///   status given by [`status_of`].
+#[post("/graphql", data = "<request>")]
+pub async fn graphql_endpoint(
+    schema: &State<CubeSchema>,
+    user: Option<AuthUser>,
+    lang: Lang,
+    request: GraphQLRequest,
+) -> (Status, GraphQLResponse) {
+    let response = schema.execute(request.0.data(user).data(lang)).await;
+    (status_of(&response), response.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use async_graphql::{Request, ServerError, Value, Variables};
+    use mongodb::options::ClientOptions;
+    use rocket::serde::json::serde_json::{self, json};
+
+    use crate::repository::memory_repo::MemoryRepo;
+
+    use super::*;
+
+    fn cube(name: &str) -> serde_json::Value {
+        json!({
+            "name": name,
+            "type_": "cubic",
+            "pieces": 26,
+            "faces": 6,
+            "stickers": 54,
+            "year_created": 1974,
+            "wr": {"time_ms": 3130, "holder": "Max Park", "date": "2023-06-11"}
+        })
+    }
+
+    /// Builds the schema on the given cubes. The contributions are never
+    /// stored, as MongoDB is not reached.
+    async fn schema_of(cubes: &[&str]) -> (CubeSchema, Vec<String>) {
+        let repo = MemoryRepo::default();
+        let mut ids = Vec::new();
+        for name in cubes {
+            let cube = serde_json::from_value(cube(name)).unwrap();
+            ids.push(repo.insert_cube(cube).await.unwrap().as_object_id().unwrap().to_hex());
+        }
+        let options = ClientOptions::parse("mongodb://127.0.0.1:1/?serverSelectionTimeoutMS=200").await.unwrap();
+        let database = mongodb::Client::with_options(options).unwrap().database("test");
+        let schema = schema(Arc::new(repo), EventBus::new(Vec::new()), ContributionRepo::init(&database), PagePolicy::default());
+        (schema, ids)
+    }
+
+    async fn execute(
+        schema: &CubeSchema, role: Option<Role>, query: &str, variables: serde_json::Value
+    ) -> (Status, serde_json::Value) {
+        let user = role.map(|role| AuthUser { id: ObjectId::new(), username: "ana".to_string(), role });
+        let request = Request::new(query).variables(Variables::from_json(variables)).data(user).data(Lang::from_header(None));
+        let response = schema.execute(request).await;
+        (status_of(&response), serde_json::to_value(&response).unwrap())
+    }
+
+    fn response(data: Value, errors: Vec<ServerError>) -> async_graphql::Response {
+        let mut response = async_graphql::Response::new(data);
+        response.errors = errors;
+        response
+    }
+
+    fn error(status: Option<u16>) -> ServerError {
+        let error = match status {
+            Some(status) => failure_with("failed".to_string(), Status::new(status)),
+            None => Error::new("failed"),
+        };
+        error.into_server_error(async_graphql::Pos::default())
+    }
+
+    #[test]
+    fn statuses_come_from_the_errors_of_unresolved_requests() {
+        let resolved = Value::from_json(json!({"cube": {"name": "3x3x3"}, "cubes": null})).unwrap();
+        let unresolved = Value::from_json(json!({"cube": null})).unwrap();
+        assert_eq!(status_of(&response(resolved.clone(), Vec::new())), Status::Ok);
+        assert_eq!(status_of(&response(resolved, vec![error(Some(404))])), Status::Ok);
+        assert_eq!(status_of(&response(unresolved.clone(), vec![error(Some(403)), error(Some(404))])), Status::Forbidden);
+        assert_eq!(status_of(&response(unresolved, vec![error(None)])), Status::BadRequest);
+        assert_eq!(status_of(&response(Value::Null, vec![error(Some(999))])), Status::BadRequest);
+    }
+
+    #[rocket::async_test]
+    async fn queries_page_through_the_cubes() {
+        let (schema, ids) = schema_of(&["2x2x2", "3x3x3", "4x4x4"]).await;
+        let (status, body) = execute(
+            &schema,
+            None,
+            "query($id: ID!) { cube(id: $id) { name type_ wr { time_ms } } cubes(page: 2, perPage: 2) { items { name } total pages } }",
+            json!({"id": ids[1]}),
+        )
+        .await;
+        assert_eq!(status, Status::Ok);
+        assert_eq!(body["data"]["cube"], json!({"name": "3x3x3", "type_": "cubic", "wr": {"time_ms": 3130}}));
+        assert_eq!(body["data"]["cubes"], json!({"items": [{"name": "4x4x4"}], "total": 3, "pages": 2}));
+
+        let (status, body) = execute(&schema, None, "{ cube(id: \"0123456789abcdef01234567\") { name } }", json!({})).await;
+        assert_eq!(status, Status::Ok);
+        assert!(body["data"]["cube"].is_null());
+    }
+
+    #[rocket::async_test]
+    async fn invalid_queries_are_bad_requests() {
+        let (schema, _) = schema_of(&[]).await;
+        for query in ["{ cube(id: \"x\") { name } }", "{ cubes(perPage: 0) { total } }", "{ cubes(page: 0) { total } }", "{ nothing }", "{"] {
+            let (status, body) = execute(&schema, None, query, json!({})).await;
+            assert_eq!(status, Status::BadRequest, "{}: {}", query, body);
+        }
+
+        let nested = format!("{{ __schema {{ types {}name{} }} }}", "{ ofType ".repeat(MAX_DEPTH), " }".repeat(MAX_DEPTH));
+        let (status, body) = execute(&schema, None, &nested, json!({})).await;
+        assert_eq!(status, Status::BadRequest);
+        assert!(body["data"].is_null());
+    }
+
+    #[rocket::async_test]
+    async fn mutations_need_the_roles_of_the_routes() {
+        let (schema, ids) = schema_of(&["3x3x3"]).await;
+        let create = "mutation($cube: CubeInput!) { createCube(cube: $cube, force: true) { name version } }";
+        let variables = json!({"cube": cube("Megaminx")});
+        let (status, body) = execute(&schema, None, create, variables.clone()).await;
+        assert_eq!(status, Status::Unauthorized, "{}", body);
+        assert_eq!(execute(&schema, Some(Role::Viewer), create, variables.clone()).await.0, Status::Forbidden);
+        let (status, body) = execute(&schema, Some(Role::Editor), create, variables.clone()).await;
+        assert_eq!(status, Status::Ok);
+        assert_eq!(body["data"]["createCube"], json!({"name": "Megaminx", "version": 1}));
+        let (status, body) = execute(&schema, Some(Role::Editor), create, variables).await;
+        assert_eq!(status, Status::Conflict);
+        assert_eq!(body["errors"][0]["extensions"]["status"], 409);
+
+        let mut invalid = cube("Skewb");
+        invalid["pieces"] = json!(0);
+        let (status, body) = execute(&schema, Some(Role::Editor), create, json!({"cube": invalid})).await;
+        assert_eq!(status, Status::UnprocessableEntity);
+        assert!(body["errors"][0]["extensions"]["errors"].is_array());
+
+        let delete = "mutation($id: ID!, $version: Int) { deleteCube(id: $id, version: $version) }";
+        let stale = json!({"id": ids[0], "version": 0});
+        assert_eq!(execute(&schema, Some(Role::Editor), delete, stale.clone()).await.0, Status::Forbidden);
+        assert_eq!(execute(&schema, Some(Role::Admin), delete, stale).await.0, Status::PreconditionFailed);
+        let (status, body) = execute(&schema, Some(Role::Admin), delete, json!({"id": ids[0], "version": 1})).await;
+        assert_eq!((status, &body["data"]["deleteCube"]), (Status::Ok, &json!(true)));
+    }
+}
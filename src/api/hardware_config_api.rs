@@ -0,0 +1,95 @@
+use crate::{
+    api::{moderation_api::hold_for_review, parse_id},
+    auth::Editor,
+    models::{
+        flagged_content_model::ContentKind,
+        hardware_config_model::{ConfigSource, HardwareConfig},
+    },
+    repository::{
+        cube_repository::CubeRepository,
+        flagged_content_repo::FlaggedContentRepo,
+        hardware_config_repo::HardwareConfigRepo,
+    },
+    screening::ContentScreening,
+};
+use mongodb::results::InsertOneResult;
+use rocket::{http::Status, serde::json::Json, State};
+
+/// POST endpoint which allows to record a factory or community
+/// configuration of the adjustable hardware of a cube.
+/// 
+/// ## Arguments
+/// * `db` - instance of the mongo database.
+/// * `configs` - instance of the hardware configurations repository.
+/// * `flagged` - instance of the flagged submissions repository.
+/// * `screening` - checks run on the configuration before it is published.
+/// * `_editor` - the editor making the change.
+/// * `id` - id of the cube the configuration is for.
+/// * `new_config` - configuration to be recorded.
+/// 
+/// ## Returns
+/// * The id of the inserted configuration, or a 202 with the id of the
+///   held one.
+#[post("/cube/<id>/configs", data = "<new_config>")]
+pub async fn insert_hardware_config(
+    db: &State<Box<dyn CubeRepository>>,
+    configs: &State<HardwareConfigRepo>,
+    flagged: &State<FlaggedContentRepo>,
+    screening: &State<ContentScreening>,
+    _editor: Editor,
+    id: &str,
+    new_config: Json<HardwareConfig>,
+) -> Result<(Status, Json<InsertOneResult>), Status> {
+    let cube_id = parse_id(id)?;
+    let cube = match db.find_cube(&cube_id).await {
+        Ok(Some(cube)) => cube,
+        Ok(None) => return Err(Status::NotFound),
+        Err(error) => return Err(error.into()),
+    };
+    if new_config.validate(cube.adjustability.as_ref()).is_err() {
+        return Err(Status::UnprocessableEntity);
+    };
+
+    let data = HardwareConfig {
+        id: None,
+        cube_id: Some(cube_id),
+        source: new_config.source.clone(),
+        author: new_config.author.clone(),
+        spring_compression: new_config.spring_compression,
+        magnet_strength: new_config.magnet_strength,
+        dual_adjustment: new_config.dual_adjustment,
+        notes: new_config.notes.clone(),
+    };
+    let reasons = screening.screen(&data);
+    if !reasons.is_empty() {
+        return hold_for_review(flagged, ContentKind::HardwareConfig, &data, reasons).await;
+    };
+    let config_detail = configs.insert_config(data).await;
+    match config_detail {
+        Ok(config) => Ok((Status::Ok, Json(config))),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
+
+/// GET endpoint which allows to get the recorded configurations of the
+/// adjustable hardware of a cube.
+/// 
+/// ## Arguments
+/// * `configs` - instance of the hardware configurations repository.
+/// * `id` - id of the cube.
+/// * `source` - `factory` or `community` to only get those configurations.
+/// 
+/// ## Returns
+/// * A vector with the configurations of the cube, the factory ones first.
+#[get("/cube/<id>/configs?<source>")]
+pub async fn get_hardware_configs(
+    configs: &State<HardwareConfigRepo>,
+    id: &str,
+    source: Option<ConfigSource>,
+) -> Result<Json<Vec<HardwareConfig>>, Status> {
+    let cube_id = parse_id(id)?;
+    match configs.get_configs_by_cube(&cube_id, source).await {
+        Ok(configs) => Ok(Json(configs)),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
@@ -0,0 +1,80 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    models::health_model::{HealthCheck, HealthReport, HealthStatus},
+    repository::cube_repository::CubeRepository,
+    warmup::Readiness,
+};
+use rocket::{http::Status, response::status::Custom, serde::json::Json, State};
+
+/// Longest wait for MongoDB to answer a readiness probe, shorter than the
+/// timeouts of the probes themselves.
+const PING_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// GET endpoint which tells load balancers whether the server can take
+/// traffic, that is, whether the warmup after the launch is over.
+/// 
+/// ## Arguments
+/// * `readiness` - whether the warmup is over.
+/// 
+/// ## Returns
+/// * `ready` on json format, or a 503 while the server warms up.
+#[get("/ready")]
+pub fn get_ready(readiness: &State<Readiness>) -> Result<Json<&'static str>, Status> {
+    match readiness.is_ready() {
+        true => Ok(Json("ready")),
+        false => Err(Status::ServiceUnavailable),
+    }
+}
+
+/// GET endpoint for liveness probes, which answers as long as the server
+/// serves requests, whatever the state of the database.
+/// 
+/// ## Arguments
+/// * `readiness` - whether the warmup is over.
+/// 
+/// ## Returns
+/// * The state of the server on json format.
+#[get("/health/live")]
+pub fn get_liveness(readiness: &State<Readiness>) -> Json<HealthReport> {
+    Json(HealthReport { status: HealthStatus::Up, warmed_up: readiness.is_ready(), checks: Vec::new() })
+}
+
+/// GET endpoint for readiness probes, which pings MongoDB through the cube
+/// repository and tells whether the server can take traffic: once the
+/// warmup is over and while the database answers.
+/// 
+/// ## Arguments
+/// * `db` - instance of the cube repository.
+/// * `readiness` - whether the warmup is over.
+/// 
+/// ## Returns
+/// * The state of the server with the latency of the database on json
+///   format, with a 503 if it cannot take traffic.
+#[get("/health/ready")]
+pub async fn get_health_readiness(
+    db: &State<Box<dyn CubeRepository>>, readiness: &State<Readiness>
+) -> Custom<Json<HealthReport>> {
+    let started = Instant::now();
+    let pinged = match rocket::tokio::time::timeout(PING_TIMEOUT, db.ping()).await {
+        Ok(pinged) => pinged,
+        Err(_) => Err(format!("no answer within {} ms", PING_TIMEOUT.as_millis())),
+    };
+    let database = HealthCheck {
+        name: "mongodb",
+        status: if pinged.is_ok() { HealthStatus::Up } else { HealthStatus::Down },
+        latency_ms: started.elapsed().as_millis() as u64,
+        error: pinged.err(),
+    };
+
+    let warmed_up = readiness.is_ready();
+    let status = match warmed_up && database.status == HealthStatus::Up {
+        true => HealthStatus::Up,
+        false => HealthStatus::Down,
+    };
+    let code = match status {
+        HealthStatus::Up => Status::Ok,
+        HealthStatus::Down => Status::ServiceUnavailable,
+    };
+    Custom(code, Json(HealthReport { status, warmed_up, checks: vec![database] }))
+}
@@ -0,0 +1,97 @@
+use crate::{
+    api::{error_api::LocalizedError, parse_id},
+    auth::Editor,
+    i18n::Lang,
+    images::ImagePolicy,
+    models::cube_image_model::CubeImage,
+    repository::{cube_repository::CubeRepository, image_store::ImageStore},
+};
+use rocket::{
+    data::{Data, ToByteUnit},
+    http::{ContentType, Status},
+    serde::json::Json,
+    State,
+};
+
+/// POST endpoint which allows to upload the picture of a cube, sent as
+/// the raw body with its `Content-Type`. The picture replaces the previous
+/// one, and pictures of another type than the one sent, of a type not
+/// accepted or larger than allowed are refused.
+/// 
+/// ## Arguments
+/// * `db` - instance of the mongo database.
+/// * `images` - store of the pictures.
+/// * `policy` - types and size of the pictures accepted.
+/// * `_editor` - the editor making the change.
+/// * `lang` - languages accepted by the client.
+/// * `id` - ID of the cube.
+/// * `content_type` - media type of the picture.
+/// * `image` - content of the picture.
+/// 
+/// ## Returns
+/// * A message with the operation status, a 415 if the type of the
+///   picture is not accepted or a 413 if it is too large.
+#[post("/cube/<id>/image", data = "<image>")]
+#[allow(clippy::too_many_arguments)]
+pub async fn upload_cube_image(
+    db: &State<Box<dyn CubeRepository>>,
+    images: &State<Box<dyn ImageStore>>,
+    policy: &State<ImagePolicy>,
+    _editor: Editor,
+    lang: Lang,
+    id: &str,
+    content_type: Option<&ContentType>,
+    image: Data<'_>,
+) -> Result<Json<String>, (Status, LocalizedError)> {
+    let id = parse_id(id).map_err(|status| LocalizedError::of(status, &lang))?;
+    match db.cube_exists(&id).await {
+        Ok(true) => {}
+        Ok(false) => return Err(LocalizedError::of(Status::NotFound, &lang)),
+        Err(error) => return Err(LocalizedError::of(error.into(), &lang)),
+    }
+
+    let bytes = image
+        .open(policy.max_bytes.bytes())
+        .into_bytes()
+        .await
+        .map_err(|_| LocalizedError::of(Status::BadRequest, &lang))?;
+    if !bytes.is_complete() {
+        let message = lang.message("error.image.size").replace("{max}", &policy.max_bytes.to_string());
+        return Err(LocalizedError::new(Status::PayloadTooLarge, &lang, message));
+    }
+    let declared = content_type.map(|kind| format!("{}/{}", kind.top(), kind.sub()));
+    let Some(kind) = policy.check(declared.as_deref(), &bytes) else {
+        let message = lang.message("error.image.type").replace("{types}", &policy.content_types.join(", "));
+        return Err(LocalizedError::new(Status::UnsupportedMediaType, &lang, message));
+    };
+
+    let image = CubeImage { content_type: kind.to_string(), bytes: bytes.into_inner() };
+    match images.put_image(&id, image).await {
+        Ok(()) => Ok(Json(lang.message("image_uploaded"))),
+        Err(error) => Err(LocalizedError::of(error.into(), &lang)),
+    }
+}
+
+/// GET endpoint which allows to get the picture of a cube, with the media
+/// type it was uploaded as.
+/// 
+/// ## Arguments
+/// * `images` - store of the pictures.
+/// * `id` - ID of the cube.
+/// 
+/// ## Returns
+/// * The picture, or a 404 if the cube has none.
+#[get("/cube/<id>/image")]
+pub async fn get_cube_image(
+    images: &State<Box<dyn ImageStore>>, id: &str
+) -> Result<(ContentType, Vec<u8>), Status> {
+    let id = parse_id(id)?;
+    match images.get_image(&id).await {
+        Ok(Some(image)) => {
+            let kind = ContentType::parse_flexible(&image.content_type).unwrap_or(ContentType::Binary);
+            Ok((kind, image.bytes))
+        }
+        Ok(None) => Err(Status::NotFound),
+        Err(error) => Err(error.into()),
+    }
+}
@@ -0,0 +1,53 @@
+use crate::manifest::{self, RouteDescriptor, RouteManifest};
+use rocket::{response::content::RawHtml, serde::json::{Json, Value}, State};
+
+/// GET endpoint which returns a machine-readable description of every
+/// endpoint of the API, its parameters and its response types.
+/// 
+/// ## Arguments
+/// * `manifest` - descriptions of the routes of the server.
+/// 
+/// ## Returns
+/// * The list of route descriptors on json format.
+#[get("/routes.json")]
+pub fn get_routes_manifest(manifest: &State<RouteManifest>) -> Json<&[RouteDescriptor]> {
+    Json(&manifest.0)
+}
+
+/// GET endpoint which returns the OpenAPI document of the API, built from
+/// the route manifest.
+/// 
+/// ## Arguments
+/// * `manifest` - descriptions of the routes of the server.
+/// 
+/// ## Returns
+/// * The OpenAPI 3 document on json format.
+#[get("/openapi.json")]
+pub fn get_openapi(manifest: &State<RouteManifest>) -> Json<Value> {
+    Json(manifest::openapi(&manifest.0))
+}
+
+/// GET endpoint which serves Swagger UI over the OpenAPI document.
+/// 
+/// ## Returns
+/// * The Swagger UI page.
+#[get("/docs")]
+pub fn get_docs() -> RawHtml<&'static str> {
+    RawHtml(r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <meta charset="utf-8">
+  <title>Rubik API</title>
+  <link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@5/swagger-ui.css">
+</head>
+<body>
+  <div id="swagger-ui"></div>
+  <script src="https://unpkg.com/swagger-ui-dist@5/swagger-ui-bundle.js"></script>
+  <script>
+    window.onload = () => {
+      window.ui = SwaggerUIBundle({ url: "/api/v1/openapi.json", dom_id: "#swagger-ui" });
+    };
+  </script>
+</body>
+</html>"##)
+}
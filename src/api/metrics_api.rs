@@ -0,0 +1,17 @@
+use crate::metrics::Metrics;
+use rocket::{http::ContentType, State};
+
+/// GET endpoint which exposes the metrics of the server for Prometheus:
+/// the requests served and their latency by route, and the duration of the
+/// MongoDB commands.
+/// 
+/// ## Arguments
+/// * `metrics` - metrics of the server.
+/// 
+/// ## Returns
+/// * The metrics on the Prometheus text format.
+#[get("/metrics")]
+pub fn get_metrics(metrics: &State<Metrics>) -> (ContentType, String) {
+    let format = ContentType::new("text", "plain").with_params(("version", "0.0.4"));
+    (format, metrics.render())
+}
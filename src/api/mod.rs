@@ -1 +1,32 @@
-pub mod cube_api;
\ No newline at end of file
+use mongodb::bson::oid::ObjectId;
+use rocket::http::Status;
+
+pub mod cube_api;
+pub mod admin_api;
+pub mod manifest_api;
+pub mod setup_api;
+pub mod hardware_config_api;
+pub mod mod_api;
+pub mod regulation_api;
+pub mod scramble_api;
+pub mod reconstruction_api;
+pub mod stats_api;
+pub mod simulation_api;
+pub mod color_scheme_api;
+pub mod training_api;
+pub mod notation_api;
+pub mod auth_api;
+pub mod api_key_api;
+pub mod moderation_api;
+pub mod graphql_api;
+pub mod sandbox_api;
+pub mod image_api;
+pub mod health_api;
+pub mod metrics_api;
+pub mod contribution_api;
+pub mod error_api;
+
+/// Parses an ID coming from the path of the request.
+fn parse_id(id: &str) -> Result<ObjectId, Status> {
+    ObjectId::parse_str(id).map_err(|_| Status::BadRequest)
+}
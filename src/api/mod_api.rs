@@ -0,0 +1,185 @@
+use crate::{
+    api::parse_id,
+    auth::{Admin, Editor},
+    models::{
+        contribution_model::ContributionKind,
+        mod_model::{Mod, ModKind, ModStatus},
+    },
+    repository::{contribution_repo::ContributionRepo, cube_repository::CubeRepository, mod_repo::ModRepo},
+    screening::ContentScreening,
+};
+use mongodb::results::InsertOneResult;
+use rocket::{http::Status, serde::json::Json, State};
+
+/// POST endpoint which allows to submit a new modding guide. Submissions
+/// stay pending until a moderator approves them, with the reasons the
+/// screening flagged them, if any.
+/// 
+/// ## Arguments
+/// * `db` - instance of the mongo database.
+/// * `mods` - instance of the mods repository.
+/// * `screening` - checks run on the mod for its moderators.
+/// * `_editor` - the editor making the change.
+/// * `new_mod` - new mod object to be submitted.
+/// 
+/// ## Returns
+/// * The id of the submitted mod.
+#[post("/mods", data = "<new_mod>")]
+pub async fn insert_mod(
+    db: &State<Box<dyn CubeRepository>>,
+    mods: &State<ModRepo>,
+    screening: &State<ContentScreening>,
+    _editor: Editor,
+    new_mod: Json<Mod>,
+) -> Result<Json<InsertOneResult>, Status> {
+    if new_mod.validate().is_err() {
+        return Err(Status::UnprocessableEntity);
+    };
+    for cube_id in &new_mod.cube_ids {
+        match db.cube_exists(cube_id).await {
+            Ok(true) => {}
+            Ok(false) => return Err(Status::UnprocessableEntity),
+            Err(error) => return Err(error.into()),
+        }
+    }
+
+    let data = Mod {
+        id: None,
+        title: new_mod.title.to_owned(),
+        kind: new_mod.kind.clone(),
+        author: new_mod.author.to_owned(),
+        steps: new_mod.steps.clone(),
+        difficulty: new_mod.difficulty,
+        materials: new_mod.materials.clone(),
+        cube_ids: new_mod.cube_ids.clone(),
+        status: ModStatus::Pending,
+        flags: screening.screen(&*new_mod),
+    };
+    let mod_detail = mods.insert_mod(data).await;
+    match mod_detail {
+        Ok(inserted) => Ok(Json(inserted)),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
+
+/// GET endpoint which allows to get all the approved mods.
+/// 
+/// ## Arguments
+/// * `mods` - instance of the mods repository.
+/// * `kind` - kind of the mods to get, if any.
+/// 
+/// ## Returns
+/// * A vector with the approved mods.
+#[get("/mods?<kind>")]
+pub async fn get_mods(mods: &State<ModRepo>, kind: Option<ModKind>) -> Result<Json<Vec<Mod>>, Status> {
+    match mods.get_mods(ModStatus::Approved, None, kind).await {
+        Ok(mods) => Ok(Json(mods)),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
+
+/// GET endpoint which allows to get an approved mod by its ID.
+/// 
+/// ## Arguments
+/// * `mods` - instance of the mods repository.
+/// * `id` - id of the mod.
+/// 
+/// ## Returns
+/// * The mod on json format.
+#[get("/mods/<id>")]
+pub async fn get_mod(mods: &State<ModRepo>, id: &str) -> Result<Json<Mod>, Status> {
+    let mod_id = parse_id(id)?;
+    match mods.get_mod(&mod_id).await {
+        Ok(Some(found)) if found.status == ModStatus::Approved => Ok(Json(found)),
+        Ok(_) => Err(Status::NotFound),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
+
+/// GET endpoint which allows to get the approved mods that apply to a cube.
+/// 
+/// ## Arguments
+/// * `mods` - instance of the mods repository.
+/// * `id` - id of the cube.
+/// * `kind` - kind of the mods to get, if any.
+/// 
+/// ## Returns
+/// * A vector with the approved mods for the cube.
+#[get("/cube/<id>/mods?<kind>")]
+pub async fn get_cube_mods(
+    mods: &State<ModRepo>,
+    id: &str,
+    kind: Option<ModKind>,
+) -> Result<Json<Vec<Mod>>, Status> {
+    let cube_id = parse_id(id)?;
+    match mods.get_mods(ModStatus::Approved, Some(&cube_id), kind).await {
+        Ok(mods) => Ok(Json(mods)),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
+
+/// GET endpoint which allows moderators to get the mods waiting for review.
+/// 
+/// ## Arguments
+/// * `mods` - instance of the mods repository.
+/// * `_admin` - the admin reviewing the mods.
+/// 
+/// ## Returns
+/// * A vector with the pending mods.
+#[get("/admin/mods/pending")]
+pub async fn get_pending_mods(mods: &State<ModRepo>, _admin: Admin) -> Result<Json<Vec<Mod>>, Status> {
+    match mods.get_mods(ModStatus::Pending, None, None).await {
+        Ok(mods) => Ok(Json(mods)),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
+
+/// POST endpoint which allows moderators to approve a mod.
+/// 
+/// ## Arguments
+/// * `mods` - instance of the mods repository.
+/// * `contributions` - counts of the contributions of the users.
+/// * `admin` - the admin making the change.
+/// * `id` - id of the mod.
+/// 
+/// ## Returns
+/// * The approved mod.
+#[post("/admin/mods/<id>/approve")]
+pub async fn approve_mod(
+    mods: &State<ModRepo>, contributions: &State<ContributionRepo>, admin: Admin, id: &str
+) -> Result<Json<Mod>, Status> {
+    moderate(mods, contributions, &admin, id, ModStatus::Approved).await
+}
+
+/// POST endpoint which allows moderators to reject a mod.
+/// 
+/// ## Arguments
+/// * `mods` - instance of the mods repository.
+/// * `contributions` - counts of the contributions of the users.
+/// * `admin` - the admin making the change.
+/// * `id` - id of the mod.
+/// 
+/// ## Returns
+/// * The rejected mod.
+#[post("/admin/mods/<id>/reject")]
+pub async fn reject_mod(
+    mods: &State<ModRepo>, contributions: &State<ContributionRepo>, admin: Admin, id: &str
+) -> Result<Json<Mod>, Status> {
+    moderate(mods, contributions, &admin, id, ModStatus::Rejected).await
+}
+
+/// Sets the moderation status of a mod, counting the review of the admin,
+/// and returns the updated mod.
+async fn moderate(
+    mods: &ModRepo, contributions: &ContributionRepo, admin: &Admin, id: &str, status: ModStatus
+) -> Result<Json<Mod>, Status> {
+    let mod_id = parse_id(id)?;
+    match mods.set_status(&mod_id, status).await {
+        Ok(Some(moderated)) => {
+            contributions.record(&admin.0.username, ContributionKind::Reviews, 1).await;
+            Ok(Json(moderated))
+        }
+        Ok(None) => Err(Status::NotFound),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
@@ -0,0 +1,141 @@
+use crate::{
+    api::parse_id,
+    auth::Admin,
+    models::{
+        contribution_model::ContributionKind,
+        flagged_content_model::{ContentKind, FlaggedContent},
+        hardware_config_model::HardwareConfig,
+        reconstruction_model::Reconstruction,
+        setup_model::Setup,
+    },
+    repository::{
+        contribution_repo::ContributionRepo,
+        flagged_content_repo::FlaggedContentRepo,
+        hardware_config_repo::HardwareConfigRepo,
+        reconstruction_repo::ReconstructionRepo,
+        setup_repo::SetupRepo,
+    },
+};
+use mongodb::{bson, results::InsertOneResult};
+use rocket::{http::Status, serde::{json::Json, Serialize}, State};
+
+/// Holds a submission flagged by the screening for review, instead of
+/// publishing it.
+///
+/// ## Arguments
+/// * `flagged` - instance of the flagged submissions repository.
+/// * `kind` - kind of the submission.
+/// * `content` - the submission, as it would have been published.
+/// * `reasons` - why the screening flagged it.
+///
+/// ## Returns
+/// * A 202 with the id of the held submission.
+pub async fn hold_for_review(
+    flagged: &FlaggedContentRepo,
+    kind: ContentKind,
+    content: &impl Serialize,
+    reasons: Vec<String>,
+) -> Result<(Status, Json<InsertOneResult>), Status> {
+    let content = bson::to_document(content).map_err(|_| Status::InternalServerError)?;
+    match flagged.insert_flagged(FlaggedContent { id: None, kind, content, reasons }).await {
+        Ok(held) => Ok((Status::Accepted, Json(held))),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
+
+/// GET endpoint which allows moderators to get the submissions flagged by
+/// the screening.
+///
+/// ## Arguments
+/// * `flagged` - instance of the flagged submissions repository.
+/// * `_admin` - the admin reviewing the submissions.
+///
+/// ## Returns
+/// * A vector with the flagged submissions, the oldest first.
+#[get("/admin/flagged")]
+pub async fn get_flagged_content(
+    flagged: &State<FlaggedContentRepo>, _admin: Admin
+) -> Result<Json<Vec<FlaggedContent>>, Status> {
+    match flagged.get_flagged().await {
+        Ok(items) => Ok(Json(items)),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
+
+/// POST endpoint which allows moderators to publish a flagged submission.
+///
+/// ## Arguments
+/// * `flagged` - instance of the flagged submissions repository.
+/// * `setups` - instance of the setups repository.
+/// * `configs` - instance of the hardware configurations repository.
+/// * `reconstructions` - instance of the reconstructions repository.
+/// * `contributions` - counts of the contributions of the users.
+/// * `admin` - the admin approving the submission.
+/// * `id` - id of the flagged submission.
+///
+/// ## Returns
+/// * The id of the published submission.
+#[post("/admin/flagged/<id>/approve")]
+pub async fn approve_flagged_content(
+    flagged: &State<FlaggedContentRepo>,
+    setups: &State<SetupRepo>,
+    configs: &State<HardwareConfigRepo>,
+    reconstructions: &State<ReconstructionRepo>,
+    contributions: &State<ContributionRepo>,
+    admin: Admin,
+    id: &str,
+) -> Result<Json<InsertOneResult>, Status> {
+    let flagged_id = parse_id(id)?;
+    let item = match flagged.take_flagged(&flagged_id).await {
+        Ok(Some(item)) => item,
+        Ok(None) => return Err(Status::NotFound),
+        Err(_) => return Err(Status::InternalServerError),
+    };
+
+    let published = match item.kind {
+        ContentKind::Setup => match bson::from_document::<Setup>(item.content) {
+            Ok(setup) => Ok(setups.insert_setup(setup).await),
+            Err(error) => Err(error),
+        },
+        ContentKind::HardwareConfig => match bson::from_document::<HardwareConfig>(item.content) {
+            Ok(config) => Ok(configs.insert_config(config).await),
+            Err(error) => Err(error),
+        },
+        ContentKind::Reconstruction => match bson::from_document::<Reconstruction>(item.content) {
+            Ok(reconstruction) => Ok(reconstructions.insert_reconstruction(reconstruction).await),
+            Err(error) => Err(error),
+        },
+    };
+    match published {
+        Ok(Ok(inserted)) => {
+            contributions.record(&admin.0.username, ContributionKind::Reviews, 1).await;
+            Ok(Json(inserted))
+        }
+        _ => Err(Status::InternalServerError),
+    }
+}
+
+/// POST endpoint which allows moderators to discard a flagged submission.
+///
+/// ## Arguments
+/// * `flagged` - instance of the flagged submissions repository.
+/// * `contributions` - counts of the contributions of the users.
+/// * `admin` - the admin rejecting the submission.
+/// * `id` - id of the flagged submission.
+///
+/// ## Returns
+/// * The discarded submission.
+#[post("/admin/flagged/<id>/reject")]
+pub async fn reject_flagged_content(
+    flagged: &State<FlaggedContentRepo>, contributions: &State<ContributionRepo>, admin: Admin, id: &str
+) -> Result<Json<FlaggedContent>, Status> {
+    let flagged_id = parse_id(id)?;
+    match flagged.take_flagged(&flagged_id).await {
+        Ok(Some(item)) => {
+            contributions.record(&admin.0.username, ContributionKind::Reviews, 1).await;
+            Ok(Json(item))
+        }
+        Ok(None) => Err(Status::NotFound),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
@@ -0,0 +1,31 @@
+use crate::{
+    models::notation_model::{ConversionRequest, ConversionResult},
+    notation::{self, ConversionError, NotationPuzzle},
+};
+use rocket::{http::Status, response::status::Custom, serde::json::Json};
+
+/// POST endpoint which allows to translate a move sequence between the WCA
+/// and SiGN notations of cubes, or between the WCA and compact notations
+/// of square-1 scrambles.
+/// 
+/// ## Arguments
+/// * `request` - the puzzle, the source and target notations and the moves.
+/// 
+/// ## Returns
+/// * The translated moves, or the construct that cannot be translated.
+#[post("/notation/convert", data = "<request>")]
+pub fn convert_notation(
+    request: Json<ConversionRequest>,
+) -> Result<Json<ConversionResult>, Custom<Json<ConversionError>>> {
+    let puzzle = request.puzzle.unwrap_or(NotationPuzzle::Cube);
+    match notation::convert(puzzle, request.from, request.to, &request.alg) {
+        Ok((alg, moves)) => Ok(Json(ConversionResult {
+            puzzle,
+            from: request.from,
+            to: request.to,
+            alg,
+            moves,
+        })),
+        Err(error) => Err(Custom(Status::UnprocessableEntity, Json(error))),
+    }
+}
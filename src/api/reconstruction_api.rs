@@ -0,0 +1,347 @@
+use crate::{
+    api::{moderation_api::hold_for_review, parse_id},
+    auth::Editor,
+    models::{
+        alg_export_model::AlgExport,
+        annotation_model::{self, Annotation, Mark, PhaseSplit, PhaseStats, VideoAttachment},
+        cube_record_model::CubeRecord,
+        flagged_content_model::ContentKind,
+        reconstruction_model::{self, Reconstruction, ReconstructionFilter},
+    },
+    repository::{
+        cube_repository::CubeRepository,
+        flagged_content_repo::FlaggedContentRepo,
+        reconstruction_repo::ReconstructionRepo,
+    },
+    screening::ContentScreening,
+    stats,
+};
+use mongodb::results::InsertOneResult;
+use rocket::{http::Status, serde::json::Json, State};
+
+/// POST endpoint which allows to add the reconstruction of a famous solve.
+/// 
+/// ## Arguments
+/// * `db` - instance of the mongo database.
+/// * `reconstructions` - instance of the reconstructions repository.
+/// * `flagged` - instance of the flagged submissions repository.
+/// * `screening` - checks run on the reconstruction before it is published.
+/// * `_editor` - the editor making the change.
+/// * `new_reconstruction` - reconstruction to be inserted.
+/// 
+/// ## Returns
+/// * The id of the inserted reconstruction, or a 202 with the id of the
+///   held one.
+#[post("/reconstructions", data = "<new_reconstruction>")]
+pub async fn insert_reconstruction(
+    db: &State<Box<dyn CubeRepository>>,
+    reconstructions: &State<ReconstructionRepo>,
+    flagged: &State<FlaggedContentRepo>,
+    screening: &State<ContentScreening>,
+    _editor: Editor,
+    new_reconstruction: Json<Reconstruction>,
+) -> Result<(Status, Json<InsertOneResult>), Status> {
+    if new_reconstruction.validate().is_err() {
+        return Err(Status::UnprocessableEntity);
+    };
+    if let Some(cube_id) = &new_reconstruction.cube_id {
+        match db.cube_exists(cube_id).await {
+            Ok(true) => {}
+            Ok(false) => return Err(Status::UnprocessableEntity),
+            Err(error) => return Err(error.into()),
+        }
+    }
+
+    let data = Reconstruction {
+        id: None,
+        video_url: new_reconstruction.video_url.to_owned(),
+        solver: new_reconstruction.solver.to_owned(),
+        competition: new_reconstruction.competition.clone(),
+        event: new_reconstruction.event.to_owned(),
+        scramble: new_reconstruction.scramble.to_owned(),
+        solution: new_reconstruction.solution.to_owned(),
+        time: new_reconstruction.time,
+        cube_id: new_reconstruction.cube_id,
+        annotations: new_reconstruction.annotations.clone(),
+    };
+    let reasons = screening.screen(&data);
+    if !reasons.is_empty() {
+        return hold_for_review(flagged, ContentKind::Reconstruction, &data, reasons).await;
+    };
+    let reconstruction_detail = reconstructions.insert_reconstruction(data).await;
+    match reconstruction_detail {
+        Ok(reconstruction) => Ok((Status::Ok, Json(reconstruction))),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
+
+/// GET endpoint which allows to search reconstructions by solver, event
+/// and cube used.
+/// 
+/// ## Arguments
+/// * `reconstructions` - instance of the reconstructions repository.
+/// * `filter` - part of the solver's name, event and cube id to match.
+/// 
+/// ## Returns
+/// * A vector with the reconstructions that matched.
+#[get("/reconstructions?<filter..>")]
+pub async fn search_reconstructions(
+    reconstructions: &State<ReconstructionRepo>,
+    filter: ReconstructionFilter,
+) -> Result<Json<Vec<Reconstruction>>, Status> {
+    let cube_id = filter.cube_id.as_deref().map(parse_id).transpose()?;
+    let result = reconstructions.search_reconstructions(
+        filter.solver.as_deref(),
+        filter.event.as_deref(),
+        cube_id.as_ref(),
+    ).await;
+    match result {
+        Ok(reconstructions) => Ok(Json(reconstructions)),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
+
+/// GET endpoint which allows to get a reconstruction by its ID.
+/// 
+/// ## Arguments
+/// * `reconstructions` - instance of the reconstructions repository.
+/// * `id` - id of the reconstruction.
+/// 
+/// ## Returns
+/// * The reconstruction on json format.
+#[get("/reconstructions/<id>")]
+pub async fn get_reconstruction(
+    reconstructions: &State<ReconstructionRepo>,
+    id: &str,
+) -> Result<Json<Reconstruction>, Status> {
+    let reconstruction_id = parse_id(id)?;
+    match reconstructions.get_reconstruction(&reconstruction_id).await {
+        Ok(Some(reconstruction)) => Ok(Json(reconstruction)),
+        Ok(None) => Err(Status::NotFound),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
+
+/// GET endpoint which allows to export a reconstruction in the formats of
+/// the cubing.js ecosystem: alg strings and a Twizzle link that plays the
+/// solve.
+/// 
+/// ## Arguments
+/// * `reconstructions` - instance of the reconstructions repository.
+/// * `id` - id of the reconstruction.
+/// 
+/// ## Returns
+/// * The scramble and solution as alg strings, and the Twizzle link.
+#[get("/reconstructions/<id>/alg")]
+pub async fn export_reconstruction_alg(
+    reconstructions: &State<ReconstructionRepo>,
+    id: &str,
+) -> Result<Json<AlgExport>, Status> {
+    let reconstruction_id = parse_id(id)?;
+    match reconstructions.get_reconstruction(&reconstruction_id).await {
+        Ok(Some(reconstruction)) => Ok(Json(AlgExport::from_reconstruction(&reconstruction))),
+        Ok(None) => Err(Status::NotFound),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
+
+/// GET endpoint which allows to get the reconstructions of the solves done
+/// with a cube.
+/// 
+/// ## Arguments
+/// * `reconstructions` - instance of the reconstructions repository.
+/// * `id` - id of the cube.
+/// 
+/// ## Returns
+/// * A vector with the reconstructions of solves done with the cube.
+#[get("/cube/<id>/reconstructions")]
+pub async fn get_cube_reconstructions(
+    reconstructions: &State<ReconstructionRepo>,
+    id: &str,
+) -> Result<Json<Vec<Reconstruction>>, Status> {
+    let cube_id = parse_id(id)?;
+    match reconstructions.search_reconstructions(None, None, Some(&cube_id)).await {
+        Ok(reconstructions) => Ok(Json(reconstructions)),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
+
+/// GET endpoint which allows to get the best known solve of every event
+/// done with a cube, among the reconstructions of its solves.
+/// 
+/// ## Arguments
+/// * `reconstructions` - instance of the reconstructions repository.
+/// * `id` - id of the cube.
+/// 
+/// ## Returns
+/// * A vector with the records of the cube, one per event.
+#[get("/cube/<id>/records")]
+pub async fn get_cube_records(
+    reconstructions: &State<ReconstructionRepo>,
+    id: &str,
+) -> Result<Json<Vec<CubeRecord>>, Status> {
+    let cube_id = parse_id(id)?;
+    match reconstructions.search_reconstructions(None, None, Some(&cube_id)).await {
+        Ok(reconstructions) => Ok(Json(CubeRecord::best_per_event(reconstructions))),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
+
+/// PUT endpoint which allows to attach a video to a reconstruction, in
+/// place of its current one.
+/// 
+/// ## Arguments
+/// * `reconstructions` - instance of the reconstructions repository.
+/// * `_editor` - the editor making the change.
+/// * `id` - id of the reconstruction.
+/// * `video` - URL of the video.
+/// 
+/// ## Returns
+/// * The updated reconstruction.
+#[put("/reconstructions/<id>/video", data = "<video>")]
+pub async fn attach_reconstruction_video(
+    reconstructions: &State<ReconstructionRepo>,
+    _editor: Editor,
+    id: &str,
+    video: Json<VideoAttachment>,
+) -> Result<Json<Reconstruction>, Status> {
+    let reconstruction_id = parse_id(id)?;
+    if !reconstruction_model::is_video_url(&video.video_url) {
+        return Err(Status::UnprocessableEntity);
+    };
+    match reconstructions.set_video(&reconstruction_id, &video.video_url).await {
+        Ok(Some(reconstruction)) => Ok(Json(reconstruction)),
+        Ok(None) => Err(Status::NotFound),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
+
+/// Replaces the annotations of a reconstruction with the ones made from
+/// its current ones, if they are still valid.
+async fn edit_annotations(
+    reconstructions: &ReconstructionRepo,
+    id: &str,
+    edit: impl FnOnce(&mut Vec<Annotation>),
+) -> Result<Json<Reconstruction>, Status> {
+    let reconstruction_id = parse_id(id)?;
+    let mut annotations = match reconstructions.get_reconstruction(&reconstruction_id).await {
+        Ok(Some(reconstruction)) => reconstruction.annotations,
+        Ok(None) => return Err(Status::NotFound),
+        Err(_) => return Err(Status::InternalServerError),
+    };
+    edit(&mut annotations);
+    if annotation_model::validate_annotations(&annotations).is_err() {
+        return Err(Status::UnprocessableEntity);
+    };
+    match reconstructions.set_annotations(&reconstruction_id, &annotations).await {
+        Ok(Some(reconstruction)) => Ok(Json(reconstruction)),
+        Ok(None) => Err(Status::NotFound),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
+
+/// POST endpoint which allows to mark a moment of a solve on the video of
+/// its reconstruction, such as the end of the inspection or the cross
+/// done. A mark already given is moved.
+/// 
+/// ## Arguments
+/// * `reconstructions` - instance of the reconstructions repository.
+/// * `_editor` - the editor making the change.
+/// * `id` - id of the reconstruction.
+/// * `annotation` - mark and its position in the video.
+/// 
+/// ## Returns
+/// * The updated reconstruction, or a 422 if the marks would be out of
+///   order.
+#[post("/reconstructions/<id>/annotations", data = "<annotation>")]
+pub async fn annotate_reconstruction(
+    reconstructions: &State<ReconstructionRepo>,
+    _editor: Editor,
+    id: &str,
+    annotation: Json<Annotation>,
+) -> Result<Json<Reconstruction>, Status> {
+    let annotation = annotation.into_inner();
+    edit_annotations(reconstructions, id, |annotations| {
+        annotations.retain(|known| known.mark != annotation.mark);
+        annotations.push(annotation);
+        annotations.sort_by_key(|annotation| annotation.mark);
+    })
+    .await
+}
+
+/// DELETE endpoint which allows to remove a mark from a reconstruction.
+/// 
+/// ## Arguments
+/// * `reconstructions` - instance of the reconstructions repository.
+/// * `_editor` - the editor making the change.
+/// * `id` - id of the reconstruction.
+/// * `mark` - mark to remove.
+/// 
+/// ## Returns
+/// * The updated reconstruction.
+#[delete("/reconstructions/<id>/annotations?<mark>")]
+pub async fn delete_reconstruction_annotation(
+    reconstructions: &State<ReconstructionRepo>,
+    _editor: Editor,
+    id: &str,
+    mark: Mark,
+) -> Result<Json<Reconstruction>, Status> {
+    edit_annotations(reconstructions, id, |annotations| {
+        annotations.retain(|known| known.mark != mark);
+    })
+    .await
+}
+
+/// GET endpoint which allows to get the time spent in every phase of a
+/// solve, between the marks of its reconstruction.
+/// 
+/// ## Arguments
+/// * `reconstructions` - instance of the reconstructions repository.
+/// * `id` - id of the reconstruction.
+/// 
+/// ## Returns
+/// * The splits of the solve, in the order of its marks.
+#[get("/reconstructions/<id>/splits")]
+pub async fn get_reconstruction_splits(
+    reconstructions: &State<ReconstructionRepo>,
+    id: &str,
+) -> Result<Json<Vec<PhaseSplit>>, Status> {
+    let reconstruction_id = parse_id(id)?;
+    match reconstructions.get_reconstruction(&reconstruction_id).await {
+        Ok(Some(reconstruction)) => Ok(Json(annotation_model::phase_splits(&reconstruction.annotations))),
+        Ok(None) => Err(Status::NotFound),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
+
+/// GET endpoint which allows to get the mean and best split of every phase
+/// over the annotated reconstructions matching a search.
+/// 
+/// ## Arguments
+/// * `reconstructions` - instance of the reconstructions repository.
+/// * `filter` - part of the solver's name, event and cube id to match.
+/// 
+/// ## Returns
+/// * The statistics of every phase, in the order of the marks.
+#[get("/reconstructions/splits?<filter..>")]
+pub async fn get_phase_stats(
+    reconstructions: &State<ReconstructionRepo>,
+    filter: ReconstructionFilter,
+) -> Result<Json<Vec<PhaseStats>>, Status> {
+    let cube_id = filter.cube_id.as_deref().map(parse_id).transpose()?;
+    let result = reconstructions.search_reconstructions(
+        filter.solver.as_deref(),
+        filter.event.as_deref(),
+        cube_id.as_ref(),
+    ).await;
+    match result {
+        Ok(reconstructions) => {
+            let splits: Vec<Vec<PhaseSplit>> = reconstructions
+                .iter()
+                .map(|reconstruction| annotation_model::phase_splits(&reconstruction.annotations))
+                .collect();
+            Ok(Json(stats::phase_stats(&splits)))
+        }
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
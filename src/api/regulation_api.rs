@@ -0,0 +1,123 @@
+use crate::{
+    api::parse_id,
+    auth::Admin,
+    models::{
+        legality_model::LegalityReport,
+        regulation_model::{EventConstraints, Regulation, RegulationSet},
+    },
+    repository::{cube_repository::CubeRepository, regulation_repo::RegulationRepo},
+};
+use rocket::{http::Status, serde::json::Json, State};
+
+/// GET endpoint which allows to get the WCA regulations subset in use,
+/// with the puzzle constraints of every event.
+/// 
+/// ## Arguments
+/// * `regulations` - instance of the regulations repository.
+/// 
+/// ## Returns
+/// * The regulation set on json format.
+#[get("/regulations")]
+pub async fn get_regulations(regulations: &State<RegulationRepo>) -> Result<Json<RegulationSet>, Status> {
+    match regulations.get_regulations().await {
+        Ok(set) => Ok(Json(set)),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
+
+/// GET endpoint which allows to get a single regulation by its ID.
+/// 
+/// ## Arguments
+/// * `regulations` - instance of the regulations repository.
+/// * `id` - id of the regulation, e.g. `puzzle.logo`.
+/// 
+/// ## Returns
+/// * The regulation on json format.
+#[get("/regulations/<id>")]
+pub async fn get_regulation(
+    regulations: &State<RegulationRepo>, id: &str
+) -> Result<Json<Regulation>, Status> {
+    match regulations.get_regulations().await {
+        Ok(set) => set.regulation(id).cloned().map(Json).ok_or(Status::NotFound),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
+
+/// GET endpoint which allows to get the puzzle constraints of a WCA event.
+/// 
+/// ## Arguments
+/// * `regulations` - instance of the regulations repository.
+/// * `event` - WCA id of the event, e.g. `333`.
+/// 
+/// ## Returns
+/// * The constraints of the event on json format.
+#[get("/regulations/events/<event>")]
+pub async fn get_event_regulations(
+    regulations: &State<RegulationRepo>, event: &str
+) -> Result<Json<EventConstraints>, Status> {
+    match regulations.get_regulations().await {
+        Ok(set) => set.event(event).cloned().map(Json).ok_or(Status::NotFound),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
+
+/// GET endpoint which allows to check whether a cube of the catalog is
+/// legal for a WCA event, according to the regulations in use.
+/// 
+/// ## Arguments
+/// * `db` - instance of the mongo database.
+/// * `regulations` - instance of the regulations repository.
+/// * `id` - id of the cube.
+/// * `event` - WCA id of the event, e.g. `333`.
+/// 
+/// ## Returns
+/// * The legality report, with every check and the regulations cited.
+#[get("/cube/<id>/legality?<event>")]
+pub async fn get_cube_legality(
+    db: &State<Box<dyn CubeRepository>>,
+    regulations: &State<RegulationRepo>,
+    id: &str,
+    event: &str,
+) -> Result<Json<LegalityReport>, Status> {
+    let cube_id = parse_id(id)?;
+    if event.is_empty() {
+        return Err(Status::BadRequest);
+    };
+
+    let cube = match db.find_cube(&cube_id).await {
+        Ok(Some(cube)) => cube,
+        Ok(None) => return Err(Status::NotFound),
+        Err(error) => return Err(error.into()),
+    };
+    let set = match regulations.get_regulations().await {
+        Ok(set) => set,
+        Err(_) => return Err(Status::InternalServerError),
+    };
+    match set.event(event) {
+        Some(constraints) => Ok(Json(LegalityReport::evaluate(&cube, constraints, &set))),
+        None => Err(Status::NotFound),
+    }
+}
+
+/// PUT endpoint which allows admins to replace the regulation set with an
+/// updated one.
+/// 
+/// ## Arguments
+/// * `regulations` - instance of the regulations repository.
+/// * `_admin` - the admin making the change.
+/// * `set` - the new regulation set.
+/// 
+/// ## Returns
+/// * The imported regulation set.
+#[put("/admin/regulations", data = "<set>")]
+pub async fn import_regulations(
+    regulations: &State<RegulationRepo>, _admin: Admin, set: Json<RegulationSet>
+) -> Result<Json<RegulationSet>, Status> {
+    if set.validate().is_err() {
+        return Err(Status::UnprocessableEntity);
+    };
+    match regulations.import_regulations(&set).await {
+        Ok(()) => Ok(set),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
@@ -0,0 +1,45 @@
+use crate::{
+    auth::Admin,
+    sandbox::{Sandbox, SandboxTarget},
+};
+use rocket::{http::Status, response::Redirect, serde::json::Json, State};
+
+/// GET endpoint which the requests with `X-Sandbox: true` are sent to on
+/// live servers, redirecting them to the same path of the sandbox. The
+/// redirect is temporary, so that clients keep the method and the body.
+/// 
+/// ## Arguments
+/// * `sandbox` - the sandbox of the server.
+/// * `target` - path and query the request was sent to.
+/// 
+/// ## Returns
+/// * A 307 to the sandbox, or a 404 without a sandbox URL.
+#[get("/sandbox/redirect")]
+pub fn redirect_to_sandbox(sandbox: &State<Sandbox>, target: SandboxTarget) -> Result<Redirect, Status> {
+    let url = sandbox.policy.url.as_deref().ok_or(Status::NotFound)?;
+    Ok(Redirect::temporary(format!("{}{}", url.trim_end_matches('/'), target.0)))
+}
+
+/// POST endpoint which allows to reset the sandbox at once, instead of
+/// waiting for its nightly reset.
+/// 
+/// ## Arguments
+/// * `sandbox` - the sandbox of the server.
+/// * `_admin` - the admin resetting the sandbox, anyone on the sandbox.
+/// 
+/// ## Returns
+/// * The number of seed cubes inserted, or a 404 if this server is not the
+///   sandbox.
+#[post("/sandbox/reset")]
+pub async fn reset_sandbox(sandbox: &State<Sandbox>, _admin: Admin) -> Result<Json<usize>, Status> {
+    if !sandbox.policy.enabled {
+        return Err(Status::NotFound);
+    };
+    match sandbox.reset().await {
+        Ok(seeded) => Ok(Json(seeded)),
+        Err(error) => {
+            error!("Could not reset the sandbox: {}", error);
+            Err(Status::InternalServerError)
+        }
+    }
+}
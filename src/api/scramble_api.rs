@@ -0,0 +1,167 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{
+    api::parse_id,
+    auth::Editor,
+    compute::ComputeSlot,
+    cube_state::parse_moves,
+    models::scramble_model::{
+        CommitmentCheck, CommitmentReport, ScrambleAnalysis, ScrambleRequest, ScrambleSet, SealRequest,
+        SealedScrambleView, SealedScrambles,
+    },
+    repository::sealed_scramble_repo::SealedScrambleRepo,
+    scramble::{self, random::Rng, RNG_ALGORITHM, SCRAMBLE_VERSION},
+};
+use rand::RngCore;
+use rocket::{http::Status, serde::json::Json, State};
+
+/// POST endpoint which allows to estimate how difficult a 3x3 scramble is:
+/// the optimal cross of every color, the F2L pairs already built and the
+/// best start for a Roux first block.
+/// 
+/// ## Arguments
+/// * `slot` - place of the analysis in the compute pool.
+/// * `request` - the scramble in WCA notation.
+/// 
+/// ## Returns
+/// * The difficulty heuristics of the scramble.
+#[post("/scramble/analyze", data = "<request>")]
+pub async fn analyze_scramble(
+    slot: ComputeSlot, request: Json<ScrambleRequest>
+) -> Result<Json<ScrambleAnalysis>, Status> {
+    match parse_moves(&request.scramble) {
+        Ok(moves) => Ok(Json(slot.run(move || ScrambleAnalysis::analyze(&moves)).await?)),
+        Err(_) => Err(Status::UnprocessableEntity),
+    }
+}
+
+/// Number of scrambles of a set when none is asked for, as in a round of
+/// five attempts.
+const DEFAULT_SCRAMBLES: usize = 5;
+/// Largest number of scrambles of a set.
+const MAX_SCRAMBLES: usize = 100;
+
+/// GET endpoint which allows to draw a set of scrambles for an event. The
+/// same seed always gives the same scrambles for a given scramble version,
+/// so that every participant of a distributed competition can get them.
+/// 
+/// ## Arguments
+/// * `event` - WCA id of the event: NxNxN events, `minx`, `pyram` or `skewb`.
+/// * `seed` - seed of the set, a random one if none is given.
+/// * `count` - number of scrambles, 5 by default and 100 at most.
+/// 
+/// ## Returns
+/// * The scrambles, with the seed and the generator needed to reproduce them.
+#[get("/scramble?<event>&<seed>&<count>")]
+pub fn get_scrambles(
+    event: &str,
+    seed: Option<&str>,
+    count: Option<usize>,
+) -> Result<Json<ScrambleSet>, Status> {
+    let count = count.unwrap_or(DEFAULT_SCRAMBLES);
+    if !(1..=MAX_SCRAMBLES).contains(&count) {
+        return Err(Status::UnprocessableEntity);
+    };
+    let seed = seed.map_or_else(|| Rng::from_time().next_u64().to_string(), str::to_string);
+
+    let scrambles = scramble::draw_set(event, &seed, count).ok_or(Status::UnprocessableEntity)?;
+    Ok(Json(ScrambleSet {
+        event: event.to_string(),
+        seed,
+        rng: RNG_ALGORITHM,
+        version: SCRAMBLE_VERSION,
+        scrambles,
+    }))
+}
+
+/// Current time, in seconds since the Unix epoch.
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0)
+}
+
+/// POST endpoint which allows to seal a set of scrambles for an online
+/// competition. The scrambles are drawn from a random seed and kept secret
+/// until `reveal_at`, and only their commitment is published before, so
+/// that anyone can check afterwards that they were not changed.
+/// 
+/// ## Arguments
+/// * `sealed` - instance of the sealed scramble sets repository.
+/// * `editor` - the organizer sealing the set.
+/// * `request` - event, number of scrambles and reveal time of the set.
+/// 
+/// ## Returns
+/// * The sealed set, with its commitment and without its scrambles.
+#[post("/scramble/sealed", data = "<request>")]
+pub async fn seal_scrambles(
+    sealed: &State<SealedScrambleRepo>, editor: Editor, request: Json<SealRequest>
+) -> Result<Json<SealedScrambleView>, Status> {
+    let count = request.count.unwrap_or(DEFAULT_SCRAMBLES);
+    if !(1..=MAX_SCRAMBLES).contains(&count) || request.reveal_at <= now() {
+        return Err(Status::UnprocessableEntity);
+    };
+    let mut seed = [0u8; 16];
+    rand::thread_rng().fill_bytes(&mut seed);
+    let seed: String = seed.iter().map(|byte| format!("{:02x}", byte)).collect();
+    let scrambles = scramble::draw_set(&request.event, &seed, count).ok_or(Status::UnprocessableEntity)?;
+
+    let mut set = SealedScrambles {
+        id: None,
+        event: request.event.clone(),
+        version: SCRAMBLE_VERSION,
+        count,
+        commitment: scramble::commitment(SCRAMBLE_VERSION, &request.event, &seed, &scrambles),
+        reveal_at: request.reveal_at,
+        sealed_by: editor.0.username,
+        seed,
+        scrambles,
+    };
+    match sealed.insert_sealed(&set).await {
+        Ok(inserted) => {
+            set.id = inserted.inserted_id.as_object_id();
+            Ok(Json(SealedScrambleView::of(set, now())))
+        }
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
+
+/// GET endpoint which allows to get a sealed scramble set: its commitment
+/// until the reveal time, and its seed and scrambles from then on.
+/// 
+/// ## Arguments
+/// * `sealed` - instance of the sealed scramble sets repository.
+/// * `id` - ID of the set.
+/// 
+/// ## Returns
+/// * The sealed set.
+#[get("/scramble/sealed/<id>")]
+pub async fn get_sealed_scrambles(sealed: &State<SealedScrambleRepo>, id: &str) -> Result<Json<SealedScrambleView>, Status> {
+    let set_id = parse_id(id)?;
+    match sealed.get_sealed(&set_id).await {
+        Ok(Some(set)) => Ok(Json(SealedScrambleView::of(set, now()))),
+        Ok(None) => Err(Status::NotFound),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
+
+/// POST endpoint which allows to check a revealed scramble set against the
+/// commitment published before, and that its seed gives its scrambles.
+/// 
+/// ## Arguments
+/// * `check` - the revealed set and its commitment.
+/// 
+/// ## Returns
+/// * Whether the set matches the commitment and its seed.
+#[post("/scramble/verify", data = "<check>")]
+pub fn verify_scrambles(check: Json<CommitmentCheck>) -> Json<CommitmentReport> {
+    let commitment = scramble::commitment(check.version, &check.event, &check.seed, &check.scrambles);
+    let reproducible = (check.version == SCRAMBLE_VERSION)
+        .then(|| scramble::draw_set(&check.event, &check.seed, check.scrambles.len()).as_ref() == Some(&check.scrambles));
+    Json(CommitmentReport {
+        matches: commitment.eq_ignore_ascii_case(check.commitment.trim()),
+        commitment,
+        reproducible,
+    })
+}
@@ -0,0 +1,139 @@
+use crate::{
+    api::{moderation_api::hold_for_review, parse_id},
+    auth::Editor,
+    models::{flagged_content_model::ContentKind, setup_model::Setup},
+    pagination::PageSize,
+    repository::{cube_repository::CubeRepository, flagged_content_repo::FlaggedContentRepo, setup_repo::SetupRepo},
+    screening::ContentScreening,
+};
+use mongodb::results::InsertOneResult;
+use rocket::{http::Status, serde::json::Json, State};
+
+/// POST endpoint which allows to submit a new setup for a cube. Setups
+/// flagged by the screening are held for review instead.
+/// 
+/// ## Arguments
+/// * `db` - instance of the mongo database.
+/// * `setups` - instance of the setups repository.
+/// * `flagged` - instance of the flagged submissions repository.
+/// * `screening` - checks run on the setup before it is published.
+/// * `_editor` - the editor making the change.
+/// * `id` - id of the cube the setup is for.
+/// * `new_setup` - new setup object to be inserted.
+/// 
+/// ## Returns
+/// * The id of the inserted setup, or a 202 with the id of the held one.
+#[post("/cube/<id>/setups", data = "<new_setup>")]
+pub async fn insert_setup(
+    db: &State<Box<dyn CubeRepository>>,
+    setups: &State<SetupRepo>,
+    flagged: &State<FlaggedContentRepo>,
+    screening: &State<ContentScreening>,
+    _editor: Editor,
+    id: &str,
+    new_setup: Json<Setup>,
+) -> Result<(Status, Json<InsertOneResult>), Status> {
+    let cube_id = parse_id(id)?;
+    match db.cube_exists(&cube_id).await {
+        Ok(true) => {}
+        Ok(false) => return Err(Status::NotFound),
+        Err(error) => return Err(error.into()),
+    }
+
+    let data = Setup {
+        id: None,
+        cube_id: Some(cube_id),
+        author: new_setup.author.to_owned(),
+        lubes: new_setup.lubes.clone(),
+        tension: new_setup.tension.to_owned(),
+        feel: new_setup.feel.to_owned(),
+        votes: 0,
+    };
+    let reasons = screening.screen(&data);
+    if !reasons.is_empty() {
+        return hold_for_review(flagged, ContentKind::Setup, &data, reasons).await;
+    };
+    let setup_detail = setups.insert_setup(data).await;
+    match setup_detail {
+        Ok(setup) => Ok((Status::Ok, Json(setup))),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
+
+/// GET endpoint which allows to get all the setups of a cube.
+/// 
+/// ## Arguments
+/// * `setups` - instance of the setups repository.
+/// * `id` - id of the cube.
+/// 
+/// ## Returns
+/// * A vector with the setups of the cube, the most voted first.
+#[get("/cube/<id>/setups")]
+pub async fn get_cube_setups(setups: &State<SetupRepo>, id: &str) -> Result<Json<Vec<Setup>>, Status> {
+    let cube_id = parse_id(id)?;
+    match setups.get_setups_by_cube(&cube_id, None).await {
+        Ok(setups) => Ok(Json(setups)),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
+
+/// GET endpoint which allows to get the most voted setups of a cube.
+/// 
+/// ## Arguments
+/// * `setups` - instance of the setups repository.
+/// * `id` - id of the cube.
+/// * `limit` - number of setups to return, from the `limit` query
+///   parameter.
+/// 
+/// ## Returns
+/// * A vector with the top setups of the cube.
+#[get("/cube/<id>/setups/top")]
+pub async fn get_top_cube_setups(
+    setups: &State<SetupRepo>,
+    id: &str,
+    limit: PageSize,
+) -> Result<Json<Vec<Setup>>, Status> {
+    let cube_id = parse_id(id)?;
+    match setups.get_setups_by_cube(&cube_id, Some(limit.0 as i64)).await {
+        Ok(setups) => Ok(Json(setups)),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
+
+/// POST endpoint which allows to upvote a setup.
+/// 
+/// ## Arguments
+/// * `setups` - instance of the setups repository.
+/// * `_editor` - the editor making the change.
+/// * `id` - id of the setup.
+/// 
+/// ## Returns
+/// * The setup with its updated votes.
+#[post("/setups/<id>/upvote")]
+pub async fn upvote_setup(setups: &State<SetupRepo>, _editor: Editor, id: &str) -> Result<Json<Setup>, Status> {
+    vote(setups, id, 1).await
+}
+
+/// POST endpoint which allows to downvote a setup.
+/// 
+/// ## Arguments
+/// * `setups` - instance of the setups repository.
+/// * `_editor` - the editor making the change.
+/// * `id` - id of the setup.
+/// 
+/// ## Returns
+/// * The setup with its updated votes.
+#[post("/setups/<id>/downvote")]
+pub async fn downvote_setup(setups: &State<SetupRepo>, _editor: Editor, id: &str) -> Result<Json<Setup>, Status> {
+    vote(setups, id, -1).await
+}
+
+/// Applies a vote to a setup and returns the updated setup.
+async fn vote(setups: &SetupRepo, id: &str, delta: i64) -> Result<Json<Setup>, Status> {
+    let setup_id = parse_id(id)?;
+    match setups.vote_setup(&setup_id, delta).await {
+        Ok(Some(setup)) => Ok(Json(setup)),
+        Ok(None) => Err(Status::NotFound),
+        Err(_) => Err(Status::InternalServerError),
+    }
+}
@@ -0,0 +1,37 @@
+use crate::{
+    api::color_scheme_api::resolve_colors,
+    compute::ComputeSlot,
+    cube_state::{parse_moves_for, MAX_SIZE, MIN_SIZE},
+    models::simulation_model::{Playback, PlaybackRequest},
+    repository::color_scheme_repo::ColorSchemeRepo,
+};
+use rocket::{http::Status, serde::json::Json, State};
+
+/// POST endpoint which allows to play a move sequence on a solved NxNxN
+/// cube, returning the state of the cube after every move so that front-ends can
+/// animate scrambles and solutions.
+/// 
+/// ## Arguments
+/// * `schemes` - instance of the color schemes repository.
+/// * `slot` - place of the playback in the compute pool.
+/// * `request` - the moves in WCA notation, the size of the cube, whether to
+///   render the states and the color scheme to apply.
+/// 
+/// ## Returns
+/// * Every intermediate state, optionally with a rendered net.
+#[post("/simulate/playback", data = "<request>")]
+pub async fn simulate_playback(
+    schemes: &State<ColorSchemeRepo>,
+    slot: ComputeSlot,
+    request: Json<PlaybackRequest>,
+) -> Result<Json<Playback>, Status> {
+    let size = request.size.unwrap_or(3);
+    if !(MIN_SIZE..=MAX_SIZE).contains(&size) {
+        return Err(Status::UnprocessableEntity);
+    }
+    let moves = parse_moves_for(&request.moves, size).map_err(|_| Status::UnprocessableEntity)?;
+    let colors = resolve_colors(schemes, request.scheme.as_deref(), request.colors.as_ref()).await?;
+    let render = request.render;
+    let playback = slot.run(move || Playback::play(size, &moves, render, colors.as_ref())).await?;
+    Ok(Json(playback))
+}
@@ -0,0 +1,19 @@
+use crate::models::stats_model::{AverageReport, AverageRequest};
+use rocket::{http::Status, serde::json::Json};
+
+/// POST endpoint which allows to compute the mean, the WCA-style trimmed
+/// averages and the best and worst of a list of times.
+/// 
+/// ## Arguments
+/// * `request` - the times, with their DNF and +2 markers, and the sizes of
+///   the rolling averages to compute.
+/// 
+/// ## Returns
+/// * The statistics of the times.
+#[post("/stats/average", data = "<request>")]
+pub fn compute_average(request: Json<AverageRequest>) -> Result<Json<AverageReport>, Status> {
+    if request.times.is_empty() {
+        return Err(Status::BadRequest);
+    };
+    Ok(Json(AverageReport::compute(&request)))
+}
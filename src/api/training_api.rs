@@ -0,0 +1,63 @@
+use crate::{
+    compute::ComputeSlot,
+    cube_state::training::{self, F2lSlot},
+    models::training_model::{TrainingCase, TrainingGoal},
+    scramble::random::Rng,
+};
+use rocket::{http::Status, serde::json::Json};
+
+/// Number of random scrambles tried to find a cross of the wanted length.
+const CROSS_ATTEMPTS: usize = 10_000;
+/// Length of the longest optimal cross.
+const MAX_CROSS_MOVES: u8 = 8;
+
+/// GET endpoint which allows to get a scramble to practice the cross on D,
+/// optionally with an exact optimal cross length.
+/// 
+/// ## Arguments
+/// * `slot` - place of the search in the compute pool.
+/// * `moves` - optimal number of moves the cross must take, up to 8.
+/// 
+/// ## Returns
+/// * The scramble and the case it leads to.
+#[get("/trainer/cross?<moves>")]
+pub async fn get_cross_case(slot: ComputeSlot, moves: Option<u8>) -> Result<Json<TrainingCase>, Status> {
+    if moves.is_some_and(|m| m > MAX_CROSS_MOVES) {
+        return Err(Status::UnprocessableEntity);
+    };
+    let search = move || {
+        let mut rng = Rng::from_time();
+        training::cross_scramble(&mut rng, moves, CROSS_ATTEMPTS)
+    };
+    match slot.run(search).await? {
+        Some(scramble) => Ok(Json(TrainingCase::new(TrainingGoal::Cross, &scramble))),
+        None => Err(Status::ServiceUnavailable),
+    }
+}
+
+/// GET endpoint which allows to get a scramble with the cross and three
+/// F2L slots solved, to practice a single pair.
+/// 
+/// ## Arguments
+/// * `slot` - slot of the pair, `fr` by default.
+/// 
+/// ## Returns
+/// * The scramble and the case it leads to.
+#[get("/trainer/f2l?<slot>")]
+pub fn get_f2l_case(slot: Option<F2lSlot>) -> Json<TrainingCase> {
+    let mut rng = Rng::from_time();
+    let scramble = training::f2l_scramble(&mut rng, slot.unwrap_or(F2lSlot::FR));
+    Json(TrainingCase::new(TrainingGoal::F2lPair, &scramble))
+}
+
+/// GET endpoint which allows to get a scramble with the first two layers
+/// solved, to practice the last layer.
+/// 
+/// ## Returns
+/// * The scramble and the case it leads to.
+#[get("/trainer/last_layer")]
+pub fn get_last_layer_case() -> Json<TrainingCase> {
+    let mut rng = Rng::from_time();
+    let scramble = training::last_layer_scramble(&mut rng);
+    Json(TrainingCase::new(TrainingGoal::LastLayer, &scramble))
+}
@@ -0,0 +1,437 @@
+use std::env;
+use std::future::Future;
+use std::pin::Pin;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use hmac::{Hmac, Mac};
+use mongodb::bson::oid::ObjectId;
+use rand::RngCore;
+use rocket::http::Status;
+use rocket::request::{FromRequest, Outcome};
+use rocket::serde::{json, Deserialize, Serialize};
+use rocket::Request;
+use sha2::{Digest, Sha256};
+
+use crate::models::api_key_model::{ApiKey, KeyScope};
+use crate::models::user_model::{Role, User};
+use crate::repository::api_key_repo::ApiKeyRepo;
+use crate::sandbox::Sandbox;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Header of every token, which are always signed with HMAC-SHA256.
+const TOKEN_HEADER: &str = r#"{"alg":"HS256","typ":"JWT"}"#;
+
+/// Rounds of PBKDF2 used to hash new passwords.
+const PASSWORD_ROUNDS: u32 = 100_000;
+
+/// Lifetime of the tokens, read from the `auth` key of the Rocket
+/// configuration. The signing key is read from `JWT_SECRET`.
+///
+/// ```toml
+/// [default.auth]
+/// token_ttl = 3600
+/// ```
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct AuthPolicy {
+    /// Seconds a token stays valid after being issued.
+    pub token_ttl: u64,
+}
+
+impl Default for AuthPolicy {
+    fn default() -> Self {
+        AuthPolicy { token_ttl: 3600 }
+    }
+}
+
+/// Claims carried by a token.
+#[derive(Debug, Serialize, Deserialize)]
+struct Claims {
+    /// ID of the user.
+    sub: String,
+    /// Name of the user.
+    name: String,
+    /// Role of the user when the token was issued. Tokens issued before
+    /// roles existed are viewers'.
+    #[serde(default)]
+    role: Role,
+    /// Issue and expiry times, in seconds since the epoch.
+    iat: u64,
+    exp: u64,
+}
+
+/// Issues and checks the tokens of the users.
+pub struct AuthKeys {
+    secret: Vec<u8>,
+    token_ttl: u64,
+}
+
+/// Gets the current time, in seconds since the epoch.
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0)
+}
+
+impl AuthKeys {
+    /// Builds the keys from `JWT_SECRET` and the `auth` configuration.
+    /// Without a secret, a random one is drawn, so tokens do not outlive
+    /// the process.
+    ///
+    /// ## Arguments
+    /// * `policy` - lifetime of the tokens.
+    ///
+    /// ## Returns
+    /// * The keys of the server.
+    pub fn new(policy: &AuthPolicy) -> Self {
+        let secret = match env::var("JWT_SECRET") {
+            Ok(secret) if !secret.is_empty() => secret.into_bytes(),
+            _ => {
+                warn!("JWT_SECRET not found in .env, tokens will not survive a restart");
+                let mut secret = vec![0; 32];
+                rand::thread_rng().fill_bytes(&mut secret);
+                secret
+            }
+        };
+        AuthKeys { secret, token_ttl: policy.token_ttl }
+    }
+
+    fn signature(&self, content: &str) -> HmacSha256 {
+        let mut mac = HmacSha256::new_from_slice(&self.secret).expect("HMAC accepts any key size");
+        mac.update(content.as_bytes());
+        mac
+    }
+
+    /// Issues a token for a user.
+    ///
+    /// ## Arguments
+    /// * `user` - the user that logged in.
+    ///
+    /// ## Returns
+    /// * The token and the seconds it is valid for.
+    pub fn issue(&self, user: &User) -> (String, u64) {
+        let issued = now();
+        let claims = Claims {
+            sub: user.id.map(|id| id.to_hex()).unwrap_or_default(),
+            name: user.username.clone(),
+            role: user.role,
+            iat: issued,
+            exp: issued + self.token_ttl,
+        };
+        let claims = json::to_string(&claims).expect("claims are serializable");
+        let content = format!(
+            "{}.{}",
+            URL_SAFE_NO_PAD.encode(TOKEN_HEADER),
+            URL_SAFE_NO_PAD.encode(claims)
+        );
+        let signature = URL_SAFE_NO_PAD.encode(self.signature(&content).finalize().into_bytes());
+        (format!("{}.{}", content, signature), self.token_ttl)
+    }
+
+    /// Checks the signature and the expiry of a token.
+    fn verify(&self, token: &str) -> Option<Claims> {
+        let (content, signature) = token.rsplit_once('.')?;
+        let (header, claims) = content.split_once('.')?;
+        let signature = URL_SAFE_NO_PAD.decode(signature).ok()?;
+        self.signature(content).verify_slice(&signature).ok()?;
+        if URL_SAFE_NO_PAD.decode(header).ok()? != TOKEN_HEADER.as_bytes() {
+            return None;
+        }
+        let claims: Claims = json::from_slice(&URL_SAFE_NO_PAD.decode(claims).ok()?).ok()?;
+        (claims.exp > now()).then_some(claims)
+    }
+}
+
+/// Hashes a password with PBKDF2-HMAC-SHA256 and a random salt.
+///
+/// ## Arguments
+/// * `password` - the password of the user.
+///
+/// ## Returns
+/// * The rounds, the salt and the hash, as stored with the user.
+pub fn hash_password(password: &str) -> String {
+    let mut salt = [0; 16];
+    rand::thread_rng().fill_bytes(&mut salt);
+    let mut hash = [0; 32];
+    pbkdf2::pbkdf2::<HmacSha256>(password.as_bytes(), &salt, PASSWORD_ROUNDS, &mut hash);
+    format!(
+        "pbkdf2-sha256${}${}${}",
+        PASSWORD_ROUNDS,
+        URL_SAFE_NO_PAD.encode(salt),
+        URL_SAFE_NO_PAD.encode(hash)
+    )
+}
+
+/// Checks a password against the hash stored with a user.
+///
+/// ## Arguments
+/// * `password` - the password sent by the client.
+/// * `stored` - the hash written by [`hash_password`].
+///
+/// ## Returns
+/// * `true` if the password is the right one.
+pub fn verify_password(password: &str, stored: &str) -> bool {
+    let mut parts = stored.split('$');
+    let (Some("pbkdf2-sha256"), Some(rounds), Some(salt), Some(hash), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return false;
+    };
+    let (Ok(rounds), Ok(salt), Ok(hash)) =
+        (rounds.parse::<u32>(), URL_SAFE_NO_PAD.decode(salt), URL_SAFE_NO_PAD.decode(hash))
+    else {
+        return false;
+    };
+    let mut computed = vec![0; hash.len()];
+    pbkdf2::pbkdf2::<HmacSha256>(password.as_bytes(), &salt, rounds, &mut computed);
+    // Compares every byte, so the time taken does not tell how much matched.
+    !hash.is_empty() && computed.iter().zip(&hash).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+/// Draws a new API key.
+///
+/// ## Returns
+/// * The key, as given to the client.
+pub fn generate_api_key() -> String {
+    let mut key = [0; 32];
+    rand::thread_rng().fill_bytes(&mut key);
+    format!("rk_{}", URL_SAFE_NO_PAD.encode(key))
+}
+
+/// Hashes an API key to look it up. Keys are random, so a plain SHA-256
+/// is enough for them, unlike passwords.
+///
+/// ## Arguments
+/// * `key` - the key sent by the client.
+///
+/// ## Returns
+/// * The hash of the key, in hexadecimal.
+pub fn hash_api_key(key: &str) -> String {
+    Sha256::digest(key.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// User making a request, from a valid token in the `Authorization:
+/// Bearer` header, or machine client, from a key in the `X-Api-Key`
+/// header. Requests without either are refused with a 401, except on the
+/// sandbox, where they act as an admin. Routes ask for an [`Editor`] or an
+/// [`Admin`] instead to also check the role.
+pub struct AuthUser {
+    /// ID of the user or of the API key.
+    pub id: ObjectId,
+    /// Name of the user or of the client of the API key.
+    pub username: String,
+    /// Role of the user, or the one granted by the scope of the API key.
+    pub role: Role,
+}
+
+impl AuthUser {
+    /// Authenticates a request with its API key. Read-write keys act as
+    /// editors and read-only keys as viewers.
+    async fn from_api_key(request: &Request<'_>, key: &str) -> Outcome<Self, ()> {
+        let Some(keys) = request.rocket().state::<ApiKeyRepo>() else {
+            return Outcome::Error((Status::InternalServerError, ()));
+        };
+        match keys.find_active_key(&hash_api_key(key.trim())).await {
+            Ok(Some(ApiKey { id: Some(id), name, scope, .. })) => {
+                let role = match scope {
+                    KeyScope::ReadWrite => Role::Editor,
+                    KeyScope::ReadOnly => Role::Viewer,
+                };
+                Outcome::Success(AuthUser { id, username: name, role })
+            }
+            Ok(_) => Outcome::Error((Status::Unauthorized, ())),
+            Err(_) => Outcome::Error((Status::InternalServerError, ())),
+        }
+    }
+
+    /// Authenticates a request, as a user or as a machine client.
+    async fn authenticate(request: &Request<'_>) -> Outcome<Self, ()> {
+        let headers = request.headers();
+        match (headers.get_one("Authorization"), headers.get_one("X-Api-Key")) {
+            (Some(value), _) => {
+                let claims = value
+                    .strip_prefix("Bearer ")
+                    .zip(request.rocket().state::<AuthKeys>())
+                    .and_then(|(token, keys)| keys.verify(token.trim()));
+                match claims.and_then(|claims| Some((ObjectId::parse_str(&claims.sub).ok()?, claims))) {
+                    Some((id, claims)) => Outcome::Success(AuthUser { id, username: claims.name, role: claims.role }),
+                    None => Outcome::Error((Status::Unauthorized, ())),
+                }
+            }
+            (None, Some(key)) => AuthUser::from_api_key(request, key).await,
+            (None, None) => match request.rocket().state::<Sandbox>() {
+                Some(sandbox) if sandbox.policy.enabled => Outcome::Success(AuthUser {
+                    id: ObjectId::from_bytes([0; 12]),
+                    username: "sandbox".to_string(),
+                    role: Role::Admin,
+                }),
+                _ => Outcome::Error((Status::Unauthorized, ())),
+            },
+        }
+    }
+
+    /// Authenticates a request and checks that the caller has at least the
+    /// given role, refusing it with a 403 otherwise.
+    async fn authorize(request: &Request<'_>, role: Role) -> Outcome<Self, ()> {
+        match AuthUser::authenticate(request).await {
+            Outcome::Success(user) if user.role < role => Outcome::Error((Status::Forbidden, ())),
+            outcome => outcome,
+        }
+    }
+}
+
+impl<'r> FromRequest<'r> for AuthUser {
+    type Error = ();
+
+    fn from_request<'life0, 'async_trait>(
+        request: &'r Request<'life0>
+    ) -> Pin<Box<dyn Future<Output = Outcome<Self, Self::Error>> + Send + 'async_trait>>
+    where
+        'r: 'async_trait,
+        'life0: 'async_trait,
+        Self: 'async_trait,
+    {
+        Box::pin(AuthUser::authenticate(request))
+    }
+}
+
+/// Caller allowed to create and update data: an editor or an admin.
+pub struct Editor(pub AuthUser);
+
+impl<'r> FromRequest<'r> for Editor {
+    type Error = ();
+
+    fn from_request<'life0, 'async_trait>(
+        request: &'r Request<'life0>
+    ) -> Pin<Box<dyn Future<Output = Outcome<Self, Self::Error>> + Send + 'async_trait>>
+    where
+        'r: 'async_trait,
+        'life0: 'async_trait,
+        Self: 'async_trait,
+    {
+        Box::pin(async move { AuthUser::authorize(request, Role::Editor).await.map(Editor) })
+    }
+}
+
+/// Caller allowed to delete data and to run the admin commands.
+pub struct Admin(pub AuthUser);
+
+impl<'r> FromRequest<'r> for Admin {
+    type Error = ();
+
+    fn from_request<'life0, 'async_trait>(
+        request: &'r Request<'life0>
+    ) -> Pin<Box<dyn Future<Output = Outcome<Self, Self::Error>> + Send + 'async_trait>>
+    where
+        'r: 'async_trait,
+        'life0: 'async_trait,
+        Self: 'async_trait,
+    {
+        Box::pin(async move { AuthUser::authorize(request, Role::Admin).await.map(Admin) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(secret: &str, token_ttl: u64) -> AuthKeys {
+        AuthKeys { secret: secret.as_bytes().to_vec(), token_ttl }
+    }
+
+    fn user(role: Role) -> User {
+        User { id: Some(ObjectId::new()), username: "ana".to_string(), password_hash: String::new(), role }
+    }
+
+    #[test]
+    fn issued_tokens_carry_the_user() {
+        let keys = keys("secret", 60);
+        let user = user(Role::Editor);
+        let (token, ttl) = keys.issue(&user);
+        assert_eq!(ttl, 60);
+        let claims = keys.verify(&token).unwrap();
+        assert_eq!(claims.sub, user.id.unwrap().to_hex());
+        assert_eq!(claims.name, "ana");
+        assert_eq!(claims.role, Role::Editor);
+        assert_eq!(claims.exp, claims.iat + 60);
+    }
+
+    #[test]
+    fn tokens_signed_otherwise_are_refused() {
+        let (token, _) = keys("secret", 60).issue(&user(Role::Viewer));
+        assert!(keys("other", 60).verify(&token).is_none());
+
+        // Claims rewritten to grant another role, keeping the signature.
+        let (header, rest) = token.split_once('.').unwrap();
+        let (claims, signature) = rest.split_once('.').unwrap();
+        let claims = String::from_utf8(URL_SAFE_NO_PAD.decode(claims).unwrap()).unwrap();
+        let forged = URL_SAFE_NO_PAD.encode(claims.replace("viewer", "admin"));
+        assert!(keys("secret", 60).verify(&format!("{}.{}.{}", header, forged, signature)).is_none());
+
+        // Another algorithm in the header, signed with the same secret.
+        let header = URL_SAFE_NO_PAD.encode(r#"{"alg":"none","typ":"JWT"}"#);
+        let content = format!("{}.{}", header, URL_SAFE_NO_PAD.encode(&claims));
+        let keys = keys("secret", 60);
+        let signature = URL_SAFE_NO_PAD.encode(keys.signature(&content).finalize().into_bytes());
+        assert!(keys.verify(&format!("{}.{}", content, signature)).is_none());
+
+        for token in ["", "a.b", "a.b.c", &token[..token.len() - 2]] {
+            assert!(keys.verify(token).is_none(), "{:?} verified", token);
+        }
+    }
+
+    #[test]
+    fn expired_tokens_are_refused() {
+        let keys = keys("secret", 0);
+        let (token, _) = keys.issue(&user(Role::Admin));
+        assert!(keys.verify(&token).is_none());
+    }
+
+    #[test]
+    fn passwords_verify_against_their_hash() {
+        let stored = hash_password("hunter2");
+        assert!(stored.starts_with("pbkdf2-sha256$100000$"));
+        assert!(verify_password("hunter2", &stored));
+        assert!(!verify_password("hunter3", &stored));
+        assert_ne!(hash_password("hunter2"), stored);
+    }
+
+    #[test]
+    fn malformed_hashes_never_verify() {
+        let mut hash = [0; 32];
+        pbkdf2::pbkdf2::<HmacSha256>(b"pw", b"salt", 2, &mut hash);
+        let stored = format!("pbkdf2-sha256$2${}${}", URL_SAFE_NO_PAD.encode("salt"), URL_SAFE_NO_PAD.encode(hash));
+        assert!(verify_password("pw", &stored));
+        for stored in [
+            String::new(),
+            "pw".to_string(),
+            stored.replace("pbkdf2-sha256", "pbkdf2-sha1"),
+            stored.replace("$2$", "$x$"),
+            format!("{}$extra", stored),
+            format!("pbkdf2-sha256$2${}$", URL_SAFE_NO_PAD.encode("salt")),
+            format!("pbkdf2-sha256$2${}$!!", URL_SAFE_NO_PAD.encode("salt")),
+        ] {
+            assert!(!verify_password("pw", &stored), "{:?} verified", stored);
+        }
+    }
+
+    #[test]
+    fn roles_grant_the_ones_below() {
+        assert!(Role::Viewer < Role::Editor && Role::Editor < Role::Admin);
+        assert_eq!(Role::default(), Role::Viewer);
+    }
+
+    #[test]
+    fn api_keys_hash_to_hex() {
+        let key = generate_api_key();
+        assert!(key.starts_with("rk_"));
+        assert_ne!(generate_api_key(), key);
+        assert_eq!(hash_api_key("abc"), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+    }
+}
@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::net::IpAddr;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use rocket::http::Status;
+use rocket::request::{FromRequest, Outcome};
+use rocket::serde::Deserialize;
+use rocket::tokio::{sync::Semaphore, task};
+use rocket::Request;
+
+use crate::proxy::TrustedProxies;
+
+/// Limits of the CPU-heavy operations (scramble analysis, playbacks,
+/// trainer searches, password hashing), read from the `compute` key of the
+/// Rocket configuration, so that they cannot starve the rest of the traffic.
+///
+/// ```toml
+/// [default.compute]
+/// workers = 4
+/// queue = 32
+/// per_client = 2
+/// ```
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct ComputePolicy {
+    /// Operations run at the same time, one per core when 0.
+    pub workers: usize,
+    /// Operations allowed to wait for a worker.
+    pub queue: usize,
+    /// Operations a single client can have running or waiting.
+    pub per_client: usize,
+}
+
+impl Default for ComputePolicy {
+    fn default() -> Self {
+        ComputePolicy { workers: 0, queue: 32, per_client: 2 }
+    }
+}
+
+/// Operations admitted in the pool, running or waiting for a worker.
+#[derive(Default)]
+struct Admissions {
+    total: usize,
+    by_client: HashMap<IpAddr, usize>,
+}
+
+struct PoolState {
+    workers: Semaphore,
+    capacity: usize,
+    per_client: usize,
+    admissions: Mutex<Admissions>,
+}
+
+/// Bounded pool running the CPU-heavy operations on blocking threads.
+pub struct ComputePool {
+    state: Arc<PoolState>,
+    proxies: TrustedProxies,
+}
+
+impl ComputePool {
+    /// Builds the pool with the limits of the configuration.
+    ///
+    /// ## Arguments
+    /// * `policy` - limits of the pool.
+    /// * `proxies` - proxies trusted to tell the address of the clients.
+    ///
+    /// ## Returns
+    /// * The compute pool of the server.
+    pub fn new(policy: &ComputePolicy, proxies: TrustedProxies) -> Self {
+        let workers = match policy.workers {
+            0 => thread::available_parallelism().map(|cores| cores.get()).unwrap_or(1),
+            workers => workers,
+        };
+        let state = PoolState {
+            workers: Semaphore::new(workers),
+            capacity: workers + policy.queue,
+            per_client: policy.per_client.max(1),
+            admissions: Mutex::new(Admissions::default()),
+        };
+        ComputePool { state: Arc::new(state), proxies }
+    }
+
+    /// Admits a new operation of a client, unless the pool or the share of
+    /// the client is full.
+    fn admit(&self, client: Option<IpAddr>) -> Option<ComputeSlot> {
+        let mut admissions = self.state.admissions.lock().unwrap();
+        if admissions.total >= self.state.capacity {
+            return None;
+        }
+        if let Some(client) = client {
+            let running = admissions.by_client.entry(client).or_default();
+            if *running >= self.state.per_client {
+                return None;
+            }
+            *running += 1;
+        }
+        admissions.total += 1;
+        Some(ComputeSlot { state: self.state.clone(), client })
+    }
+}
+
+/// Place of a request in the compute pool, taken before the handler runs.
+/// Requests that find the pool, or their client's share of it, full are
+/// refused with a 429.
+pub struct ComputeSlot {
+    state: Arc<PoolState>,
+    client: Option<IpAddr>,
+}
+
+impl ComputeSlot {
+    /// Runs an operation once a worker is free.
+    ///
+    /// ## Arguments
+    /// * `job` - the operation to run.
+    ///
+    /// ## Returns
+    /// * The result of the operation, or a 500 if it panicked.
+    pub async fn run<T, F>(self, job: F) -> Result<T, Status>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        let _worker = self.state.workers.acquire().await.map_err(|_| Status::ServiceUnavailable)?;
+        task::spawn_blocking(job).await.map_err(|_| Status::InternalServerError)
+    }
+}
+
+impl Drop for ComputeSlot {
+    fn drop(&mut self) {
+        let mut admissions = self.state.admissions.lock().unwrap();
+        admissions.total -= 1;
+        if let Some(client) = self.client {
+            if let Some(running) = admissions.by_client.get_mut(&client) {
+                *running -= 1;
+                if *running == 0 {
+                    admissions.by_client.remove(&client);
+                }
+            }
+        }
+    }
+}
+
+impl<'r> FromRequest<'r> for ComputeSlot {
+    type Error = ();
+
+    fn from_request<'life0, 'async_trait>(
+        request: &'r Request<'life0>
+    ) -> Pin<Box<dyn Future<Output = Outcome<Self, Self::Error>> + Send + 'async_trait>>
+    where
+        'r: 'async_trait,
+        'life0: 'async_trait,
+        Self: 'async_trait,
+    {
+        let outcome = match request.rocket().state::<ComputePool>() {
+            Some(pool) => match pool.admit(pool.proxies.client_ip(request)) {
+                Some(slot) => Outcome::Success(slot),
+                None => Outcome::Error((Status::TooManyRequests, ())),
+            },
+            None => Outcome::Error((Status::InternalServerError, ())),
+        };
+        Box::pin(async move { outcome })
+    }
+}
@@ -0,0 +1,326 @@
+use std::env;
+use std::net::IpAddr;
+
+use rocket::figment::Figment;
+use rocket::serde::{de::DeserializeOwned, Deserialize};
+
+use crate::auth::AuthPolicy;
+use crate::compute::ComputePolicy;
+use crate::cors::CorsPolicy;
+use crate::deprecation::DeprecatedRoute;
+use crate::images::ImagePolicy;
+use crate::models::user_model::Credentials;
+use crate::pagination::PagePolicy;
+use crate::proxy::TrustedProxy;
+use crate::repository::analytics_repo::DEFAULT_SAMPLE_RATE;
+use crate::repository::query_cost::QueryPolicy;
+use crate::retention::RetentionPolicy;
+use crate::sandbox::SandboxPolicy;
+use crate::screening::ScreeningPolicy;
+use crate::validation::validate_credentials;
+
+/// Connection to MongoDB, read from the `database` key of the Rocket
+/// configuration. Without `uri`, the `MONGOURI` environment variable, or
+/// the one of `.env`, is used.
+///
+/// ```toml
+/// [default.database]
+/// uri = "mongodb://localhost:27017"
+/// name = "rubikapi"
+/// sandbox_name = "rubikapi_sandbox"
+/// cubes_collection = "cubes"
+/// ```
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct DatabaseConfig {
+    pub uri: Option<String>,
+    /// Database of the API.
+    pub name: String,
+    /// Database of the sandbox, kept apart from the live data.
+    pub sandbox_name: String,
+    /// Collection of the cubes.
+    pub cubes_collection: String,
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        DatabaseConfig {
+            uri: None,
+            name: "rubikapi".to_string(),
+            sandbox_name: "rubikapi_sandbox".to_string(),
+            cubes_collection: "cubes".to_string(),
+        }
+    }
+}
+
+impl DatabaseConfig {
+    /// Gets the URI of MongoDB, from the configuration or the environment.
+    ///
+    /// ## Returns
+    /// * The URI, if it is given anywhere.
+    pub fn uri(&self) -> Option<String> {
+        self.uri
+            .clone()
+            .or_else(|| env::var("MONGOURI").ok())
+            .filter(|uri| !uri.trim().is_empty())
+    }
+
+    /// Gets the name of the database the server works on.
+    ///
+    /// ## Arguments
+    /// * `sandbox` - whether the server is the sandbox.
+    ///
+    /// ## Returns
+    /// * The name of the database.
+    pub fn database_name(&self, sandbox: bool) -> &str {
+        if sandbox {
+            &self.sandbox_name
+        } else {
+            &self.name
+        }
+    }
+}
+
+/// Parts of the API that can be turned off, read from the `features` key
+/// of the Rocket configuration. They are all on by default.
+///
+/// ```toml
+/// [default.features]
+/// graphql = true
+/// metrics = true
+/// analytics = true
+/// ```
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct Features {
+    /// Whether `/graphql` is served.
+    pub graphql: bool,
+    /// Whether the metrics are recorded and served at `/metrics`.
+    pub metrics: bool,
+    /// Whether the catalog lookups are sampled into the query analytics.
+    pub analytics: bool,
+}
+
+impl Default for Features {
+    fn default() -> Self {
+        Features { graphql: true, metrics: true, analytics: true }
+    }
+}
+
+impl Features {
+    /// Tells whether a built-in route is served with these features.
+    ///
+    /// ## Arguments
+    /// * `route` - name of the route in the manifest.
+    ///
+    /// ## Returns
+    /// * Whether the route is mounted.
+    pub fn serves(&self, route: &str) -> bool {
+        match route {
+            "graphql_endpoint" => self.graphql,
+            "get_metrics" => self.metrics,
+            _ => true,
+        }
+    }
+}
+
+/// Cache of the hot cube lookups, read from the `cache` key of the Rocket
+/// configuration. It is off by default, and needs the server built with
+/// the `cache` feature.
+///
+/// ```toml
+/// [default.cache]
+/// enabled = true
+/// ttl_secs = 60
+/// max_entries = 1000
+/// ```
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct CachePolicy {
+    pub enabled: bool,
+    /// Seconds a lookup is served from the cache. It bounds how long the
+    /// writes of other instances of the server take to be seen.
+    pub ttl_secs: u64,
+    /// Lookups kept at most, the least used ones being dropped first.
+    pub max_entries: usize,
+}
+
+impl Default for CachePolicy {
+    fn default() -> Self {
+        CachePolicy { enabled: false, ttl_secs: 60, max_entries: 1000 }
+    }
+}
+
+/// Configuration of the server, read and checked once before anything is
+/// started. Each section is given to the part of the server it configures.
+pub struct ServerConfig {
+    /// Port from the `PORT` environment variable, over the one of Rocket.
+    pub port: Option<u16>,
+    /// Address from the `ADDRESS` environment variable, over the one of
+    /// Rocket.
+    pub address: Option<IpAddr>,
+    pub database: DatabaseConfig,
+    pub features: Features,
+    /// Admin from the `ADMIN_USERNAME` and `ADMIN_PASSWORD` environment
+    /// variables, created or reset at launch.
+    pub admin: Option<Credentials>,
+    /// Share of the lookups recorded, from `ANALYTICS_SAMPLE_RATE`, none
+    /// when the `analytics` feature is off.
+    pub analytics_sample_rate: f64,
+    pub pagination: PagePolicy,
+    pub query_cost: QueryPolicy,
+    pub auth: AuthPolicy,
+    pub images: ImagePolicy,
+    pub compute: ComputePolicy,
+    pub screening: ScreeningPolicy,
+    pub sandbox: SandboxPolicy,
+    pub retention: RetentionPolicy,
+    pub cors: CorsPolicy,
+    /// Only read with the `cache` feature, refused when enabled otherwise.
+    #[cfg_attr(not(feature = "cache"), allow(dead_code))]
+    pub cache: CachePolicy,
+    pub deprecations: Vec<DeprecatedRoute>,
+    pub trusted_proxies: Vec<TrustedProxy>,
+}
+
+/// Reads a key of the configuration, noting why it cannot be read. A
+/// missing key is not a problem, its default is used.
+fn check<T: DeserializeOwned>(figment: &Figment, key: &str, errors: &mut Vec<String>) -> Option<T> {
+    if figment.find_value(key).is_err() {
+        return None;
+    }
+    match figment.extract_inner::<T>(key) {
+        Ok(value) => Some(value),
+        Err(error) => {
+            errors.push(format!("`{}`: {}", key, error));
+            None
+        }
+    }
+}
+
+/// Checks a MongoDB name: the database ones cannot have the characters
+/// MongoDB refuses in them, nor the collection ones a `$`.
+fn check_name(key: &str, name: &str, forbidden: &[char], errors: &mut Vec<String>) {
+    if name.is_empty() {
+        errors.push(format!("`{}` must not be empty", key));
+    } else if name.len() > 63 {
+        errors.push(format!("`{}` must be at most 63 characters long", key));
+    } else if let Some(c) = name.chars().find(|c| forbidden.contains(c)) {
+        errors.push(format!("`{}` must not contain `{}`", key, c));
+    }
+}
+
+/// Reads an environment variable, noting its value if it cannot be parsed.
+fn env_var<T: std::str::FromStr>(name: &str, expected: &str, errors: &mut Vec<String>) -> Option<T> {
+    let value = env::var(name).ok()?;
+    match value.trim().parse() {
+        Ok(value) => Some(value),
+        Err(_) => {
+            errors.push(format!("{} must be {}, not `{}`", name, expected, value));
+            None
+        }
+    }
+}
+
+impl ServerConfig {
+    /// Reads the configuration of the server and checks every key of it,
+    /// so that a mistake stops the launch instead of falling back to a
+    /// default. Missing keys get their defaults.
+    ///
+    /// ## Returns
+    /// * The configuration of the server, or every problem found in it.
+    pub fn load() -> Result<Self, Vec<String>> {
+        let figment = rocket::Config::figment();
+        let mut errors = Vec::new();
+        let pagination = check::<PagePolicy>(&figment, "pagination", &mut errors).unwrap_or_default();
+        let query_cost = check::<QueryPolicy>(&figment, "query_cost", &mut errors).unwrap_or_default();
+        let auth = check::<AuthPolicy>(&figment, "auth", &mut errors).unwrap_or_default();
+        let images = check::<ImagePolicy>(&figment, "images", &mut errors).unwrap_or_default();
+        let compute = check::<ComputePolicy>(&figment, "compute", &mut errors).unwrap_or_default();
+        let screening = check::<ScreeningPolicy>(&figment, "screening", &mut errors).unwrap_or_default();
+        let sandbox = check::<SandboxPolicy>(&figment, "sandbox", &mut errors).unwrap_or_default();
+        let retention = check::<RetentionPolicy>(&figment, "retention", &mut errors).unwrap_or_default();
+        let cors = check::<CorsPolicy>(&figment, "cors", &mut errors).unwrap_or_default();
+        if let Err(error) = cors.check() {
+            errors.push(error);
+        }
+        let cache = check::<CachePolicy>(&figment, "cache", &mut errors).unwrap_or_default();
+        if cache.enabled && !cfg!(feature = "cache") {
+            errors.push("`cache.enabled` needs the server built with the `cache` feature".to_string());
+        }
+        let deprecations = check::<Vec<DeprecatedRoute>>(&figment, "deprecations", &mut errors).unwrap_or_default();
+        let mut trusted_proxies = Vec::new();
+        for proxy in check::<Vec<String>>(&figment, "trusted_proxies", &mut errors).unwrap_or_default() {
+            match proxy.parse::<TrustedProxy>() {
+                Ok(proxy) => trusted_proxies.push(proxy),
+                Err(error) => errors.push(format!("`trusted_proxies`: {}", error)),
+            }
+        }
+
+        let database = check::<DatabaseConfig>(&figment, "database", &mut errors).unwrap_or_default();
+        match database.uri() {
+            None => errors.push("the MongoDB URI is missing, set `database.uri` or MONGOURI".to_string()),
+            Some(uri) if !uri.starts_with("mongodb://") && !uri.starts_with("mongodb+srv://") => {
+                errors.push("the MongoDB URI must start with mongodb:// or mongodb+srv://".to_string())
+            }
+            Some(_) => {}
+        }
+        let forbidden = ['/', '\\', '.', ' ', '"', '$'];
+        check_name("database.name", &database.name, &forbidden, &mut errors);
+        check_name("database.sandbox_name", &database.sandbox_name, &forbidden, &mut errors);
+        check_name("database.cubes_collection", &database.cubes_collection, &['$'], &mut errors);
+        if database.cubes_collection.starts_with("system.") {
+            errors.push("`database.cubes_collection` must not start with `system.`".to_string());
+        }
+        let features = check::<Features>(&figment, "features", &mut errors).unwrap_or_default();
+
+        let port = env_var::<u16>("PORT", "a port number", &mut errors);
+        let address = env_var::<IpAddr>("ADDRESS", "an IP address", &mut errors);
+        let rate = env_var::<f64>("ANALYTICS_SAMPLE_RATE", "a number between 0 and 1", &mut errors);
+        if rate.is_some_and(|rate| !(0.0..=1.0).contains(&rate)) {
+            errors.push("ANALYTICS_SAMPLE_RATE must be between 0 and 1".to_string());
+        }
+        let analytics_sample_rate = if features.analytics { rate.unwrap_or(DEFAULT_SAMPLE_RATE) } else { 0.0 };
+
+        let admin = match (env::var("ADMIN_USERNAME").ok(), env::var("ADMIN_PASSWORD").ok()) {
+            (Some(username), Some(password)) => {
+                let admin = Credentials { username: username.trim().to_lowercase(), password };
+                if let Err(invalid) = validate_credentials(&admin) {
+                    let invalid = invalid.iter().map(|error| error.message.as_str()).collect::<Vec<_>>();
+                    errors.push(format!("the bootstrap admin is invalid: {}", invalid.join(", ")));
+                }
+                Some(admin)
+            }
+            (None, None) => None,
+            _ => {
+                errors.push("ADMIN_USERNAME and ADMIN_PASSWORD must be set together".to_string());
+                None
+            }
+        };
+
+        if errors.is_empty() {
+            Ok(ServerConfig {
+                port,
+                address,
+                database,
+                features,
+                admin,
+                analytics_sample_rate,
+                pagination,
+                query_cost,
+                auth,
+                images,
+                compute,
+                screening,
+                sandbox,
+                retention,
+                cors,
+                cache,
+                deprecations,
+                trusted_proxies,
+            })
+        } else {
+            Err(errors)
+        }
+    }
+}
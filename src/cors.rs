@@ -1,9 +1,119 @@
 use std::future::Future;
 use std::pin::Pin;
 use rocket::fairing::{Fairing, Info, Kind};
-use rocket::{http::Method, http::Status, Request, Response};
+use rocket::serde::Deserialize;
+use rocket::{http::Header, http::Method, http::Status, Request, Response};
 
-pub struct CorsFairing;
+/// Cross-origin access to the API from browsers, read from the `cors` key
+/// of the Rocket configuration. Every origin is allowed by default, but
+/// credentials are only allowed for an explicit list of origins.
+///
+/// ```toml
+/// [default.cors]
+/// allowed_origins = ["https://app.example.com"]
+/// allowed_methods = ["GET", "POST", "PUT", "PATCH", "DELETE"]
+/// allowed_headers = ["Authorization", "Content-Type", "X-Api-Key"]
+/// exposed_headers = ["ETag", "X-Request-Id"]
+/// allow_credentials = false
+/// max_age = 86400
+/// ```
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct CorsPolicy {
+    /// Origins allowed to call the API, `*` for any of them.
+    pub allowed_origins: Vec<String>,
+    /// Methods allowed in cross-origin requests.
+    pub allowed_methods: Vec<String>,
+    /// Request headers allowed in cross-origin requests.
+    pub allowed_headers: Vec<String>,
+    /// Response headers the browsers let the frontends read.
+    pub exposed_headers: Vec<String>,
+    /// Whether the browsers send their cookies and credentials along.
+    pub allow_credentials: bool,
+    /// Seconds the browsers may keep the answer of a preflight request.
+    pub max_age: u64,
+}
+
+impl Default for CorsPolicy {
+    fn default() -> Self {
+        let list = |items: &[&str]| items.iter().map(|item| item.to_string()).collect();
+        CorsPolicy {
+            allowed_origins: list(&["*"]),
+            allowed_methods: list(&["GET", "POST", "PUT", "PATCH", "DELETE"]),
+            allowed_headers: list(&[
+                "Accept",
+                "Accept-Language",
+                "Authorization",
+                "Content-Type",
+                "If-Match",
+                "If-None-Match",
+                "X-Api-Envelope",
+                "X-Api-Key",
+                "X-Request-Id",
+                "X-Sandbox",
+            ]),
+            exposed_headers: list(&[
+                "Api-Version",
+                "Deprecation",
+                "ETag",
+                "Link",
+                "Sunset",
+                "X-Api-Envelope",
+                "X-Migration-Hint",
+                "X-Request-Id",
+                "X-Sandbox",
+            ]),
+            allow_credentials: false,
+            max_age: 86400,
+        }
+    }
+}
+
+impl CorsPolicy {
+    /// Checks that the policy is safe. Allowing credentials for any origin
+    /// would let every site call the API as the users signed in.
+    ///
+    /// ## Returns
+    /// * Why the policy is refused, if it is.
+    pub fn check(&self) -> Result<(), String> {
+        if self.allow_credentials && self.allowed_origins.iter().any(|allowed| allowed == "*") {
+            return Err("`cors.allow_credentials` needs an explicit list of `cors.allowed_origins`, not `*`".to_string());
+        }
+        Ok(())
+    }
+
+    /// Finds the value of `Access-Control-Allow-Origin` for a request. Any
+    /// origin is answered with `*`, which browsers never send credentials
+    /// to, see [`CorsPolicy::check`].
+    ///
+    /// ## Arguments
+    /// * `origin` - `Origin` header of the request, if any.
+    ///
+    /// ## Returns
+    /// * The origin to allow, and whether it depends on the request.
+    fn allowed_origin(&self, origin: Option<&str>) -> Option<(String, bool)> {
+        if self.allowed_origins.iter().any(|allowed| allowed == "*") {
+            return (!self.allow_credentials).then(|| ("*".to_string(), false));
+        }
+        let origin = origin?;
+        self.allowed_origins
+            .iter()
+            .any(|allowed| allowed.trim_end_matches('/').eq_ignore_ascii_case(origin))
+            .then(|| (origin.to_string(), true))
+    }
+}
+
+/// Adds the CORS headers of the [`CorsPolicy`] to every response, and
+/// answers the preflight requests of the browsers with a 204.
+pub struct CorsFairing {
+    policy: CorsPolicy,
+}
+
+impl CorsFairing {
+    pub fn new(policy: CorsPolicy) -> Self {
+        CorsFairing { policy }
+    }
+}
 
 impl Fairing for CorsFairing {
     fn info(&self) -> Info {
@@ -14,8 +124,8 @@ impl Fairing for CorsFairing {
     }
 
     fn on_response<'r, 'life0, 'life1, 'life2, 'async_trait>(
-        &'life0 self, 
-        request: &'r Request<'life1>, 
+        &'life0 self,
+        request: &'r Request<'life1>,
         response: &'life2 mut Response<'r>
     ) -> Pin<Box<dyn Future<Output = ()> + Send + 'async_trait>>
     where
@@ -25,16 +135,71 @@ impl Fairing for CorsFairing {
         'life1: 'async_trait,
         'life2: 'async_trait,
     {
-        // Add CORS headers to allow all origins to all outgoing requests
-        response.set_header(rocket::http::Header::new(
-            "Access-Control-Allow-Origin",
-            "*",
-        ));
+        let policy = &self.policy;
+        let origin = request.headers().get_one("Origin");
+        if let Some((allowed, varies)) = policy.allowed_origin(origin) {
+            response.set_header(Header::new("Access-Control-Allow-Origin", allowed));
+            if varies {
+                response.adjoin_header(Header::new("Vary", "Origin"));
+            }
+            if policy.allow_credentials {
+                response.set_header(Header::new("Access-Control-Allow-Credentials", "true"));
+            }
+            if !policy.exposed_headers.is_empty() {
+                response.set_header(Header::new("Access-Control-Expose-Headers", policy.exposed_headers.join(", ")));
+            }
+        }
 
-        // Respond to all `OPTIONS` requests with a `204` (no content) status
+        // Respond to all `OPTIONS` requests with a `204` (no content) status,
+        // telling browsers what their requests may use
         if response.status() == Status::NotFound && request.method() == Method::Options {
             response.set_status(Status::NoContent);
+            if request.headers().contains("Access-Control-Request-Method") {
+                response.set_header(Header::new("Access-Control-Allow-Methods", policy.allowed_methods.join(", ")));
+                response.set_header(Header::new("Access-Control-Allow-Headers", policy.allowed_headers.join(", ")));
+                response.set_header(Header::new("Access-Control-Max-Age", policy.max_age.to_string()));
+            }
         }
         Box::pin(async {})
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn listing(origins: &[&str], allow_credentials: bool) -> CorsPolicy {
+        CorsPolicy {
+            allowed_origins: origins.iter().map(|origin| origin.to_string()).collect(),
+            allow_credentials,
+            ..CorsPolicy::default()
+        }
+    }
+
+    #[test]
+    fn credentials_need_a_list_of_origins() {
+        assert!(CorsPolicy::default().check().is_ok());
+        assert!(listing(&["*"], true).check().is_err());
+        assert!(listing(&["https://app.example.com", "*"], true).check().is_err());
+        assert!(listing(&["https://app.example.com"], true).check().is_ok());
+    }
+
+    #[test]
+    fn any_origin_is_answered_with_a_wildcard() {
+        let policy = CorsPolicy::default();
+        assert_eq!(policy.allowed_origin(Some("https://a.example")), Some(("*".to_string(), false)));
+        assert_eq!(policy.allowed_origin(None), Some(("*".to_string(), false)));
+        assert_eq!(listing(&["*"], true).allowed_origin(Some("https://a.example")), None);
+    }
+
+    #[test]
+    fn listed_origins_are_echoed() {
+        let policy = listing(&["https://App.example.com/", "http://localhost:3000"], true);
+        let echoed = |origin: &str| Some((origin.to_string(), true));
+        assert_eq!(policy.allowed_origin(Some("https://app.example.com")), echoed("https://app.example.com"));
+        assert_eq!(policy.allowed_origin(Some("http://localhost:3000")), echoed("http://localhost:3000"));
+        assert_eq!(policy.allowed_origin(Some("http://localhost:3001")), None);
+        assert_eq!(policy.allowed_origin(Some("https://app.example.com.evil")), None);
+        assert_eq!(policy.allowed_origin(None), None);
+    }
+}
@@ -0,0 +1,172 @@
+use std::collections::VecDeque;
+use std::sync::OnceLock;
+
+use crate::cube_state::{
+    facelet_face, facelet_position, facelets, piece_position, CubeState, Face, Move,
+};
+
+/// Size of the cube the heuristics are computed for.
+const SIZE: usize = 3;
+/// Number of layers of the cube, as a coordinate.
+const N: i32 = SIZE as i32;
+
+/// Number of stickers on the edge pieces of the cube.
+const EDGE_FACELETS: usize = 24;
+/// Distance value of the cross states that have not been reached.
+const UNREACHED: u8 = u8::MAX;
+
+/// Gets the stickers that belong to the piece at the given position of the
+/// solved cube.
+fn piece_stickers(piece: [i32; 3]) -> Vec<usize> {
+    (0..facelets(SIZE))
+        .filter(|f| piece_position(facelet_position(*f, SIZE), SIZE) == piece)
+        .collect()
+}
+
+/// Gets the index of an edge sticker among all the edge stickers.
+fn edge_slot(facelet: usize) -> usize {
+    let slot = facelet % 9;
+    facelet / 9 * 4 + (slot - 1) / 2
+}
+
+/// Stickers that the edges of the cross of a face show on that face.
+fn cross_stickers(face: Face) -> [usize; 4] {
+    let base = Face::ALL.iter().position(|f| *f == face).unwrap() * 9;
+    [base + 1, base + 3, base + 5, base + 7]
+}
+
+fn encode_cross(positions: &[usize; 4]) -> usize {
+    positions
+        .iter()
+        .fold(0, |code, p| code * EDGE_FACELETS + edge_slot(*p))
+}
+
+/// Builds the table with the optimal number of moves needed to solve the
+/// cross of a face from every possible placement of its edges, by a
+/// breadth-first search from the solved cross.
+fn build_cross_table(face: Face) -> Vec<u8> {
+    let mut table = vec![UNREACHED; EDGE_FACELETS.pow(4)];
+    let solved = cross_stickers(face);
+    table[encode_cross(&solved)] = 0;
+    let permutations: Vec<Vec<usize>> = Move::all().map(|m| m.permutation(SIZE)).collect();
+    let mut queue = VecDeque::from([solved]);
+    while let Some(positions) = queue.pop_front() {
+        let distance = table[encode_cross(&positions)];
+        for permutation in &permutations {
+            let next = positions.map(|p| permutation[p]);
+            let code = encode_cross(&next);
+            if table[code] == UNREACHED {
+                table[code] = distance + 1;
+                queue.push_back(next);
+            }
+        }
+    }
+    table
+}
+
+/// Gets the cross tables of every face, building them the first time.
+pub fn cross_tables() -> &'static [Vec<u8>] {
+    static TABLES: OnceLock<Vec<Vec<u8>>> = OnceLock::new();
+    TABLES.get_or_init(|| Face::ALL.into_iter().map(build_cross_table).collect())
+}
+
+/// Gets the optimal number of moves needed to solve the cross of a face.
+///
+/// ## Arguments
+/// * `state` - the scrambled cube.
+/// * `face` - face of the cross.
+///
+/// ## Returns
+/// * The number of moves of an optimal cross solution.
+pub fn cross_moves(state: &CubeState, face: Face) -> u8 {
+    let positions = cross_stickers(face).map(|s| state.position_of(s));
+    let index = Face::ALL.iter().position(|f| *f == face).unwrap();
+    cross_tables()[index][encode_cross(&positions)]
+}
+
+/// Counts the F2L pairs of a cross face: the corners that are already
+/// joined with their edge, and the slots that are already solved.
+///
+/// ## Arguments
+/// * `state` - the scrambled cube.
+/// * `face` - face of the cross.
+///
+/// ## Returns
+/// * The number of paired corners and the number of solved slots.
+pub fn f2l_pairs(state: &CubeState, face: Face) -> (u8, u8) {
+    let (axis, sign) = face.axis();
+    let mut paired = 0;
+    let mut solved = 0;
+    for first in [-(N - 1), N - 1] {
+        for second in [-(N - 1), N - 1] {
+            let mut corner = [0; 3];
+            corner[axis] = sign * (N - 1);
+            corner[(axis + 1) % 3] = first;
+            corner[(axis + 2) % 3] = second;
+            let mut edge = corner;
+            edge[axis] = 0;
+
+            let corner_stickers = piece_stickers(corner);
+            let edge_stickers = piece_stickers(edge);
+            let joined = edge_stickers.iter().all(|e| {
+                let home_face = facelet_face(facelet_position(*e, SIZE), SIZE);
+                corner_stickers
+                    .iter()
+                    .find(|c| facelet_face(facelet_position(**c, SIZE), SIZE) == home_face)
+                    .is_some_and(|c| {
+                        facelet_face(facelet_position(state.position_of(*c), SIZE), SIZE)
+                            == facelet_face(facelet_position(state.position_of(*e), SIZE), SIZE)
+                    })
+            });
+            if joined {
+                paired += 1;
+            }
+            if corner_stickers.iter().chain(&edge_stickers).all(|s| state.position_of(*s) == *s) {
+                solved += 1;
+            }
+        }
+    }
+    (paired, solved)
+}
+
+/// Counts, for every possible Roux first block, how many of its five
+/// pieces are already solved.
+///
+/// ## Arguments
+/// * `state` - the scrambled cube.
+///
+/// ## Returns
+/// * The side face, bottom face and number of solved pieces of every block.
+pub fn roux_blocks(state: &CubeState) -> Vec<(Face, Face, u8)> {
+    let mut blocks = Vec::new();
+    for side in Face::ALL {
+        for bottom in Face::ALL {
+            let (side_axis, side_sign) = side.axis();
+            let (bottom_axis, bottom_sign) = bottom.axis();
+            if side_axis == bottom_axis {
+                continue;
+            }
+            let depth_axis = 3 - side_axis - bottom_axis;
+
+            let mut pieces = Vec::new();
+            for depth in [-(N - 1), 0, N - 1] {
+                let mut piece = [0; 3];
+                piece[side_axis] = side_sign * (N - 1);
+                piece[bottom_axis] = bottom_sign * (N - 1);
+                piece[depth_axis] = depth;
+                pieces.push(piece);
+                if depth != 0 {
+                    piece[bottom_axis] = 0;
+                    pieces.push(piece);
+                }
+            }
+
+            let solved = pieces
+                .into_iter()
+                .filter(|p| piece_stickers(*p).iter().all(|s| state.position_of(*s) == *s))
+                .count();
+            blocks.push((side, bottom, solved as u8));
+        }
+    }
+    blocks
+}
@@ -0,0 +1,480 @@
+use std::fmt;
+use std::str::FromStr;
+
+use rocket::serde::Serialize;
+use schemars::JsonSchema;
+
+pub mod analysis;
+pub mod training;
+
+/// Smallest cube size supported by the engine.
+pub const MIN_SIZE: usize = 2;
+/// Largest cube size supported by the engine.
+pub const MAX_SIZE: usize = 7;
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, Hash, JsonSchema)]
+pub enum Face {
+    U,
+    R,
+    F,
+    D,
+    L,
+    B,
+}
+
+impl Face {
+    pub const ALL: [Face; 6] = [Face::U, Face::R, Face::F, Face::D, Face::L, Face::B];
+
+    /// Gets the axis (0 = x, 1 = y, 2 = z) the face is perpendicular to and
+    /// the side of the axis it lies on.
+    pub fn axis(self) -> (usize, i32) {
+        match self {
+            Face::U => (1, 1),
+            Face::R => (0, 1),
+            Face::F => (2, 1),
+            Face::D => (1, -1),
+            Face::L => (0, -1),
+            Face::B => (2, -1),
+        }
+    }
+
+    fn index(self) -> usize {
+        Face::ALL.iter().position(|f| *f == self).unwrap()
+    }
+
+    fn from_axis(axis: usize, sign: i32) -> Face {
+        *Face::ALL.iter().find(|f| f.axis() == (axis, sign)).unwrap()
+    }
+
+    /// Gets the letter of the face's color, as shown on rendered nets.
+    pub fn color_letter(self) -> char {
+        self.color().chars().next().unwrap().to_ascii_uppercase()
+    }
+
+    /// Gets the color of the face's center on a cube held with white on
+    /// top and green in front.
+    pub fn color(self) -> &'static str {
+        match self {
+            Face::U => "white",
+            Face::R => "red",
+            Face::F => "green",
+            Face::D => "yellow",
+            Face::L => "orange",
+            Face::B => "blue",
+        }
+    }
+}
+
+impl fmt::Display for Face {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// A quarter, half or three-quarter clockwise turn of one or more layers,
+/// counted from a face.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Move {
+    pub face: Face,
+    /// Innermost layer turned, 1 being the outer face.
+    pub depth: u8,
+    /// Whether every layer from the face to `depth` is turned, or only the
+    /// layer at `depth`.
+    pub wide: bool,
+    /// Number of clockwise quarter turns, from 1 to 3.
+    pub turns: u8,
+}
+
+impl Move {
+    /// Builds a turn of an outer face.
+    pub fn face_turn(face: Face, turns: u8) -> Self {
+        Move { face, depth: 1, wide: false, turns }
+    }
+
+    /// Gets all the 18 face turns of the cube.
+    pub fn all() -> impl Iterator<Item = Move> {
+        Face::ALL
+            .into_iter()
+            .flat_map(|face| (1..=3).map(move |turns| Move::face_turn(face, turns)))
+    }
+
+    /// Whether the move turns only an outer face.
+    pub fn is_face_turn(self) -> bool {
+        self.depth == 1
+    }
+
+    /// Whether the move can be done on a cube of the given size without
+    /// turning the whole cube.
+    pub fn fits(self, size: usize) -> bool {
+        (self.depth as usize) < size
+    }
+
+    /// Whether the move turns the layer at the given depth, 1 being the
+    /// outer face.
+    fn turns_layer(self, depth: usize) -> bool {
+        let own = self.depth as usize;
+        depth == own || (self.wide && depth < own)
+    }
+
+    /// Gets the permutation of the move on a cube of the given size: the
+    /// position every sticker is moved to.
+    pub fn permutation(self, size: usize) -> Vec<usize> {
+        let n = size as i32;
+        let (axis, sign) = self.face.axis();
+        (0..facelets(size))
+            .map(|facelet| {
+                let mut position = facelet_position(facelet, size);
+                let depth = (n - 1 - piece_position(position, size)[axis] * sign) / 2 + 1;
+                if self.turns_layer(depth as usize) {
+                    for _ in 0..self.turns {
+                        position = rotate(position, axis, sign);
+                    }
+                }
+                facelet_index(position, size)
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotationError {
+    pub token: String,
+}
+
+impl fmt::Display for NotationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` is not a valid move", self.token)
+    }
+}
+
+impl FromStr for Move {
+    type Err = NotationError;
+
+    /// Parses a move in WCA notation: an outer turn (`R`), a wide turn of
+    /// the two outer layers (`Rw`) or of the `n` outer layers (`3Rw`), or a
+    /// turn of the `n`-th layer alone (`2R`).
+    fn from_str(token: &str) -> Result<Self, Self::Err> {
+        let error = || NotationError { token: token.to_string() };
+        let digits = token.chars().take_while(char::is_ascii_digit).count();
+        let layers: Option<u8> = match digits {
+            0 => None,
+            _ => Some(token[..digits].parse().map_err(|_| error())?),
+        };
+        let mut chars = token[digits..].chars();
+        let face = match chars.next() {
+            Some('U') => Face::U,
+            Some('R') => Face::R,
+            Some('F') => Face::F,
+            Some('D') => Face::D,
+            Some('L') => Face::L,
+            Some('B') => Face::B,
+            _ => return Err(error()),
+        };
+        let rest = chars.as_str();
+        let (wide, rest) = match rest.strip_prefix('w') {
+            Some(rest) => (true, rest),
+            None => (false, rest),
+        };
+        let turns = match rest {
+            "" => 1,
+            "2" | "2'" => 2,
+            "'" => 3,
+            _ => return Err(error()),
+        };
+        let depth = match (layers, wide) {
+            (Some(0), _) => return Err(error()),
+            (Some(layers), _) => layers,
+            (None, true) => 2,
+            (None, false) => 1,
+        };
+        Ok(Move { face, depth, wide: wide && depth > 1, turns })
+    }
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let suffix = match self.turns {
+            1 => "",
+            2 => "2",
+            _ => "'",
+        };
+        match (self.depth, self.wide) {
+            (1, _) => write!(f, "{}{}", self.face, suffix),
+            (2, true) => write!(f, "{}w{}", self.face, suffix),
+            (depth, true) => write!(f, "{}{}w{}", depth, self.face, suffix),
+            (depth, false) => write!(f, "{}{}{}", depth, self.face, suffix),
+        }
+    }
+}
+
+/// Parses a whitespace separated sequence of outer face turns.
+///
+/// ## Arguments
+/// * `sequence` - the moves in WCA notation, e.g. `R U R' U'`.
+///
+/// ## Returns
+/// * The parsed moves, or the first token that is not a valid face turn.
+pub fn parse_moves(sequence: &str) -> Result<Vec<Move>, NotationError> {
+    sequence
+        .split_whitespace()
+        .map(|token| match token.parse::<Move>() {
+            Ok(m) if m.is_face_turn() => Ok(m),
+            _ => Err(NotationError { token: token.to_string() }),
+        })
+        .collect()
+}
+
+/// Parses a whitespace separated sequence of moves for a cube of the given
+/// size, allowing wide and inner layer turns.
+///
+/// ## Arguments
+/// * `sequence` - the moves in WCA notation, e.g. `Rw U2 3Fw'`.
+/// * `size` - the number of layers of the cube.
+///
+/// ## Returns
+/// * The parsed moves, or the first token that is not a valid move on a
+///   cube of that size.
+pub fn parse_moves_for(sequence: &str, size: usize) -> Result<Vec<Move>, NotationError> {
+    sequence
+        .split_whitespace()
+        .map(|token| match token.parse::<Move>() {
+            Ok(m) if m.fits(size) => Ok(m),
+            _ => Err(NotationError { token: token.to_string() }),
+        })
+        .collect()
+}
+
+/// Gets the number of stickers of a cube of the given size.
+pub fn facelets(size: usize) -> usize {
+    6 * size * size
+}
+
+/// Gets the coordinate of the `k`-th layer. Coordinates are doubled so that
+/// layers lie on integers between `-(size - 1)` and `size - 1`, while
+/// stickers lie on `±size`.
+fn layer_coord(k: i32, n: i32) -> i32 {
+    2 * k - (n - 1)
+}
+
+fn layer_index(coord: i32, n: i32) -> i32 {
+    (coord + n - 1) / 2
+}
+
+/// Gets the position in space of a sticker. Every face is read row by row
+/// as seen from outside the cube, with U read with B on top, D with F on
+/// top and the side faces with U on top.
+pub fn facelet_position(facelet: usize, size: usize) -> [i32; 3] {
+    let n = size as i32;
+    let face = Face::ALL[facelet / (size * size)];
+    let row = layer_coord((facelet as i32 % (n * n)) / n, n);
+    let col = layer_coord(facelet as i32 % n, n);
+    match face {
+        Face::U => [col, n, row],
+        Face::R => [n, -row, -col],
+        Face::F => [col, -row, n],
+        Face::D => [col, -n, -row],
+        Face::L => [-n, -row, col],
+        Face::B => [-col, -row, -n],
+    }
+}
+
+/// Gets the sticker placed at a position in space.
+pub fn facelet_index(position: [i32; 3], size: usize) -> usize {
+    let n = size as i32;
+    let [x, y, z] = position;
+    let (face, row, col) = if y == n {
+        (Face::U, z, x)
+    } else if x == n {
+        (Face::R, -y, -z)
+    } else if z == n {
+        (Face::F, -y, x)
+    } else if y == -n {
+        (Face::D, -z, x)
+    } else if x == -n {
+        (Face::L, -y, z)
+    } else {
+        (Face::B, -y, -x)
+    };
+    (face.index() as i32 * n * n + layer_index(row, n) * n + layer_index(col, n)) as usize
+}
+
+/// Gets the face a sticker belongs to given its position in space.
+pub fn facelet_face(position: [i32; 3], size: usize) -> Face {
+    let n = size as i32;
+    let axis = (0..3).find(|&a| position[a].abs() == n).unwrap();
+    Face::from_axis(axis, position[axis].signum())
+}
+
+/// Gets the position of the piece a sticker is on.
+pub fn piece_position(position: [i32; 3], size: usize) -> [i32; 3] {
+    let n = size as i32;
+    position.map(|c| c.clamp(-(n - 1), n - 1))
+}
+
+/// Rotates a position a quarter turn clockwise as seen from the given side
+/// of an axis.
+fn rotate(position: [i32; 3], axis: usize, sign: i32) -> [i32; 3] {
+    let [x, y, z] = position;
+    match (axis, sign) {
+        (0, 1) => [x, z, -y],
+        (0, _) => [x, -z, y],
+        (1, 1) => [-z, y, x],
+        (1, _) => [z, y, -x],
+        (2, 1) => [y, -x, z],
+        _ => [-y, x, z],
+    }
+}
+
+/// State of an NxNxN cube. Every position holds the sticker that is on it,
+/// identified by the position it has on the solved cube.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CubeState {
+    size: usize,
+    facelets: Vec<usize>,
+}
+
+impl CubeState {
+    /// Builds a solved 3x3.
+    pub fn solved() -> Self {
+        CubeState::solved_of(3)
+    }
+
+    /// Builds a solved cube of the given size.
+    pub fn solved_of(size: usize) -> Self {
+        CubeState { size, facelets: (0..facelets(size)).collect() }
+    }
+
+    /// Gets the number of layers of the cube.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Applies a move to the cube.
+    pub fn apply(&mut self, m: Move) {
+        let permutation = m.permutation(self.size);
+        let mut facelets = vec![0; self.facelets.len()];
+        for (position, sticker) in self.facelets.iter().enumerate() {
+            facelets[permutation[position]] = *sticker;
+        }
+        self.facelets = facelets;
+    }
+
+    /// Applies a sequence of moves to the cube.
+    pub fn apply_all(&mut self, moves: &[Move]) {
+        for m in moves {
+            self.apply(*m);
+        }
+    }
+
+    /// Gets the face whose color is shown at a position.
+    pub fn face_at(&self, position: usize) -> Face {
+        Face::ALL[self.facelets[position] / (self.size * self.size)]
+    }
+
+    /// Gets the facelet string of the cube: the face whose color every
+    /// position shows, faces in URFDLB order, e.g. `UUUUUUUUURRR...`.
+    pub fn facelet_string(&self) -> String {
+        (0..self.facelets.len()).map(|p| self.face_at(p).to_string()).collect()
+    }
+
+    /// Renders the cube as an unfolded net, with U on top of F, D below it
+    /// and L, F, R and B side by side.
+    ///
+    /// ## Arguments
+    /// * `letter` - the letter every face's color is shown with.
+    ///
+    /// ## Returns
+    /// * The lines of the net.
+    pub fn net(&self, letter: impl Fn(Face) -> char) -> Vec<String> {
+        let n = self.size;
+        let row = |face: Face, r: usize| -> String {
+            let base = face.index() * n * n;
+            (0..n)
+                .map(|c| letter(self.face_at(base + r * n + c)).to_string())
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        let padding = " ".repeat(2 * n);
+        let mut lines = Vec::new();
+        for r in 0..n {
+            lines.push(format!("{}{}", padding, row(Face::U, r)));
+        }
+        for r in 0..n {
+            let sides: Vec<String> = [Face::L, Face::F, Face::R, Face::B]
+                .into_iter()
+                .map(|face| row(face, r))
+                .collect();
+            lines.push(sides.join(" "));
+        }
+        for r in 0..n {
+            lines.push(format!("{}{}", padding, row(Face::D, r)));
+        }
+        lines
+    }
+
+    /// Gets the current position of a sticker.
+    pub fn position_of(&self, sticker: usize) -> usize {
+        self.facelets.iter().position(|s| *s == sticker).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scrambled(sequence: &str, size: usize) -> CubeState {
+        let mut state = CubeState::solved_of(size);
+        state.apply_all(&parse_moves_for(sequence, size).unwrap());
+        state
+    }
+
+    #[test]
+    fn moves_parse_and_display_in_wca_notation() {
+        for token in ["R", "U'", "F2", "Rw", "Rw'", "3Fw2", "2R", "3L'"] {
+            assert_eq!(token.parse::<Move>().unwrap().to_string(), token);
+        }
+        assert_eq!("R2'".parse::<Move>().unwrap(), Move::face_turn(Face::R, 2));
+        assert_eq!("2Rw".parse::<Move>().unwrap().to_string(), "Rw");
+        for token in ["", "X", "R3", "0R", "Rw2w", "r"] {
+            assert!(token.parse::<Move>().is_err(), "{:?} parsed", token);
+        }
+    }
+
+    #[test]
+    fn sequences_parse_for_their_size() {
+        assert_eq!(parse_moves("R U R' U'").unwrap().len(), 4);
+        assert_eq!(parse_moves("R Rw").unwrap_err().token, "Rw");
+        assert!(parse_moves_for("Rw 2R", 3).is_ok());
+        assert_eq!(parse_moves_for("R 3Rw", 3).unwrap_err().token, "3Rw");
+        assert_eq!(Move::all().count(), 18);
+    }
+
+    #[test]
+    fn a_quarter_turn_moves_four_stickers_of_every_side() {
+        let state = scrambled("R", 3);
+        assert_eq!(state.facelet_string(), "UUFUUFUUFRRRRRRRRRFFDFFDFFDDDBDDBDDBLLLLLLLLLUBBUBBUBB");
+        assert_ne!(scrambled("R", 3), scrambled("R'", 3));
+        assert_eq!(scrambled("R2", 3), scrambled("R R", 3));
+    }
+
+    #[test]
+    fn moves_have_the_order_of_their_turns() {
+        for size in MIN_SIZE..=MAX_SIZE {
+            for m in Move::all() {
+                let mut state = CubeState::solved_of(size);
+                state.apply_all(&[m; 4]);
+                assert_eq!(state, CubeState::solved_of(size), "{} on a {}x{}", m, size, size);
+            }
+        }
+        assert_eq!(scrambled(&"R U R' U' ".repeat(6), 3), CubeState::solved());
+    }
+
+    #[test]
+    fn a_sequence_is_undone_by_its_inverse() {
+        let state = scrambled("R U2 F' 2L Dw B", 4);
+        let mut undone = state.clone();
+        undone.apply_all(&parse_moves_for("B' Dw' 2L' F U2 R'", 4).unwrap());
+        assert_ne!(state, CubeState::solved_of(4));
+        assert_eq!(undone, CubeState::solved_of(4));
+    }
+}
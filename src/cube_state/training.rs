@@ -0,0 +1,120 @@
+use rocket::serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+
+use crate::cube_state::{analysis, parse_moves, CubeState, Face, Move};
+use crate::scramble::{self, random::Rng};
+
+/// Number of inserts or algorithms used to scramble a partially solved state.
+const SEQUENCES: usize = 8;
+/// Algorithms that only change the last layer.
+const LAST_LAYER_ALGORITHMS: [&str; 3] = [
+    "R U R' U R U2 R'",
+    "F R U R' U' F'",
+    "R U R' U' R' F R2 U' R' U' R U R' F'",
+];
+
+/// F2L slot of a cross on D.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, FromFormField, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum F2lSlot {
+    #[field(value = "fr")]
+    FR,
+    #[field(value = "fl")]
+    FL,
+    #[field(value = "br")]
+    BR,
+    #[field(value = "bl")]
+    BL,
+}
+
+impl F2lSlot {
+    /// Gets the two inserts of the slot, `X U X'` with `X` being one of its
+    /// faces, which change nothing but the slot and the last layer.
+    fn inserts(self) -> [(Face, u8); 2] {
+        match self {
+            F2lSlot::FR => [(Face::R, 1), (Face::F, 3)],
+            F2lSlot::FL => [(Face::L, 3), (Face::F, 1)],
+            F2lSlot::BR => [(Face::R, 3), (Face::B, 1)],
+            F2lSlot::BL => [(Face::L, 1), (Face::B, 3)],
+        }
+    }
+}
+
+/// Merges consecutive turns of the same face, removing those that cancel.
+fn simplify(moves: Vec<Move>) -> Vec<Move> {
+    let mut simplified: Vec<Move> = Vec::new();
+    for m in moves {
+        match simplified.last_mut() {
+            Some(last) if last.face == m.face && last.is_face_turn() => {
+                last.turns = (last.turns + m.turns) % 4;
+                if last.turns == 0 {
+                    simplified.pop();
+                }
+            }
+            _ => simplified.push(m),
+        }
+    }
+    simplified
+}
+
+fn random_u_turn(rng: &mut Rng) -> Move {
+    Move::face_turn(Face::U, rng.below(3) as u8 + 1)
+}
+
+/// Generates a scramble whose cross on D is solved in exactly the given
+/// number of moves, trying random-move scrambles until one fits.
+///
+/// ## Arguments
+/// * `rng` - the random generator to pick moves with.
+/// * `cross_moves` - the optimal length of the cross, any if none.
+/// * `attempts` - the number of scrambles to try before giving up.
+///
+/// ## Returns
+/// * The moves of the scramble, if one was found.
+pub fn cross_scramble(rng: &mut Rng, cross_moves: Option<u8>, attempts: usize) -> Option<Vec<Move>> {
+    (0..attempts).map(|_| scramble::cube_scramble(rng, 3)).find(|moves| {
+        let mut state = CubeState::solved();
+        state.apply_all(moves);
+        cross_moves.is_none_or(|target| analysis::cross_moves(&state, Face::D) == target)
+    })
+}
+
+/// Generates a scramble that keeps the cross on D and every F2L slot but
+/// one solved, by doing random inserts into that slot.
+///
+/// ## Arguments
+/// * `rng` - the random generator to pick moves with.
+/// * `slot` - the slot to scramble.
+///
+/// ## Returns
+/// * The moves of the scramble.
+pub fn f2l_scramble(rng: &mut Rng, slot: F2lSlot) -> Vec<Move> {
+    let inserts = slot.inserts();
+    let mut moves = Vec::new();
+    for _ in 0..SEQUENCES {
+        let (face, turns) = inserts[rng.below(inserts.len())];
+        moves.push(Move::face_turn(face, turns));
+        moves.push(random_u_turn(rng));
+        moves.push(Move::face_turn(face, 4 - turns));
+        moves.push(random_u_turn(rng));
+    }
+    simplify(moves)
+}
+
+/// Generates a scramble that keeps the first two layers solved, by doing
+/// random last layer algorithms.
+///
+/// ## Arguments
+/// * `rng` - the random generator to pick moves with.
+///
+/// ## Returns
+/// * The moves of the scramble.
+pub fn last_layer_scramble(rng: &mut Rng) -> Vec<Move> {
+    let mut moves = Vec::new();
+    for _ in 0..SEQUENCES {
+        let algorithm = LAST_LAYER_ALGORITHMS[rng.below(LAST_LAYER_ALGORITHMS.len())];
+        moves.extend(parse_moves(algorithm).unwrap());
+        moves.push(random_u_turn(rng));
+    }
+    simplify(moves)
+}
@@ -0,0 +1,90 @@
+use std::future::Future;
+use std::pin::Pin;
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::Header;
+use rocket::serde::Deserialize;
+use rocket::{Request, Response};
+
+/// Metadata describing a deprecated route, read from the `deprecations`
+/// key of the Rocket configuration (`Rocket.toml` or `ROCKET_DEPRECATIONS`).
+///
+/// ```toml
+/// [[default.deprecations]]
+/// path = "/cube_by_id"
+/// sunset = "Wed, 01 Jul 2026 00:00:00 GMT"
+/// successor = "/api/v1/cubes/<id>"
+/// hint = "Use /api/v1/cubes/<id> instead"
+/// ```
+#[derive(Debug, Deserialize, Clone)]
+pub struct DeprecatedRoute {
+    /// Path of the route as it is mounted, without the query part. The
+    /// prefix of the version is part of it: `/cube_by_id` only matches the
+    /// route without prefix, and `/api/v1/cube_by_id` the versioned one.
+    pub path: String,
+    /// HTTP-date after which the route may stop working.
+    pub sunset: Option<String>,
+    /// Route that replaces the deprecated one.
+    pub successor: Option<String>,
+    /// Human readable migration hint.
+    pub hint: Option<String>,
+}
+
+pub struct DeprecationFairing {
+    routes: Vec<DeprecatedRoute>,
+}
+
+impl DeprecationFairing {
+    /// Builds the fairing with the deprecated routes of the configuration.
+    ///
+    /// ## Arguments
+    /// * `routes` - the deprecated routes, from the `deprecations` key.
+    ///
+    /// ## Returns
+    /// * An instance of the deprecation fairing.
+    pub fn new(routes: Vec<DeprecatedRoute>) -> Self {
+        DeprecationFairing { routes }
+    }
+}
+
+impl Fairing for DeprecationFairing {
+    fn info(&self) -> Info {
+        Info {
+            name: "Deprecation Fairing",
+            kind: Kind::Response,
+        }
+    }
+
+    fn on_response<'r, 'life0, 'life1, 'life2, 'async_trait>(
+        &'life0 self,
+        request: &'r Request<'life1>,
+        response: &'life2 mut Response<'r>
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'async_trait>>
+    where
+        Self: 'async_trait,
+        'r: 'async_trait,
+        'life0: 'async_trait,
+        'life1: 'async_trait,
+        'life2: 'async_trait,
+    {
+        let deprecated = request
+            .route()
+            .and_then(|route| self.routes.iter().find(|d| d.path == route.uri.path()));
+
+        if let Some(deprecated) = deprecated {
+            response.set_header(Header::new("Deprecation", "true"));
+            if let Some(sunset) = &deprecated.sunset {
+                response.set_header(Header::new("Sunset", sunset.clone()));
+            }
+            if let Some(successor) = &deprecated.successor {
+                response.set_header(Header::new(
+                    "Link",
+                    format!("<{}>; rel=\"successor-version\"", successor),
+                ));
+            }
+            if let Some(hint) = &deprecated.hint {
+                response.set_header(Header::new("X-Migration-Hint", hint.clone()));
+            }
+        }
+        Box::pin(async {})
+    }
+}
@@ -0,0 +1,73 @@
+use crate::models::{cube_filter_model::CubeFilter, cube_model::Cube, duplicate_model::DuplicateCandidate};
+use crate::repository::{cube_repository::CubeRepository, repo_error::RepoError};
+
+/// Cubes with the specs of a new cube that are compared with it, so that
+/// a check never reads more than a page of the catalog.
+const SCANNED: i64 = 1000;
+
+/// Normalizes a cube name into the key used to detect duplicates, so that
+/// names only differing in case, spacing or punctuation collide.
+pub fn name_key(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
+
+/// Counts the insertions, deletions and substitutions of characters that
+/// turn a text into the other.
+fn distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Distance between two names when they are close enough to be the same
+/// cube: one edit for short names, one every five characters otherwise.
+fn close_names(a: &str, b: &str) -> Option<usize> {
+    let (a, b) = (name_key(a), name_key(b));
+    let allowed = (a.chars().count().min(b.chars().count()) / 5).max(1);
+    Some(distance(&a, &b)).filter(|distance| *distance <= allowed)
+}
+
+/// Finds the cubes of the catalog that a new cube looks like: cubes of the
+/// same type with the same pieces, faces and stickers, whose name or any
+/// alias is close to the name or an alias of the new one.
+///
+/// ## Arguments
+/// * `db` - instance of the cubes repository.
+/// * `cube` - the new cube.
+///
+/// ## Returns
+/// * The candidate duplicates, the closest first.
+pub async fn near_duplicates(db: &dyn CubeRepository, cube: &Cube) -> Result<Vec<DuplicateCandidate>, RepoError> {
+    let filter = CubeFilter {
+        type_: Some(cube.type_),
+        min_pieces: Some(cube.pieces),
+        max_pieces: Some(cube.pieces),
+        ..CubeFilter::default()
+    };
+    let (known, _) = db.get_cubes_page(&filter, None, 0, SCANNED).await?;
+    let names: Vec<&String> = std::iter::once(&cube.name).chain(&cube.aliases).collect();
+    let mut candidates: Vec<DuplicateCandidate> = known
+        .into_iter()
+        .filter(|known| known.faces == cube.faces && known.stickers == cube.stickers)
+        .filter_map(|known| {
+            let closest = std::iter::once(&known.name)
+                .chain(&known.aliases)
+                .flat_map(|known_name| names.iter().filter_map(|name| close_names(name, known_name)))
+                .min()?;
+            Some(DuplicateCandidate { id: known.id, name: known.name, distance: closest })
+        })
+        .collect();
+    candidates.sort_by_key(|candidate| candidate.distance);
+    Ok(candidates)
+}
@@ -0,0 +1,152 @@
+use std::future::Future;
+use std::io::Cursor;
+use std::pin::Pin;
+
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::{ContentType, Header};
+use rocket::serde::json::{self, serde_json::Map, Value};
+use rocket::{Request, Response};
+
+/// Name of the header, and of the `Accept` media-type parameter, that
+/// negotiates the shape of the responses.
+const ENVELOPE: &str = "X-Api-Envelope";
+
+/// Shape of the JSON responses asked for by a client.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Envelope {
+    /// The bare body, as every response was before envelopes.
+    Flat,
+    /// The body under `data`, with `meta` and `errors` beside it.
+    Enveloped,
+}
+
+impl Envelope {
+    /// Reads the shape asked for with the `X-Api-Envelope` header or, failing
+    /// that, the `envelope` parameter of the `Accept` media type (`1` flat,
+    /// `2` enveloped). Anything else keeps the flat shape.
+    fn of(request: &Request<'_>) -> Self {
+        let asked = request.headers().get_one(ENVELOPE).map(str::to_string).or_else(|| {
+            request.accept().and_then(|accept| {
+                accept
+                    .preferred()
+                    .media_type()
+                    .params()
+                    .find(|(key, _)| key == "envelope")
+                    .map(|(_, value)| value.to_string())
+            })
+        });
+        match asked.as_deref().map(str::trim) {
+            Some("2") => Envelope::Enveloped,
+            _ => Envelope::Flat,
+        }
+    }
+
+    fn version(self) -> &'static str {
+        match self {
+            Envelope::Flat => "1",
+            Envelope::Enveloped => "2",
+        }
+    }
+}
+
+/// Wraps a flat body into the envelope. Errors list their message, and one
+/// entry by field for invalid payloads, and pages move their paging fields
+/// to `meta`, as do near-duplicate warnings with their candidates.
+fn wrap(body: Value, status: u16) -> Value {
+    let mut meta = Map::new();
+    meta.insert("status".to_string(), status.into());
+    let mut envelope = Map::new();
+    if status >= 400 {
+        let message = body.get("message").cloned().unwrap_or(Value::Null);
+        let mut errors: Vec<Value> = body
+            .get("errors")
+            .and_then(Value::as_array)
+            .map(|fields| {
+                fields
+                    .iter()
+                    .map(|field| {
+                        json::json!({
+                            "status": status,
+                            "message": field.get("message"),
+                            "field": field.get("field"),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        if errors.is_empty() {
+            errors.push(json::json!({"status": status, "message": message}));
+        }
+        if let Some(candidates) = body.get("candidates") {
+            meta.insert("candidates".to_string(), candidates.clone());
+        }
+        envelope.insert("data".to_string(), Value::Null);
+        envelope.insert("errors".to_string(), Value::Array(errors));
+    } else {
+        let paging = ["page", "per_page", "total", "pages"];
+        let data = match body {
+            Value::Object(mut page) if page.contains_key("items") && paging.iter().all(|k| page.contains_key(*k)) => {
+                for key in paging {
+                    if let Some(value) = page.remove(key) {
+                        meta.insert(key.to_string(), value);
+                    }
+                }
+                page.remove("items").unwrap_or(Value::Null)
+            }
+            body => body,
+        };
+        envelope.insert("data".to_string(), data);
+        envelope.insert("errors".to_string(), Value::Array(Vec::new()));
+    }
+    envelope.insert("meta".to_string(), Value::Object(meta));
+    Value::Object(envelope)
+}
+
+/// Reshapes the JSON responses into the envelope for the clients that ask
+/// for it, so that flat and enveloped clients can coexist while they
+/// migrate. Every response tells the shape it has in `X-Api-Envelope`.
+pub struct EnvelopeFairing;
+
+impl Fairing for EnvelopeFairing {
+    fn info(&self) -> Info {
+        Info {
+            name: "Envelope Fairing",
+            kind: Kind::Response,
+        }
+    }
+
+    fn on_response<'r, 'life0, 'life1, 'life2, 'async_trait>(
+        &'life0 self,
+        request: &'r Request<'life1>,
+        response: &'life2 mut Response<'r>
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'async_trait>>
+    where
+        Self: 'async_trait,
+        'r: 'async_trait,
+        'life0: 'async_trait,
+        'life1: 'async_trait,
+        'life2: 'async_trait,
+    {
+        let envelope = Envelope::of(request);
+        Box::pin(async move {
+            response.adjoin_header(Header::new("Vary", format!("Accept, {}", ENVELOPE)));
+            let json = response.content_type().is_some_and(|kind| kind.is_json());
+            if envelope == Envelope::Flat || !json {
+                response.set_header(Header::new(ENVELOPE, Envelope::Flat.version()));
+                return;
+            }
+            let Ok(text) = response.body_mut().to_string().await else {
+                return;
+            };
+            let Ok(body) = json::from_str::<Value>(&text) else {
+                response.set_sized_body(text.len(), Cursor::new(text));
+                response.set_header(Header::new(ENVELOPE, Envelope::Flat.version()));
+                return;
+            };
+            let wrapped = wrap(body, response.status().code).to_string();
+            response.set_sized_body(wrapped.len(), Cursor::new(wrapped));
+            response.set_header(ContentType::JSON);
+            response.set_header(Header::new(ENVELOPE, envelope.version()));
+        })
+    }
+}
@@ -0,0 +1,39 @@
+use std::sync::Arc;
+
+use mongodb::bson::oid::ObjectId;
+
+/// Change made to the catalog through the API.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ApiEvent {
+    CubeCreated { id: Option<ObjectId> },
+    CubeUpdated { id: Option<ObjectId> },
+    CubeDeleted { id: ObjectId },
+    CubeRestored { id: ObjectId },
+}
+
+/// Listener of the changes made to the catalog, registered with
+/// [`crate::RubikApiBuilder::add_listener`].
+pub trait EventListener: Send + Sync {
+    /// Called after every change, once it has been written.
+    fn on_event(&self, event: &ApiEvent);
+}
+
+/// Listeners registered on the server, managed as state so that handlers
+/// can notify them. Clones share the listeners, as the GraphQL schema does.
+#[derive(Clone)]
+pub struct EventBus {
+    listeners: Arc<Vec<Box<dyn EventListener>>>,
+}
+
+impl EventBus {
+    pub fn new(listeners: Vec<Box<dyn EventListener>>) -> Self {
+        EventBus { listeners: Arc::new(listeners) }
+    }
+
+    /// Notifies every listener of an event.
+    pub fn emit(&self, event: ApiEvent) {
+        for listener in self.listeners.iter() {
+            listener.on_event(&event);
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use futures::stream::BoxStream;
+use rocket::http::{ContentType, Header};
+use rocket::response::{self, stream::TextStream, Responder, Response};
+use rocket::Request;
+use rocket::serde::{json, Serialize};
+
+use crate::models::cube_model::Cube;
+
+/// Columns of the CSV export of the catalog. Nested specs are flattened,
+/// and the aliases are joined with `|`.
+pub const CSV_HEADER: &str = "id,name,aliases,type,pieces,faces,stickers,year_created,wr_time_ms,wr_holder,\
+wr_competition,wr_date,corner_cutting_forward,corner_cutting_reverse,stability,speed,one_handed,electronics,shape,version,created_at,updated_at";
+
+/// Quotes a field when it holds a comma, a quote or a line break, doubling
+/// its quotes, as RFC 4180 describes. Fields starting like a formula are
+/// prefixed with a quote, so that spreadsheets do not run them.
+fn escape(field: &str) -> String {
+    let field = match field.starts_with(['=', '+', '-', '@']) && field.parse::<f64>().is_err() {
+        true => format!("'{}", field),
+        false => field.to_string(),
+    };
+    match field.contains([',', '"', '\n', '\r']) {
+        true => format!("\"{}\"", field.replace('"', "\"\"")),
+        false => field,
+    }
+}
+
+/// Name of an enum value as written in the JSON of the API.
+fn serde_name(value: &impl Serialize) -> String {
+    match json::to_value(value) {
+        Ok(json::Value::String(name)) => name,
+        _ => String::new(),
+    }
+}
+
+fn optional<T: ToString>(value: Option<T>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
+
+/// Writes a cube as a row of the CSV export.
+///
+/// ## Arguments
+/// * `cube` - the cube.
+///
+/// ## Returns
+/// * The row, in the order of [`CSV_HEADER`], ending with a line break.
+pub fn csv_row(cube: &Cube) -> String {
+    let performance = cube.performance.as_ref();
+    let fields = [
+        optional(cube.id.map(|id| id.to_hex())),
+        cube.name.clone(),
+        cube.aliases.join("|"),
+        serde_name(&cube.type_),
+        cube.pieces.to_string(),
+        cube.faces.to_string(),
+        cube.stickers.to_string(),
+        cube.year_created.to_string(),
+        optional(cube.wr.time_ms),
+        cube.wr.holder.clone(),
+        cube.wr.competition.clone().unwrap_or_default(),
+        cube.wr.date.clone(),
+        optional(performance.map(|p| p.corner_cutting_forward)),
+        optional(performance.map(|p| p.corner_cutting_reverse)),
+        optional(performance.map(|p| p.stability)),
+        optional(performance.map(|p| p.speed)),
+        optional(performance.map(|p| p.one_handed)),
+        optional(cube.electronics),
+        cube.shape.as_ref().map(|shape| serde_name(&shape.shape)).unwrap_or_default(),
+        cube.version.to_string(),
+        optional(cube.created_at),
+        optional(cube.updated_at),
+    ];
+    let mut row = fields.iter().map(|field| escape(field)).collect::<Vec<_>>().join(",");
+    row.push_str("\r\n");
+    row
+}
+
+/// CSV file streamed to the client, downloaded under the given name.
+pub struct CsvExport<'r> {
+    rows: TextStream<BoxStream<'r, String>>,
+    filename: String,
+}
+
+impl<'r> Responder<'r, 'r> for CsvExport<'r> {
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'r> {
+        Response::build_from(self.rows.respond_to(request)?)
+            .header(ContentType::new("text", "csv").with_params(("charset", "utf-8")))
+            .header(Header::new("Content-Disposition", format!("attachment; filename=\"{}\"", self.filename)))
+            .ok()
+    }
+}
+
+impl<'r> CsvExport<'r> {
+    /// Builds the response of a CSV export.
+    ///
+    /// ## Arguments
+    /// * `rows` - lines of the file, the header first.
+    /// * `filename` - name the file is downloaded under.
+    ///
+    /// ## Returns
+    /// * The CSV response.
+    pub fn new(rows: BoxStream<'r, String>, filename: &str) -> Self {
+        CsvExport { rows: TextStream(rows), filename: filename.to_string() }
+    }
+}
@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::OnceLock;
+
+use rocket::request::{FromRequest, Outcome};
+use rocket::serde::json::serde_json;
+use rocket::Request;
+
+/// Language used when none of the accepted ones has a catalog, and for the
+/// messages missing from the other catalogs.
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+/// Message catalogs, by language.
+const CATALOGS: [(&str, &str); 2] = [
+    ("en", include_str!("../data/i18n/en.json")),
+    ("es", include_str!("../data/i18n/es.json")),
+];
+
+fn catalogs() -> &'static HashMap<&'static str, HashMap<String, String>> {
+    static PARSED: OnceLock<HashMap<&'static str, HashMap<String, String>>> = OnceLock::new();
+    PARSED.get_or_init(|| {
+        CATALOGS
+            .iter()
+            .map(|(language, catalog)| {
+                (*language, serde_json::from_str(catalog).expect("Invalid message catalog"))
+            })
+            .collect()
+    })
+}
+
+/// Languages to look messages up in, from the most to the least preferred.
+#[derive(Debug, Clone)]
+pub struct Lang {
+    chain: Vec<String>,
+}
+
+impl Lang {
+    /// Builds the fallback chain of an `Accept-Language` header: every
+    /// language by decreasing quality, each followed by its primary subtag
+    /// (`es-MX` then `es`), and the default language last.
+    /// 
+    /// ## Arguments
+    /// * `header` - the value of the header, if any.
+    /// 
+    /// ## Returns
+    /// * The languages to look messages up in.
+    pub fn from_header(header: Option<&str>) -> Self {
+        let mut ranges: Vec<(String, f32)> = header
+            .unwrap_or("")
+            .split(',')
+            .filter_map(|range| {
+                let mut parts = range.split(';');
+                let tag = parts.next()?.trim().to_lowercase();
+                let quality = parts
+                    .find_map(|p| p.trim().strip_prefix("q="))
+                    .map_or(Some(1.0), |q| q.parse::<f32>().ok())?;
+                (!tag.is_empty() && tag != "*" && quality > 0.0).then_some((tag, quality))
+            })
+            .collect();
+        ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        let mut chain: Vec<String> = Vec::new();
+        let tags = ranges.into_iter().flat_map(|(tag, _)| {
+            let primary = tag.split('-').next().unwrap_or("").to_string();
+            [tag, primary]
+        });
+        for tag in tags.chain([DEFAULT_LANGUAGE.to_string()]) {
+            if !chain.contains(&tag) {
+                chain.push(tag);
+            }
+        }
+        Lang { chain }
+    }
+
+    /// Gets the first language of the chain that has a catalog.
+    pub fn language(&self) -> &str {
+        self.chain
+            .iter()
+            .find(|l| catalogs().contains_key(l.as_str()))
+            .map_or(DEFAULT_LANGUAGE, String::as_str)
+    }
+
+    /// Gets a message in the most preferred language that has it.
+    /// 
+    /// ## Arguments
+    /// * `key` - key of the message, e.g. `cube_deleted`.
+    /// 
+    /// ## Returns
+    /// * The localized message, or the key itself if no catalog has it.
+    pub fn message(&self, key: &str) -> String {
+        self.chain
+            .iter()
+            .find_map(|language| catalogs().get(language.as_str())?.get(key))
+            .cloned()
+            .unwrap_or_else(|| key.to_string())
+    }
+}
+
+impl<'r> FromRequest<'r> for Lang {
+    type Error = Infallible;
+
+    fn from_request<'life0, 'async_trait>(
+        request: &'r Request<'life0>
+    ) -> Pin<Box<dyn Future<Output = Outcome<Self, Self::Error>> + Send + 'async_trait>>
+    where
+        'r: 'async_trait,
+        'life0: 'async_trait,
+        Self: 'async_trait,
+    {
+        let lang = Lang::from_header(request.headers().get_one("Accept-Language"));
+        Box::pin(async move { Outcome::Success(lang) })
+    }
+}
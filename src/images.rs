@@ -0,0 +1,61 @@
+use rocket::serde::Deserialize;
+
+/// Limits of the pictures of the cubes, read from the `images` key of the
+/// Rocket configuration.
+///
+/// ```toml
+/// [default.images]
+/// max_bytes = 2097152
+/// content_types = ["image/png", "image/jpeg", "image/webp"]
+/// ```
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct ImagePolicy {
+    /// Largest picture accepted, in bytes.
+    pub max_bytes: u64,
+    /// Media types accepted, among `image/png`, `image/jpeg`, `image/gif`
+    /// and `image/webp`.
+    pub content_types: Vec<String>,
+}
+
+impl Default for ImagePolicy {
+    fn default() -> Self {
+        ImagePolicy {
+            max_bytes: 2 * 1024 * 1024,
+            content_types: ["image/png", "image/jpeg", "image/gif", "image/webp"]
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+        }
+    }
+}
+
+/// Tells the media type of a picture from its first bytes, so that a file
+/// is never served under a type it does not have.
+fn sniff(bytes: &[u8]) -> Option<&'static str> {
+    match bytes {
+        [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n', ..] => Some("image/png"),
+        [0xff, 0xd8, 0xff, ..] => Some("image/jpeg"),
+        [b'G', b'I', b'F', b'8', b'7' | b'9', b'a', ..] => Some("image/gif"),
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => Some("image/webp"),
+        _ => None,
+    }
+}
+
+impl ImagePolicy {
+    /// Checks that a picture has an accepted type, and that its content
+    /// is of the type it was sent as.
+    ///
+    /// ## Arguments
+    /// * `declared` - media type sent with the picture, if any.
+    /// * `bytes` - content of the picture.
+    ///
+    /// ## Returns
+    /// * The media type of the picture, if it is accepted.
+    pub fn check(&self, declared: Option<&str>, bytes: &[u8]) -> Option<&'static str> {
+        let sniffed = sniff(bytes)?;
+        let allowed = self.content_types.iter().any(|allowed| allowed.eq_ignore_ascii_case(sniffed));
+        let matches = declared.is_none_or(|declared| declared.eq_ignore_ascii_case(sniffed));
+        (allowed && matches).then_some(sniffed)
+    }
+}
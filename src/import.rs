@@ -0,0 +1,236 @@
+use std::str::FromStr;
+
+use mongodb::bson::Document;
+use rocket::serde::json::{self, Value};
+
+use crate::export::CSV_HEADER;
+use crate::models::cube_model::Cube;
+use crate::models::cube_type_model::CubeType;
+use crate::models::performance_model::Performance;
+use crate::models::world_record_model::WorldRecord;
+use crate::validation::FieldError;
+
+/// Record of an imported file: the cube it describes, or why it could not
+/// be read.
+pub type ImportedRow = Result<Cube, Vec<FieldError>>;
+
+/// Splits a CSV text into its records, following RFC 4180: fields may be
+/// quoted, with doubled quotes inside, and quoted fields may hold commas
+/// and line breaks. Blank lines are skipped.
+fn parse_csv(text: &str) -> Result<Vec<Vec<String>>, String> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.trim_start_matches('\u{feff}').chars().peekable();
+    while let Some(c) = chars.next() {
+        match (quoted, c) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            (true, '"') => quoted = false,
+            (true, c) => field.push(c),
+            (false, '"') if field.is_empty() => quoted = true,
+            (false, ',') => record.push(std::mem::take(&mut field)),
+            (false, '\r') if chars.peek() == Some(&'\n') => {}
+            (false, '\n') | (false, '\r') => {
+                record.push(std::mem::take(&mut field));
+                let done = std::mem::take(&mut record);
+                if done.iter().any(|field| !field.is_empty()) {
+                    records.push(done);
+                }
+            }
+            (false, c) => field.push(c),
+        }
+    }
+    if quoted {
+        return Err("the file ends inside a quoted field".to_string());
+    }
+    record.push(field);
+    if record.iter().any(|field| !field.is_empty()) {
+        records.push(record);
+    }
+    Ok(records)
+}
+
+/// Undoes the quote the export puts before fields that look like formulas.
+fn unescape(field: &str) -> &str {
+    match field.strip_prefix('\'') {
+        Some(rest) if rest.starts_with(['=', '+', '-', '@']) => rest,
+        _ => field,
+    }
+}
+
+/// Columns a CSV import needs, the others being optional.
+const REQUIRED_COLUMNS: [&str; 8] =
+    ["name", "type", "pieces", "faces", "stickers", "year_created", "wr_holder", "wr_date"];
+/// Columns of the performance, which are given all together or not at all.
+const PERFORMANCE_COLUMNS: [&str; 5] =
+    ["corner_cutting_forward", "corner_cutting_reverse", "stability", "speed", "one_handed"];
+
+/// Record of a CSV file, with the header to read its fields by column.
+struct CsvRecord<'a> {
+    header: &'a [String],
+    fields: &'a [String],
+}
+
+impl CsvRecord<'_> {
+    fn get(&self, column: &str) -> &str {
+        self.header
+            .iter()
+            .position(|name| name == column)
+            .and_then(|index| self.fields.get(index))
+            .map(|field| unescape(field.trim()))
+            .unwrap_or("")
+    }
+
+    /// Reads a field into its type, noting the problem if it cannot be.
+    fn parse<T: FromStr>(&self, column: &str, errors: &mut Vec<FieldError>) -> Option<T> {
+        let field = self.get(column);
+        if field.is_empty() {
+            if REQUIRED_COLUMNS.contains(&column) {
+                errors.push(FieldError::new(column, format!("{} must not be empty", column)));
+            }
+            return None;
+        }
+        match field.parse() {
+            Ok(value) => Some(value),
+            Err(_) => {
+                errors.push(FieldError::new(column, format!("{} has an invalid value `{}`", column, field)));
+                None
+            }
+        }
+    }
+
+    fn text(&self, column: &str) -> Option<String> {
+        Some(self.get(column)).filter(|field| !field.is_empty()).map(str::to_string)
+    }
+
+    /// Builds the cube described by the record.
+    fn cube(&self) -> ImportedRow {
+        let mut errors = Vec::new();
+        let type_ = match CubeType::from_str(self.get("type")) {
+            Ok(type_) => Some(type_),
+            Err(_) => {
+                errors.push(FieldError::new("type", "type must be cubic, cuboid, minx or other"));
+                None
+            }
+        };
+        let pieces = self.parse::<u32>("pieces", &mut errors);
+        let faces = self.parse::<u32>("faces", &mut errors);
+        let stickers = self.parse::<u32>("stickers", &mut errors);
+        let year_created = self.parse::<u32>("year_created", &mut errors);
+        let time_ms = self.parse::<u64>("wr_time_ms", &mut errors);
+        let electronics = self.parse::<bool>("electronics", &mut errors);
+        if self.text("wr_holder").is_none() {
+            errors.push(FieldError::new("wr_holder", "wr_holder must not be empty"));
+        }
+        if self.text("wr_date").is_none() {
+            errors.push(FieldError::new("wr_date", "wr_date must not be empty"));
+        }
+        if self.text("shape").is_some() {
+            errors.push(FieldError::new("shape", "shapes need their pieces and notation, import them from JSON"));
+        }
+
+        let given = PERFORMANCE_COLUMNS.iter().filter(|column| !self.get(column).is_empty()).count();
+        let performance = match given {
+            0 => None,
+            5 => {
+                let before = errors.len();
+                let performance = Performance {
+                    corner_cutting_forward: self.parse("corner_cutting_forward", &mut errors).unwrap_or_default(),
+                    corner_cutting_reverse: self.parse("corner_cutting_reverse", &mut errors).unwrap_or_default(),
+                    stability: self.parse("stability", &mut errors).unwrap_or_default(),
+                    speed: self.parse("speed", &mut errors).unwrap_or_default(),
+                    one_handed: self.parse("one_handed", &mut errors).unwrap_or_default(),
+                };
+                (errors.len() == before).then_some(performance)
+            }
+            _ => {
+                errors.push(FieldError::new("performance", "the performance columns must all be given, or none"));
+                None
+            }
+        };
+
+        match (type_, pieces, faces, stickers, year_created) {
+            (Some(type_), Some(pieces), Some(faces), Some(stickers), Some(year_created)) if errors.is_empty() => {
+                Ok(Cube {
+                    id: None,
+                    name: self.get("name").to_string(),
+                    aliases: self
+                        .get("aliases")
+                        .split('|')
+                        .map(str::trim)
+                        .filter(|alias| !alias.is_empty())
+                        .map(str::to_string)
+                        .collect(),
+                    type_,
+                    pieces,
+                    faces,
+                    stickers,
+                    year_created,
+                    wr: WorldRecord {
+                        time_ms,
+                        holder: self.get("wr_holder").to_string(),
+                        competition: self.text("wr_competition"),
+                        date: self.get("wr_date").to_string(),
+                    },
+                    performance,
+                    adjustability: None,
+                    electronics,
+                    shape: None,
+                    quality: None,
+                    created_at: None,
+                    updated_at: None,
+                    version: 0,
+                    extra: Document::new(),
+                })
+            }
+            _ => Err(errors),
+        }
+    }
+}
+
+/// Reads the cubes of a CSV file with the columns of the CSV export, in
+/// any order. `id`, `version` and the timestamps are managed by the API
+/// and ignored.
+///
+/// ## Arguments
+/// * `text` - content of the file.
+///
+/// ## Returns
+/// * The records of the file, or why the file cannot be read.
+pub fn read_csv(text: &str) -> Result<Vec<ImportedRow>, String> {
+    let mut records = parse_csv(text)?.into_iter();
+    let header: Vec<String> = records
+        .next()
+        .ok_or("the file is empty")?
+        .iter()
+        .map(|column| column.trim().to_lowercase())
+        .collect();
+    let known: Vec<&str> = CSV_HEADER.split(',').collect();
+    if let Some(column) = header.iter().find(|column| !known.contains(&column.as_str())) {
+        return Err(format!("unknown column `{}`", column));
+    }
+    if let Some(column) = REQUIRED_COLUMNS.iter().find(|column| !header.iter().any(|c| c == *column)) {
+        return Err(format!("missing column `{}`", column));
+    }
+    Ok(records.map(|fields| CsvRecord { header: &header, fields: &fields }.cube()).collect())
+}
+
+/// Reads the cubes of a JSON file, holding an array of cubes as sent to
+/// `/add_cubes`.
+///
+/// ## Arguments
+/// * `text` - content of the file.
+///
+/// ## Returns
+/// * The records of the file, or why the file cannot be read.
+pub fn read_json(text: &str) -> Result<Vec<ImportedRow>, String> {
+    let items: Vec<Value> = json::from_str(text).map_err(|error| format!("the file is not a JSON array: {}", error))?;
+    Ok(items
+        .into_iter()
+        .map(|item| json::from_value::<Cube>(item).map_err(|error| vec![FieldError::new("cube", error.to_string())]))
+        .collect())
+}
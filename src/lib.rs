@@ -0,0 +1,49 @@
+//! Rubik API: a REST API about speedcubing puzzles, built on Rocket and
+//! MongoDB. Besides running the `rubik_api` binary, the server can be
+//! embedded and extended with [`RubikApi::builder`].
+
+#[macro_use]
+extern crate rocket;
+
+mod api;
+pub mod models;
+mod repository;
+mod config;
+mod cors;
+mod cube_state;
+mod compute;
+mod stats;
+mod deprecation;
+mod envelope;
+mod pagination;
+mod precondition;
+mod auth;
+mod validation;
+mod screening;
+mod export;
+mod import;
+mod duplicates;
+mod images;
+mod sandbox;
+mod warmup;
+mod retention;
+mod proxy;
+mod access_log;
+mod logging;
+mod metrics;
+pub mod manifest;
+pub mod versioning;
+mod notation;
+mod scramble;
+mod i18n;
+pub mod events;
+pub mod server;
+
+pub use access_log::RequestId;
+pub use auth::{Admin, AuthUser, Editor};
+pub use events::{ApiEvent, EventListener};
+pub use repository::cube_repository::CubeRepository;
+pub use repository::image_store::ImageStore;
+pub use screening::ContentScreen;
+pub use repository::repo_error::RepoError;
+pub use server::{Extension, RubikApi, RubikApiBuilder};
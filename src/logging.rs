@@ -0,0 +1,77 @@
+use std::fmt;
+use std::io::Write;
+
+use mongodb::bson::DateTime;
+use rocket::serde::json::{serde_json::Map, Value};
+use tracing::field::{Field, Visit};
+use tracing::{span, Event, Level, Metadata, Subscriber};
+
+/// Subscriber of the `tracing` events of the server, which writes each one
+/// on a line of standard output as a JSON object with its timestamp, level,
+/// target and fields. Spans are not tracked.
+struct JsonLog;
+
+/// Collects the fields of an event into a JSON object.
+struct JsonFields<'a>(&'a mut Map<String, Value>);
+
+impl Visit for JsonFields<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0.insert(field.name().to_string(), format!("{:?}", value).into());
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+}
+
+impl Subscriber for JsonLog {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.is_event() && *metadata.level() <= Level::INFO
+    }
+
+    fn new_span(&self, _span: &span::Attributes<'_>) -> span::Id {
+        span::Id::from_u64(1)
+    }
+
+    fn record(&self, _span: &span::Id, _values: &span::Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &span::Id, _follows: &span::Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let metadata = event.metadata();
+        let mut line = Map::new();
+        if let Ok(timestamp) = DateTime::now().try_to_rfc3339_string() {
+            line.insert("timestamp".to_string(), timestamp.into());
+        }
+        line.insert("level".to_string(), metadata.level().as_str().into());
+        line.insert("target".to_string(), metadata.target().into());
+        event.record(&mut JsonFields(&mut line));
+        let _ = writeln!(std::io::stdout().lock(), "{}", Value::Object(line));
+    }
+
+    fn enter(&self, _span: &span::Id) {}
+
+    fn exit(&self, _span: &span::Id) {}
+}
+
+/// Writes the `tracing` events of the server as JSON lines, unless the
+/// program embedding the server already installed a subscriber of its own.
+pub fn init() {
+    let _ = tracing::subscriber::set_global_default(JsonLog);
+}
@@ -0,0 +1,1340 @@
+use std::collections::BTreeMap;
+
+use rocket::serde::json::{json, serde_json::Map, Value};
+use rocket::serde::Serialize;
+use rocket::{Build, Rocket};
+use schemars::generate::{SchemaGenerator, SchemaSettings};
+use schemars::{JsonSchema, Schema};
+
+use crate::api::graphql_api::{GraphqlRequest, GraphqlResponse};
+use crate::cube_state::training::F2lSlot;
+use crate::models::alg_export_model::AlgExport;
+use crate::models::bson_schema_model::InsertOneResultSchema;
+use crate::models::annotation_model::{Annotation, Mark, PhaseSplit, PhaseStats, VideoAttachment};
+use crate::models::api_key_model::{ApiKey, IssuedApiKey, NewApiKey};
+use crate::models::bulk_insert_model::{BulkInsertReport, InsertedCube};
+use crate::models::color_scheme_model::ColorScheme;
+use crate::models::consistency_report_model::ConsistencyReport;
+use crate::models::contribution_model::{ContributionKind, Contributions};
+use crate::models::cube_filter_model::{BulkDelete, BulkDeleteReport};
+use crate::models::cube_model::Cube;
+use crate::models::cube_record_model::CubeRecord;
+use crate::models::cube_type_model::CubeType;
+use crate::models::data_quality_model::DataQualityEntry;
+use crate::models::flagged_content_model::FlaggedContent;
+use crate::models::hardware_config_model::{ConfigSource, HardwareConfig};
+use crate::models::health_model::HealthReport;
+use crate::models::legality_model::LegalityReport;
+use crate::models::mod_model::{Mod, ModKind};
+use crate::models::notation_model::{ConversionRequest, ConversionResult};
+use crate::models::page_model::Page;
+use crate::models::puzzle_shape_model::ShapeKind;
+use crate::models::query_analytics_model::QueryStat;
+use crate::models::reconstruction_model::Reconstruction;
+use crate::models::regulation_model::{EventConstraints, Regulation, RegulationSet};
+use crate::models::retention_model::{RetentionReport, RetentionStatus};
+use crate::models::scramble_model::{
+    CommitmentCheck, CommitmentReport, ScrambleAnalysis, ScrambleRequest, ScrambleSet, SealRequest, SealedScrambleView,
+};
+use crate::models::search_model::SearchHit;
+use crate::models::setup_model::Setup;
+use crate::models::simulation_model::{Playback, PlaybackRequest};
+use crate::models::stats_model::{AverageReport, AverageRequest};
+use crate::models::training_model::TrainingCase;
+use crate::models::user_model::{Credentials, RoleChange, TokenResponse, User};
+use crate::models::world_record_model::WorldRecord;
+use crate::versioning::{self, ApiVersion};
+
+#[derive(Debug, Serialize, Clone, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ParamLocation {
+    Path,
+    Query,
+}
+
+#[derive(Debug, Serialize, Clone, JsonSchema)]
+pub struct ParamDescriptor {
+    pub name: &'static str,
+    pub location: ParamLocation,
+    #[serde(rename = "type")]
+    pub type_: &'static str,
+    pub required: bool,
+}
+
+#[derive(Debug, Serialize, Clone, JsonSchema)]
+pub struct RouteDescriptor {
+    pub name: &'static str,
+    pub method: &'static str,
+    pub path: &'static str,
+    pub params: &'static [ParamDescriptor],
+    pub body: Option<&'static str>,
+    pub response: &'static str,
+}
+
+/// Describes a path parameter.
+pub const fn path(name: &'static str) -> ParamDescriptor {
+    ParamDescriptor { name, location: ParamLocation::Path, type_: "string", required: true }
+}
+
+/// Describes a query parameter.
+pub const fn query(name: &'static str, type_: &'static str, required: bool) -> ParamDescriptor {
+    ParamDescriptor { name, location: ParamLocation::Query, type_, required }
+}
+
+/// Description of every built-in endpoint of the API. Any route mounted on
+/// the server must be listed here or in the manifest of an extension, which
+/// is enforced by [`check_routes`].
+pub const ROUTES: &[RouteDescriptor] = &[
+    RouteDescriptor {
+        name: "insert_cube",
+        method: "POST",
+        path: "/add_cube",
+        params: &[query("force", "bool", false)],
+        body: Some("Cube"),
+        response: "InsertedCube",
+    },
+    RouteDescriptor {
+        name: "insert_cubes",
+        method: "POST",
+        path: "/add_cubes",
+        params: &[query("force", "bool", false)],
+        body: Some("Vec<Cube>"),
+        response: "BulkInsertReport",
+    },
+    RouteDescriptor {
+        name: "get_cube",
+        method: "GET",
+        path: "/cube_by_id",
+        params: &[query("id", "string", true)],
+        body: None,
+        response: "Cube",
+    },
+    RouteDescriptor {
+        name: "get_cube_by_name",
+        method: "GET",
+        path: "/cube_by_name",
+        params: &[query("name", "string", true)],
+        body: None,
+        response: "Cube",
+    },
+    RouteDescriptor {
+        name: "get_cube_by_type",
+        method: "GET",
+        path: "/cube_by_type",
+        params: &[
+            query("type_", "CubeType", true),
+            query("sort_by", "string", false),
+            query("order", "string", false),
+        ],
+        body: None,
+        response: "Vec<Cube>",
+    },
+    RouteDescriptor {
+        name: "search_cubes",
+        method: "GET",
+        path: "/search",
+        params: &[query("q", "string", true), query("limit", "u64", false)],
+        body: None,
+        response: "Vec<SearchHit>",
+    },
+    RouteDescriptor {
+        name: "get_cube_by_performance",
+        method: "GET",
+        path: "/cube_by_performance",
+        params: &[
+            query("min_corner_cutting_forward", "f64", false),
+            query("min_corner_cutting_reverse", "f64", false),
+            query("min_stability", "u8", false),
+            query("min_speed", "u8", false),
+            query("one_handed", "bool", false),
+        ],
+        body: None,
+        response: "Vec<Cube>",
+    },
+    RouteDescriptor {
+        name: "get_cube_by_shape",
+        method: "GET",
+        path: "/cube_by_shape",
+        params: &[query("shape", "ShapeKind", true)],
+        body: None,
+        response: "Vec<Cube>",
+    },
+    RouteDescriptor {
+        name: "get_cube_by_holder",
+        method: "GET",
+        path: "/cube_by_holder",
+        params: &[query("holder", "string", true)],
+        body: None,
+        response: "Vec<Cube>",
+    },
+    RouteDescriptor {
+        name: "export_cubes",
+        method: "GET",
+        path: "/cubes/export",
+        params: &[query("format", "string", true)],
+        body: None,
+        response: "Csv",
+    },
+    RouteDescriptor {
+        name: "import_cubes",
+        method: "POST",
+        path: "/cubes/import",
+        params: &[query("force", "bool", false)],
+        body: Some("CubeUpload"),
+        response: "BulkInsertReport",
+    },
+    RouteDescriptor {
+        name: "get_all_cubes",
+        method: "GET",
+        path: "/cubes",
+        params: &[
+            query("page", "u64", false),
+            query("per_page", "u64", false),
+            query("sort_by", "string", false),
+            query("order", "string", false),
+            query("type_", "CubeType", false),
+            query("min_year", "u32", false),
+            query("max_year", "u32", false),
+            query("min_pieces", "u32", false),
+            query("max_pieces", "u32", false),
+            query("min_created_at", "u64", false),
+            query("max_created_at", "u64", false),
+            query("min_updated_at", "u64", false),
+            query("max_updated_at", "u64", false),
+        ],
+        body: None,
+        response: "Page<Cube>",
+    },
+    RouteDescriptor {
+        name: "update_cube",
+        method: "PUT",
+        path: "/update_cube",
+        params: &[query("id", "string", true)],
+        body: Some("Cube"),
+        response: "Cube",
+    },
+    RouteDescriptor {
+        name: "update_cube_by_name",
+        method: "PUT",
+        path: "/update_by_name",
+        params: &[query("name", "string", true)],
+        body: Some("Cube"),
+        response: "Cube",
+    },
+    RouteDescriptor {
+        name: "update_world_record",
+        method: "PUT",
+        path: "/cube/<id>/wr",
+        params: &[path("id")],
+        body: Some("WorldRecord"),
+        response: "Cube",
+    },
+    RouteDescriptor {
+        name: "upload_cube_image",
+        method: "POST",
+        path: "/cube/<id>/image",
+        params: &[path("id")],
+        body: Some("Image"),
+        response: "String",
+    },
+    RouteDescriptor {
+        name: "get_cube_image",
+        method: "GET",
+        path: "/cube/<id>/image",
+        params: &[path("id")],
+        body: None,
+        response: "Image",
+    },
+    RouteDescriptor {
+        name: "delete_cube",
+        method: "DELETE",
+        path: "/delete_cube",
+        params: &[query("id", "string", true)],
+        body: None,
+        response: "String",
+    },
+    RouteDescriptor {
+        name: "delete_cubes",
+        method: "DELETE",
+        path: "/cubes",
+        params: &[],
+        body: Some("BulkDelete"),
+        response: "BulkDeleteReport",
+    },
+    RouteDescriptor {
+        name: "restore_cube",
+        method: "POST",
+        path: "/cube/<id>/restore",
+        params: &[path("id")],
+        body: None,
+        response: "Cube",
+    },
+    RouteDescriptor {
+        name: "purge_cube",
+        method: "DELETE",
+        path: "/admin/cubes/<id>",
+        params: &[path("id")],
+        body: None,
+        response: "String",
+    },
+    RouteDescriptor {
+        name: "check_consistency",
+        method: "POST",
+        path: "/admin/check_consistency",
+        params: &[query("fix", "bool", false)],
+        body: None,
+        response: "ConsistencyReport",
+    },
+    RouteDescriptor {
+        name: "get_data_quality",
+        method: "GET",
+        path: "/admin/data_quality",
+        params: &[query("limit", "u64", false)],
+        body: None,
+        response: "Vec<DataQualityEntry>",
+    },
+    RouteDescriptor {
+        name: "get_top_queries",
+        method: "GET",
+        path: "/admin/analytics/top_queries",
+        params: &[query("limit", "u64", false)],
+        body: None,
+        response: "Vec<QueryStat>",
+    },
+    RouteDescriptor {
+        name: "get_zero_result_queries",
+        method: "GET",
+        path: "/admin/analytics/zero_results",
+        params: &[query("limit", "u64", false)],
+        body: None,
+        response: "Vec<QueryStat>",
+    },
+    RouteDescriptor {
+        name: "get_retention",
+        method: "GET",
+        path: "/admin/retention",
+        params: &[],
+        body: None,
+        response: "Vec<RetentionStatus>",
+    },
+    RouteDescriptor {
+        name: "run_retention",
+        method: "POST",
+        path: "/admin/retention/run",
+        params: &[],
+        body: None,
+        response: "Vec<RetentionReport>",
+    },
+    RouteDescriptor {
+        name: "get_top_contributors",
+        method: "GET",
+        path: "/contributors/top",
+        params: &[query("by", "ContributionKind", false), query("limit", "u64", false)],
+        body: None,
+        response: "Vec<Contributions>",
+    },
+    RouteDescriptor {
+        name: "get_contributor",
+        method: "GET",
+        path: "/contributors/<username>",
+        params: &[path("username")],
+        body: None,
+        response: "Contributions",
+    },
+    RouteDescriptor {
+        name: "get_routes_manifest",
+        method: "GET",
+        path: "/routes.json",
+        params: &[],
+        body: None,
+        response: "Vec<RouteDescriptor>",
+    },
+    RouteDescriptor {
+        name: "get_openapi",
+        method: "GET",
+        path: "/openapi.json",
+        params: &[],
+        body: None,
+        response: "OpenApiDocument",
+    },
+    RouteDescriptor {
+        name: "get_docs",
+        method: "GET",
+        path: "/docs",
+        params: &[],
+        body: None,
+        response: "Html",
+    },
+    RouteDescriptor {
+        name: "graphql_endpoint",
+        method: "POST",
+        path: "/graphql",
+        params: &[],
+        body: Some("GraphQLRequest"),
+        response: "GraphQLResponse",
+    },
+    RouteDescriptor {
+        name: "insert_setup",
+        method: "POST",
+        path: "/cube/<id>/setups",
+        params: &[path("id")],
+        body: Some("Setup"),
+        response: "InsertOneResult",
+    },
+    RouteDescriptor {
+        name: "get_cube_setups",
+        method: "GET",
+        path: "/cube/<id>/setups",
+        params: &[path("id")],
+        body: None,
+        response: "Vec<Setup>",
+    },
+    RouteDescriptor {
+        name: "get_top_cube_setups",
+        method: "GET",
+        path: "/cube/<id>/setups/top",
+        params: &[path("id"), query("limit", "u64", false)],
+        body: None,
+        response: "Vec<Setup>",
+    },
+    RouteDescriptor {
+        name: "upvote_setup",
+        method: "POST",
+        path: "/setups/<id>/upvote",
+        params: &[path("id")],
+        body: None,
+        response: "Setup",
+    },
+    RouteDescriptor {
+        name: "downvote_setup",
+        method: "POST",
+        path: "/setups/<id>/downvote",
+        params: &[path("id")],
+        body: None,
+        response: "Setup",
+    },
+    RouteDescriptor {
+        name: "insert_hardware_config",
+        method: "POST",
+        path: "/cube/<id>/configs",
+        params: &[path("id")],
+        body: Some("HardwareConfig"),
+        response: "InsertOneResult",
+    },
+    RouteDescriptor {
+        name: "get_hardware_configs",
+        method: "GET",
+        path: "/cube/<id>/configs",
+        params: &[path("id"), query("source", "ConfigSource", false)],
+        body: None,
+        response: "Vec<HardwareConfig>",
+    },
+    RouteDescriptor {
+        name: "insert_mod",
+        method: "POST",
+        path: "/mods",
+        params: &[],
+        body: Some("Mod"),
+        response: "InsertOneResult",
+    },
+    RouteDescriptor {
+        name: "get_mods",
+        method: "GET",
+        path: "/mods",
+        params: &[query("kind", "ModKind", false)],
+        body: None,
+        response: "Vec<Mod>",
+    },
+    RouteDescriptor {
+        name: "get_mod",
+        method: "GET",
+        path: "/mods/<id>",
+        params: &[path("id")],
+        body: None,
+        response: "Mod",
+    },
+    RouteDescriptor {
+        name: "get_cube_mods",
+        method: "GET",
+        path: "/cube/<id>/mods",
+        params: &[path("id"), query("kind", "ModKind", false)],
+        body: None,
+        response: "Vec<Mod>",
+    },
+    RouteDescriptor {
+        name: "get_pending_mods",
+        method: "GET",
+        path: "/admin/mods/pending",
+        params: &[],
+        body: None,
+        response: "Vec<Mod>",
+    },
+    RouteDescriptor {
+        name: "approve_mod",
+        method: "POST",
+        path: "/admin/mods/<id>/approve",
+        params: &[path("id")],
+        body: None,
+        response: "Mod",
+    },
+    RouteDescriptor {
+        name: "reject_mod",
+        method: "POST",
+        path: "/admin/mods/<id>/reject",
+        params: &[path("id")],
+        body: None,
+        response: "Mod",
+    },
+    RouteDescriptor {
+        name: "get_regulations",
+        method: "GET",
+        path: "/regulations",
+        params: &[],
+        body: None,
+        response: "RegulationSet",
+    },
+    RouteDescriptor {
+        name: "get_regulation",
+        method: "GET",
+        path: "/regulations/<id>",
+        params: &[path("id")],
+        body: None,
+        response: "Regulation",
+    },
+    RouteDescriptor {
+        name: "get_event_regulations",
+        method: "GET",
+        path: "/regulations/events/<event>",
+        params: &[path("event")],
+        body: None,
+        response: "EventConstraints",
+    },
+    RouteDescriptor {
+        name: "get_cube_legality",
+        method: "GET",
+        path: "/cube/<id>/legality",
+        params: &[path("id"), query("event", "string", true)],
+        body: None,
+        response: "LegalityReport",
+    },
+    RouteDescriptor {
+        name: "import_regulations",
+        method: "PUT",
+        path: "/admin/regulations",
+        params: &[],
+        body: Some("RegulationSet"),
+        response: "RegulationSet",
+    },
+    RouteDescriptor {
+        name: "analyze_scramble",
+        method: "POST",
+        path: "/scramble/analyze",
+        params: &[],
+        body: Some("ScrambleRequest"),
+        response: "ScrambleAnalysis",
+    },
+    RouteDescriptor {
+        name: "get_scrambles",
+        method: "GET",
+        path: "/scramble",
+        params: &[
+            query("event", "string", true),
+            query("seed", "string", false),
+            query("count", "usize", false),
+        ],
+        body: None,
+        response: "ScrambleSet",
+    },
+    RouteDescriptor {
+        name: "seal_scrambles",
+        method: "POST",
+        path: "/scramble/sealed",
+        params: &[],
+        body: Some("SealRequest"),
+        response: "SealedScrambleView",
+    },
+    RouteDescriptor {
+        name: "get_sealed_scrambles",
+        method: "GET",
+        path: "/scramble/sealed/<id>",
+        params: &[path("id")],
+        body: None,
+        response: "SealedScrambleView",
+    },
+    RouteDescriptor {
+        name: "verify_scrambles",
+        method: "POST",
+        path: "/scramble/verify",
+        params: &[],
+        body: Some("CommitmentCheck"),
+        response: "CommitmentReport",
+    },
+    RouteDescriptor {
+        name: "insert_reconstruction",
+        method: "POST",
+        path: "/reconstructions",
+        params: &[],
+        body: Some("Reconstruction"),
+        response: "InsertOneResult",
+    },
+    RouteDescriptor {
+        name: "search_reconstructions",
+        method: "GET",
+        path: "/reconstructions",
+        params: &[
+            query("solver", "string", false),
+            query("event", "string", false),
+            query("cube_id", "string", false),
+        ],
+        body: None,
+        response: "Vec<Reconstruction>",
+    },
+    RouteDescriptor {
+        name: "get_reconstruction",
+        method: "GET",
+        path: "/reconstructions/<id>",
+        params: &[path("id")],
+        body: None,
+        response: "Reconstruction",
+    },
+    RouteDescriptor {
+        name: "export_reconstruction_alg",
+        method: "GET",
+        path: "/reconstructions/<id>/alg",
+        params: &[path("id")],
+        body: None,
+        response: "AlgExport",
+    },
+    RouteDescriptor {
+        name: "attach_reconstruction_video",
+        method: "PUT",
+        path: "/reconstructions/<id>/video",
+        params: &[path("id")],
+        body: Some("VideoAttachment"),
+        response: "Reconstruction",
+    },
+    RouteDescriptor {
+        name: "annotate_reconstruction",
+        method: "POST",
+        path: "/reconstructions/<id>/annotations",
+        params: &[path("id")],
+        body: Some("Annotation"),
+        response: "Reconstruction",
+    },
+    RouteDescriptor {
+        name: "delete_reconstruction_annotation",
+        method: "DELETE",
+        path: "/reconstructions/<id>/annotations",
+        params: &[path("id"), query("mark", "Mark", true)],
+        body: None,
+        response: "Reconstruction",
+    },
+    RouteDescriptor {
+        name: "get_reconstruction_splits",
+        method: "GET",
+        path: "/reconstructions/<id>/splits",
+        params: &[path("id")],
+        body: None,
+        response: "Vec<PhaseSplit>",
+    },
+    RouteDescriptor {
+        name: "get_phase_stats",
+        method: "GET",
+        path: "/reconstructions/splits",
+        params: &[
+            query("solver", "string", false),
+            query("event", "string", false),
+            query("cube_id", "string", false),
+        ],
+        body: None,
+        response: "Vec<PhaseStats>",
+    },
+    RouteDescriptor {
+        name: "get_cube_reconstructions",
+        method: "GET",
+        path: "/cube/<id>/reconstructions",
+        params: &[path("id")],
+        body: None,
+        response: "Vec<Reconstruction>",
+    },
+    RouteDescriptor {
+        name: "get_cube_records",
+        method: "GET",
+        path: "/cube/<id>/records",
+        params: &[path("id")],
+        body: None,
+        response: "Vec<CubeRecord>",
+    },
+    RouteDescriptor {
+        name: "compute_average",
+        method: "POST",
+        path: "/stats/average",
+        params: &[],
+        body: Some("AverageRequest"),
+        response: "AverageReport",
+    },
+    RouteDescriptor {
+        name: "simulate_playback",
+        method: "POST",
+        path: "/simulate/playback",
+        params: &[],
+        body: Some("PlaybackRequest"),
+        response: "Playback",
+    },
+    RouteDescriptor {
+        name: "insert_color_scheme",
+        method: "POST",
+        path: "/color_schemes",
+        params: &[],
+        body: Some("ColorScheme"),
+        response: "InsertOneResult",
+    },
+    RouteDescriptor {
+        name: "get_color_schemes",
+        method: "GET",
+        path: "/color_schemes",
+        params: &[],
+        body: None,
+        response: "Vec<ColorScheme>",
+    },
+    RouteDescriptor {
+        name: "get_color_scheme",
+        method: "GET",
+        path: "/color_schemes/<name>",
+        params: &[path("name")],
+        body: None,
+        response: "ColorScheme",
+    },
+    RouteDescriptor {
+        name: "get_cross_case",
+        method: "GET",
+        path: "/trainer/cross",
+        params: &[query("moves", "u8", false)],
+        body: None,
+        response: "TrainingCase",
+    },
+    RouteDescriptor {
+        name: "get_f2l_case",
+        method: "GET",
+        path: "/trainer/f2l",
+        params: &[query("slot", "F2lSlot", false)],
+        body: None,
+        response: "TrainingCase",
+    },
+    RouteDescriptor {
+        name: "get_last_layer_case",
+        method: "GET",
+        path: "/trainer/last_layer",
+        params: &[],
+        body: None,
+        response: "TrainingCase",
+    },
+    RouteDescriptor {
+        name: "convert_notation",
+        method: "POST",
+        path: "/notation/convert",
+        params: &[],
+        body: Some("ConversionRequest"),
+        response: "ConversionResult",
+    },
+    RouteDescriptor {
+        name: "register",
+        method: "POST",
+        path: "/auth/register",
+        params: &[],
+        body: Some("Credentials"),
+        response: "InsertOneResult",
+    },
+    RouteDescriptor {
+        name: "login",
+        method: "POST",
+        path: "/auth/login",
+        params: &[],
+        body: Some("Credentials"),
+        response: "TokenResponse",
+    },
+    RouteDescriptor {
+        name: "get_users",
+        method: "GET",
+        path: "/admin/users",
+        params: &[],
+        body: None,
+        response: "Vec<User>",
+    },
+    RouteDescriptor {
+        name: "set_user_role",
+        method: "PUT",
+        path: "/admin/users/<username>/role",
+        params: &[path("username")],
+        body: Some("RoleChange"),
+        response: "User",
+    },
+    RouteDescriptor {
+        name: "insert_api_key",
+        method: "POST",
+        path: "/admin/api_keys",
+        params: &[],
+        body: Some("NewApiKey"),
+        response: "IssuedApiKey",
+    },
+    RouteDescriptor {
+        name: "get_api_keys",
+        method: "GET",
+        path: "/admin/api_keys",
+        params: &[],
+        body: None,
+        response: "Vec<ApiKey>",
+    },
+    RouteDescriptor {
+        name: "revoke_api_key",
+        method: "DELETE",
+        path: "/admin/api_keys/<id>",
+        params: &[path("id")],
+        body: None,
+        response: "ApiKey",
+    },
+    RouteDescriptor {
+        name: "get_flagged_content",
+        method: "GET",
+        path: "/admin/flagged",
+        params: &[],
+        body: None,
+        response: "Vec<FlaggedContent>",
+    },
+    RouteDescriptor {
+        name: "approve_flagged_content",
+        method: "POST",
+        path: "/admin/flagged/<id>/approve",
+        params: &[path("id")],
+        body: None,
+        response: "InsertOneResult",
+    },
+    RouteDescriptor {
+        name: "reject_flagged_content",
+        method: "POST",
+        path: "/admin/flagged/<id>/reject",
+        params: &[path("id")],
+        body: None,
+        response: "FlaggedContent",
+    },
+    RouteDescriptor {
+        name: "redirect_to_sandbox",
+        method: "GET",
+        path: "/sandbox/redirect",
+        params: &[],
+        body: None,
+        response: "Redirect",
+    },
+    RouteDescriptor {
+        name: "reset_sandbox",
+        method: "POST",
+        path: "/sandbox/reset",
+        params: &[],
+        body: None,
+        response: "usize",
+    },
+    RouteDescriptor {
+        name: "get_ready",
+        method: "GET",
+        path: "/ready",
+        params: &[],
+        body: None,
+        response: "String",
+    },
+    RouteDescriptor {
+        name: "get_liveness",
+        method: "GET",
+        path: "/health/live",
+        params: &[],
+        body: None,
+        response: "HealthReport",
+    },
+    RouteDescriptor {
+        name: "get_health_readiness",
+        method: "GET",
+        path: "/health/ready",
+        params: &[],
+        body: None,
+        response: "HealthReport",
+    },
+    RouteDescriptor {
+        name: "get_metrics",
+        method: "GET",
+        path: "/metrics",
+        params: &[],
+        body: None,
+        response: "Text",
+    },
+];
+
+/// Descriptions of every route of the server, the built-in ones and those
+/// of the extensions, managed as state.
+pub struct RouteManifest(pub Vec<RouteDescriptor>);
+
+/// Checks that every route mounted on the server is described in the
+/// manifest with the same method and path, and the other way around, and
+/// that the query parameters of each route are described. The manifest
+/// describes the paths without the prefix of their version, and every
+/// route it describes must be mounted under the current one. The types of
+/// the parameters and responses are checked against the handlers by the
+/// tests of this module, as routes do not carry them.
+///
+/// ## Arguments
+/// * `rocket` - the rocket instance about to be launched.
+/// * `descriptors` - descriptions of the routes that must be mounted.
+///
+/// ## Returns
+/// * A list with the mismatches found, if any.
+pub fn check_routes(rocket: &Rocket<Build>, descriptors: &[RouteDescriptor]) -> Result<(), Vec<String>> {
+    let mounted: Vec<(&str, Option<ApiVersion>, &str, Option<&str>)> = rocket
+        .routes()
+        .map(|route| {
+            let (version, path) = versioning::unversioned(route.uri.path());
+            (route.method.as_str(), version, path, route.uri.query())
+        })
+        .collect();
+
+    let mut errors = Vec::new();
+    for (method, _, path, query) in &mounted {
+        let Some(descriptor) = descriptors.iter().find(|d| d.method == *method && d.path == *path) else {
+            errors.push(format!("{} {} is mounted but missing from the manifest", method, path));
+            continue;
+        };
+        // Parameters taking the rest of the query, such as filters, stand
+        // for several described ones.
+        let names = query
+            .iter()
+            .flat_map(|query| query.split('&'))
+            .map(|segment| segment.trim_start_matches('<').trim_end_matches('>'))
+            .filter(|name| !name.is_empty() && !name.ends_with(".."));
+        for name in names {
+            let described = descriptor
+                .params
+                .iter()
+                .any(|param| param.name == name && matches!(param.location, ParamLocation::Query));
+            if !described {
+                errors.push(format!("{} {} takes the query parameter `{}`, missing from the manifest", method, path, name));
+            }
+        }
+    }
+    let current = ApiVersion::CURRENT;
+    for descriptor in descriptors {
+        let mounted = mounted
+            .iter()
+            .any(|(method, version, path, _)| (*method, *version, *path) == (descriptor.method, Some(current), descriptor.path));
+        if !mounted {
+            errors.push(format!(
+                "{} {} is in the manifest but not mounted under {}",
+                descriptor.method, descriptor.path, current.base()
+            ));
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Schema of a model named in a descriptor, derived from its type, which
+/// the generator registers under the components of the document.
+///
+/// ## Arguments
+/// * `name` - name of the model in the descriptors.
+/// * `generator` - generator of the schemas of the document.
+///
+/// ## Returns
+/// * The schema, or none if no model of the API has the name.
+fn model_schema(name: &str, generator: &mut SchemaGenerator) -> Option<Schema> {
+    let schema = match name {
+        "AlgExport" => generator.subschema_for::<AlgExport>(),
+        "Annotation" => generator.subschema_for::<Annotation>(),
+        "ApiKey" => generator.subschema_for::<ApiKey>(),
+        "AverageReport" => generator.subschema_for::<AverageReport>(),
+        "AverageRequest" => generator.subschema_for::<AverageRequest>(),
+        "BulkDelete" => generator.subschema_for::<BulkDelete>(),
+        "BulkDeleteReport" => generator.subschema_for::<BulkDeleteReport>(),
+        "BulkInsertReport" => generator.subschema_for::<BulkInsertReport>(),
+        "ColorScheme" => generator.subschema_for::<ColorScheme>(),
+        "CommitmentCheck" => generator.subschema_for::<CommitmentCheck>(),
+        "CommitmentReport" => generator.subschema_for::<CommitmentReport>(),
+        "ConfigSource" => generator.subschema_for::<ConfigSource>(),
+        "ConsistencyReport" => generator.subschema_for::<ConsistencyReport>(),
+        "ContributionKind" => generator.subschema_for::<ContributionKind>(),
+        "Contributions" => generator.subschema_for::<Contributions>(),
+        "ConversionRequest" => generator.subschema_for::<ConversionRequest>(),
+        "ConversionResult" => generator.subschema_for::<ConversionResult>(),
+        "Credentials" => generator.subschema_for::<Credentials>(),
+        "Cube" => generator.subschema_for::<Cube>(),
+        "CubeRecord" => generator.subschema_for::<CubeRecord>(),
+        "CubeType" => generator.subschema_for::<CubeType>(),
+        "DataQualityEntry" => generator.subschema_for::<DataQualityEntry>(),
+        "EventConstraints" => generator.subschema_for::<EventConstraints>(),
+        "F2lSlot" => generator.subschema_for::<F2lSlot>(),
+        "FlaggedContent" => generator.subschema_for::<FlaggedContent>(),
+        "GraphQLRequest" => generator.subschema_for::<GraphqlRequest>(),
+        "GraphQLResponse" => generator.subschema_for::<GraphqlResponse>(),
+        "HardwareConfig" => generator.subschema_for::<HardwareConfig>(),
+        "HealthReport" => generator.subschema_for::<HealthReport>(),
+        "InsertedCube" => generator.subschema_for::<InsertedCube>(),
+        "InsertOneResult" => generator.subschema_for::<InsertOneResultSchema>(),
+        "IssuedApiKey" => generator.subschema_for::<IssuedApiKey>(),
+        "LegalityReport" => generator.subschema_for::<LegalityReport>(),
+        "Mark" => generator.subschema_for::<Mark>(),
+        "Mod" => generator.subschema_for::<Mod>(),
+        "ModKind" => generator.subschema_for::<ModKind>(),
+        "NewApiKey" => generator.subschema_for::<NewApiKey>(),
+        "Page<Cube>" => generator.subschema_for::<Page<Cube>>(),
+        "PhaseSplit" => generator.subschema_for::<PhaseSplit>(),
+        "PhaseStats" => generator.subschema_for::<PhaseStats>(),
+        "Playback" => generator.subschema_for::<Playback>(),
+        "PlaybackRequest" => generator.subschema_for::<PlaybackRequest>(),
+        "QueryStat" => generator.subschema_for::<QueryStat>(),
+        "Reconstruction" => generator.subschema_for::<Reconstruction>(),
+        "Regulation" => generator.subschema_for::<Regulation>(),
+        "RegulationSet" => generator.subschema_for::<RegulationSet>(),
+        "RetentionReport" => generator.subschema_for::<RetentionReport>(),
+        "RetentionStatus" => generator.subschema_for::<RetentionStatus>(),
+        "RoleChange" => generator.subschema_for::<RoleChange>(),
+        "RouteDescriptor" => generator.subschema_for::<RouteDescriptor>(),
+        "ScrambleAnalysis" => generator.subschema_for::<ScrambleAnalysis>(),
+        "ScrambleRequest" => generator.subschema_for::<ScrambleRequest>(),
+        "ScrambleSet" => generator.subschema_for::<ScrambleSet>(),
+        "SealRequest" => generator.subschema_for::<SealRequest>(),
+        "SealedScrambleView" => generator.subschema_for::<SealedScrambleView>(),
+        "SearchHit" => generator.subschema_for::<SearchHit>(),
+        "Setup" => generator.subschema_for::<Setup>(),
+        "ShapeKind" => generator.subschema_for::<ShapeKind>(),
+        "TokenResponse" => generator.subschema_for::<TokenResponse>(),
+        "TrainingCase" => generator.subschema_for::<TrainingCase>(),
+        "User" => generator.subschema_for::<User>(),
+        "VideoAttachment" => generator.subschema_for::<VideoAttachment>(),
+        "WorldRecord" => generator.subschema_for::<WorldRecord>(),
+        _ => return None,
+    };
+    Some(schema)
+}
+
+/// Builds the JSON schema of a type named in a descriptor. The models of
+/// the API get the schema derived from their type, and those of extensions
+/// an object schema with their name, all of them referenced from the
+/// components of the document.
+fn schema_of(type_: &str, generator: &mut SchemaGenerator) -> Value {
+    if let Some(item) = type_.strip_prefix("Vec<").and_then(|rest| rest.strip_suffix('>')) {
+        return json!({"type": "array", "items": schema_of(item, generator)});
+    }
+    match type_ {
+        "string" | "String" => json!({"type": "string"}),
+        "bool" => json!({"type": "boolean"}),
+        "usize" | "u64" | "u32" | "u8" => json!({"type": "integer", "minimum": 0}),
+        "i64" | "i32" => json!({"type": "integer"}),
+        "f64" | "f32" => json!({"type": "number"}),
+        "OpenApiDocument" => json!({"type": "object"}),
+        model => match model_schema(model, generator) {
+            Some(schema) => schema.to_value(),
+            None => {
+                generator
+                    .definitions_mut()
+                    .entry(model.to_string())
+                    .or_insert_with(|| json!({"type": "object", "title": model}));
+                json!({"$ref": format!("#/components/schemas/{}", model)})
+            }
+        },
+    }
+}
+
+/// Builds an OpenAPI 3 document out of the descriptions of the routes, so
+/// that it never drifts from what the server mounts. The paths are relative
+/// to the prefix of the current version, given as the server of the API.
+///
+/// ## Arguments
+/// * `descriptors` - descriptions of the routes of the server.
+///
+/// ## Returns
+/// * The OpenAPI document on json format.
+pub fn openapi(descriptors: &[RouteDescriptor]) -> Value {
+    let mut generator = SchemaSettings::openapi3().into_generator();
+    let mut paths: BTreeMap<String, Map<String, Value>> = BTreeMap::new();
+    for descriptor in descriptors {
+        let path = descriptor.path.replace('<', "{").replace('>', "}");
+        let parameters: Vec<Value> = descriptor
+            .params
+            .iter()
+            .map(|param| {
+                json!({
+                    "name": param.name,
+                    "in": param.location,
+                    "required": param.required,
+                    "schema": schema_of(param.type_, &mut generator),
+                })
+            })
+            .collect();
+
+        let mut operation = Map::new();
+        operation.insert("operationId".to_string(), descriptor.name.into());
+        operation.insert("parameters".to_string(), Value::Array(parameters));
+        if let Some(body) = descriptor.body {
+            let content = match body {
+                "Image" => json!({"image/*": {"schema": {"type": "string", "format": "binary"}}}),
+                "CubeUpload" => json!({"multipart/form-data": {"schema": {
+                    "type": "object",
+                    "properties": {"file": {"type": "string", "format": "binary"}},
+                    "required": ["file"],
+                }}}),
+                body => json!({"application/json": {"schema": schema_of(body, &mut generator)}}),
+            };
+            operation.insert("requestBody".to_string(), json!({"required": true, "content": content}));
+        }
+        let content = match descriptor.response {
+            "Html" => json!({"text/html": {"schema": {"type": "string"}}}),
+            "Csv" => json!({"text/csv": {"schema": {"type": "string"}}}),
+            "Text" => json!({"text/plain": {"schema": {"type": "string"}}}),
+            "Image" => json!({"image/*": {"schema": {"type": "string", "format": "binary"}}}),
+            "Redirect" => json!({}),
+            response => json!({"application/json": {"schema": schema_of(response, &mut generator)}}),
+        };
+        operation.insert(
+            "responses".to_string(),
+            json!({"200": {"description": descriptor.response, "content": content}}),
+        );
+        paths
+            .entry(path)
+            .or_default()
+            .insert(descriptor.method.to_lowercase(), Value::Object(operation));
+    }
+
+    json!({
+        "openapi": "3.0.3",
+        "info": {"title": env!("CARGO_PKG_NAME"), "version": env!("CARGO_PKG_VERSION")},
+        "servers": [{"url": ApiVersion::CURRENT.base()}],
+        "paths": paths,
+        "components": {
+            "schemas": generator.take_definitions(true),
+            "securitySchemes": {
+                "bearer": {"type": "http", "scheme": "bearer", "bearerFormat": "JWT"},
+                "api_key": {"type": "apiKey", "in": "header", "name": "X-Api-Key"},
+            }
+        },
+        "security": [{}, {"bearer": []}, {"api_key": []}],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use quote::ToTokens;
+    use schemars::generate::SchemaSettings;
+    use syn::{FnArg, GenericArgument, Item, ItemFn, Pat, PathArguments, ReturnType, Type};
+
+    use super::*;
+
+    /// Responses that are not JSON, named after what the handlers return.
+    const RAW_RESPONSES: &[&str] = &["Html", "Csv", "Text", "Image", "Redirect"];
+
+    /// Parameters parsed by their handler from a string, named after the
+    /// type they are parsed into.
+    const PARSED: &[(&str, &str)] = &[("type_", "CubeType")];
+
+    /// Writes a type the way the descriptors do: without spaces, with
+    /// borrowed strings and slices as the owned types they serialize like.
+    fn text(type_: &Type) -> String {
+        let text = type_.to_token_stream().to_string().replace(' ', "");
+        match text.as_str() {
+            "&str" | "&'staticstr" => "String".to_string(),
+            // The document is built as a value, the manifest names it.
+            "Value" => "OpenApiDocument".to_string(),
+            _ => match text.strip_prefix("&[").and_then(|rest| rest.strip_suffix(']')) {
+                Some(item) => format!("Vec<{}>", item),
+                None => text,
+            },
+        }
+    }
+
+    /// Finds the first `Json<T>` in a type, through results, tuples and
+    /// wrappers such as `Tagged`, and gives its `T`.
+    fn json_of(type_: &Type) -> Option<&Type> {
+        match type_ {
+            Type::Tuple(tuple) => tuple.elems.iter().find_map(json_of),
+            Type::Path(path) => {
+                let last = path.path.segments.last()?;
+                let PathArguments::AngleBracketed(arguments) = &last.arguments else {
+                    return None;
+                };
+                let mut types = arguments.args.iter().filter_map(|argument| match argument {
+                    GenericArgument::Type(type_) => Some(type_),
+                    _ => None,
+                });
+                if last.ident == "Json" {
+                    types.next()
+                } else {
+                    types.find_map(json_of)
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Checks whether a type names another one, as a segment of its path.
+    fn names(tokens: &impl ToTokens, name: &str) -> bool {
+        fn any(stream: proc_macro2::TokenStream, name: &str) -> bool {
+            stream.into_iter().any(|token| match token {
+                proc_macro2::TokenTree::Group(group) => any(group.stream(), name),
+                token => token.to_string() == name,
+            })
+        }
+        any(tokens.to_token_stream(), name)
+    }
+
+    /// Type of a parameter as the descriptors name it, and whether it is
+    /// required.
+    fn parameter_type(type_: &Type) -> (String, bool) {
+        let text = text(type_);
+        let (inner, required) = match text.strip_prefix("Option<").and_then(|rest| rest.strip_suffix('>')) {
+            Some(inner) => (inner.to_string(), false),
+            None => (text, true),
+        };
+        match inner.as_str() {
+            // Read from the `limit` or `per_page` query parameter, with a default.
+            "PageSize" => ("u64".to_string(), false),
+            "String" | "&str" => ("string".to_string(), required),
+            _ => (inner, required),
+        }
+    }
+
+    /// Routes of the handlers of the API: the method, the path and the
+    /// name of the data parameter of their attribute, with their function.
+    fn handlers() -> Vec<(String, String, Option<String>, ItemFn)> {
+        let directory = concat!(env!("CARGO_MANIFEST_DIR"), "/src/api");
+        let mut handlers = Vec::new();
+        for entry in fs::read_dir(directory).expect("the handlers can be listed") {
+            let source = fs::read_to_string(entry.expect("the handlers can be listed").path())
+                .expect("the handlers can be read");
+            let file = syn::parse_file(&source).expect("the handlers parse");
+            for item in file.items {
+                let Item::Fn(function) = item else { continue };
+                let Some(attribute) = function.attrs.iter().find(|attribute| {
+                    ["get", "post", "put", "patch", "delete"].iter().any(|method| attribute.path().is_ident(method))
+                }) else {
+                    continue;
+                };
+                let method = attribute.path().get_ident().expect("methods are idents").to_string().to_uppercase();
+                let arguments = attribute.meta.to_token_stream().to_string();
+                let mut literals = arguments.split('"').skip(1).step_by(2);
+                let uri = literals.next().expect("routes have a URI");
+                let path = uri.split('?').next().unwrap_or(uri).to_string();
+                let data = arguments.contains("data =").then(|| {
+                    literals.next().expect("data has a parameter").trim_matches(|c| c == '<' || c == '>').to_string()
+                });
+                handlers.push((method, path, data, function));
+            }
+        }
+        handlers
+    }
+
+    #[test]
+    fn descriptors_match_the_handlers() {
+        let mut errors = Vec::new();
+        let mut checked = 0;
+        for (method, path, data, function) in handlers() {
+            let Some(descriptor) = ROUTES.iter().find(|d| d.method == method && d.path == path) else {
+                continue;
+            };
+            checked += 1;
+            let route = format!("{} {}", method, path);
+
+            let response = match &function.sig.output {
+                ReturnType::Type(_, type_) => json_of(type_).map(text),
+                ReturnType::Default => None,
+            };
+            match response {
+                Some(response) if response != descriptor.response => {
+                    errors.push(format!("{} answers {}, not {}", route, response, descriptor.response))
+                }
+                // Responders of their own, such as the GraphQL one, are
+                // described under their name.
+                None if !RAW_RESPONSES.contains(&descriptor.response) && !names(&function.sig.output, descriptor.response) => {
+                    errors.push(format!("{} does not answer JSON, not {}", route, descriptor.response))
+                }
+                _ => {}
+            }
+
+            for input in &function.sig.inputs {
+                let FnArg::Typed(argument) = input else { continue };
+                let Pat::Ident(name) = argument.pat.as_ref() else { continue };
+                let name = name.ident.to_string();
+                if data.as_deref() == Some(name.as_str()) {
+                    let body = json_of(&argument.ty).map(text);
+                    if let Some(described) = descriptor.body.filter(|body| !RAW_RESPONSES.contains(body)).filter(|_| body.is_none()) {
+                        if !names(&argument.ty, described) {
+                            errors.push(format!("{} does not take a {} body", route, described));
+                        }
+                    }
+                    if let (Some(body), Some(described)) = (&body, descriptor.body) {
+                        if body != described {
+                            errors.push(format!("{} takes a {} body, not {}", route, body, described));
+                        }
+                    }
+                    if descriptor.body.is_none() {
+                        errors.push(format!("{} takes a body, missing from the manifest", route));
+                    }
+                    continue;
+                }
+                let name = name.trim_start_matches("r#");
+                let Some(param) = descriptor.params.iter().find(|param| param.name == name) else { continue };
+                let (mut type_, required) = parameter_type(&argument.ty);
+                if let Some((_, parsed)) = PARSED.iter().find(|(parsed, _)| *parsed == name) {
+                    type_ = parsed.to_string();
+                }
+                if type_ != param.type_ || required != param.required {
+                    errors.push(format!(
+                        "{} takes `{}` as {} ({}), not {} ({})",
+                        route,
+                        name,
+                        type_,
+                        if required { "required" } else { "optional" },
+                        param.type_,
+                        if param.required { "required" } else { "optional" },
+                    ));
+                }
+            }
+            if data.is_none() && descriptor.body.is_some() {
+                errors.push(format!("{} takes no body, not {}", route, descriptor.body.unwrap_or_default()));
+            }
+        }
+        assert_eq!(checked, ROUTES.len(), "every descriptor has a handler");
+        assert!(errors.is_empty(), "the manifest does not match the handlers:\n{}", errors.join("\n"));
+    }
+
+    #[test]
+    fn every_model_has_a_schema() {
+        let mut generator = SchemaSettings::openapi3().into_generator();
+        let mut names: Vec<&str> = Vec::new();
+        for descriptor in ROUTES {
+            names.extend(descriptor.params.iter().map(|param| param.type_));
+            names.extend(descriptor.body);
+            names.push(descriptor.response);
+        }
+        let primitives = ["string", "String", "bool", "u8", "u32", "u64", "usize", "i32", "i64", "f32", "f64"];
+        let unknown: Vec<&str> = names
+            .into_iter()
+            .map(|name| name.strip_prefix("Vec<").and_then(|rest| rest.strip_suffix('>')).unwrap_or(name))
+            .filter(|name| !primitives.contains(name) && !RAW_RESPONSES.contains(name))
+            .filter(|name| !["OpenApiDocument", "CubeUpload"].contains(name))
+            .filter(|name| model_schema(name, &mut generator).is_none())
+            .collect();
+        assert!(unknown.is_empty(), "models without a schema: {:?}", unknown);
+    }
+
+    #[test]
+    fn client_snapshot_is_current() {
+        let snapshot = concat!(env!("CARGO_MANIFEST_DIR"), "/rubik_api_client/openapi.json");
+        let document = format!("{:#}\n", openapi(ROUTES));
+        if std::env::var_os("UPDATE_SNAPSHOT").is_some() {
+            fs::write(snapshot, &document).expect("the snapshot can be written");
+        }
+        let current = fs::read_to_string(snapshot).unwrap_or_default();
+        assert!(current == document, "the OpenAPI snapshot of the client is stale, rerun with UPDATE_SNAPSHOT=1");
+    }
+}
@@ -0,0 +1,187 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use mongodb::event::command::{CommandEventHandler, CommandFailedEvent, CommandSucceededEvent};
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::{Data, Request, Response};
+
+/// Upper bounds of the latency buckets, in seconds, the default ones of the
+/// Prometheus clients.
+const BUCKETS: [f64; 11] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+/// Distribution of durations over [`BUCKETS`].
+#[derive(Default)]
+struct Histogram {
+    buckets: [u64; BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn observe(&mut self, duration: Duration) {
+        let seconds = duration.as_secs_f64();
+        for (bucket, bound) in self.buckets.iter_mut().zip(BUCKETS) {
+            if seconds <= bound {
+                *bucket += 1;
+            }
+        }
+        self.sum += seconds;
+        self.count += 1;
+    }
+
+    /// Writes the series of the histogram, its buckets being cumulative.
+    fn render(&self, out: &mut String, name: &str, labels: &str) {
+        for (count, bound) in self.buckets.iter().zip(BUCKETS) {
+            let _ = writeln!(out, "{}_bucket{{{},le=\"{}\"}} {}", name, labels, bound, count);
+        }
+        let _ = writeln!(out, "{}_bucket{{{},le=\"+Inf\"}} {}", name, labels, self.count);
+        let _ = writeln!(out, "{}_sum{{{}}} {}", name, labels, self.sum);
+        let _ = writeln!(out, "{}_count{{{}}} {}", name, labels, self.count);
+    }
+}
+
+/// Escapes a label value of the Prometheus text format.
+fn label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+#[derive(Default)]
+struct Registry {
+    /// Requests served, by method, route and status.
+    requests: BTreeMap<(String, String, u16), u64>,
+    /// Time to serve the requests, by method and route.
+    latencies: BTreeMap<(String, String), Histogram>,
+    /// Time of the MongoDB commands, by command and outcome.
+    commands: BTreeMap<(String, &'static str), Histogram>,
+}
+
+/// Metrics of the server, managed as state and shared with the
+/// [`MetricsFairing`] and the MongoDB client, which reports its commands.
+#[derive(Clone, Default)]
+pub struct Metrics(Arc<Mutex<Registry>>);
+
+impl Metrics {
+    fn observe_request(&self, method: &str, route: &str, status: u16, duration: Duration) {
+        let mut registry = self.0.lock().unwrap();
+        *registry.requests.entry((method.to_string(), route.to_string(), status)).or_default() += 1;
+        registry
+            .latencies
+            .entry((method.to_string(), route.to_string()))
+            .or_default()
+            .observe(duration);
+    }
+
+    fn observe_command(&self, command: &str, outcome: &'static str, duration: Duration) {
+        let mut registry = self.0.lock().unwrap();
+        registry.commands.entry((command.to_string(), outcome)).or_default().observe(duration);
+    }
+
+    /// Writes every metric on the Prometheus text format.
+    ///
+    /// ## Returns
+    /// * The metrics, ready to be scraped.
+    pub fn render(&self) -> String {
+        let registry = self.0.lock().unwrap();
+        let mut out = String::new();
+        out.push_str("# HELP http_requests_total Requests served, by route and status.\n");
+        out.push_str("# TYPE http_requests_total counter\n");
+        for ((method, route, status), count) in &registry.requests {
+            let _ = writeln!(
+                out,
+                "http_requests_total{{method=\"{}\",route=\"{}\",status=\"{}\"}} {}",
+                method,
+                label(route),
+                status,
+                count
+            );
+        }
+        out.push_str("# HELP http_request_duration_seconds Time to serve the requests, by route.\n");
+        out.push_str("# TYPE http_request_duration_seconds histogram\n");
+        for ((method, route), histogram) in &registry.latencies {
+            let labels = format!("method=\"{}\",route=\"{}\"", method, label(route));
+            histogram.render(&mut out, "http_request_duration_seconds", &labels);
+        }
+        out.push_str("# HELP mongodb_command_duration_seconds Time of the MongoDB commands, by command and outcome.\n");
+        out.push_str("# TYPE mongodb_command_duration_seconds histogram\n");
+        for ((command, outcome), histogram) in &registry.commands {
+            let labels = format!("command=\"{}\",outcome=\"{}\"", label(command), outcome);
+            histogram.render(&mut out, "mongodb_command_duration_seconds", &labels);
+        }
+        out
+    }
+}
+
+impl CommandEventHandler for Metrics {
+    fn handle_command_succeeded_event(&self, event: CommandSucceededEvent) {
+        self.observe_command(&event.command_name, "succeeded", event.duration);
+    }
+
+    fn handle_command_failed_event(&self, event: CommandFailedEvent) {
+        self.observe_command(&event.command_name, "failed", event.duration);
+    }
+}
+
+/// When the server started handling a request.
+struct RequestStarted(Instant);
+
+/// Records the count and latency of every request in the [`Metrics`]. The
+/// requests are grouped by the route that served them, so that the number
+/// of series does not grow with the IDs in the paths.
+pub struct MetricsFairing {
+    metrics: Metrics,
+}
+
+impl MetricsFairing {
+    pub fn new(metrics: Metrics) -> Self {
+        MetricsFairing { metrics }
+    }
+}
+
+impl Fairing for MetricsFairing {
+    fn info(&self) -> Info {
+        Info {
+            name: "Metrics Fairing",
+            kind: Kind::Request | Kind::Response,
+        }
+    }
+
+    fn on_request<'life0, 'life1, 'life2, 'life3, 'life4, 'async_trait>(
+        &'life0 self,
+        request: &'life1 mut Request<'life2>,
+        _data: &'life3 mut Data<'life4>
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'async_trait>>
+    where
+        Self: 'async_trait,
+        'life0: 'async_trait,
+        'life1: 'async_trait,
+        'life2: 'async_trait,
+        'life3: 'async_trait,
+        'life4: 'async_trait,
+    {
+        request.local_cache(|| RequestStarted(Instant::now()));
+        Box::pin(async move {})
+    }
+
+    fn on_response<'r, 'life0, 'life1, 'life2, 'async_trait>(
+        &'life0 self,
+        request: &'r Request<'life1>,
+        response: &'life2 mut Response<'r>
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'async_trait>>
+    where
+        Self: 'async_trait,
+        'r: 'async_trait,
+        'life0: 'async_trait,
+        'life1: 'async_trait,
+        'life2: 'async_trait,
+    {
+        let started = request.local_cache(|| RequestStarted(Instant::now()));
+        let route = request.route().map_or("unmatched", |route| route.uri.path());
+        self.metrics
+            .observe_request(request.method().as_str(), route, response.status().code, started.0.elapsed());
+        Box::pin(async move {})
+    }
+}
@@ -0,0 +1,81 @@
+use rocket::serde::Serialize;
+use schemars::JsonSchema;
+use crate::models::reconstruction_model::Reconstruction;
+
+/// Base URL of the Twizzle editor of cubing.js.
+const TWIZZLE_URL: &str = "https://alpha.twizzle.net/edit/";
+
+/// Gets the cubing.js puzzle id of a WCA event.
+fn puzzle_id(event: &str) -> Option<&'static str> {
+    let id = match event {
+        "222" => "2x2x2",
+        "444" | "444bf" => "4x4x4",
+        "555" | "555bf" => "5x5x5",
+        "666" => "6x6x6",
+        "777" => "7x7x7",
+        "minx" => "megaminx",
+        "pyram" => "pyraminx",
+        "skewb" => "skewb",
+        "sq1" => "square1",
+        "clock" => "clock",
+        e if e.starts_with("333") => "3x3x3",
+        _ => return None,
+    };
+    Some(id)
+}
+
+/// Encodes a query string value, spaces being written as `+`.
+fn encode_query(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            b' ' => "+".to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// A scramble and its solution as cubing.js alg strings, with a Twizzle
+/// link that plays them.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct AlgExport {
+    /// cubing.js puzzle id, e.g. `3x3x3`, if the event is known.
+    pub puzzle: Option<&'static str>,
+    pub setup_alg: String,
+    pub alg: String,
+    pub twizzle_url: String,
+}
+
+impl AlgExport {
+    /// Exports the scramble and the solution of a reconstruction.
+    /// 
+    /// ## Arguments
+    /// * `reconstruction` - the reconstruction to export.
+    /// 
+    /// ## Returns
+    /// * The alg strings and the Twizzle link.
+    pub fn from_reconstruction(reconstruction: &Reconstruction) -> Self {
+        let puzzle = puzzle_id(&reconstruction.event);
+        let setup_alg = reconstruction.scramble.trim().to_string();
+        let alg = reconstruction.solution.trim().to_string();
+
+        let mut params = Vec::new();
+        if let Some(puzzle) = puzzle {
+            params.push(format!("puzzle={}", puzzle));
+        }
+        params.push(format!("setup-alg={}", encode_query(&setup_alg)));
+        params.push(format!("alg={}", encode_query(&alg)));
+        params.push(format!("title={}", encode_query(&format!(
+            "{} {} ({})",
+            reconstruction.solver, reconstruction.time, reconstruction.event
+        ))));
+
+        AlgExport {
+            puzzle,
+            twizzle_url: format!("{}?{}", TWIZZLE_URL, params.join("&")),
+            setup_alg,
+            alg,
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use rocket::serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+
+/// Moment of a solve marked on its video, in the order they happen.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, FromFormField, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Mark {
+    #[field(value = "inspection_end")]
+    InspectionEnd,
+    #[field(value = "cross_done")]
+    CrossDone,
+    #[field(value = "f2l_done")]
+    F2lDone,
+    #[field(value = "oll_start")]
+    OllStart,
+    #[field(value = "oll_done")]
+    OllDone,
+    #[field(value = "pll_start")]
+    PllStart,
+    #[field(value = "solve_end")]
+    SolveEnd,
+}
+
+impl Mark {
+    /// Name of the mark in requests and responses.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Mark::InspectionEnd => "inspection_end",
+            Mark::CrossDone => "cross_done",
+            Mark::F2lDone => "f2l_done",
+            Mark::OllStart => "oll_start",
+            Mark::OllDone => "oll_done",
+            Mark::PllStart => "pll_start",
+            Mark::SolveEnd => "solve_end",
+        }
+    }
+}
+
+/// Mark of a solve at a position of its video.
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct Annotation {
+    pub mark: Mark,
+    /// Position in the video, in milliseconds.
+    pub at_ms: u64,
+}
+
+/// Video attached to a solve.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct VideoAttachment {
+    pub video_url: String,
+}
+
+/// Time spent between two consecutive marks of a solve.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PhaseSplit {
+    pub from: Mark,
+    pub to: Mark,
+    pub duration_ms: u64,
+}
+
+/// Splits of a phase over several solves.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PhaseStats {
+    pub from: Mark,
+    pub to: Mark,
+    /// Number of solves with both marks, next to each other.
+    pub solves: usize,
+    pub mean_ms: u64,
+    pub best_ms: u64,
+}
+
+/// Checks that every mark of a solve is given once, and that the marks
+/// follow each other in the video.
+///
+/// ## Arguments
+/// * `annotations` - annotations of the solve.
+///
+/// ## Returns
+/// * A list with the problems of the annotations, if any.
+pub fn validate_annotations(annotations: &[Annotation]) -> Result<(), Vec<String>> {
+    let mut errors = Vec::new();
+    let mut sorted: Vec<&Annotation> = annotations.iter().collect();
+    sorted.sort_by_key(|annotation| annotation.mark);
+    for pair in sorted.windows(2) {
+        if pair[0].mark == pair[1].mark {
+            errors.push(format!("{} is marked more than once", pair[0].mark.key()));
+        } else if pair[0].at_ms > pair[1].at_ms {
+            errors.push(format!("{} is marked after {}", pair[0].mark.key(), pair[1].mark.key()));
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Splits a solve into the phases between its consecutive marks. Marks
+/// left out merge the phases around them.
+///
+/// ## Arguments
+/// * `annotations` - valid annotations of the solve.
+///
+/// ## Returns
+/// * The splits of the solve, in the order of its marks.
+pub fn phase_splits(annotations: &[Annotation]) -> Vec<PhaseSplit> {
+    let mut sorted: Vec<&Annotation> = annotations.iter().collect();
+    sorted.sort_by_key(|annotation| annotation.mark);
+    sorted
+        .windows(2)
+        .map(|pair| PhaseSplit {
+            from: pair[0].mark,
+            to: pair[1].mark,
+            duration_ms: pair[1].at_ms.saturating_sub(pair[0].at_ms),
+        })
+        .collect()
+}
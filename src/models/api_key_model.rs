@@ -0,0 +1,50 @@
+use mongodb::bson::oid::ObjectId;
+use rocket::serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+
+/// What a machine client holding an API key is allowed to do.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyScope {
+    /// Acts as a viewer, identifying the client on reads.
+    ReadOnly,
+    /// Acts as an editor, creating and updating data.
+    ReadWrite,
+}
+
+/// API key of a machine client. Only the SHA-256 hash of the key is
+/// stored, and it is never returned.
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct ApiKey {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<crate::models::bson_schema_model::ObjectIdSchema>")]
+    pub id: Option<ObjectId>,
+    /// Name of the client the key was issued to.
+    pub name: String,
+    pub scope: KeyScope,
+    /// Hash of the key, left out of the listings.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub key_hash: String,
+    /// Username of the user who issued the key.
+    pub created_by: String,
+    #[serde(default)]
+    pub revoked: bool,
+}
+
+/// Request to issue a new API key.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct NewApiKey {
+    pub name: String,
+    pub scope: KeyScope,
+}
+
+/// API key just issued, the only time the key itself is shown.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct IssuedApiKey {
+    #[schemars(with = "crate::models::bson_schema_model::ObjectIdSchema")]
+    pub id: ObjectId,
+    pub name: String,
+    pub scope: KeyScope,
+    /// The key, to be sent in the `X-Api-Key` header.
+    pub key: String,
+}
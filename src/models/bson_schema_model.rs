@@ -0,0 +1,76 @@
+use std::borrow::Cow;
+
+use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
+
+/// JSON schema of an `ObjectId`, which the JSON responses write as
+/// `{"$oid": "<hex>"}`. Fields holding one are derived `with` it.
+pub struct ObjectIdSchema;
+
+impl JsonSchema for ObjectIdSchema {
+    fn schema_name() -> Cow<'static, str> {
+        "ObjectId".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "type": "object",
+            "properties": {"$oid": {"type": "string", "pattern": "^[0-9a-f]{24}$"}},
+            "required": ["$oid"]
+        })
+    }
+}
+
+/// JSON schema of a BSON `DateTime`, written as
+/// `{"$date": {"$numberLong": "<milliseconds>"}}`.
+pub struct DateTimeSchema;
+
+impl JsonSchema for DateTimeSchema {
+    fn schema_name() -> Cow<'static, str> {
+        "DateTime".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "type": "object",
+            "properties": {
+                "$date": {
+                    "type": "object",
+                    "properties": {"$numberLong": {"type": "string"}},
+                    "required": ["$numberLong"]
+                }
+            },
+            "required": ["$date"]
+        })
+    }
+}
+
+/// JSON schema of a BSON document of any shape.
+pub struct DocumentSchema;
+
+impl JsonSchema for DocumentSchema {
+    fn schema_name() -> Cow<'static, str> {
+        "Document".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({"type": "object"})
+    }
+}
+
+/// JSON schema of the `InsertOneResult` of the driver, answered by the
+/// routes adding a document other than a cube: `{"insertedId": <id>}`.
+pub struct InsertOneResultSchema;
+
+impl JsonSchema for InsertOneResultSchema {
+    fn schema_name() -> Cow<'static, str> {
+        "InsertOneResult".into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "type": "object",
+            "properties": {"insertedId": generator.subschema_for::<ObjectIdSchema>()},
+            "required": ["insertedId"]
+        })
+    }
+}
@@ -0,0 +1,43 @@
+use mongodb::bson::Bson;
+use rocket::fs::TempFile;
+use rocket::serde::Serialize;
+use schemars::JsonSchema;
+
+use crate::validation::FieldError;
+
+/// ID given to a cube inserted on its own.
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct InsertedCube {
+    #[schemars(with = "crate::models::bson_schema_model::ObjectIdSchema")]
+    pub inserted_id: Bson,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct InsertedItem {
+    /// Position of the cube in the request.
+    pub index: usize,
+    #[schemars(with = "crate::models::bson_schema_model::ObjectIdSchema")]
+    pub id: Bson,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct RejectedItem {
+    /// Position of the cube in the request.
+    pub index: usize,
+    pub errors: Vec<FieldError>,
+}
+
+/// Outcome of a bulk insert: the cubes that were inserted, and why the
+/// others were not.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct BulkInsertReport {
+    pub inserted: Vec<InsertedItem>,
+    pub rejected: Vec<RejectedItem>,
+}
+
+/// Multipart form of a catalog import, with the file in its `file` field.
+#[derive(Debug, FromForm)]
+pub struct CubeUpload<'r> {
+    pub file: TempFile<'r>,
+}
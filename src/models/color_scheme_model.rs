@@ -0,0 +1,120 @@
+use mongodb::bson::oid::ObjectId;
+use rocket::serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use crate::cube_state::Face;
+
+/// Color of every face of the cube, by the face it is on when the cube is
+/// solved. Colors are free-form names, e.g. `white` or `light pink`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct FaceColors {
+    pub u: String,
+    pub r: String,
+    pub f: String,
+    pub d: String,
+    pub l: String,
+    pub b: String,
+}
+
+impl FaceColors {
+    /// Gets the color of a face.
+    pub fn color(&self, face: Face) -> &str {
+        match face {
+            Face::U => &self.u,
+            Face::R => &self.r,
+            Face::F => &self.f,
+            Face::D => &self.d,
+            Face::L => &self.l,
+            Face::B => &self.b,
+        }
+    }
+
+    /// Gets the letter a face's color is shown with on rendered nets.
+    pub fn letter(&self, face: Face) -> char {
+        self.color(face)
+            .trim()
+            .chars()
+            .next()
+            .map_or('?', |c| c.to_ascii_uppercase())
+    }
+
+    /// Validates the colors, which must be set and start with different
+    /// letters so that rendered nets are unambiguous.
+    /// 
+    /// ## Returns
+    /// * The list of errors found, if any.
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        for face in Face::ALL {
+            if self.color(face).trim().is_empty() {
+                errors.push(format!("color of face {} must not be empty", face));
+            }
+        }
+        for (i, face) in Face::ALL.iter().enumerate() {
+            if let Some(other) = Face::ALL[..i].iter().find(|o| self.letter(**o) == self.letter(*face)) {
+                errors.push(format!(
+                    "colors of faces {} and {} must start with different letters",
+                    other, face
+                ));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct ColorScheme {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<crate::models::bson_schema_model::ObjectIdSchema>")]
+    pub id: Option<ObjectId>,
+    pub name: String,
+    pub colors: FaceColors,
+}
+
+impl ColorScheme {
+    /// Gets the color schemes that are always available: the `western`
+    /// scheme, which is the default one, and the `japanese` scheme, which
+    /// swaps blue and yellow.
+    pub fn built_in() -> Vec<ColorScheme> {
+        let scheme = |name: &str, colors: [&str; 6]| ColorScheme {
+            id: None,
+            name: name.to_string(),
+            colors: FaceColors {
+                u: colors[0].to_string(),
+                r: colors[1].to_string(),
+                f: colors[2].to_string(),
+                d: colors[3].to_string(),
+                l: colors[4].to_string(),
+                b: colors[5].to_string(),
+            },
+        };
+        vec![
+            scheme("western", Face::ALL.map(Face::color)),
+            scheme("japanese", ["white", "red", "green", "blue", "orange", "yellow"]),
+        ]
+    }
+
+    /// Validates the name and the colors of the scheme.
+    /// 
+    /// ## Returns
+    /// * The list of errors found, if any.
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        if self.name.trim().is_empty() {
+            errors.push("name must not be empty".to_string());
+        }
+        if let Err(color_errors) = self.colors.validate() {
+            errors.extend(color_errors);
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use mongodb::bson::oid::ObjectId;
+use rocket::serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueKind {
+    SchemaViolation,
+    DuplicateNameKey,
+    StrayField,
+    UntrimmedName,
+    OrphanedReference,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct ConsistencyIssue {
+    pub kind: IssueKind,
+    #[schemars(with = "Option<crate::models::bson_schema_model::ObjectIdSchema>")]
+    pub cube_id: Option<ObjectId>,
+    pub detail: String,
+    pub fixable: bool,
+    pub fixed: bool,
+    /// Why the fix of a fixable issue failed, if it did.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fix_error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ConsistencyReport {
+    pub scanned: u64,
+    pub issues: Vec<ConsistencyIssue>,
+    pub fixed: u64,
+}
@@ -0,0 +1,49 @@
+use rocket::serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+
+/// Kind of work a user does on the catalog.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, FromFormField, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ContributionKind {
+    /// Cubes added, one at a time, in bulk or imported.
+    #[field(value = "cubes_added")]
+    CubesAdded,
+    /// Edits of cubes applied to the catalog.
+    #[field(value = "edits")]
+    Edits,
+    /// Mods and flagged submissions approved or rejected.
+    #[field(value = "reviews")]
+    Reviews,
+    /// Every contribution together.
+    #[field(value = "total")]
+    Total,
+}
+
+impl ContributionKind {
+    /// Field of the contributions counting this kind of work.
+    pub fn key(&self) -> &'static str {
+        match self {
+            ContributionKind::CubesAdded => "cubes_added",
+            ContributionKind::Edits => "edits",
+            ContributionKind::Reviews => "reviews",
+            ContributionKind::Total => "total",
+        }
+    }
+}
+
+/// Contributions of a user to the catalog, counted as they are made.
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct Contributions {
+    /// Name of the user, or of the client of the API key.
+    #[serde(rename = "_id")]
+    pub username: String,
+    #[serde(default)]
+    pub cubes_added: u64,
+    #[serde(default)]
+    pub edits: u64,
+    #[serde(default)]
+    pub reviews: u64,
+    /// Sum of the other counts, which ranks the contributors.
+    #[serde(default)]
+    pub total: u64,
+}
@@ -0,0 +1,66 @@
+use std::collections::HashMap;
+
+use async_graphql::InputObject;
+use rocket::serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use crate::models::cube_type_model::CubeType;
+
+/// Criteria selecting a group of cubes, read from the query of listings
+/// or from a request body. Every given criterion must match.
+#[derive(Debug, Default, Deserialize, FromForm, JsonSchema, InputObject)]
+#[graphql(rename_fields = "snake_case")]
+pub struct CubeFilter {
+    #[graphql(name = "type_")]
+    pub type_: Option<CubeType>,
+    /// Earliest year the cubes were created, inclusive.
+    pub min_year: Option<u32>,
+    /// Latest year the cubes were created, inclusive.
+    pub max_year: Option<u32>,
+    /// Least number of pieces, inclusive.
+    pub min_pieces: Option<u32>,
+    /// Largest number of pieces, inclusive.
+    pub max_pieces: Option<u32>,
+    /// Earliest insertion time, in seconds since the Unix epoch, inclusive.
+    pub min_created_at: Option<u64>,
+    /// Latest insertion time, in seconds since the Unix epoch, inclusive.
+    pub max_created_at: Option<u64>,
+    /// Earliest time of the last change, in seconds since the Unix epoch,
+    /// inclusive.
+    pub min_updated_at: Option<u64>,
+    /// Latest time of the last change, in seconds since the Unix epoch,
+    /// inclusive.
+    pub max_updated_at: Option<u64>,
+}
+
+impl CubeFilter {
+    /// Checks whether the filter has no criteria, so it would match every
+    /// cube.
+    pub fn is_empty(&self) -> bool {
+        self.type_.is_none()
+            && self.min_year.is_none()
+            && self.max_year.is_none()
+            && self.min_pieces.is_none()
+            && self.max_pieces.is_none()
+            && self.min_created_at.is_none()
+            && self.max_created_at.is_none()
+            && self.min_updated_at.is_none()
+            && self.max_updated_at.is_none()
+    }
+}
+
+/// Body of a bulk delete: the criteria of the cubes to delete and,
+/// optionally, the versions some of them must still be at.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct BulkDelete {
+    #[serde(flatten)]
+    pub filter: CubeFilter,
+    /// Expected version of the cubes, by ID. If any matching cube has
+    /// changed since, nothing is deleted.
+    #[serde(default)]
+    pub expected_versions: HashMap<String, u64>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct BulkDeleteReport {
+    pub deleted: u64,
+}
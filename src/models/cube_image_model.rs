@@ -0,0 +1,7 @@
+/// Picture of a cube, as uploaded by an editor.
+#[derive(Debug, Clone)]
+pub struct CubeImage {
+    /// Media type of the image, such as `image/png`.
+    pub content_type: String,
+    pub bytes: Vec<u8>,
+}
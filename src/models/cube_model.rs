@@ -1,17 +1,67 @@
-use mongodb::bson::oid::ObjectId;
+use async_graphql::{InputObject, SimpleObject};
+use mongodb::bson::{oid::ObjectId, Document};
 use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
 use crate::models::cube_type_model::CubeType;
 use crate::models::world_record_model::WorldRecord;
+use crate::models::performance_model::Performance;
+use crate::models::hardware_config_model::Adjustability;
+use crate::models::puzzle_shape_model::PuzzleShape;
+use crate::models::data_quality_model::DataQuality;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, SimpleObject, InputObject)]
+#[graphql(input_name = "CubeInput", rename_fields = "snake_case")]
 pub struct Cube {
     #[serde(rename = "_id", skip_serializing_if = "Option::is_none")] 
+    #[schemars(with = "Option<crate::models::bson_schema_model::ObjectIdSchema>")]
+    #[graphql(skip_input)]
     pub id: Option<ObjectId>,
     pub name: String,
+    /// Other names the cube is known by: regional names, common
+    /// misspellings or old product codes.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[graphql(default)]
+    pub aliases: Vec<String>,
+    #[graphql(name = "type_")]
     pub type_: CubeType,
     pub pieces: u32,
     pub faces: u32,
     pub stickers: u32,
     pub year_created: u32,
     pub wr: WorldRecord,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub performance: Option<Performance>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub adjustability: Option<Adjustability>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub electronics: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shape: Option<PuzzleShape>,
+    /// Completeness of the cube's data, computed by the API on every write.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[graphql(skip_input)]
+    pub quality: Option<DataQuality>,
+    /// When the cube was inserted, in seconds since the Unix epoch, set by
+    /// the API. Cubes inserted before timestamps existed have none.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[graphql(skip_input)]
+    pub created_at: Option<u64>,
+    /// When the data of the cube last changed, in seconds since the Unix
+    /// epoch, set by the API.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[graphql(skip_input)]
+    pub updated_at: Option<u64>,
+    /// Number of writes of the cube, managed by the API. Clients send it
+    /// back in `If-Match` to delete only what they last read.
+    #[serde(default)]
+    #[graphql(skip_input)]
+    pub version: u64,
+    /// Fields unknown to this version of the API, read from the stored
+    /// document. The updates never write them, so that documents written
+    /// by newer deployments survive them, and those sent by clients are
+    /// dropped.
+    #[serde(flatten, default, skip_serializing_if = "Document::is_empty")]
+    #[schemars(skip)]
+    #[graphql(skip)]
+    pub extra: Document,
 }
\ No newline at end of file
@@ -0,0 +1,57 @@
+use std::collections::BTreeMap;
+
+use mongodb::bson::oid::ObjectId;
+use rocket::serde::Serialize;
+use schemars::JsonSchema;
+use crate::models::reconstruction_model::Reconstruction;
+use crate::models::solve_time_model::SolveTime;
+
+/// Best known solve of an event done with a catalog cube.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct CubeRecord {
+    /// WCA id of the event, e.g. `333`.
+    pub event: String,
+    pub time: SolveTime,
+    pub solver: String,
+    pub competition: Option<String>,
+    pub video_url: String,
+    /// Reconstruction the record comes from.
+    #[schemars(with = "Option<crate::models::bson_schema_model::ObjectIdSchema>")]
+    pub reconstruction_id: Option<ObjectId>,
+}
+
+impl CubeRecord {
+    /// Picks the best solve of every event among the reconstructions of a
+    /// cube. DNFs and DNSs are never records.
+    ///
+    /// ## Arguments
+    /// * `reconstructions` - reconstructions of solves done with the cube.
+    ///
+    /// ## Returns
+    /// * The records of the cube, one per event, sorted by event.
+    pub fn best_per_event(reconstructions: Vec<Reconstruction>) -> Vec<CubeRecord> {
+        let mut best: BTreeMap<String, Reconstruction> = BTreeMap::new();
+        for reconstruction in reconstructions {
+            if reconstruction.time.result().is_none() {
+                continue;
+            }
+            let beaten = best
+                .get(&reconstruction.event)
+                .is_none_or(|current| reconstruction.time.rank() < current.time.rank());
+            if beaten {
+                best.insert(reconstruction.event.clone(), reconstruction);
+            }
+        }
+
+        best.into_values()
+            .map(|r| CubeRecord {
+                event: r.event,
+                time: r.time,
+                solver: r.solver,
+                competition: r.competition,
+                video_url: r.video_url,
+                reconstruction_id: r.id,
+            })
+            .collect()
+    }
+}
@@ -1,10 +1,29 @@
+use std::str::FromStr;
+
+use async_graphql::Enum;
 use rocket::serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, FromFormField, JsonSchema, Enum)]
+#[graphql(rename_items = "lowercase")]
 #[serde(rename_all = "lowercase")]
 pub enum CubeType {
     Cubic,
     Cuboid,
     Minx,
     Other
-}
\ No newline at end of file
+}
+
+impl FromStr for CubeType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "cubic" => Ok(CubeType::Cubic),
+            "cuboid" => Ok(CubeType::Cuboid),
+            "minx" => Ok(CubeType::Minx),
+            "other" => Ok(CubeType::Other),
+            _ => Err(format!("'{}' is not a cube type", s)),
+        }
+    }
+}
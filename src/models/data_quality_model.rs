@@ -0,0 +1,58 @@
+use async_graphql::SimpleObject;
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use crate::models::cube_model::Cube;
+use crate::models::cube_type_model::CubeType;
+
+/// Completeness of a cube's data.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, JsonSchema, SimpleObject)]
+#[graphql(rename_fields = "snake_case")]
+pub struct DataQuality {
+    /// Percentage of the expected fields that are filled in.
+    pub score: u8,
+    /// Expected fields that are missing, e.g. `year_created` or `wr.date`.
+    pub missing: Vec<String>,
+}
+
+impl DataQuality {
+    /// Computes the completeness of a cube: its specs, release year, world
+    /// record, performance, adjustability and electronics data, and its
+    /// shape if it is not a cubic puzzle.
+    /// 
+    /// ## Arguments
+    /// * `cube` - the cube to score.
+    /// 
+    /// ## Returns
+    /// * The score and the missing fields of the cube.
+    pub fn of(cube: &Cube) -> Self {
+        let checks = [
+            ("pieces", cube.pieces > 0),
+            ("faces", cube.faces > 0),
+            ("stickers", cube.stickers > 0),
+            ("year_created", cube.year_created > 0),
+            ("wr.holder", !cube.wr.holder.trim().is_empty()),
+            ("wr.time_ms", cube.wr.time_ms.is_some()),
+            ("wr.date", !cube.wr.date.trim().is_empty()),
+            ("performance", cube.performance.is_some()),
+            ("adjustability", cube.adjustability.is_some()),
+            ("electronics", cube.electronics.is_some()),
+            ("shape", cube.type_ == CubeType::Cubic || cube.shape.is_some()),
+        ];
+        let missing: Vec<String> = checks
+            .iter()
+            .filter(|(_, present)| !present)
+            .map(|(field, _)| field.to_string())
+            .collect();
+        let score = (checks.len() - missing.len()) * 100 / checks.len();
+        DataQuality { score: score as u8, missing }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct DataQualityEntry {
+    #[schemars(with = "Option<crate::models::bson_schema_model::ObjectIdSchema>")]
+    pub cube_id: Option<ObjectId>,
+    pub name: String,
+    pub quality: DataQuality,
+}
@@ -0,0 +1,14 @@
+use mongodb::bson::oid::ObjectId;
+use rocket::serde::Serialize;
+use schemars::JsonSchema;
+
+/// Cube of the catalog that a new cube may duplicate.
+#[derive(Debug, Serialize, Clone, JsonSchema)]
+pub struct DuplicateCandidate {
+    #[schemars(with = "Option<crate::models::bson_schema_model::ObjectIdSchema>")]
+    pub id: Option<ObjectId>,
+    pub name: String,
+    /// Edits between the normalized names, 0 when they only differ in
+    /// case, spacing or punctuation.
+    pub distance: usize,
+}
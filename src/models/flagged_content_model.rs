@@ -0,0 +1,28 @@
+use mongodb::bson::{oid::ObjectId, Document};
+use rocket::serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+
+/// Kind of a community submission held for review.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ContentKind {
+    Setup,
+    HardwareConfig,
+    Reconstruction,
+}
+
+/// Community submission flagged by the screening, which is only published
+/// once a moderator approves it. Mods are kept in their own pending queue
+/// instead, with their flags.
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct FlaggedContent {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<crate::models::bson_schema_model::ObjectIdSchema>")]
+    pub id: Option<ObjectId>,
+    pub kind: ContentKind,
+    /// The submission, as it will be published.
+    #[schemars(with = "crate::models::bson_schema_model::DocumentSchema")]
+    pub content: Document,
+    /// Why the screening flagged the submission.
+    pub reasons: Vec<String>,
+}
@@ -0,0 +1,86 @@
+use async_graphql::{InputObject, SimpleObject};
+use mongodb::bson::oid::ObjectId;
+use rocket::serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+
+/// Adjustable hardware of a cube. Every setting is the number of levels or
+/// positions available, `None` meaning that the cube cannot adjust it.
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, SimpleObject, InputObject)]
+#[graphql(input_name = "AdjustabilityInput", rename_fields = "snake_case")]
+pub struct Adjustability {
+    pub spring_compression_levels: Option<u8>,
+    pub magnet_strength_levels: Option<u8>,
+    pub dual_adjustment_positions: Option<u8>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, FromFormField, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigSource {
+    Factory,
+    Community,
+}
+
+/// A concrete configuration of the adjustable hardware of a cube. Levels
+/// and positions start at 1.
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct HardwareConfig {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<crate::models::bson_schema_model::ObjectIdSchema>")]
+    pub id: Option<ObjectId>,
+    #[serde(default)]
+    #[schemars(with = "Option<crate::models::bson_schema_model::ObjectIdSchema>")]
+    pub cube_id: Option<ObjectId>,
+    pub source: ConfigSource,
+    pub author: Option<String>,
+    pub spring_compression: Option<u8>,
+    pub magnet_strength: Option<u8>,
+    pub dual_adjustment: Option<u8>,
+    pub notes: Option<String>,
+}
+
+impl HardwareConfig {
+    /// Checks the configuration against the hardware the cube can adjust.
+    /// 
+    /// ## Arguments
+    /// * `adjustability` - adjustable hardware of the cube, if any.
+    /// 
+    /// ## Returns
+    /// * A list with the settings that the cube cannot take, if any.
+    pub fn validate(&self, adjustability: Option<&Adjustability>) -> Result<(), Vec<String>> {
+        let settings = [
+            (
+                "spring_compression",
+                self.spring_compression,
+                adjustability.and_then(|a| a.spring_compression_levels),
+            ),
+            (
+                "magnet_strength",
+                self.magnet_strength,
+                adjustability.and_then(|a| a.magnet_strength_levels),
+            ),
+            (
+                "dual_adjustment",
+                self.dual_adjustment,
+                adjustability.and_then(|a| a.dual_adjustment_positions),
+            ),
+        ];
+
+        let mut errors = Vec::new();
+        for (name, value, levels) in settings {
+            match (value, levels) {
+                (None, _) => {}
+                (Some(_), None) => errors.push(format!("the cube has no adjustable {}", name)),
+                (Some(value), Some(levels)) if value == 0 || value > levels => {
+                    errors.push(format!("{} must be between 1 and {}", name, levels))
+                }
+                _ => {}
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
@@ -0,0 +1,30 @@
+use rocket::serde::Serialize;
+use schemars::JsonSchema;
+
+/// State of a dependency of the server, or of the server itself.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    Up,
+    Down,
+}
+
+/// Result of reaching a dependency of the server.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct HealthCheck {
+    pub name: &'static str,
+    pub status: HealthStatus,
+    /// Time the dependency took to answer, or to fail.
+    pub latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Whether the server can take traffic, with the checks that tell.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct HealthReport {
+    pub status: HealthStatus,
+    /// Whether the warmup after the launch is over.
+    pub warmed_up: bool,
+    pub checks: Vec<HealthCheck>,
+}
@@ -0,0 +1,118 @@
+use mongodb::bson::oid::ObjectId;
+use rocket::serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use crate::models::cube_model::Cube;
+use crate::models::regulation_model::{EventConstraints, Regulation, RegulationSet};
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct LegalityCheck {
+    /// ID of the regulation the check is based on.
+    pub regulation: String,
+    pub passed: bool,
+    pub detail: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct LegalityReport {
+    #[schemars(with = "Option<crate::models::bson_schema_model::ObjectIdSchema>")]
+    pub cube_id: Option<ObjectId>,
+    pub event: String,
+    pub regulations_version: String,
+    pub legal: bool,
+    pub checks: Vec<LegalityCheck>,
+    /// Regulations cited by the checks.
+    pub cited: Vec<Regulation>,
+}
+
+/// Gets the number of layers of an NxNxN cube given its number of pieces.
+fn layers_from_pieces(pieces: u32) -> Option<u32> {
+    (2..=21).find(|n: &u32| n.pow(3) - (n - 2).pow(3) == pieces)
+}
+
+impl LegalityReport {
+    /// Evaluates the specs of a cube against the constraints of an event.
+    /// 
+    /// ## Arguments
+    /// * `cube` - the cube to evaluate.
+    /// * `constraints` - puzzle constraints of the event.
+    /// * `set` - regulation set the constraints come from.
+    /// 
+    /// ## Returns
+    /// * The report with every check and the regulations cited.
+    pub fn evaluate(cube: &Cube, constraints: &EventConstraints, set: &RegulationSet) -> Self {
+        let mut checks = Vec::new();
+        let mut check = |regulation: &str, passed: bool, detail: String| {
+            checks.push(LegalityCheck { regulation: regulation.to_string(), passed, detail });
+        };
+
+        if let Some(cube_type) = &constraints.cube_type {
+            check(
+                "puzzle.definition",
+                *cube_type == cube.type_,
+                format!("{} requires a {:?} puzzle, the cube is {:?}", constraints.name, cube_type, cube.type_),
+            );
+        }
+        if let Some(layers) = constraints.layers {
+            let cube_layers = layers_from_pieces(cube.pieces);
+            check(
+                "puzzle.definition",
+                cube_layers == Some(layers),
+                match cube_layers {
+                    Some(n) => format!("{} requires {} layers, the cube has {}", constraints.name, layers, n),
+                    None => format!("{} requires {} layers, the cube is not NxNxN", constraints.name, layers),
+                },
+            );
+        }
+        if let Some(faces) = constraints.faces {
+            check(
+                "puzzle.definition",
+                faces == cube.faces,
+                format!("{} requires {} faces, the cube has {}", constraints.name, faces, cube.faces),
+            );
+        }
+        if let Some(pieces) = constraints.pieces {
+            check(
+                "puzzle.definition",
+                pieces == cube.pieces,
+                format!("{} requires {} pieces, the cube has {}", constraints.name, pieces, cube.pieces),
+            );
+        }
+        if let Some(stickers) = constraints.stickers {
+            check(
+                "puzzle.stickers",
+                stickers == cube.stickers,
+                format!("{} requires {} stickers, the cube has {}", constraints.name, stickers, cube.stickers),
+            );
+        }
+        if !constraints.electronics_allowed {
+            let electronics = cube.electronics.unwrap_or(false);
+            check(
+                "puzzle.electronics",
+                !electronics,
+                if electronics {
+                    "the cube contains electronics".to_string()
+                } else {
+                    "the cube has no electronics".to_string()
+                },
+            );
+        }
+
+        let mut cited: Vec<Regulation> = Vec::new();
+        for id in checks.iter().map(|c| &c.regulation).chain(&constraints.regulations) {
+            if let Some(regulation) = set.regulation(id) {
+                if !cited.iter().any(|r| r.id == regulation.id) {
+                    cited.push(regulation.clone());
+                }
+            }
+        }
+
+        LegalityReport {
+            cube_id: cube.id,
+            event: constraints.event.clone(),
+            regulations_version: set.version.clone(),
+            legal: checks.iter().all(|c| c.passed),
+            checks,
+            cited,
+        }
+    }
+}
@@ -1,3 +1,38 @@
 pub mod cube_model;
 pub mod world_record_model;
-pub mod cube_type_model;
\ No newline at end of file
+pub mod cube_type_model;
+pub mod consistency_report_model;
+pub mod performance_model;
+pub mod setup_model;
+pub mod hardware_config_model;
+pub mod mod_model;
+pub mod regulation_model;
+pub mod legality_model;
+pub mod puzzle_shape_model;
+pub mod scramble_model;
+pub mod reconstruction_model;
+pub mod annotation_model;
+pub mod stats_model;
+pub mod solve_time_model;
+pub mod simulation_model;
+pub mod color_scheme_model;
+pub mod training_model;
+pub mod alg_export_model;
+pub mod notation_model;
+pub mod query_analytics_model;
+pub mod data_quality_model;
+pub mod page_model;
+pub mod sort_model;
+pub mod bulk_insert_model;
+pub mod cube_record_model;
+pub mod cube_filter_model;
+pub mod search_model;
+pub mod user_model;
+pub mod api_key_model;
+pub mod flagged_content_model;
+pub mod cube_image_model;
+pub mod contribution_model;
+pub mod duplicate_model;
+pub mod retention_model;
+pub mod health_model;
+pub mod bson_schema_model;
@@ -0,0 +1,74 @@
+use mongodb::bson::oid::ObjectId;
+use rocket::serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, FromFormField, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ModKind {
+    Magnetization,
+    Lube,
+    Sticker,
+    Other,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ModStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct Mod {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<crate::models::bson_schema_model::ObjectIdSchema>")]
+    pub id: Option<ObjectId>,
+    pub title: String,
+    pub kind: ModKind,
+    pub author: String,
+    pub steps: Vec<String>,
+    /// Difficulty of the mod, from 1 to 5.
+    pub difficulty: u8,
+    pub materials: Vec<String>,
+    #[schemars(with = "Vec<crate::models::bson_schema_model::ObjectIdSchema>")]
+    pub cube_ids: Vec<ObjectId>,
+    #[serde(default = "default_status")]
+    pub status: ModStatus,
+    /// Why the screening flagged the mod, for its moderators.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub flags: Vec<String>,
+}
+
+fn default_status() -> ModStatus {
+    ModStatus::Pending
+}
+
+impl Mod {
+    /// Checks that the mod has a title, at least one step, applies to at
+    /// least one cube and has a valid difficulty.
+    /// 
+    /// ## Returns
+    /// * A list with the invalid fields, if any.
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        if self.title.trim().is_empty() {
+            errors.push("title must not be empty".to_string());
+        }
+        if self.steps.is_empty() {
+            errors.push("steps must not be empty".to_string());
+        }
+        if self.cube_ids.is_empty() {
+            errors.push("cube_ids must not be empty".to_string());
+        }
+        if !(1..=5).contains(&self.difficulty) {
+            errors.push("difficulty must be between 1 and 5".to_string());
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
@@ -0,0 +1,21 @@
+use rocket::serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use crate::notation::{Notation, NotationPuzzle};
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ConversionRequest {
+    /// Puzzle the moves are for, a cube by default.
+    pub puzzle: Option<NotationPuzzle>,
+    pub from: Notation,
+    pub to: Notation,
+    pub alg: String,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ConversionResult {
+    pub puzzle: NotationPuzzle,
+    pub from: Notation,
+    pub to: Notation,
+    pub alg: String,
+    pub moves: usize,
+}
@@ -0,0 +1,25 @@
+use async_graphql::{OutputType, SimpleObject};
+use rocket::serde::Serialize;
+use schemars::JsonSchema;
+
+use crate::models::cube_model::Cube;
+
+/// One page of a listing, with what clients need to page through the rest.
+#[derive(Debug, Serialize, JsonSchema, SimpleObject)]
+#[graphql(concrete(name = "CubePage", params(Cube)), rename_fields = "snake_case")]
+pub struct Page<T: OutputType> {
+    pub items: Vec<T>,
+    /// Number of the page, starting at 1.
+    pub page: u64,
+    pub per_page: u64,
+    /// Number of items in the whole listing.
+    pub total: u64,
+    /// Number of pages of the listing.
+    pub pages: u64,
+}
+
+impl<T: OutputType> Page<T> {
+    pub fn new(items: Vec<T>, page: u64, per_page: u64, total: u64) -> Self {
+        Page { items, page, per_page, total, pages: total.div_ceil(per_page) }
+    }
+}
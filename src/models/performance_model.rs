@@ -0,0 +1,56 @@
+use async_graphql::{InputObject, SimpleObject};
+use rocket::serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, SimpleObject, InputObject)]
+#[graphql(input_name = "PerformanceInput", rename_fields = "snake_case")]
+pub struct Performance {
+    /// Forward corner cutting, in degrees.
+    pub corner_cutting_forward: f64,
+    /// Reverse corner cutting, in degrees.
+    pub corner_cutting_reverse: f64,
+    /// Stability rating, from 1 to 10.
+    pub stability: u8,
+    /// Speed rating, from 1 to 10.
+    pub speed: u8,
+    /// Whether the cube is suited for one-handed solving.
+    pub one_handed: bool,
+}
+
+impl Performance {
+    /// Checks that the performance values are within their valid ranges.
+    /// 
+    /// ## Returns
+    /// * A list with the fields that are out of range, if any.
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        if !(0.0..=90.0).contains(&self.corner_cutting_forward) {
+            errors.push("corner_cutting_forward must be between 0 and 90 degrees".to_string());
+        }
+        if !(0.0..=90.0).contains(&self.corner_cutting_reverse) {
+            errors.push("corner_cutting_reverse must be between 0 and 90 degrees".to_string());
+        }
+        if !(1..=10).contains(&self.stability) {
+            errors.push("stability must be between 1 and 10".to_string());
+        }
+        if !(1..=10).contains(&self.speed) {
+            errors.push("speed must be between 1 and 10".to_string());
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Optional lower bounds used to filter cubes by their performance.
+#[derive(Debug, FromForm)]
+pub struct PerformanceFilter {
+    pub min_corner_cutting_forward: Option<f64>,
+    pub min_corner_cutting_reverse: Option<f64>,
+    pub min_stability: Option<u8>,
+    pub min_speed: Option<u8>,
+    pub one_handed: Option<bool>,
+}
@@ -0,0 +1,78 @@
+use async_graphql::{Enum, InputObject, SimpleObject};
+use rocket::serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, FromFormField, JsonSchema, Enum)]
+#[graphql(rename_items = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ShapeKind {
+    Megaminx,
+    Pyraminx,
+    #[field(value = "square_one")]
+    SquareOne,
+    Skewb,
+    Clock,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, JsonSchema, Enum)]
+#[graphql(rename_items = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ScrambleNotation {
+    /// Random-state scrambles written with WCA move notation.
+    Wca,
+    /// Pochmann `R++ D--` notation of megaminx scrambles.
+    Pochmann,
+    /// `(x, y)/` notation of square-1 scrambles.
+    SquareOne,
+    /// Pin and dial notation of clock scrambles.
+    Clock,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, SimpleObject, InputObject)]
+#[graphql(input_name = "PieceCountInput", rename_fields = "snake_case")]
+pub struct PieceCount {
+    /// Kind of piece, e.g. `corner`, `edge`, `tip`, `dial`.
+    pub kind: String,
+    pub count: u32,
+}
+
+/// Shape-specific description of a puzzle that is not a regular NxNxN cube.
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, SimpleObject, InputObject)]
+#[graphql(input_name = "PuzzleShapeInput", rename_fields = "snake_case")]
+pub struct PuzzleShape {
+    pub shape: ShapeKind,
+    pub face_count: u32,
+    pub piece_types: Vec<PieceCount>,
+    pub scramble_notation: ScrambleNotation,
+}
+
+impl PuzzleShape {
+    /// Checks that the shape agrees with the faces and pieces of the cube
+    /// it describes.
+    /// 
+    /// ## Arguments
+    /// * `faces` - number of faces of the cube.
+    /// * `pieces` - number of pieces of the cube.
+    /// 
+    /// ## Returns
+    /// * A list with the inconsistencies found, if any.
+    pub fn validate(&self, faces: u32, pieces: u32) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        if self.face_count != faces {
+            errors.push(format!("face_count is {} but the cube has {} faces", self.face_count, faces));
+        }
+        if self.piece_types.iter().any(|p| p.kind.trim().is_empty() || p.count == 0) {
+            errors.push("piece_types must have a kind and a positive count".to_string());
+        }
+        let total: u32 = self.piece_types.iter().map(|p| p.count).sum();
+        if !self.piece_types.is_empty() && total != pieces {
+            errors.push(format!("piece_types add up to {} but the cube has {} pieces", total, pieces));
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use mongodb::bson::{oid::ObjectId, DateTime};
+use rocket::serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+
+/// Longest search term kept, longer ones are truncated.
+const MAX_TERM_LENGTH: usize = 64;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryKind {
+    Name,
+    Type,
+}
+
+/// A sampled catalog lookup. Only the normalized term, the number of
+/// results and the day are kept, nothing about who made the request.
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct QueryEvent {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<crate::models::bson_schema_model::ObjectIdSchema>")]
+    pub id: Option<ObjectId>,
+    pub kind: QueryKind,
+    pub term: String,
+    pub results: u64,
+    /// Number of lookups the event stands for, given the sampling rate.
+    pub weight: i64,
+    /// Day of the lookup, at midnight UTC.
+    #[schemars(with = "crate::models::bson_schema_model::DateTimeSchema")]
+    pub day: DateTime,
+}
+
+impl QueryEvent {
+    /// Normalizes a search term: trimmed, lowercase, with single spaces and
+    /// truncated, so that the same query is always counted together.
+    pub fn normalize(term: &str) -> String {
+        term.split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_lowercase()
+            .chars()
+            .take(MAX_TERM_LENGTH)
+            .collect()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct QueryStat {
+    pub kind: QueryKind,
+    pub term: String,
+    /// Estimated number of lookups, scaled up from the sampled ones.
+    pub searches: i64,
+}
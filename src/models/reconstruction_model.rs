@@ -0,0 +1,72 @@
+use mongodb::bson::oid::ObjectId;
+use rocket::serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use crate::models::annotation_model::{self, Annotation};
+use crate::models::solve_time_model::SolveTime;
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct Reconstruction {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<crate::models::bson_schema_model::ObjectIdSchema>")]
+    pub id: Option<ObjectId>,
+    pub video_url: String,
+    pub solver: String,
+    pub competition: Option<String>,
+    /// WCA id of the event, e.g. `333`.
+    pub event: String,
+    pub scramble: String,
+    pub solution: String,
+    pub time: SolveTime,
+    /// Catalog cube the solve was done with, if known.
+    #[schemars(with = "Option<crate::models::bson_schema_model::ObjectIdSchema>")]
+    pub cube_id: Option<ObjectId>,
+    /// Marks of the phases of the solve on its video.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub annotations: Vec<Annotation>,
+}
+
+/// Checks that a video is linked with an http(s) URL.
+pub fn is_video_url(url: &str) -> bool {
+    url.starts_with("https://") || url.starts_with("http://")
+}
+
+impl Reconstruction {
+    /// Checks that the reconstruction links to a video and has a solver,
+    /// an event, a scramble, a solution and valid annotations.
+    /// 
+    /// ## Returns
+    /// * A list with the invalid fields, if any.
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        if !is_video_url(&self.video_url) {
+            errors.push("video_url must be an http(s) URL".to_string());
+        }
+        for (name, value) in [
+            ("solver", &self.solver),
+            ("event", &self.event),
+            ("scramble", &self.scramble),
+            ("solution", &self.solution),
+        ] {
+            if value.trim().is_empty() {
+                errors.push(format!("{} must not be empty", name));
+            }
+        }
+        if let Err(annotations) = annotation_model::validate_annotations(&self.annotations) {
+            errors.extend(annotations);
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Optional criteria used to search reconstructions.
+#[derive(Debug, FromForm)]
+pub struct ReconstructionFilter {
+    pub solver: Option<String>,
+    pub event: Option<String>,
+    pub cube_id: Option<String>,
+}
@@ -0,0 +1,98 @@
+use rocket::serde::{json::serde_json, Deserialize, Serialize};
+use schemars::JsonSchema;
+use crate::models::cube_type_model::CubeType;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum RegulationCategory {
+    Puzzle,
+    Logo,
+    Event,
+}
+
+/// Machine-readable summary of a WCA regulation.
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct Regulation {
+    pub id: String,
+    /// Article of the WCA Regulations the rule comes from.
+    pub article: String,
+    pub category: RegulationCategory,
+    pub summary: String,
+}
+
+/// Puzzle constraints of a WCA event. `None` means that the event does not
+/// constrain that spec.
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct EventConstraints {
+    /// WCA event id, e.g. `333`.
+    pub event: String,
+    pub name: String,
+    pub cube_type: Option<CubeType>,
+    /// Number of layers of NxNxN puzzles.
+    pub layers: Option<u32>,
+    pub faces: Option<u32>,
+    pub pieces: Option<u32>,
+    pub stickers: Option<u32>,
+    pub electronics_allowed: bool,
+    /// IDs of the regulations that apply to the event.
+    pub regulations: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct RegulationSet {
+    pub version: String,
+    pub regulations: Vec<Regulation>,
+    pub events: Vec<EventConstraints>,
+}
+
+impl RegulationSet {
+    /// Gets the subset of the WCA regulations shipped with the API, used
+    /// until an admin imports a newer set.
+    /// 
+    /// ## Returns
+    /// * The embedded regulation set.
+    pub fn embedded() -> Self {
+        serde_json::from_str(include_str!("../data/wca_regulations.json"))
+            .expect("Embedded WCA regulations are not valid")
+    }
+
+    /// Checks that every event only references known regulations and that
+    /// ids are not repeated.
+    /// 
+    /// ## Returns
+    /// * A list with the problems found, if any.
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        for (i, regulation) in self.regulations.iter().enumerate() {
+            if self.regulations[..i].iter().any(|r| r.id == regulation.id) {
+                errors.push(format!("regulation `{}` is repeated", regulation.id));
+            }
+        }
+        for (i, event) in self.events.iter().enumerate() {
+            if self.events[..i].iter().any(|e| e.event == event.event) {
+                errors.push(format!("event `{}` is repeated", event.event));
+            }
+            for id in &event.regulations {
+                if self.regulation(id).is_none() {
+                    errors.push(format!("event `{}` references unknown regulation `{}`", event.event, id));
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Finds a regulation of the set by its ID.
+    pub fn regulation(&self, id: &str) -> Option<&Regulation> {
+        self.regulations.iter().find(|r| r.id == id)
+    }
+
+    /// Finds the constraints of an event of the set by its WCA id.
+    pub fn event(&self, event: &str) -> Option<&EventConstraints> {
+        self.events.iter().find(|e| e.event == event)
+    }
+}
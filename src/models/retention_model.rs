@@ -0,0 +1,30 @@
+use rocket::serde::Serialize;
+use schemars::JsonSchema;
+
+use crate::retention::CollectionRetention;
+
+/// What a run of the retention did to a collection.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct RetentionReport {
+    pub collection: &'static str,
+    /// Records older than the policy allows, or beyond its size, removed
+    /// from the collection.
+    pub expired: u64,
+    /// Whether the expired records were downsampled into the archive
+    /// before being removed.
+    pub archived: bool,
+    pub remaining: u64,
+}
+
+/// Size of a collection under a retention policy.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct RetentionStatus {
+    pub collection: &'static str,
+    pub records: u64,
+    /// Day of the oldest record kept, on RFC 3339 format.
+    pub oldest: Option<String>,
+    /// Collection the expired records are downsampled into.
+    pub archive: &'static str,
+    pub archived_records: u64,
+    pub policy: CollectionRetention,
+}
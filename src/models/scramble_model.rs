@@ -0,0 +1,193 @@
+use mongodb::bson::oid::ObjectId;
+use rocket::serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use crate::cube_state::{analysis, CubeState, Face, Move};
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ScrambleRequest {
+    pub scramble: String,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct CrossAnalysis {
+    pub face: Face,
+    pub color: &'static str,
+    /// Moves of an optimal solution of the cross.
+    pub optimal_moves: u8,
+    /// F2L corners that are already joined with their edge.
+    pub paired_slots: u8,
+    /// F2L slots that are already solved.
+    pub solved_slots: u8,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct RouxBlock {
+    pub side: Face,
+    pub bottom: Face,
+    /// Pieces of the block, out of five, that are already solved.
+    pub solved_pieces: u8,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ScrambleAnalysis {
+    pub scramble: String,
+    pub moves: usize,
+    pub crosses: Vec<CrossAnalysis>,
+    pub easiest_cross: Face,
+    pub best_roux_block: RouxBlock,
+}
+
+impl ScrambleAnalysis {
+    /// Computes the difficulty heuristics of a 3x3 scramble.
+    /// 
+    /// ## Arguments
+    /// * `moves` - the moves of the scramble.
+    /// 
+    /// ## Returns
+    /// * The analysis of the scramble.
+    pub fn analyze(moves: &[Move]) -> Self {
+        let mut state = CubeState::solved();
+        state.apply_all(moves);
+
+        let crosses: Vec<CrossAnalysis> = Face::ALL
+            .into_iter()
+            .map(|face| {
+                let (paired_slots, solved_slots) = analysis::f2l_pairs(&state, face);
+                CrossAnalysis {
+                    face,
+                    color: face.color(),
+                    optimal_moves: analysis::cross_moves(&state, face),
+                    paired_slots,
+                    solved_slots,
+                }
+            })
+            .collect();
+        let easiest_cross = crosses
+            .iter()
+            .min_by_key(|c| (c.optimal_moves, u8::MAX - c.paired_slots))
+            .map(|c| c.face)
+            .unwrap();
+        let (side, bottom, solved_pieces) = analysis::roux_blocks(&state)
+            .into_iter()
+            .max_by_key(|(_, _, solved)| *solved)
+            .unwrap();
+
+        ScrambleAnalysis {
+            scramble: moves.iter().map(Move::to_string).collect::<Vec<_>>().join(" "),
+            moves: moves.len(),
+            crosses,
+            easiest_cross,
+            best_roux_block: RouxBlock { side, bottom, solved_pieces },
+        }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ScrambleSet {
+    pub event: String,
+    /// Seed the scrambles were drawn from, generated if none was given.
+    pub seed: String,
+    /// Pseudo-random generator used, seeded with the FNV-1a hash of `seed`.
+    pub rng: &'static str,
+    /// Version of the scramble generation, see [`crate::scramble::SCRAMBLE_VERSION`].
+    pub version: u32,
+    pub scrambles: Vec<String>,
+}
+
+/// Body of the sealing of a scramble set.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SealRequest {
+    pub event: String,
+    pub count: Option<usize>,
+    /// When the scrambles are revealed, in seconds since the Unix epoch.
+    pub reveal_at: u64,
+}
+
+/// Scramble set kept secret until its reveal time, with the commitment
+/// published beforehand.
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SealedScrambles {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<crate::models::bson_schema_model::ObjectIdSchema>")]
+    pub id: Option<ObjectId>,
+    pub event: String,
+    pub version: u32,
+    pub count: usize,
+    /// SHA-256 of the set, see [`crate::scramble::commitment`].
+    pub commitment: String,
+    /// When the scrambles are revealed, in seconds since the Unix epoch.
+    pub reveal_at: u64,
+    /// Name of the user who sealed the set.
+    pub sealed_by: String,
+    pub seed: String,
+    pub scrambles: Vec<String>,
+}
+
+/// Sealed scramble set as shown to the clients: the seed and the scrambles
+/// are only there once they are revealed.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SealedScrambleView {
+    #[schemars(with = "Option<crate::models::bson_schema_model::ObjectIdSchema>")]
+    pub id: Option<ObjectId>,
+    pub event: String,
+    pub version: u32,
+    pub rng: &'static str,
+    pub count: usize,
+    pub commitment: String,
+    pub reveal_at: u64,
+    pub sealed_by: String,
+    pub revealed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scrambles: Option<Vec<String>>,
+}
+
+impl SealedScrambleView {
+    /// Shows a sealed set, hiding its seed and scrambles until the reveal
+    /// time.
+    ///
+    /// ## Arguments
+    /// * `sealed` - the sealed set.
+    /// * `now` - current time, in seconds since the Unix epoch.
+    ///
+    /// ## Returns
+    /// * The set as clients can see it.
+    pub fn of(sealed: SealedScrambles, now: u64) -> Self {
+        let revealed = now >= sealed.reveal_at;
+        SealedScrambleView {
+            id: sealed.id,
+            event: sealed.event,
+            version: sealed.version,
+            rng: crate::scramble::RNG_ALGORITHM,
+            count: sealed.count,
+            commitment: sealed.commitment,
+            reveal_at: sealed.reveal_at,
+            sealed_by: sealed.sealed_by,
+            revealed,
+            seed: revealed.then_some(sealed.seed),
+            scrambles: revealed.then_some(sealed.scrambles),
+        }
+    }
+}
+
+/// Revealed scramble set to check against its commitment.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CommitmentCheck {
+    pub event: String,
+    pub version: u32,
+    pub seed: String,
+    pub scrambles: Vec<String>,
+    pub commitment: String,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct CommitmentReport {
+    /// SHA-256 of the set sent.
+    pub commitment: String,
+    /// Whether it is the commitment published before the reveal.
+    pub matches: bool,
+    /// Whether the seed gives the scrambles sent, only known for sets of
+    /// the current scramble version.
+    pub reproducible: Option<bool>,
+}
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use crate::models::cube_model::Cube;
+
+/// A cube matching a full-text search, with its relevance.
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SearchHit {
+    /// Text score given by Mongo, the higher the more relevant.
+    pub score: f64,
+    pub cube: Cube,
+}
@@ -0,0 +1,26 @@
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct LubeApplication {
+    pub lube: String,
+    pub amount: String,
+    pub applied_to: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct Setup {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<crate::models::bson_schema_model::ObjectIdSchema>")]
+    pub id: Option<ObjectId>,
+    #[serde(default)]
+    #[schemars(with = "Option<crate::models::bson_schema_model::ObjectIdSchema>")]
+    pub cube_id: Option<ObjectId>,
+    pub author: String,
+    pub lubes: Vec<LubeApplication>,
+    pub tension: String,
+    pub feel: String,
+    #[serde(default)]
+    pub votes: i64,
+}
@@ -0,0 +1,79 @@
+use rocket::serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use crate::cube_state::{facelets, CubeState, Face, Move};
+use crate::models::color_scheme_model::FaceColors;
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct PlaybackRequest {
+    /// Moves to play in WCA notation, e.g. `R U R' U'` or `Rw 3Uw'`.
+    pub moves: String,
+    /// Number of layers of the cube, from 2 to 7. A 3x3 by default.
+    pub size: Option<usize>,
+    /// Whether to include a rendered net of every state.
+    #[serde(default)]
+    pub render: bool,
+    /// Name of the color scheme to apply, built-in or stored.
+    pub scheme: Option<String>,
+    /// Colors to apply, taking precedence over `scheme`.
+    pub colors: Option<FaceColors>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PlaybackFrame {
+    /// Number of moves applied so far.
+    pub step: usize,
+    /// Move that led to this state, none for the initial state.
+    #[serde(rename = "move", skip_serializing_if = "Option::is_none")]
+    pub move_: Option<String>,
+    /// Facelet string of the state, faces in URFDLB order.
+    pub facelets: String,
+    /// Color shown at every position, when a color scheme is applied.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub colors: Option<Vec<String>>,
+    /// Unfolded net of the state, one line per row of stickers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct Playback {
+    pub size: usize,
+    pub moves: usize,
+    /// Every state of the cube, from the solved one to the final one.
+    pub frames: Vec<PlaybackFrame>,
+}
+
+impl Playback {
+    /// Plays a sequence of moves on a solved cube, recording every state.
+    /// 
+    /// ## Arguments
+    /// * `size` - the number of layers of the cube.
+    /// * `moves` - the moves to play.
+    /// * `render` - whether to render a net of every state.
+    /// * `colors` - the color scheme to apply, the default one if none.
+    /// 
+    /// ## Returns
+    /// * The states of the cube after every move.
+    pub fn play(size: usize, moves: &[Move], render: bool, colors: Option<&FaceColors>) -> Self {
+        let mut state = CubeState::solved_of(size);
+        let letter = |face: Face| colors.map_or_else(|| face.color_letter(), |c| c.letter(face));
+        let frame = |step: usize, move_: Option<&Move>, state: &CubeState| PlaybackFrame {
+            step,
+            move_: move_.map(Move::to_string),
+            facelets: state.facelet_string(),
+            colors: colors.map(|c| {
+                (0..facelets(state.size()))
+                    .map(|p| c.color(state.face_at(p)).to_string())
+                    .collect()
+            }),
+            net: render.then(|| state.net(letter)),
+        };
+
+        let mut frames = vec![frame(0, None, &state)];
+        for (i, m) in moves.iter().enumerate() {
+            state.apply(*m);
+            frames.push(frame(i + 1, Some(m), &state));
+        }
+        Playback { size: state.size(), moves: moves.len(), frames }
+    }
+}
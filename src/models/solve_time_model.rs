@@ -0,0 +1,220 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::str::FromStr;
+
+use rocket::serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
+
+/// Centiseconds added by a +2 penalty.
+const PLUS_TWO: u32 = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Penalty {
+    None,
+    PlusTwo,
+    Dnf,
+    Dns,
+}
+
+/// Time of a solve together with its penalty. It is stored and serialized
+/// the way results are displayed: `12.34`, `14.34+` for a +2 (the penalty
+/// is included in the time shown), `DNF`, `DNF(12.34)` or `DNS`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SolveTime {
+    /// Time measured by the timer, in centiseconds, if any.
+    pub time: Option<u32>,
+    pub penalty: Penalty,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeError {
+    pub input: String,
+}
+
+impl fmt::Display for TimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` is not a valid time", self.input)
+    }
+}
+
+impl SolveTime {
+    /// Builds a solve without penalty.
+    pub fn new(centiseconds: u32) -> Self {
+        SolveTime { time: Some(centiseconds), penalty: Penalty::None }
+    }
+
+    /// Builds a DNF without a known time.
+    pub fn dnf() -> Self {
+        SolveTime { time: None, penalty: Penalty::Dnf }
+    }
+
+    /// Builds a solve from a result in centiseconds, `None` being a DNF.
+    pub fn from_result(result: Option<u32>) -> Self {
+        result.map_or_else(SolveTime::dnf, SolveTime::new)
+    }
+
+    /// Gets the result of the solve in centiseconds, with the penalty
+    /// applied. DNFs and DNSs have no result, nor times too long to be
+    /// counted with their penalty.
+    pub fn result(&self) -> Option<u32> {
+        match self.penalty {
+            Penalty::None => self.time,
+            Penalty::PlusTwo => self.time.and_then(|t| t.checked_add(PLUS_TWO)),
+            Penalty::Dnf | Penalty::Dns => None,
+        }
+    }
+
+    /// Key to sort solves from best to worst, DNFs and DNSs being the worst.
+    pub fn rank(&self) -> u32 {
+        self.result().unwrap_or(u32::MAX)
+    }
+}
+
+/// Parses a time in centiseconds written as `12.34`, `1:02.35` or
+/// `1:02:03.45`. Times that do not fit in centiseconds are refused.
+fn parse_centiseconds(text: &str) -> Option<u32> {
+    let mut centiseconds: u32 = 0;
+    for (i, part) in text.rsplit(':').enumerate() {
+        let value = if i == 0 {
+            let (seconds, fraction) = part.split_once('.').unwrap_or((part, ""));
+            if fraction.len() > 2 || !fraction.chars().all(|c| c.is_ascii_digit()) {
+                return None;
+            }
+            let seconds: u32 = seconds.parse().ok()?;
+            let fraction: u32 = format!("{:0<2}", fraction).parse().ok()?;
+            seconds.checked_mul(100)?.checked_add(fraction)?
+        } else if i <= 2 {
+            let value: u32 = part.parse().ok()?;
+            value.checked_mul(100 * 60u32.pow(i as u32))?
+        } else {
+            return None;
+        };
+        centiseconds = centiseconds.checked_add(value)?;
+    }
+    Some(centiseconds)
+}
+
+/// Formats a time in centiseconds the way the WCA displays results.
+fn format_centiseconds(centiseconds: u64) -> String {
+    let hours = centiseconds / 360_000;
+    let minutes = centiseconds / 6_000 % 60;
+    let seconds = centiseconds / 100 % 60;
+    let fraction = centiseconds % 100;
+    if hours > 0 {
+        format!("{}:{:02}:{:02}.{:02}", hours, minutes, seconds, fraction)
+    } else if minutes > 0 {
+        format!("{}:{:02}.{:02}", minutes, seconds, fraction)
+    } else {
+        format!("{}.{:02}", seconds, fraction)
+    }
+}
+
+impl FromStr for SolveTime {
+    type Err = TimeError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let error = || TimeError { input: input.to_string() };
+        let text = input.trim();
+        let upper = text.to_ascii_uppercase();
+        if upper == "DNS" {
+            return Ok(SolveTime { time: None, penalty: Penalty::Dns });
+        }
+        if upper == "DNF" {
+            return Ok(SolveTime::dnf());
+        }
+        if let Some(inner) = upper.strip_prefix("DNF(").and_then(|t| t.strip_suffix(')')) {
+            let time = parse_centiseconds(inner).ok_or_else(error)?;
+            return Ok(SolveTime { time: Some(time), penalty: Penalty::Dnf });
+        }
+        if let Some(shown) = text.strip_suffix('+') {
+            let shown = parse_centiseconds(shown).ok_or_else(error)?;
+            let time = shown.checked_sub(PLUS_TWO).ok_or_else(error)?;
+            return Ok(SolveTime { time: Some(time), penalty: Penalty::PlusTwo });
+        }
+        parse_centiseconds(text).map(SolveTime::new).ok_or_else(error)
+    }
+}
+
+impl fmt::Display for SolveTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.penalty, self.time) {
+            (Penalty::Dns, _) => write!(f, "DNS"),
+            (Penalty::Dnf, Some(time)) => write!(f, "DNF({})", format_centiseconds(time.into())),
+            (Penalty::Dnf, None) | (_, None) => write!(f, "DNF"),
+            (Penalty::PlusTwo, Some(time)) => write!(f, "{}+", format_centiseconds(u64::from(time) + u64::from(PLUS_TWO))),
+            (Penalty::None, Some(time)) => write!(f, "{}", format_centiseconds(time.into())),
+        }
+    }
+}
+
+impl Serialize for SolveTime {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for SolveTime {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        text.parse().map_err(de::Error::custom)
+    }
+}
+
+impl JsonSchema for SolveTime {
+    fn schema_name() -> Cow<'static, str> {
+        "SolveTime".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "type": "string",
+            "description": "Time as displayed, such as `12.34`, `1:02.34`, `14.34+`, `DNF`, `DNF(12.34)` or `DNS`.",
+            "examples": ["12.34", "14.34+", "DNF"]
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> SolveTime {
+        input.parse().unwrap()
+    }
+
+    #[test]
+    fn times_parse_as_displayed() {
+        assert_eq!(parse("12.34"), SolveTime::new(1234));
+        assert_eq!(parse("12.3"), SolveTime::new(1230));
+        assert_eq!(parse("12"), SolveTime::new(1200));
+        assert_eq!(parse(" 1:02.34 "), SolveTime::new(6234));
+        assert_eq!(parse("1:00:00.00"), SolveTime::new(360_000));
+        assert_eq!(parse("14.34+"), SolveTime { time: Some(1234), penalty: Penalty::PlusTwo });
+        assert_eq!(parse("dnf"), SolveTime::dnf());
+        assert_eq!(parse("DNF(12.34)"), SolveTime { time: Some(1234), penalty: Penalty::Dnf });
+        assert_eq!(parse("DNS"), SolveTime { time: None, penalty: Penalty::Dns });
+    }
+
+    #[test]
+    fn invalid_times_are_refused() {
+        for input in ["", "abc", "12.345", "12.3a", "1.50+", "1:2:3:4.00", "DNF()", "-1.00", "99999999999"] {
+            assert_eq!(input.parse::<SolveTime>(), Err(TimeError { input: input.to_string() }));
+        }
+    }
+
+    #[test]
+    fn times_display_the_way_they_parse() {
+        for shown in ["0.05", "12.34", "1:02.34", "1:00:00.00", "14.34+", "DNF", "DNF(12.34)", "DNS"] {
+            assert_eq!(parse(shown).to_string(), shown);
+        }
+        assert_eq!(SolveTime::new(6000).to_string(), "1:00.00");
+    }
+
+    #[test]
+    fn penalties_change_the_result() {
+        assert_eq!(parse("14.34+").result(), Some(1434));
+        assert_eq!(parse("DNF(12.34)").result(), None);
+        assert!(parse("59.99").rank() < parse("DNF").rank());
+        assert!(parse("DNF").rank() <= parse("DNS").rank());
+    }
+}
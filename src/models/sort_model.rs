@@ -0,0 +1,96 @@
+use std::str::FromStr;
+
+/// Cube fields that listings can be ordered by.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SortField {
+    Name,
+    Type,
+    Pieces,
+    Faces,
+    Stickers,
+    YearCreated,
+    CreatedAt,
+    UpdatedAt,
+}
+
+impl SortField {
+    /// Name of the field on the stored cube documents.
+    pub fn key(&self) -> &'static str {
+        match self {
+            SortField::Name => "name",
+            SortField::Type => "type_",
+            SortField::Pieces => "pieces",
+            SortField::Faces => "faces",
+            SortField::Stickers => "stickers",
+            SortField::YearCreated => "year_created",
+            SortField::CreatedAt => "created_at",
+            SortField::UpdatedAt => "updated_at",
+        }
+    }
+}
+
+impl FromStr for SortField {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "name" => Ok(SortField::Name),
+            "type_" => Ok(SortField::Type),
+            "pieces" => Ok(SortField::Pieces),
+            "faces" => Ok(SortField::Faces),
+            "stickers" => Ok(SortField::Stickers),
+            "year_created" => Ok(SortField::YearCreated),
+            "created_at" => Ok(SortField::CreatedAt),
+            "updated_at" => Ok(SortField::UpdatedAt),
+            _ => Err(format!("cubes cannot be sorted by '{}'", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+impl FromStr for SortOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "asc" => Ok(SortOrder::Asc),
+            "desc" => Ok(SortOrder::Desc),
+            _ => Err(format!("'{}' is not a sort order, use asc or desc", s)),
+        }
+    }
+}
+
+/// Order asked for by a client on a cube listing.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sort {
+    pub field: SortField,
+    pub order: SortOrder,
+}
+
+impl Sort {
+    /// Reads the `sort_by` and `order` query parameters. The order is
+    /// ascending unless told otherwise, and it cannot be given alone.
+    ///
+    /// ## Arguments
+    /// * `sort_by` - field to sort by, if any.
+    /// * `order` - `asc` or `desc`, if any.
+    ///
+    /// ## Returns
+    /// * The sort to apply, if any, or why the parameters are wrong.
+    pub fn parse(sort_by: Option<&str>, order: Option<&str>) -> Result<Option<Sort>, String> {
+        let order = order.map(SortOrder::from_str).transpose()?;
+        match sort_by {
+            Some(field) => Ok(Some(Sort {
+                field: field.parse()?,
+                order: order.unwrap_or(SortOrder::Asc),
+            })),
+            None if order.is_some() => Err("order needs a sort_by field".to_string()),
+            None => Ok(None),
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use rocket::serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use crate::models::solve_time_model::SolveTime;
+use crate::stats;
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct AverageRequest {
+    /// Solves to average, e.g. `12.34`, `14.34+`, `DNF`.
+    pub times: Vec<SolveTime>,
+    /// Sizes of the rolling averages to compute, ao5 and ao12 by default.
+    pub sizes: Option<Vec<usize>>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct RollingAverage {
+    pub size: usize,
+    /// Average of the last `size` solves.
+    pub current: Option<SolveTime>,
+    /// Best average of `size` consecutive solves.
+    pub best: Option<SolveTime>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct AverageReport {
+    pub count: usize,
+    pub mean: SolveTime,
+    /// Trimmed average of all the solves.
+    pub average: SolveTime,
+    pub best: Option<SolveTime>,
+    pub worst: Option<SolveTime>,
+    pub averages: Vec<RollingAverage>,
+    /// Results of the solves, with penalties applied.
+    pub results: Vec<Option<u32>>,
+}
+
+impl AverageReport {
+    /// Computes the statistics of a list of solves.
+    /// 
+    /// ## Arguments
+    /// * `request` - the solves and the rolling average sizes.
+    /// 
+    /// ## Returns
+    /// * The report of the solves.
+    pub fn compute(request: &AverageRequest) -> Self {
+        let solves = &request.times;
+        let sizes = request.sizes.clone().unwrap_or_else(|| vec![5, 12]);
+
+        let averages = sizes
+            .into_iter()
+            .map(|size| {
+                let rolling = stats::rolling_averages(solves, size);
+                RollingAverage {
+                    size,
+                    current: rolling.last().copied(),
+                    best: rolling.iter().copied().min_by_key(SolveTime::rank),
+                }
+            })
+            .collect();
+
+        AverageReport {
+            count: solves.len(),
+            mean: stats::mean(solves),
+            average: stats::average(solves),
+            best: stats::best(solves),
+            worst: stats::worst(solves),
+            averages,
+            results: solves.iter().map(SolveTime::result).collect(),
+        }
+    }
+}
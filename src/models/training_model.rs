@@ -0,0 +1,44 @@
+use rocket::serde::Serialize;
+use schemars::JsonSchema;
+use crate::cube_state::{analysis, CubeState, Face, Move};
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TrainingGoal {
+    Cross,
+    F2lPair,
+    LastLayer,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct TrainingCase {
+    pub goal: TrainingGoal,
+    pub scramble: String,
+    pub moves: usize,
+    /// Facelet string of the scrambled cube, faces in URFDLB order.
+    pub facelets: String,
+    /// Moves of an optimal solution of the cross on D.
+    pub cross_moves: u8,
+}
+
+impl TrainingCase {
+    /// Describes the case a training scramble leads to.
+    /// 
+    /// ## Arguments
+    /// * `goal` - what the scramble trains.
+    /// * `moves` - the moves of the scramble.
+    /// 
+    /// ## Returns
+    /// * The training case.
+    pub fn new(goal: TrainingGoal, moves: &[Move]) -> Self {
+        let mut state = CubeState::solved();
+        state.apply_all(moves);
+        TrainingCase {
+            goal,
+            scramble: moves.iter().map(Move::to_string).collect::<Vec<_>>().join(" "),
+            moves: moves.len(),
+            facelets: state.facelet_string(),
+            cross_moves: analysis::cross_moves(&state, Face::D),
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use mongodb::bson::oid::ObjectId;
+use rocket::serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+
+/// What a user is allowed to do, each role allowing what the previous
+/// ones do.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    /// Reads, like anonymous clients.
+    #[default]
+    Viewer,
+    /// Creates and updates data.
+    Editor,
+    /// Deletes data, runs the admin commands and manages the roles.
+    Admin,
+}
+
+/// Account of a user of the API.
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct User {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<crate::models::bson_schema_model::ObjectIdSchema>")]
+    pub id: Option<ObjectId>,
+    /// Name the user logs in with, in lowercase.
+    pub username: String,
+    /// Hash of the password, never the password itself, left out of the
+    /// listings.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub password_hash: String,
+    #[serde(default)]
+    pub role: Role,
+}
+
+/// New role of a user.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct RoleChange {
+    pub role: Role,
+}
+
+/// Username and password sent to register or log in.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct Credentials {
+    pub username: String,
+    pub password: String,
+}
+
+/// Token issued on login, to be sent as `Authorization: Bearer <token>`.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct TokenResponse {
+    pub access_token: String,
+    pub token_type: &'static str,
+    /// Seconds the token is valid for.
+    pub expires_in: u64,
+}
@@ -1,8 +1,50 @@
-use rocket::serde::{Deserialize, Serialize};
+use async_graphql::{InputObject, SimpleObject};
+use rocket::serde::{Deserialize, Deserializer, Serialize};
+use schemars::JsonSchema;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+/// World record of a cube. Records stored before the time was kept in
+/// milliseconds (`author`, `time` as `"3.47"` or `"1:02.34"`) are read into
+/// the same fields.
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, SimpleObject, InputObject)]
+#[graphql(input_name = "WorldRecordInput", rename_fields = "snake_case")]
 pub struct WorldRecord {
-    pub author: String,
-    pub time: String,
+    /// Time of the record in milliseconds, if known.
+    #[serde(default, alias = "time", deserialize_with = "record_time", skip_serializing_if = "Option::is_none")]
+    pub time_ms: Option<u64>,
+    /// Name of the person holding the record.
+    #[serde(alias = "author")]
+    pub holder: String,
+    /// Competition where the record was set, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub competition: Option<String>,
     pub date: String,
-}
\ No newline at end of file
+}
+
+/// Time of a record, as milliseconds or as a legacy text.
+#[derive(Deserialize, JsonSchema)]
+#[serde(untagged)]
+enum RecordTime {
+    Millis(u64),
+    Text(String),
+}
+
+/// Reads the time of a record, converting the legacy texts to milliseconds.
+fn record_time<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u64>, D::Error> {
+    Ok(match Option::<RecordTime>::deserialize(deserializer)? {
+        Some(RecordTime::Millis(millis)) => Some(millis),
+        Some(RecordTime::Text(text)) => parse_millis(&text),
+        None => None,
+    })
+}
+
+/// Parses a time written as seconds (`"3.47"`) or minutes and seconds
+/// (`"1:02.34"`) into milliseconds.
+fn parse_millis(text: &str) -> Option<u64> {
+    let text = text.trim();
+    let (minutes, seconds) = match text.split_once(':') {
+        Some((minutes, seconds)) => (minutes.parse::<u64>().ok()?, seconds),
+        None => (0, text),
+    };
+    let seconds = seconds.parse::<f64>().ok().filter(|s| s.is_finite() && *s >= 0.0)?;
+    Some(minutes * 60_000 + (seconds * 1000.0).round() as u64)
+}
@@ -0,0 +1,240 @@
+use std::fmt;
+
+use rocket::serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+
+use crate::cube_state::Move;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Notation {
+    /// WCA notation: `Rw`, `3Rw`, `x` for cubes, Pochmann `R++` for
+    /// megaminx and `(1,0) /` for square-1.
+    Wca,
+    /// SiGN notation of cubes: `r`, `3r`, `M`, `x`.
+    Sign,
+    /// Compact square-1 notation, as typed in timers: `1,0/-1,3/`.
+    Compact,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum NotationPuzzle {
+    Cube,
+    Megaminx,
+    Square1,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq, JsonSchema)]
+pub struct ConversionError {
+    /// Token that could not be read or translated, if the error is about one.
+    pub token: Option<String>,
+    pub message: String,
+}
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.token {
+            Some(token) => write!(f, "`{}`: {}", token, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+fn token_error(token: &str, message: &str) -> ConversionError {
+    ConversionError { token: Some(token.to_string()), message: message.to_string() }
+}
+
+fn parse_suffix(suffix: &str) -> Option<u8> {
+    match suffix {
+        "" => Some(1),
+        "2" | "2'" => Some(2),
+        "'" => Some(3),
+        _ => None,
+    }
+}
+
+fn suffix(turns: u8) -> &'static str {
+    match turns {
+        1 => "",
+        2 => "2",
+        _ => "'",
+    }
+}
+
+/// A move of a cube, in a form every cube notation can be built from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CubeToken {
+    Turn(Move),
+    /// Middle slice `M`, `E` or `S`, which only SiGN can write.
+    Slice(char, u8),
+    /// Whole cube rotation `x`, `y` or `z`.
+    Rotation(char, u8),
+}
+
+fn parse_cube_token(token: &str, notation: Notation) -> Result<CubeToken, ConversionError> {
+    let invalid = || token_error(token, "is not a valid move");
+    let mut chars = token.chars();
+    let first = chars.next().ok_or_else(invalid)?;
+    match first {
+        'x' | 'y' | 'z' => {
+            let turns = parse_suffix(chars.as_str()).ok_or_else(invalid)?;
+            return Ok(CubeToken::Rotation(first, turns));
+        }
+        'M' | 'E' | 'S' => {
+            if notation != Notation::Sign {
+                return Err(token_error(token, "slice moves only exist in SiGN notation"));
+            }
+            let turns = parse_suffix(chars.as_str()).ok_or_else(invalid)?;
+            return Ok(CubeToken::Slice(first, turns));
+        }
+        _ => {}
+    }
+
+    let digits = token.chars().take_while(char::is_ascii_digit).count();
+    let face = token[digits..].chars().next().ok_or_else(invalid)?;
+    let token = if face.is_ascii_lowercase() {
+        if notation != Notation::Sign {
+            return Err(token_error(token, "lowercase wide moves only exist in SiGN notation"));
+        }
+        let rest = &token[digits + 1..];
+        format!("{}{}w{}", &token[..digits], face.to_ascii_uppercase(), rest)
+    } else {
+        token.to_string()
+    };
+    token.parse::<Move>().map(CubeToken::Turn).map_err(|_| invalid())
+}
+
+fn write_cube_token(token: CubeToken, notation: Notation) -> Result<String, ConversionError> {
+    match (token, notation) {
+        (CubeToken::Rotation(axis, turns), _) => Ok(format!("{}{}", axis, suffix(turns))),
+        (CubeToken::Slice(slice, turns), Notation::Sign) => Ok(format!("{}{}", slice, suffix(turns))),
+        (CubeToken::Slice(slice, turns), _) => Err(token_error(
+            &format!("{}{}", slice, suffix(turns)),
+            "slice moves have no WCA equivalent, write them as inner layer turns such as `2R`",
+        )),
+        (CubeToken::Turn(m), Notation::Sign) if m.wide => {
+            let face = m.face.to_string().to_ascii_lowercase();
+            match m.depth {
+                2 => Ok(format!("{}{}", face, suffix(m.turns))),
+                depth => Ok(format!("{}{}{}", depth, face, suffix(m.turns))),
+            }
+        }
+        (CubeToken::Turn(m), _) => Ok(m.to_string()),
+    }
+}
+
+/// A step of a square-1 scramble.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Square1Token {
+    /// Turn of the top and bottom layers, in twelfths of a turn.
+    Turn(i8, i8),
+    Slash,
+}
+
+fn parse_square1(alg: &str) -> Result<Vec<Square1Token>, ConversionError> {
+    let compact: String = alg.chars().filter(|c| !c.is_whitespace()).collect();
+    let mut tokens = Vec::new();
+    for (i, part) in compact.split('/').enumerate() {
+        if i > 0 {
+            tokens.push(Square1Token::Slash);
+        }
+        if part.is_empty() {
+            continue;
+        }
+        let inner = part.strip_prefix('(').and_then(|p| p.strip_suffix(')')).unwrap_or(part);
+        let invalid = || token_error(part, "is not a valid square-1 turn, expected `(top,bottom)`");
+        let (top, bottom) = inner.split_once(',').ok_or_else(invalid)?;
+        let top: i8 = top.parse().map_err(|_| invalid())?;
+        let bottom: i8 = bottom.parse().map_err(|_| invalid())?;
+        if !(-5..=6).contains(&top) || !(-5..=6).contains(&bottom) {
+            return Err(token_error(part, "square-1 turns must be between -5 and 6"));
+        }
+        tokens.push(Square1Token::Turn(top, bottom));
+    }
+    Ok(tokens)
+}
+
+fn write_square1(tokens: &[Square1Token], notation: Notation) -> String {
+    let parts: Vec<String> = tokens
+        .iter()
+        .map(|token| match (token, notation) {
+            (Square1Token::Slash, _) => "/".to_string(),
+            (Square1Token::Turn(top, bottom), Notation::Compact) => format!("{},{}", top, bottom),
+            (Square1Token::Turn(top, bottom), _) => format!("({},{})", top, bottom),
+        })
+        .collect();
+    match notation {
+        Notation::Compact => parts.concat(),
+        _ => parts.join(" "),
+    }
+}
+
+fn parse_megaminx_token(token: &str) -> Result<&str, ConversionError> {
+    match token {
+        "R++" | "R--" | "D++" | "D--" | "U" | "U'" => Ok(token),
+        _ => Err(token_error(token, "is not a valid Pochmann megaminx move")),
+    }
+}
+
+fn unsupported(puzzle: NotationPuzzle, notation: Notation) -> ConversionError {
+    ConversionError {
+        token: None,
+        message: format!("{:?} notation does not exist for {:?} puzzles", notation, puzzle).to_lowercase(),
+    }
+}
+
+/// Translates a move sequence from one notation to another. Every output
+/// reads back, in the target notation, as the same sequence of moves.
+///
+/// ## Arguments
+/// * `puzzle` - the puzzle the moves are for.
+/// * `from` - the notation the moves are written in.
+/// * `to` - the notation to translate the moves to.
+/// * `alg` - the moves.
+///
+/// ## Returns
+/// * The translated moves and their number, or the first construct that
+///   cannot be read or translated.
+pub fn convert(
+    puzzle: NotationPuzzle,
+    from: Notation,
+    to: Notation,
+    alg: &str,
+) -> Result<(String, usize), ConversionError> {
+    for notation in [from, to] {
+        let supported = match puzzle {
+            NotationPuzzle::Cube => notation != Notation::Compact,
+            NotationPuzzle::Megaminx => notation == Notation::Wca,
+            NotationPuzzle::Square1 => notation != Notation::Sign,
+        };
+        if !supported {
+            return Err(unsupported(puzzle, notation));
+        }
+    }
+
+    match puzzle {
+        NotationPuzzle::Cube => {
+            let tokens = alg
+                .split_whitespace()
+                .map(|token| parse_cube_token(token, from))
+                .collect::<Result<Vec<_>, _>>()?;
+            let written = tokens
+                .iter()
+                .map(|token| write_cube_token(*token, to))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok((written.join(" "), tokens.len()))
+        }
+        NotationPuzzle::Megaminx => {
+            let tokens = alg
+                .split_whitespace()
+                .map(parse_megaminx_token)
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok((tokens.join(" "), tokens.len()))
+        }
+        NotationPuzzle::Square1 => {
+            let tokens = parse_square1(alg)?;
+            Ok((write_square1(&tokens, to), tokens.len()))
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use std::future::Future;
+use std::pin::Pin;
+
+use rocket::http::Status;
+use rocket::request::{FromRequest, Outcome};
+use rocket::serde::Deserialize;
+use rocket::Request;
+
+use crate::api::error_api::ErrorDetail;
+use crate::i18n::Lang;
+
+/// What to do when a client asks for more items than allowed.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExceedBehavior {
+    /// The request is refused with a 400.
+    Reject,
+    /// The maximum is used instead.
+    Clamp,
+}
+
+/// Page sizes allowed on the list endpoints, read from the `pagination` key
+/// of the Rocket configuration (`Rocket.toml` or `ROCKET_PAGINATION`).
+///
+/// ```toml
+/// [default.pagination]
+/// default_limit = 20
+/// max_limit = 100
+/// on_exceed = "reject"
+/// ```
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct PagePolicy {
+    /// Number of items returned when the client does not ask for one.
+    pub default_limit: u64,
+    /// Largest number of items a client can ask for.
+    pub max_limit: u64,
+    pub on_exceed: ExceedBehavior,
+}
+
+impl Default for PagePolicy {
+    fn default() -> Self {
+        PagePolicy { default_limit: 20, max_limit: 100, on_exceed: ExceedBehavior::Reject }
+    }
+}
+
+/// Gets the number of items before a page, as skipped by the database.
+///
+/// ## Arguments
+/// * `page` - number of the page, starting at 1.
+/// * `per_page` - number of items per page.
+///
+/// ## Returns
+/// * The items to skip, or `None` if the page does not exist or is too far
+///   for the database to reach.
+pub fn skip(page: u64, per_page: u64) -> Option<u64> {
+    page.checked_sub(1)?
+        .checked_mul(per_page)
+        .filter(|skip| i64::try_from(*skip).is_ok())
+}
+
+impl PagePolicy {
+    /// Applies the policy to the page size asked for by a client.
+    ///
+    /// ## Arguments
+    /// * `requested` - number of items asked for, if any.
+    /// * `lang` - languages accepted by the client, to explain a refusal.
+    ///
+    /// ## Returns
+    /// * The number of items to return, or why the size is not allowed.
+    pub fn limit(&self, requested: Option<u64>, lang: &Lang) -> Result<u64, String> {
+        match requested {
+            None => Ok(self.default_limit.min(self.max_limit)),
+            Some(0) => Err(lang.message("error.page_size.zero")),
+            Some(size) if size <= self.max_limit => Ok(size),
+            Some(_) if self.on_exceed == ExceedBehavior::Clamp => Ok(self.max_limit),
+            Some(_) => Err(lang
+                .message("error.page_size.max")
+                .replace("{max}", &self.max_limit.to_string())),
+        }
+    }
+}
+
+/// Number of items a list endpoint must return, read from the `limit` (or
+/// `per_page`) query parameter and checked against the [`PagePolicy`].
+pub struct PageSize(pub u64);
+
+impl<'r> FromRequest<'r> for PageSize {
+    type Error = String;
+
+    fn from_request<'life0, 'async_trait>(
+        request: &'r Request<'life0>
+    ) -> Pin<Box<dyn Future<Output = Outcome<Self, Self::Error>> + Send + 'async_trait>>
+    where
+        'r: 'async_trait,
+        'life0: 'async_trait,
+        Self: 'async_trait,
+    {
+        let lang = Lang::from_header(request.headers().get_one("Accept-Language"));
+        let policy = request.rocket().state::<PagePolicy>().cloned().unwrap_or_default();
+        let requested = request
+            .query_value::<u64>("limit")
+            .or_else(|| request.query_value::<u64>("per_page"))
+            .transpose()
+            .map_err(|_| lang.message("error.page_size.number"));
+
+        let outcome = match requested.and_then(|requested| policy.limit(requested, &lang)) {
+            Ok(size) => Outcome::Success(PageSize(size)),
+            Err(message) => {
+                request.local_cache(|| ErrorDetail(Some(message.clone())));
+                Outcome::Error((Status::BadRequest, message))
+            }
+        };
+        Box::pin(async move { outcome })
+    }
+}
@@ -0,0 +1,110 @@
+use std::future::Future;
+use std::pin::Pin;
+
+use rocket::http::{Header, Status};
+use rocket::request::{FromRequest, Outcome};
+use rocket::response::{self, Responder, Response};
+use rocket::Request;
+
+use crate::models::cube_model::Cube;
+
+/// Tag of the cube the client last read, from the `If-Match` header, as
+/// sent in its `ETag`. A bare version number, quoted or not, is taken too,
+/// as older clients send it. A missing header or `*` sets no
+/// precondition.
+pub struct IfMatch(Option<(Option<String>, u64)>);
+
+impl IfMatch {
+    /// Gets the version the cube with an ID must be at to be written.
+    ///
+    /// ## Arguments
+    /// * `id` - ID of the cube written.
+    ///
+    /// ## Returns
+    /// * The expected version, if any, or a 412 when the tag is the one of
+    ///   another cube.
+    pub fn version_for(&self, id: &str) -> Result<Option<u64>, Status> {
+        match &self.0 {
+            None => Ok(None),
+            Some((Some(tagged), _)) if !tagged.eq_ignore_ascii_case(id.trim()) => Err(Status::PreconditionFailed),
+            Some((_, version)) => Ok(Some(*version)),
+        }
+    }
+}
+
+/// Reads an entity tag, `"<id>-<version>"` or a bare version.
+fn parse_tag(value: &str) -> Option<(Option<String>, u64)> {
+    let tag = value.trim_start_matches("W/").trim_matches('"');
+    match tag.rsplit_once('-') {
+        Some((id, version)) => Some((Some(id.to_string()), version.parse().ok()?)),
+        None => Some((None, tag.parse().ok()?)),
+    }
+}
+
+impl<'r> FromRequest<'r> for IfMatch {
+    type Error = ();
+
+    fn from_request<'life0, 'async_trait>(
+        request: &'r Request<'life0>
+    ) -> Pin<Box<dyn Future<Output = Outcome<Self, Self::Error>> + Send + 'async_trait>>
+    where
+        'r: 'async_trait,
+        'life0: 'async_trait,
+        Self: 'async_trait,
+    {
+        let outcome = match request.headers().get_one("If-Match").map(str::trim) {
+            None | Some("*") => Outcome::Success(IfMatch(None)),
+            Some(value) => match parse_tag(value) {
+                Some(tag) => Outcome::Success(IfMatch(Some(tag))),
+                None => Outcome::Error((Status::BadRequest, ())),
+            },
+        };
+        Box::pin(async move { outcome })
+    }
+}
+
+/// Entity tag of a cube at a version: its ID and its version, so that two
+/// cubes at the same version, such as one found through the alias of
+/// another, never share a tag. Cubes without ID, from custom
+/// repositories, are tagged with their version only.
+///
+/// ## Arguments
+/// * `cube` - the cube read.
+///
+/// ## Returns
+/// * The tag, quoted.
+pub fn entity_tag(cube: &Cube) -> String {
+    match &cube.id {
+        Some(id) => format!("\"{}-{}\"", id.to_hex(), cube.version),
+        None => format!("\"{}\"", cube.version),
+    }
+}
+
+/// Checks whether an `If-None-Match` header lists a tag. Tags are compared
+/// weakly, so that `W/"<tag>"` matches, and `*` matches any tag.
+fn none_match(header: &str, tag: &str) -> bool {
+    header
+        .split(',')
+        .map(str::trim)
+        .any(|candidate| candidate == "*" || candidate.trim_start_matches("W/") == tag)
+}
+
+/// Response for a cube tagged with its `ETag`, built by [`entity_tag`].
+/// Clients that send the tag back in `If-None-Match` get a 304 without
+/// body instead, as nothing changed since they read the cube.
+pub struct Tagged<R>(pub R, pub String);
+
+impl<'r, 'o: 'r, R: Responder<'r, 'o>> Responder<'r, 'o> for Tagged<R> {
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'o> {
+        let Tagged(body, tag) = self;
+        let unchanged = request
+            .headers()
+            .get_one("If-None-Match")
+            .is_some_and(|header| none_match(header, &tag));
+        let etag = Header::new("ETag", tag);
+        if unchanged {
+            return Response::build().status(Status::NotModified).header(etag).ok();
+        }
+        Response::build_from(body.respond_to(request)?).header(etag).ok()
+    }
+}
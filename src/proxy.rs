@@ -0,0 +1,167 @@
+use std::net::IpAddr;
+use std::str::FromStr;
+
+use rocket::Request;
+
+/// Address, or range of addresses in CIDR notation, of a proxy or load
+/// balancer allowed to tell the address of the client it forwards.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrustedProxy {
+    network: IpAddr,
+    prefix: u8,
+}
+
+impl TrustedProxy {
+    /// Checks whether an address belongs to the proxy range.
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.network, ip.to_canonical()) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix as u32).unwrap_or(0);
+                u32::from(network) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix as u32).unwrap_or(0);
+                u128::from(network) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for TrustedProxy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (address, prefix) = s.split_once('/').unwrap_or((s, ""));
+        let network = address
+            .parse::<IpAddr>()
+            .map_err(|_| format!("'{}' is not an IP address", address))?
+            .to_canonical();
+        let max_prefix = if network.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix {
+            "" => max_prefix,
+            prefix => prefix
+                .parse::<u8>()
+                .ok()
+                .filter(|p| *p <= max_prefix)
+                .ok_or_else(|| format!("'{}' is not a valid prefix length", prefix))?,
+        };
+        Ok(TrustedProxy { network, prefix })
+    }
+}
+
+/// Proxies in front of the server, read from the `trusted_proxies` key of
+/// the Rocket configuration (`Rocket.toml` or `ROCKET_TRUSTED_PROXIES`).
+/// Without any, the address of the peer is always taken as the client's.
+///
+/// ```toml
+/// [default]
+/// trusted_proxies = ["10.0.0.0/8", "203.0.113.7"]
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxies {
+    proxies: Vec<TrustedProxy>,
+}
+
+impl TrustedProxies {
+    /// Builds the list of trusted proxies.
+    ///
+    /// ## Arguments
+    /// * `proxies` - the proxies, from the `trusted_proxies` key.
+    ///
+    /// ## Returns
+    /// * The trusted proxies of the server.
+    pub fn new(proxies: Vec<TrustedProxy>) -> Self {
+        TrustedProxies { proxies }
+    }
+
+    fn is_trusted(&self, ip: IpAddr) -> bool {
+        self.proxies.iter().any(|proxy| proxy.contains(ip))
+    }
+
+    /// Gets the address of the client that sent a request. When the peer
+    /// is a trusted proxy, `X-Forwarded-For` is walked from the closest hop
+    /// and the first address that is not a trusted proxy is the client's,
+    /// so that clients cannot spoof it by sending the header themselves.
+    ///
+    /// ## Arguments
+    /// * `request` - the incoming request.
+    ///
+    /// ## Returns
+    /// * The address of the client, if known.
+    pub fn client_ip(&self, request: &Request<'_>) -> Option<IpAddr> {
+        let peer = request.remote()?.ip().to_canonical();
+        if !self.is_trusted(peer) {
+            return Some(peer);
+        }
+
+        let mut client = peer;
+        let hops = request
+            .headers()
+            .get("X-Forwarded-For")
+            .flat_map(|value| value.split(','))
+            .collect::<Vec<_>>();
+        for hop in hops.into_iter().rev() {
+            match hop.trim().parse::<IpAddr>() {
+                Ok(ip) => {
+                    client = ip.to_canonical();
+                    if !self.is_trusted(client) {
+                        break;
+                    }
+                }
+                Err(_) => break,
+            }
+        }
+        Some(client)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn proxy(range: &str) -> TrustedProxy {
+        range.parse().unwrap()
+    }
+
+    fn ip(address: &str) -> IpAddr {
+        address.parse().unwrap()
+    }
+
+    #[test]
+    fn ranges_contain_the_addresses_of_their_prefix() {
+        let range = proxy("10.1.0.0/16");
+        assert!(range.contains(ip("10.1.0.1")));
+        assert!(range.contains(ip("10.1.255.255")));
+        assert!(!range.contains(ip("10.2.0.1")));
+        assert!(!range.contains(ip("::1")));
+
+        let range = proxy("2001:db8::/32");
+        assert!(range.contains(ip("2001:db8:ffff::1")));
+        assert!(!range.contains(ip("2001:db9::1")));
+        assert!(!range.contains(ip("10.1.0.1")));
+    }
+
+    #[test]
+    fn addresses_without_prefix_are_single_hosts() {
+        assert!(proxy("203.0.113.7").contains(ip("203.0.113.7")));
+        assert!(!proxy("203.0.113.7").contains(ip("203.0.113.8")));
+        assert!(proxy("0.0.0.0/0").contains(ip("198.51.100.1")));
+        assert!(proxy("::/0").contains(ip("fe80::1")));
+    }
+
+    #[test]
+    fn mapped_addresses_are_ipv4() {
+        assert!(proxy("10.0.0.0/8").contains(ip("::ffff:10.0.0.1")));
+        assert!(proxy("::ffff:10.0.0.0/8").contains(ip("10.0.0.1")));
+    }
+
+    #[test]
+    fn invalid_ranges_are_refused() {
+        assert_eq!("10.0.0/8".parse::<TrustedProxy>(), Err("'10.0.0' is not an IP address".to_string()));
+        assert_eq!("10.0.0.0/33".parse::<TrustedProxy>(), Err("'33' is not a valid prefix length".to_string()));
+        assert!("::/129".parse::<TrustedProxy>().is_err());
+        assert!("10.0.0.0/".parse::<TrustedProxy>().is_ok());
+        assert!("10.0.0.0/x".parse::<TrustedProxy>().is_err());
+    }
+}
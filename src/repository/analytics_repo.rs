@@ -0,0 +1,245 @@
+use futures::stream::TryStreamExt;
+use mongodb::{
+    bson::{self, doc, DateTime, Document},
+    error::Result,
+    options::FindOneOptions,
+    Collection, Database,
+};
+
+use crate::{
+    models::{
+        query_analytics_model::{QueryEvent, QueryKind, QueryStat},
+        retention_model::{RetentionReport, RetentionStatus},
+    },
+    retention::CollectionRetention,
+    scramble::random::Rng,
+};
+
+/// Share of the lookups recorded when `ANALYTICS_SAMPLE_RATE` is not set.
+pub const DEFAULT_SAMPLE_RATE: f64 = 0.1;
+const DAY_MILLIS: i64 = 24 * 60 * 60 * 1000;
+/// Collection of the sampled lookups.
+const COLLECTION: &str = "query_analytics";
+/// Collection the expired lookups are downsampled into, one record per
+/// term and day.
+const ARCHIVE: &str = "query_analytics_archive";
+
+#[derive(Clone)]
+pub struct AnalyticsRepo {
+    col: Collection<QueryEvent>,
+    archive: Collection<Document>,
+    sample_rate: f64,
+}
+
+impl AnalyticsRepo {
+    /// Inits the query analytics repository on top of the given database.
+    /// 
+    /// ## Arguments
+    /// * `db` - the mongoDB database.
+    /// * `sample_rate` - share of lookups recorded, between 0 and 1.
+    /// 
+    /// ## Returns
+    /// * An instance of the query analytics repository.
+    pub fn init(db: &Database, sample_rate: f64) -> Self {
+        let col: Collection<QueryEvent> = db.collection(COLLECTION);
+        let archive: Collection<Document> = db.collection(ARCHIVE);
+        AnalyticsRepo { col, archive, sample_rate }
+    }
+
+    /// Records a catalog lookup, if it is sampled. The record is written in
+    /// the background, so that the lookup does not wait for it, and a
+    /// failure to write it is only logged.
+    /// 
+    /// ## Arguments
+    /// * `self` - instance of the query analytics repository.
+    /// * `kind` - kind of lookup.
+    /// * `term` - term looked up.
+    /// * `results` - number of results of the lookup.
+    pub fn record(&self, kind: QueryKind, term: &str, results: u64) {
+        let sampled = (Rng::from_time().below(1_000_000) as f64) < self.sample_rate * 1_000_000.0;
+        if !sampled {
+            return;
+        }
+        let now = DateTime::now().timestamp_millis();
+        let event = QueryEvent {
+            id: None,
+            kind,
+            term: QueryEvent::normalize(term),
+            results,
+            weight: (1.0 / self.sample_rate).round() as i64,
+            day: DateTime::from_millis(now - now.rem_euclid(DAY_MILLIS)),
+        };
+        let col = self.col.clone();
+        rocket::tokio::spawn(async move {
+            if let Err(error) = col.insert_one(event, None).await {
+                warn!("Could not record a catalog lookup: {}", error);
+            }
+        });
+    }
+
+    /// Sums the lookups of every term, the sampled ones and the archived
+    /// ones, keeping either all of them or those without results.
+    async fn aggregate_terms(&self, zero_results: bool, limit: i64) -> Result<Vec<QueryStat>> {
+        let counted = if zero_results { "$zero_results" } else { "$searches" };
+        let pipeline = vec![
+            doc! {"$project": {
+                "_id": 0,
+                "kind": 1,
+                "term": 1,
+                "searches": "$weight",
+                "zero_results": {"$cond": [{"$eq": ["$results", 0]}, "$weight", 0]},
+            }},
+            doc! {"$unionWith": {
+                "coll": ARCHIVE,
+                "pipeline": [{"$project": {
+                    "_id": 0,
+                    "kind": "$_id.kind",
+                    "term": "$_id.term",
+                    "searches": 1,
+                    "zero_results": 1,
+                }}],
+            }},
+            doc! {"$group": {
+                "_id": {"kind": "$kind", "term": "$term"},
+                "searches": {"$sum": counted},
+            }},
+            doc! {"$match": {"searches": {"$gt": 0}}},
+            doc! {"$sort": {"searches": -1, "_id.term": 1}},
+            doc! {"$limit": limit},
+            doc! {"$project": {
+                "_id": 0,
+                "kind": "$_id.kind",
+                "term": "$_id.term",
+                "searches": 1,
+            }},
+        ];
+        let documents: Vec<Document> = self.col.aggregate(pipeline, None).await?.try_collect().await?;
+        let stats = documents
+            .into_iter()
+            .map(bson::from_document)
+            .collect::<std::result::Result<_, _>>()?;
+
+        Ok(stats)
+    }
+
+    /// Gets the terms looked up the most.
+    /// 
+    /// ## Arguments
+    /// * `self` - instance of the query analytics repository.
+    /// * `limit` - maximum number of terms to return.
+    /// 
+    /// ## Returns
+    /// * The terms with their estimated number of lookups.
+    pub async fn most_searched(&self, limit: i64) -> Result<Vec<QueryStat>> {
+        self.aggregate_terms(false, limit).await
+    }
+
+    /// Gets the terms looked up the most that had no results, which point
+    /// at what the catalog is missing.
+    /// 
+    /// ## Arguments
+    /// * `self` - instance of the query analytics repository.
+    /// * `limit` - maximum number of terms to return.
+    /// 
+    /// ## Returns
+    /// * The terms with their estimated number of lookups.
+    pub async fn zero_results(&self, limit: i64) -> Result<Vec<QueryStat>> {
+        self.aggregate_terms(true, limit).await
+    }
+
+    /// Removes the lookups matching a filter, downsampling them first into
+    /// the archive if asked to: their weights are added to the counts of
+    /// their term and day.
+    async fn expire(&self, filter: Document, archive: bool) -> Result<u64> {
+        if archive {
+            let pipeline = vec![
+                doc! {"$match": filter.clone()},
+                doc! {"$group": {
+                    "_id": {"kind": "$kind", "term": "$term", "day": "$day"},
+                    "searches": {"$sum": "$weight"},
+                    "zero_results": {"$sum": {"$cond": [{"$eq": ["$results", 0]}, "$weight", 0]}},
+                }},
+                doc! {"$merge": {
+                    "into": ARCHIVE,
+                    "on": "_id",
+                    "whenMatched": [{"$set": {
+                        "searches": {"$add": ["$searches", "$$new.searches"]},
+                        "zero_results": {"$add": ["$zero_results", "$$new.zero_results"]},
+                    }}],
+                    "whenNotMatched": "insert",
+                }},
+            ];
+            // `$merge` only writes once the cursor is consumed.
+            let mut cursor = self.col.aggregate(pipeline, None).await?;
+            while cursor.try_next().await?.is_some() {}
+        }
+        Ok(self.col.delete_many(filter, None).await?.deleted_count)
+    }
+
+    /// Applies a retention policy to the sampled lookups: those older than
+    /// its age are expired, then the oldest ones beyond its size.
+    /// 
+    /// ## Arguments
+    /// * `self` - instance of the query analytics repository.
+    /// * `policy` - retention of the lookups.
+    /// 
+    /// ## Returns
+    /// * What was expired, and how many lookups remain.
+    pub async fn apply_retention(&self, policy: &CollectionRetention) -> Result<RetentionReport> {
+        let mut expired = 0;
+        if let Some(days) = policy.max_age_days {
+            let now = DateTime::now().timestamp_millis();
+            let cutoff = DateTime::from_millis(now - now.rem_euclid(DAY_MILLIS) - i64::from(days) * DAY_MILLIS);
+            expired += self.expire(doc! {"day": {"$lt": cutoff}}, policy.archive).await?;
+        }
+        if let Some(max) = policy.max_records {
+            let records = self.col.count_documents(None, None).await?;
+            if records > max {
+                // The newest lookup to expire bounds the oldest ones.
+                let options = FindOneOptions::builder()
+                    .sort(doc! {"day": 1, "_id": 1})
+                    .skip(records - max - 1)
+                    .build();
+                if let Some(QueryEvent { id: Some(id), day, .. }) = self.col.find_one(None, options).await? {
+                    let filter = doc! {"$or": [
+                        {"day": {"$lt": day}},
+                        {"day": day, "_id": {"$lte": id}},
+                    ]};
+                    expired += self.expire(filter, policy.archive).await?;
+                }
+            }
+        }
+
+        Ok(RetentionReport {
+            collection: COLLECTION,
+            expired,
+            archived: policy.archive,
+            remaining: self.col.count_documents(None, None).await?,
+        })
+    }
+
+    /// Gets the size of the sampled lookups and of their archive.
+    /// 
+    /// ## Arguments
+    /// * `self` - instance of the query analytics repository.
+    /// * `policy` - retention of the lookups.
+    /// 
+    /// ## Returns
+    /// * The number of lookups kept and archived, with the oldest day kept.
+    pub async fn retention_status(&self, policy: &CollectionRetention) -> Result<RetentionStatus> {
+        let options = FindOneOptions::builder().sort(doc! {"day": 1}).build();
+        let oldest = self
+            .col
+            .find_one(None, options)
+            .await?
+            .and_then(|event| event.day.try_to_rfc3339_string().ok());
+        Ok(RetentionStatus {
+            collection: COLLECTION,
+            records: self.col.count_documents(None, None).await?,
+            oldest,
+            archive: ARCHIVE,
+            archived_records: self.archive.count_documents(None, None).await?,
+            policy: policy.clone(),
+        })
+    }
+}
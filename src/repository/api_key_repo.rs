@@ -0,0 +1,101 @@
+use futures::stream::TryStreamExt;
+use mongodb::{
+    bson::{doc, oid::ObjectId},
+    error::Error,
+    options::{FindOneAndUpdateOptions, FindOptions, IndexOptions, ReturnDocument},
+    results::InsertOneResult,
+    Collection, Database, IndexModel,
+};
+
+use crate::models::api_key_model::ApiKey;
+
+pub struct ApiKeyRepo {
+    col: Collection<ApiKey>,
+}
+
+impl ApiKeyRepo {
+    /// Inits the API keys repository on top of the given database.
+    ///
+    /// ## Arguments
+    /// * `db` - the mongoDB database.
+    ///
+    /// ## Returns
+    /// * An instance of the API keys repository.
+    pub fn init(db: &Database) -> Self {
+        let col: Collection<ApiKey> = db.collection("api_keys");
+        ApiKeyRepo { col }
+    }
+
+    /// Creates the unique index on the hashes of the keys, which the
+    /// requests carrying a key are looked up by.
+    ///
+    /// ## Arguments
+    /// * `self` - instance of the API keys repository.
+    ///
+    /// ## Returns
+    /// * The result of the operation.
+    pub async fn create_indexes(&self) -> Result<(), Error> {
+        let hashes = IndexModel::builder()
+            .keys(doc! {"key_hash": 1})
+            .options(IndexOptions::builder()
+                .name("api_key_hash".to_string())
+                .unique(true)
+                .build())
+            .build();
+        self.col.create_index(hashes, None).await.map(|_| ())
+    }
+
+    /// Inserts an API key into the database.
+    ///
+    /// ## Arguments
+    /// * `self` - instance of the API keys repository.
+    /// * `key` - API key to be inserted, with the key already hashed.
+    ///
+    /// ## Returns
+    /// * The result of the operation.
+    pub async fn insert_key(&self, key: ApiKey) -> Result<InsertOneResult, Error> {
+        self.col.insert_one(key, None).await
+    }
+
+    /// Gets every API key, revoked ones included, without their hashes.
+    ///
+    /// ## Arguments
+    /// * `self` - instance of the API keys repository.
+    ///
+    /// ## Returns
+    /// * A vector with the API keys.
+    pub async fn get_keys(&self) -> Result<Vec<ApiKey>, Error> {
+        let options = FindOptions::builder()
+            .projection(doc! {"key_hash": 0})
+            .build();
+        self.col.find(None, options).await?.try_collect().await
+    }
+
+    /// Finds the API key with the given hash, unless it was revoked.
+    ///
+    /// ## Arguments
+    /// * `self` - instance of the API keys repository.
+    /// * `key_hash` - hash of the key sent by the client.
+    ///
+    /// ## Returns
+    /// * The API key, if it exists and is still valid.
+    pub async fn find_active_key(&self, key_hash: &str) -> Result<Option<ApiKey>, Error> {
+        self.col.find_one(doc! {"key_hash": key_hash, "revoked": false}, None).await
+    }
+
+    /// Revokes an API key, which is kept to tell who it was issued to.
+    ///
+    /// ## Arguments
+    /// * `self` - instance of the API keys repository.
+    /// * `id` - ID of the API key.
+    ///
+    /// ## Returns
+    /// * The revoked API key, if it exists.
+    pub async fn revoke_key(&self, id: &ObjectId) -> Result<Option<ApiKey>, Error> {
+        let options = FindOneAndUpdateOptions::builder()
+            .projection(doc! {"key_hash": 0})
+            .return_document(ReturnDocument::After)
+            .build();
+        self.col.find_one_and_update(doc! {"_id": id}, doc! {"$set": {"revoked": true}}, options).await
+    }
+}
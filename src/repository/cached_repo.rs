@@ -0,0 +1,307 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::future::BoxFuture;
+use futures::stream::BoxStream;
+use mongodb::bson::{oid::ObjectId, Bson};
+use moka::future::Cache;
+
+use crate::config::CachePolicy;
+use crate::models::consistency_report_model::ConsistencyReport;
+use crate::models::cube_filter_model::CubeFilter;
+use crate::models::cube_model::Cube;
+use crate::models::cube_type_model::CubeType;
+use crate::models::performance_model::PerformanceFilter;
+use crate::models::puzzle_shape_model::ShapeKind;
+use crate::models::search_model::SearchHit;
+use crate::models::sort_model::Sort;
+use crate::models::world_record_model::WorldRecord;
+use crate::repository::cube_repository::{CubeRepository, InsertedCubes};
+use crate::repository::repo_error::RepoError;
+
+/// Lookup of the cache.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Key {
+    Cube(String),
+    /// Cube with the exact name.
+    ExactName(String),
+    /// Cube with the name or the alias, found or not.
+    Name(String),
+    Page(String),
+    All,
+}
+
+/// Result of a lookup.
+#[derive(Clone)]
+enum Cached {
+    Cube(Box<Cube>),
+    Found(Option<Box<Cube>>),
+    Page(Vec<Cube>, u64),
+    All(Vec<Cube>),
+}
+
+/// Cube repository keeping the results of the hot lookups in a
+/// [`moka`] cache in front of another one: the cubes by ID and by name, the
+/// pages of the catalog and the whole catalog. Every write through it
+/// empties the cache. Its clones share the cache.
+#[derive(Clone)]
+pub struct CachedRepo {
+    inner: Arc<dyn CubeRepository>,
+    cache: Cache<Key, Cached>,
+    /// Number of writes seen, so that a lookup started before a write does
+    /// not keep what it read.
+    generation: Arc<AtomicU64>,
+}
+
+impl CachedRepo {
+    /// Puts a cache in front of a cube repository.
+    ///
+    /// ## Arguments
+    /// * `inner` - the repository the lookups are read from.
+    /// * `policy` - how long and how many lookups are kept.
+    ///
+    /// ## Returns
+    /// * An instance of the cached repository.
+    pub fn new(inner: Arc<dyn CubeRepository>, policy: CachePolicy) -> Self {
+        let cache = Cache::builder()
+            .max_capacity(policy.max_entries as u64)
+            .time_to_live(Duration::from_secs(policy.ttl_secs))
+            .build();
+        CachedRepo { inner, cache, generation: Arc::default() }
+    }
+
+    /// Gets a lookup, if it is cached and fresh, with the generation to
+    /// keep it with otherwise.
+    async fn lookup(&self, key: &Key) -> Result<Cached, u64> {
+        let generation = self.generation.load(Ordering::SeqCst);
+        self.cache.get(key).await.ok_or(generation)
+    }
+
+    /// Keeps the result of a lookup, unless a write happened since it was
+    /// started. A write ending while it is kept drops it again.
+    async fn keep(&self, key: Key, cached: Cached, generation: u64) {
+        if self.generation.load(Ordering::SeqCst) != generation {
+            return;
+        }
+        self.cache.insert(key.clone(), cached).await;
+        if self.generation.load(Ordering::SeqCst) != generation {
+            self.cache.invalidate(&key).await;
+        }
+    }
+
+    /// Runs a write on the repository behind, emptying the cache once it is
+    /// over, whether it failed or not, as it may have written part of it.
+    async fn write<T>(&self, write: BoxFuture<'_, Result<T, RepoError>>) -> Result<T, RepoError> {
+        let result = write.await;
+        self.invalidate();
+        result
+    }
+
+    /// Reads a cube from the cache or from an inner lookup.
+    async fn cube(&self, key: Key, read: BoxFuture<'_, Result<Cube, RepoError>>) -> Result<Cube, RepoError> {
+        let generation = match self.lookup(&key).await {
+            Ok(Cached::Cube(cube)) => return Ok(*cube),
+            Ok(_) => unreachable!("cube keys hold cubes"),
+            Err(generation) => generation,
+        };
+        let cube = read.await?;
+        self.keep(key, Cached::Cube(Box::new(cube.clone())), generation).await;
+        Ok(cube)
+    }
+}
+
+impl CubeRepository for CachedRepo {
+    fn ping(&self) -> BoxFuture<'_, Result<(), String>> {
+        self.inner.ping()
+    }
+
+    fn invalidate(&self) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        self.cache.invalidate_all();
+        self.inner.invalidate();
+    }
+
+    fn create_indexes(&self) -> BoxFuture<'_, Result<(), String>> {
+        self.inner.create_indexes()
+    }
+
+    fn insert_cube<'a>(&'a self, new_cube: Cube) -> BoxFuture<'a, Result<Bson, RepoError>> {
+        Box::pin(self.write(self.inner.insert_cube(new_cube)))
+    }
+
+    fn insert_cubes<'a>(
+        &'a self,
+        new_cubes: Vec<Cube>
+    ) -> BoxFuture<'a, Result<InsertedCubes, RepoError>> {
+        Box::pin(self.write(self.inner.insert_cubes(new_cubes)))
+    }
+
+    fn get_cube<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<Cube, RepoError>> {
+        Box::pin(self.cube(Key::Cube(id.to_string()), self.inner.get_cube(id)))
+    }
+
+    fn find_cube<'a>(&'a self, id: &'a ObjectId) -> BoxFuture<'a, Result<Option<Cube>, RepoError>> {
+        self.inner.find_cube(id)
+    }
+
+    fn cube_exists<'a>(&'a self, id: &'a ObjectId) -> BoxFuture<'a, Result<bool, RepoError>> {
+        self.inner.cube_exists(id)
+    }
+
+    fn edit_cube<'a>(
+        &'a self,
+        id: &'a str,
+        new_cube: Cube
+    ) -> BoxFuture<'a, Result<(), RepoError>> {
+        Box::pin(self.write(self.inner.edit_cube(id, new_cube)))
+    }
+
+    fn edit_cube_by_name<'a>(
+        &'a self,
+        name: &'a str,
+        new_cube: Cube
+    ) -> BoxFuture<'a, Result<(), RepoError>> {
+        Box::pin(self.write(self.inner.edit_cube_by_name(name, new_cube)))
+    }
+
+    fn set_world_record<'a>(
+        &'a self,
+        id: &'a str,
+        wr: WorldRecord
+    ) -> BoxFuture<'a, Result<Cube, RepoError>> {
+        Box::pin(self.write(self.inner.set_world_record(id, wr)))
+    }
+
+    fn delete_cube<'a>(
+        &'a self,
+        id: &'a str,
+        version: Option<u64>
+    ) -> BoxFuture<'a, Result<(), RepoError>> {
+        Box::pin(self.write(self.inner.delete_cube(id, version)))
+    }
+
+    fn restore_cube<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<Cube, RepoError>> {
+        Box::pin(self.write(self.inner.restore_cube(id)))
+    }
+
+    fn purge_cube<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<(), RepoError>> {
+        Box::pin(self.write(self.inner.purge_cube(id)))
+    }
+
+    fn get_versions<'a>(
+        &'a self,
+        filter: &'a CubeFilter
+    ) -> BoxFuture<'a, Result<Vec<(ObjectId, u64)>, RepoError>> {
+        self.inner.get_versions(filter)
+    }
+
+    fn delete_versions<'a>(
+        &'a self,
+        versions: &'a [(ObjectId, u64)]
+    ) -> BoxFuture<'a, Result<Vec<ObjectId>, RepoError>> {
+        Box::pin(self.write(self.inner.delete_versions(versions)))
+    }
+
+    fn get_all_cubes<'a>(&'a self) -> BoxFuture<'a, Result<Vec<Cube>, RepoError>> {
+        Box::pin(async move {
+            let generation = match self.lookup(&Key::All).await {
+                Ok(Cached::All(cubes)) => return Ok(cubes),
+                Ok(_) => unreachable!("the catalog key holds the catalog"),
+                Err(generation) => generation,
+            };
+            let cubes = self.inner.get_all_cubes().await?;
+            self.keep(Key::All, Cached::All(cubes.clone()), generation).await;
+            Ok(cubes)
+        })
+    }
+
+    fn stream_cubes<'a>(&'a self) -> BoxFuture<'a, Result<BoxStream<'a, Result<Cube, RepoError>>, RepoError>> {
+        self.inner.stream_cubes()
+    }
+
+    fn get_cubes_page<'a>(
+        &'a self,
+        filter: &'a CubeFilter,
+        sort: Option<Sort>,
+        skip: u64,
+        limit: i64
+    ) -> BoxFuture<'a, Result<(Vec<Cube>, u64), RepoError>> {
+        Box::pin(async move {
+            let key = Key::Page(format!("{:?} {:?} {} {}", filter, sort, skip, limit));
+            let generation = match self.lookup(&key).await {
+                Ok(Cached::Page(cubes, total)) => return Ok((cubes, total)),
+                Ok(_) => unreachable!("page keys hold pages"),
+                Err(generation) => generation,
+            };
+            let (cubes, total) = self.inner.get_cubes_page(filter, sort, skip, limit).await?;
+            self.keep(key, Cached::Page(cubes.clone(), total), generation).await;
+            Ok((cubes, total))
+        })
+    }
+
+    fn get_cube_by_name<'a>(&'a self, name: &'a str) -> BoxFuture<'a, Result<Cube, RepoError>> {
+        Box::pin(self.cube(Key::ExactName(name.to_string()), self.inner.get_cube_by_name(name)))
+    }
+
+    fn find_cube_by_name<'a>(
+        &'a self,
+        name: &'a str
+    ) -> BoxFuture<'a, Result<Option<Cube>, RepoError>> {
+        Box::pin(async move {
+            let key = Key::Name(name.to_string());
+            let generation = match self.lookup(&key).await {
+                Ok(Cached::Found(cube)) => return Ok(cube.map(|cube| *cube)),
+                Ok(_) => unreachable!("name keys hold what was found"),
+                Err(generation) => generation,
+            };
+            let cube = self.inner.find_cube_by_name(name).await?;
+            self.keep(key, Cached::Found(cube.clone().map(Box::new)), generation).await;
+            Ok(cube)
+        })
+    }
+
+    fn search_cubes<'a>(
+        &'a self,
+        query: &'a str,
+        limit: i64
+    ) -> BoxFuture<'a, Result<Vec<SearchHit>, RepoError>> {
+        self.inner.search_cubes(query, limit)
+    }
+
+    fn get_cube_by_type<'a>(
+        &'a self,
+        type_: &'a CubeType,
+        sort: Option<Sort>
+    ) -> BoxFuture<'a, Result<Vec<Cube>, RepoError>> {
+        self.inner.get_cube_by_type(type_, sort)
+    }
+
+    fn get_cubes_by_performance<'a>(
+        &'a self,
+        filter: &'a PerformanceFilter
+    ) -> BoxFuture<'a, Result<Vec<Cube>, RepoError>> {
+        self.inner.get_cubes_by_performance(filter)
+    }
+
+    fn get_cubes_by_shape<'a>(
+        &'a self,
+        shape: &'a ShapeKind
+    ) -> BoxFuture<'a, Result<Vec<Cube>, RepoError>> {
+        self.inner.get_cubes_by_shape(shape)
+    }
+
+    fn get_cubes_by_holder<'a>(
+        &'a self,
+        holder: &'a str
+    ) -> BoxFuture<'a, Result<Vec<Cube>, RepoError>> {
+        self.inner.get_cubes_by_holder(holder)
+    }
+
+    fn check_consistency<'a>(
+        &'a self,
+        fix: bool
+    ) -> BoxFuture<'a, Result<ConsistencyReport, RepoError>> {
+        Box::pin(self.write(self.inner.check_consistency(fix)))
+    }
+}
@@ -0,0 +1,67 @@
+use futures::stream::TryStreamExt;
+use mongodb::{
+    bson::doc,
+    error::Error,
+    results::InsertOneResult,
+    Collection, Database,
+};
+
+use crate::models::color_scheme_model::ColorScheme;
+
+pub struct ColorSchemeRepo {
+    col: Collection<ColorScheme>,
+}
+
+impl ColorSchemeRepo {
+    /// Inits the color schemes repository on top of the given database.
+    /// 
+    /// ## Arguments
+    /// * `db` - the mongoDB database.
+    /// 
+    /// ## Returns
+    /// * An instance of the color schemes repository.
+    pub fn init(db: &Database) -> Self {
+        let col: Collection<ColorScheme> = db.collection("color_schemes");
+        ColorSchemeRepo { col }
+    }
+
+    /// Inserts a color scheme into the database.
+    /// 
+    /// ## Arguments
+    /// * `self` - instance of the color schemes repository.
+    /// * `scheme` - color scheme to be inserted.
+    /// 
+    /// ## Returns
+    /// * The result of the operation.
+    pub async fn insert_scheme(&self, scheme: ColorScheme) -> Result<InsertOneResult, Error> {
+        self.col.insert_one(scheme, None).await
+    }
+
+    /// Gets a color scheme by its name, looking first at the built-in ones.
+    /// 
+    /// ## Arguments
+    /// * `self` - instance of the color schemes repository.
+    /// * `name` - name of the color scheme.
+    /// 
+    /// ## Returns
+    /// * The color scheme, if it exists.
+    pub async fn get_scheme(&self, name: &str) -> Result<Option<ColorScheme>, Error> {
+        if let Some(scheme) = ColorScheme::built_in().into_iter().find(|s| s.name == name) {
+            return Ok(Some(scheme));
+        }
+        self.col.find_one(doc! {"name": name}, None).await
+    }
+
+    /// Gets all the color schemes, the built-in ones first.
+    /// 
+    /// ## Arguments
+    /// * `self` - instance of the color schemes repository.
+    /// 
+    /// ## Returns
+    /// * The list of color schemes.
+    pub async fn get_schemes(&self) -> Result<Vec<ColorScheme>, Error> {
+        let stored: Vec<ColorScheme> = self.col.find(None, None).await?.try_collect().await?;
+
+        Ok(ColorScheme::built_in().into_iter().chain(stored).collect())
+    }
+}
@@ -0,0 +1,79 @@
+use futures::stream::TryStreamExt;
+use mongodb::{
+    bson::doc,
+    error::Error,
+    options::{FindOptions, UpdateOptions},
+    Collection, Database,
+};
+
+use crate::models::contribution_model::{ContributionKind, Contributions};
+
+#[derive(Clone)]
+pub struct ContributionRepo {
+    col: Collection<Contributions>,
+}
+
+impl ContributionRepo {
+    /// Inits the contributions repository on top of the given database.
+    /// 
+    /// ## Arguments
+    /// * `db` - the mongoDB database.
+    /// 
+    /// ## Returns
+    /// * An instance of the contributions repository.
+    pub fn init(db: &Database) -> Self {
+        let col: Collection<Contributions> = db.collection("contributions");
+        ContributionRepo { col }
+    }
+
+    /// Counts contributions of a user, creating the user's counts on the
+    /// first one. The contributions are already written when they are
+    /// counted, so a failure to count them is only logged.
+    /// 
+    /// ## Arguments
+    /// * `self` - instance of the contributions repository.
+    /// * `username` - name of the contributor.
+    /// * `kind` - kind of the contributions, never the total.
+    /// * `count` - number of contributions made.
+    pub async fn record(&self, username: &str, kind: ContributionKind, count: u64) {
+        if count == 0 || kind == ContributionKind::Total {
+            return;
+        }
+        let count = count as i64;
+        let update = doc! {"$inc": {kind.key(): count, "total": count}};
+        let options = UpdateOptions::builder().upsert(true).build();
+        if let Err(error) = self.col.update_one(doc! {"_id": username}, update, options).await {
+            warn!("Could not count {} contributions of {}: {}", kind.key(), username, error);
+        }
+    }
+
+    /// Gets the contributions of a user.
+    /// 
+    /// ## Arguments
+    /// * `self` - instance of the contributions repository.
+    /// * `username` - name of the contributor.
+    /// 
+    /// ## Returns
+    /// * The contributions of the user, if the user made any.
+    pub async fn get_contributions(&self, username: &str) -> Result<Option<Contributions>, Error> {
+        self.col.find_one(doc! {"_id": username}, None).await
+    }
+
+    /// Gets the users who contributed the most.
+    /// 
+    /// ## Arguments
+    /// * `self` - instance of the contributions repository.
+    /// * `by` - kind of contributions the users are ranked by.
+    /// * `limit` - maximum number of users to return.
+    /// 
+    /// ## Returns
+    /// * A vector with the contributions of the top users, the first being
+    ///   the top one.
+    pub async fn top_contributors(&self, by: ContributionKind, limit: i64) -> Result<Vec<Contributions>, Error> {
+        let options = FindOptions::builder()
+            .sort(doc! {by.key(): -1, "_id": 1})
+            .limit(limit)
+            .build();
+        self.col.find(doc! {by.key(): {"$gt": 0}}, options).await?.try_collect().await
+    }
+}
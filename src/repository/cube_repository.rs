@@ -0,0 +1,531 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use futures::future::BoxFuture;
+use futures::stream::{self, BoxStream, StreamExt};
+use mongodb::bson::{oid::ObjectId, Bson};
+
+use crate::models::consistency_report_model::ConsistencyReport;
+use crate::models::cube_filter_model::CubeFilter;
+use crate::models::cube_model::Cube;
+use crate::models::cube_type_model::CubeType;
+use crate::models::performance_model::PerformanceFilter;
+use crate::models::puzzle_shape_model::ShapeKind;
+use crate::models::search_model::SearchHit;
+use crate::models::sort_model::Sort;
+use crate::models::world_record_model::WorldRecord;
+use crate::repository::repo_error::RepoError;
+
+/// Outcome of [`CubeRepository::insert_cubes`], by position of the cubes
+/// in the list: every cube is either inserted or failed.
+#[derive(Debug, Default)]
+pub struct InsertedCubes {
+    /// IDs of the inserted cubes.
+    pub inserted: HashMap<usize, Bson>,
+    /// Why the other cubes were not inserted, such as
+    /// [`RepoError::DuplicateName`].
+    pub failed: Vec<(usize, RepoError)>,
+}
+
+/// Storage of the cubes, which the routes reach through the managed
+/// `Box<dyn CubeRepository>`. [`MongoRepo`](super::mongodb_repo::MongoRepo)
+/// is the default backend, and [`RubikApiBuilder::cube_repository`]
+/// replaces it with any other one.
+///
+/// [`RubikApiBuilder::cube_repository`]: crate::RubikApiBuilder::cube_repository
+pub trait CubeRepository: Send + Sync {
+    /// Checks that the backend can be reached. Backends without a server
+    /// to reach always can.
+    ///
+    /// ## Arguments
+    /// * `self` - instance of the repository.
+    ///
+    /// ## Returns
+    /// * The result of the operation, with the reason of a failure.
+    fn ping(&self) -> BoxFuture<'_, Result<(), String>> {
+        Box::pin(async { Ok(()) })
+    }
+
+    /// Forgets what the repository keeps in memory, after the data behind
+    /// it was changed without going through it, as the sandbox resets do.
+    /// Backends keeping nothing have nothing to do.
+    ///
+    /// ## Arguments
+    /// * `self` - instance of the repository.
+    fn invalidate(&self) {}
+
+    /// Prepares the indexes the backend needs before serving, such as the
+    /// one of the full-text search. Backends without indexes have nothing
+    /// to do.
+    ///
+    /// ## Arguments
+    /// * `self` - instance of the repository.
+    ///
+    /// ## Returns
+    /// * The result of the operation, with the reason of a failure.
+    fn create_indexes(&self) -> BoxFuture<'_, Result<(), String>> {
+        Box::pin(async { Ok(()) })
+    }
+
+    /// Inserts a cube, with a new ID and its data-quality score.
+    ///
+    /// ## Arguments
+    /// * `self` - instance of the repository.
+    /// * `new_cube` - cube object to be inserted.
+    ///
+    /// ## Returns
+    /// * The ID of the inserted cube, [`RepoError::DuplicateName`] if
+    ///   another cube has its name.
+    fn insert_cube<'a>(&'a self, new_cube: Cube) -> BoxFuture<'a, Result<Bson, RepoError>>;
+
+    /// Inserts several cubes at once. A cube that cannot be inserted, such
+    /// as one named like an existing cube, does not stop the others.
+    ///
+    /// ## Arguments
+    /// * `self` - instance of the repository.
+    /// * `new_cubes` - cube objects to be inserted, at least one.
+    ///
+    /// ## Returns
+    /// * The IDs of the inserted cubes and why the others failed, by their
+    ///   position in the list.
+    fn insert_cubes<'a>(
+        &'a self,
+        new_cubes: Vec<Cube>
+    ) -> BoxFuture<'a, Result<InsertedCubes, RepoError>>;
+
+    /// Gets a cube by its ID.
+    ///
+    /// ## Arguments
+    /// * `self` - instance of the repository.
+    /// * `id` - ID of the cube.
+    ///
+    /// ## Returns
+    /// * The cube object, [`RepoError::InvalidId`] if the ID is malformed
+    ///   or [`RepoError::NotFound`] if no cube has it.
+    fn get_cube<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<Cube, RepoError>>;
+
+    /// Finds a cube by its ID.
+    ///
+    /// ## Arguments
+    /// * `self` - instance of the repository.
+    /// * `id` - ID of the cube.
+    ///
+    /// ## Returns
+    /// * The cube object, if it exists.
+    fn find_cube<'a>(&'a self, id: &'a ObjectId) -> BoxFuture<'a, Result<Option<Cube>, RepoError>>;
+
+    /// Checks whether a cube with the given ID exists.
+    ///
+    /// ## Arguments
+    /// * `self` - instance of the repository.
+    /// * `id` - ID of the cube.
+    ///
+    /// ## Returns
+    /// * `true` if the cube exists.
+    fn cube_exists<'a>(&'a self, id: &'a ObjectId) -> BoxFuture<'a, Result<bool, RepoError>>;
+
+    /// Edits a cube given its ID, keeping its unknown fields and
+    /// increasing its version.
+    ///
+    /// ## Arguments
+    /// * `self` - instance of the repository.
+    /// * `id` - ID of the cube.
+    /// * `new_cube` - new cube object definition.
+    ///
+    /// ## Returns
+    /// * The result of the operation, [`RepoError::NotFound`] if no cube
+    ///   has the ID.
+    fn edit_cube<'a>(
+        &'a self,
+        id: &'a str,
+        new_cube: Cube
+    ) -> BoxFuture<'a, Result<(), RepoError>>;
+
+    /// Edits a cube given its name, keeping its unknown fields and
+    /// increasing its version.
+    ///
+    /// ## Arguments
+    /// * `self` - instance of the repository.
+    /// * `name` - name of the cube.
+    /// * `new_cube` - new cube object definition.
+    ///
+    /// ## Returns
+    /// * The result of the operation, [`RepoError::NotFound`] if no cube
+    ///   has the name.
+    fn edit_cube_by_name<'a>(
+        &'a self,
+        name: &'a str,
+        new_cube: Cube
+    ) -> BoxFuture<'a, Result<(), RepoError>>;
+
+    /// Replaces the world record of a cube given its ID, refreshing its
+    /// data-quality score and increasing its version.
+    ///
+    /// ## Arguments
+    /// * `self` - instance of the repository.
+    /// * `id` - ID of the cube.
+    /// * `wr` - new world record of the cube.
+    ///
+    /// ## Returns
+    /// * The updated cube, [`RepoError::InvalidId`] if the ID is malformed
+    ///   or [`RepoError::NotFound`] if no cube has it.
+    fn set_world_record<'a>(
+        &'a self,
+        id: &'a str,
+        wr: WorldRecord
+    ) -> BoxFuture<'a, Result<Cube, RepoError>>;
+
+    /// Deletes a cube given its ID, if it is still at the expected version.
+    /// Cubes written before versions existed count as version 0. The cube
+    /// is only marked as deleted: it is left out of every read until it is
+    /// restored, and keeps its name taken.
+    ///
+    /// ## Arguments
+    /// * `self` - instance of the repository.
+    /// * `id` - ID of the cube to be deleted.
+    /// * `version` - version the cube must be at, if any.
+    ///
+    /// ## Returns
+    /// * The result of the operation, [`RepoError::NotFound`] if no cube
+    ///   has the ID or it is at another version.
+    fn delete_cube<'a>(
+        &'a self,
+        id: &'a str,
+        version: Option<u64>
+    ) -> BoxFuture<'a, Result<(), RepoError>>;
+
+    /// Restores a deleted cube given its ID, increasing its version.
+    ///
+    /// ## Arguments
+    /// * `self` - instance of the repository.
+    /// * `id` - ID of the cube to be restored.
+    ///
+    /// ## Returns
+    /// * The restored cube, [`RepoError::InvalidId`] if the ID is malformed
+    ///   or [`RepoError::NotFound`] if no deleted cube has it.
+    fn restore_cube<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<Cube, RepoError>>;
+
+    /// Removes a cube for good given its ID, whether it is deleted or not.
+    ///
+    /// ## Arguments
+    /// * `self` - instance of the repository.
+    /// * `id` - ID of the cube to be removed.
+    ///
+    /// ## Returns
+    /// * The result of the operation, [`RepoError::NotFound`] if no cube
+    ///   has the ID.
+    fn purge_cube<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<(), RepoError>>;
+
+    /// Gets the ID and the version of every cube matching a filter.
+    ///
+    /// ## Arguments
+    /// * `self` - instance of the repository.
+    /// * `filter` - criteria of the cubes.
+    ///
+    /// ## Returns
+    /// * The IDs of the matching cubes with their versions.
+    fn get_versions<'a>(
+        &'a self,
+        filter: &'a CubeFilter
+    ) -> BoxFuture<'a, Result<Vec<(ObjectId, u64)>, RepoError>>;
+
+    /// Deletes several cubes at once, all of them or none: if any of them
+    /// is no longer at the given version, nothing is deleted. Like
+    /// [`CubeRepository::delete_cube`], the cubes are only marked as
+    /// deleted.
+    ///
+    /// ## Arguments
+    /// * `self` - instance of the repository.
+    /// * `versions` - IDs of the cubes to be deleted with their versions.
+    ///
+    /// ## Returns
+    /// * The IDs of the deleted cubes, or [`RepoError::Changed`] if any of
+    ///   them changed.
+    fn delete_versions<'a>(
+        &'a self,
+        versions: &'a [(ObjectId, u64)]
+    ) -> BoxFuture<'a, Result<Vec<ObjectId>, RepoError>>;
+
+    /// Gets all the cubes.
+    ///
+    /// ## Arguments
+    /// * `self` - instance of the repository.
+    ///
+    /// ## Returns
+    /// * A vector that contains all the cubes.
+    fn get_all_cubes<'a>(&'a self) -> BoxFuture<'a, Result<Vec<Cube>, RepoError>>;
+
+    /// Gets all the cubes one at a time, for exports that should not hold
+    /// the whole catalog in memory. Backends without cursors can keep the
+    /// default, which reads every cube first.
+    ///
+    /// ## Arguments
+    /// * `self` - instance of the repository.
+    ///
+    /// ## Returns
+    /// * A stream of the cubes.
+    fn stream_cubes<'a>(&'a self) -> BoxFuture<'a, Result<BoxStream<'a, Result<Cube, RepoError>>, RepoError>> {
+        Box::pin(async move {
+            let cubes = self.get_all_cubes().await?;
+            Ok(stream::iter(cubes.into_iter().map(Ok)).boxed())
+        })
+    }
+
+    /// Gets a page of the cubes matching a filter.
+    ///
+    /// ## Arguments
+    /// * `self` - instance of the repository.
+    /// * `filter` - criteria the cubes must match.
+    /// * `sort` - order of the cubes, insertion order if none.
+    /// * `skip` - number of cubes before the page.
+    /// * `limit` - maximum number of cubes in the page.
+    ///
+    /// ## Returns
+    /// * The cubes of the page and the number of cubes matching the filter.
+    fn get_cubes_page<'a>(
+        &'a self,
+        filter: &'a CubeFilter,
+        sort: Option<Sort>,
+        skip: u64,
+        limit: i64
+    ) -> BoxFuture<'a, Result<(Vec<Cube>, u64), RepoError>>;
+
+    /// Gets a cube by its name.
+    ///
+    /// ## Arguments
+    /// * `self` - instance of the repository.
+    /// * `name` - name of the cube.
+    ///
+    /// ## Returns
+    /// * The cube object, [`RepoError::NotFound`] if no cube has the name.
+    fn get_cube_by_name<'a>(&'a self, name: &'a str) -> BoxFuture<'a, Result<Cube, RepoError>>;
+
+    /// Finds a cube by its name or, if no cube has that name, by one of its
+    /// aliases.
+    ///
+    /// ## Arguments
+    /// * `self` - instance of the repository.
+    /// * `name` - name or alias of the cube.
+    ///
+    /// ## Returns
+    /// * The cube object, if it exists.
+    fn find_cube_by_name<'a>(
+        &'a self,
+        name: &'a str
+    ) -> BoxFuture<'a, Result<Option<Cube>, RepoError>>;
+
+    /// Searches the cubes whose name, aliases or type contain the words of
+    /// a query.
+    ///
+    /// ## Arguments
+    /// * `self` - instance of the repository.
+    /// * `query` - words to search for.
+    /// * `limit` - maximum number of cubes to return.
+    ///
+    /// ## Returns
+    /// * The matching cubes with their score, the most relevant first.
+    fn search_cubes<'a>(
+        &'a self,
+        query: &'a str,
+        limit: i64
+    ) -> BoxFuture<'a, Result<Vec<SearchHit>, RepoError>>;
+
+    /// Gets all the cubes that match the specified type.
+    ///
+    /// ## Arguments
+    /// * `self` - instance of the repository.
+    /// * `type_` - type that the cubes must match.
+    /// * `sort` - order of the cubes, insertion order if none.
+    ///
+    /// ## Returns
+    /// A vector with all the cubes that matched the type,
+    /// [`RepoError::NotFound`] if none did.
+    fn get_cube_by_type<'a>(
+        &'a self,
+        type_: &'a CubeType,
+        sort: Option<Sort>
+    ) -> BoxFuture<'a, Result<Vec<Cube>, RepoError>>;
+
+    /// Gets all the cubes whose performance matches the given filter.
+    /// Cubes without performance data never match.
+    ///
+    /// ## Arguments
+    /// * `self` - instance of the repository.
+    /// * `filter` - lower bounds that the performance must reach.
+    ///
+    /// ## Returns
+    /// A vector with all the cubes that matched the filter.
+    fn get_cubes_by_performance<'a>(
+        &'a self,
+        filter: &'a PerformanceFilter
+    ) -> BoxFuture<'a, Result<Vec<Cube>, RepoError>>;
+
+    /// Gets all the puzzles with the given shape.
+    ///
+    /// ## Arguments
+    /// * `self` - instance of the repository.
+    /// * `shape` - shape that the puzzles must have.
+    ///
+    /// ## Returns
+    /// A vector with all the puzzles that matched the shape.
+    fn get_cubes_by_shape<'a>(
+        &'a self,
+        shape: &'a ShapeKind
+    ) -> BoxFuture<'a, Result<Vec<Cube>, RepoError>>;
+
+    /// Gets all the cubes whose world record is held by the given person.
+    ///
+    /// ## Arguments
+    /// * `self` - instance of the repository.
+    /// * `holder` - name of the record holder.
+    ///
+    /// ## Returns
+    /// A vector with all the cubes whose record the person holds.
+    fn get_cubes_by_holder<'a>(
+        &'a self,
+        holder: &'a str
+    ) -> BoxFuture<'a, Result<Vec<Cube>, RepoError>>;
+
+    /// Scans every stored cube looking for schema violations, duplicate
+    /// name keys and leftovers of older writes. When `fix` is set, the
+    /// issues that can be repaired without losing data are fixed in place.
+    ///
+    /// ## Arguments
+    /// * `self` - instance of the repository.
+    /// * `fix` - whether the safe issues should be fixed.
+    ///
+    /// ## Returns
+    /// * A report with all the issues found.
+    fn check_consistency<'a>(
+        &'a self,
+        fix: bool
+    ) -> BoxFuture<'a, Result<ConsistencyReport, RepoError>>;
+}
+
+/// Repositories shared behind an [`Arc`] are repositories too, so that the
+/// routes, the sandbox and the warmup go through the same one.
+impl<T: CubeRepository + ?Sized> CubeRepository for Arc<T> {
+    fn ping(&self) -> BoxFuture<'_, Result<(), String>> {
+        (**self).ping()
+    }
+
+    fn invalidate(&self) {
+        (**self).invalidate()
+    }
+
+    fn create_indexes(&self) -> BoxFuture<'_, Result<(), String>> {
+        (**self).create_indexes()
+    }
+
+    fn insert_cube<'a>(&'a self, new_cube: Cube) -> BoxFuture<'a, Result<Bson, RepoError>> {
+        (**self).insert_cube(new_cube)
+    }
+
+    fn insert_cubes<'a>(&'a self, new_cubes: Vec<Cube>) -> BoxFuture<'a, Result<InsertedCubes, RepoError>> {
+        (**self).insert_cubes(new_cubes)
+    }
+
+    fn get_cube<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<Cube, RepoError>> {
+        (**self).get_cube(id)
+    }
+
+    fn find_cube<'a>(&'a self, id: &'a ObjectId) -> BoxFuture<'a, Result<Option<Cube>, RepoError>> {
+        (**self).find_cube(id)
+    }
+
+    fn cube_exists<'a>(&'a self, id: &'a ObjectId) -> BoxFuture<'a, Result<bool, RepoError>> {
+        (**self).cube_exists(id)
+    }
+
+    fn edit_cube<'a>(&'a self, id: &'a str, new_cube: Cube) -> BoxFuture<'a, Result<(), RepoError>> {
+        (**self).edit_cube(id, new_cube)
+    }
+
+    fn edit_cube_by_name<'a>(&'a self, name: &'a str, new_cube: Cube) -> BoxFuture<'a, Result<(), RepoError>> {
+        (**self).edit_cube_by_name(name, new_cube)
+    }
+
+    fn set_world_record<'a>(&'a self, id: &'a str, wr: WorldRecord) -> BoxFuture<'a, Result<Cube, RepoError>> {
+        (**self).set_world_record(id, wr)
+    }
+
+    fn delete_cube<'a>(&'a self, id: &'a str, version: Option<u64>) -> BoxFuture<'a, Result<(), RepoError>> {
+        (**self).delete_cube(id, version)
+    }
+
+    fn restore_cube<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<Cube, RepoError>> {
+        (**self).restore_cube(id)
+    }
+
+    fn purge_cube<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<(), RepoError>> {
+        (**self).purge_cube(id)
+    }
+
+    fn get_versions<'a>(&'a self, filter: &'a CubeFilter) -> BoxFuture<'a, Result<Vec<(ObjectId, u64)>, RepoError>> {
+        (**self).get_versions(filter)
+    }
+
+    fn delete_versions<'a>(
+        &'a self,
+        versions: &'a [(ObjectId, u64)]
+    ) -> BoxFuture<'a, Result<Vec<ObjectId>, RepoError>> {
+        (**self).delete_versions(versions)
+    }
+
+    fn get_all_cubes<'a>(&'a self) -> BoxFuture<'a, Result<Vec<Cube>, RepoError>> {
+        (**self).get_all_cubes()
+    }
+
+    fn stream_cubes<'a>(&'a self) -> BoxFuture<'a, Result<BoxStream<'a, Result<Cube, RepoError>>, RepoError>> {
+        (**self).stream_cubes()
+    }
+
+    fn get_cubes_page<'a>(
+        &'a self,
+        filter: &'a CubeFilter,
+        sort: Option<Sort>,
+        skip: u64,
+        limit: i64
+    ) -> BoxFuture<'a, Result<(Vec<Cube>, u64), RepoError>> {
+        (**self).get_cubes_page(filter, sort, skip, limit)
+    }
+
+    fn get_cube_by_name<'a>(&'a self, name: &'a str) -> BoxFuture<'a, Result<Cube, RepoError>> {
+        (**self).get_cube_by_name(name)
+    }
+
+    fn find_cube_by_name<'a>(&'a self, name: &'a str) -> BoxFuture<'a, Result<Option<Cube>, RepoError>> {
+        (**self).find_cube_by_name(name)
+    }
+
+    fn search_cubes<'a>(&'a self, query: &'a str, limit: i64) -> BoxFuture<'a, Result<Vec<SearchHit>, RepoError>> {
+        (**self).search_cubes(query, limit)
+    }
+
+    fn get_cube_by_type<'a>(
+        &'a self,
+        type_: &'a CubeType,
+        sort: Option<Sort>
+    ) -> BoxFuture<'a, Result<Vec<Cube>, RepoError>> {
+        (**self).get_cube_by_type(type_, sort)
+    }
+
+    fn get_cubes_by_performance<'a>(
+        &'a self,
+        filter: &'a PerformanceFilter
+    ) -> BoxFuture<'a, Result<Vec<Cube>, RepoError>> {
+        (**self).get_cubes_by_performance(filter)
+    }
+
+    fn get_cubes_by_shape<'a>(&'a self, shape: &'a ShapeKind) -> BoxFuture<'a, Result<Vec<Cube>, RepoError>> {
+        (**self).get_cubes_by_shape(shape)
+    }
+
+    fn get_cubes_by_holder<'a>(&'a self, holder: &'a str) -> BoxFuture<'a, Result<Vec<Cube>, RepoError>> {
+        (**self).get_cubes_by_holder(holder)
+    }
+
+    fn check_consistency<'a>(&'a self, fix: bool) -> BoxFuture<'a, Result<ConsistencyReport, RepoError>> {
+        (**self).check_consistency(fix)
+    }
+}
@@ -0,0 +1,65 @@
+use futures::stream::TryStreamExt;
+use mongodb::{
+    bson::{doc, oid::ObjectId},
+    error::Error,
+    options::FindOptions,
+    results::InsertOneResult,
+    Collection, Database,
+};
+
+use crate::models::flagged_content_model::FlaggedContent;
+
+pub struct FlaggedContentRepo {
+    col: Collection<FlaggedContent>,
+}
+
+impl FlaggedContentRepo {
+    /// Inits the repository of the flagged submissions on top of the given
+    /// database.
+    /// 
+    /// ## Arguments
+    /// * `db` - the mongoDB database.
+    /// 
+    /// ## Returns
+    /// * An instance of the flagged submissions repository.
+    pub fn init(db: &Database) -> Self {
+        let col: Collection<FlaggedContent> = db.collection("flagged_content");
+        FlaggedContentRepo { col }
+    }
+
+    /// Holds a flagged submission for review.
+    /// 
+    /// ## Arguments
+    /// * `self` - instance of the flagged submissions repository.
+    /// * `flagged` - the submission with the reasons it was flagged.
+    /// 
+    /// ## Returns
+    /// * The result of the operation.
+    pub async fn insert_flagged(&self, flagged: FlaggedContent) -> Result<InsertOneResult, Error> {
+        self.col.insert_one(flagged, None).await
+    }
+
+    /// Gets the submissions waiting for review, the oldest first.
+    /// 
+    /// ## Arguments
+    /// * `self` - instance of the flagged submissions repository.
+    /// 
+    /// ## Returns
+    /// * A vector with the flagged submissions.
+    pub async fn get_flagged(&self) -> Result<Vec<FlaggedContent>, Error> {
+        let options = FindOptions::builder().sort(doc! {"_id": 1}).build();
+        self.col.find(None, options).await?.try_collect().await
+    }
+
+    /// Takes a submission out of the review queue.
+    /// 
+    /// ## Arguments
+    /// * `self` - instance of the flagged submissions repository.
+    /// * `id` - ID of the flagged submission.
+    /// 
+    /// ## Returns
+    /// * The submission, if it was waiting for review.
+    pub async fn take_flagged(&self, id: &ObjectId) -> Result<Option<FlaggedContent>, Error> {
+        self.col.find_one_and_delete(doc! {"_id": id}, None).await
+    }
+}
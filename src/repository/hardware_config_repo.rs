@@ -0,0 +1,63 @@
+use futures::stream::TryStreamExt;
+use mongodb::{
+    bson::{oid::ObjectId, doc},
+    error::Error,
+    options::FindOptions,
+    results::InsertOneResult,
+    Collection, Database,
+};
+
+use crate::models::hardware_config_model::{ConfigSource, HardwareConfig};
+
+pub struct HardwareConfigRepo {
+    col: Collection<HardwareConfig>,
+}
+
+impl HardwareConfigRepo {
+    /// Inits the hardware configurations repository on top of the given database.
+    /// 
+    /// ## Arguments
+    /// * `db` - the mongoDB database.
+    /// 
+    /// ## Returns
+    /// * An instance of the hardware configurations repository.
+    pub fn init(db: &Database) -> Self {
+        let col: Collection<HardwareConfig> = db.collection("hardware_configs");
+        HardwareConfigRepo { col }
+    }
+
+    /// Inserts a hardware configuration into the database.
+    /// 
+    /// ## Arguments
+    /// * `self` - instance of the hardware configurations repository.
+    /// * `new_config` - configuration to be inserted.
+    /// 
+    /// ## Returns
+    /// * The result of the operation.
+    pub async fn insert_config(&self, new_config: HardwareConfig) -> Result<InsertOneResult, Error> {
+        self.col.insert_one(new_config, None).await
+    }
+
+    /// Gets the hardware configurations recorded for a cube, the factory
+    /// ones first.
+    /// 
+    /// ## Arguments
+    /// * `self` - instance of the hardware configurations repository.
+    /// * `cube_id` - ID of the cube.
+    /// * `source` - only return the configurations from this source, if any.
+    /// 
+    /// ## Returns
+    /// * A vector with the configurations of the cube.
+    pub async fn get_configs_by_cube(
+        &self,
+        cube_id: &ObjectId,
+        source: Option<ConfigSource>
+    ) -> Result<Vec<HardwareConfig>, Error> {
+        let mut filter = doc! {"cube_id": cube_id};
+        if let Some(source) = source {
+            filter.insert("source", mongodb::bson::to_bson(&source)?);
+        }
+        let options = FindOptions::builder().sort(doc! {"source": -1}).build();
+        self.col.find(filter, options).await?.try_collect().await
+    }
+}
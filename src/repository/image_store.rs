@@ -0,0 +1,109 @@
+use futures::future::BoxFuture;
+use futures::stream::TryStreamExt;
+use mongodb::{
+    bson::{doc, oid::ObjectId},
+    gridfs::GridFsBucket,
+    options::{GridFsBucketOptions, GridFsFindOptions, GridFsUploadOptions},
+    Database,
+};
+
+use crate::models::cube_image_model::CubeImage;
+use crate::repository::repo_error::RepoError;
+
+/// Storage of the pictures of the cubes, one per cube, which the routes
+/// reach through the managed `Box<dyn ImageStore>`.
+/// [`GridFsImageStore`] is the default backend, and
+/// [`RubikApiBuilder::image_store`] replaces it with any other blob store.
+///
+/// [`RubikApiBuilder::image_store`]: crate::RubikApiBuilder::image_store
+pub trait ImageStore: Send + Sync {
+    /// Stores the picture of a cube, replacing the previous one.
+    ///
+    /// ## Arguments
+    /// * `self` - instance of the store.
+    /// * `cube_id` - ID of the cube.
+    /// * `image` - the picture, already checked.
+    ///
+    /// ## Returns
+    /// * The result of the operation.
+    fn put_image<'a>(&'a self, cube_id: &'a ObjectId, image: CubeImage) -> BoxFuture<'a, Result<(), RepoError>>;
+
+    /// Gets the picture of a cube.
+    ///
+    /// ## Arguments
+    /// * `self` - instance of the store.
+    /// * `cube_id` - ID of the cube.
+    ///
+    /// ## Returns
+    /// * The picture, if the cube has one.
+    fn get_image<'a>(&'a self, cube_id: &'a ObjectId) -> BoxFuture<'a, Result<Option<CubeImage>, RepoError>>;
+}
+
+/// Store of the pictures in the `cube_images` GridFS bucket of MongoDB,
+/// named after the ID of their cube.
+pub struct GridFsImageStore {
+    bucket: GridFsBucket,
+}
+
+impl GridFsImageStore {
+    /// Inits the picture store on top of the given database.
+    ///
+    /// ## Arguments
+    /// * `db` - the mongoDB database.
+    ///
+    /// ## Returns
+    /// * An instance of the GridFS picture store.
+    pub fn init(db: &Database) -> Self {
+        let options = GridFsBucketOptions::builder().bucket_name("cube_images".to_string()).build();
+        GridFsImageStore { bucket: db.gridfs_bucket(options) }
+    }
+}
+
+impl ImageStore for GridFsImageStore {
+    fn put_image<'a>(&'a self, cube_id: &'a ObjectId, image: CubeImage) -> BoxFuture<'a, Result<(), RepoError>> {
+        Box::pin(async move {
+            let filename = cube_id.to_hex();
+            let options = GridFsUploadOptions::builder()
+                .metadata(doc! {"content_type": &image.content_type})
+                .build();
+            let uploaded = self
+                .bucket
+                .upload_from_futures_0_3_reader(&filename, image.bytes.as_slice(), options)
+                .await?;
+
+            // The new picture is stored before the old ones are removed, so
+            // that a failed upload keeps the previous picture.
+            let mut previous = self
+                .bucket
+                .find(doc! {"filename": &filename, "_id": {"$ne": uploaded}}, None)
+                .await?;
+            while let Some(file) = previous.try_next().await? {
+                self.bucket.delete(file.id).await?;
+            }
+            Ok(())
+        })
+    }
+
+    fn get_image<'a>(&'a self, cube_id: &'a ObjectId) -> BoxFuture<'a, Result<Option<CubeImage>, RepoError>> {
+        Box::pin(async move {
+            let options = GridFsFindOptions::builder()
+                .sort(doc! {"uploadDate": -1})
+                .limit(1)
+                .build();
+            let mut files = self.bucket.find(doc! {"filename": cube_id.to_hex()}, options).await?;
+            let Some(file) = files.try_next().await? else {
+                return Ok(None);
+            };
+            let content_type = file
+                .metadata
+                .as_ref()
+                .and_then(|metadata| metadata.get_str("content_type").ok())
+                .unwrap_or("application/octet-stream")
+                .to_string();
+            let mut bytes = Vec::new();
+            self.bucket.download_to_futures_0_3_writer(file.id, &mut bytes).await?;
+
+            Ok(Some(CubeImage { content_type, bytes }))
+        })
+    }
+}
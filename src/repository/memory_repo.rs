@@ -0,0 +1,252 @@
+use std::sync::Mutex;
+
+use futures::future::BoxFuture;
+use mongodb::bson::{oid::ObjectId, Bson};
+
+use crate::models::consistency_report_model::ConsistencyReport;
+use crate::models::cube_filter_model::CubeFilter;
+use crate::models::cube_model::Cube;
+use crate::models::cube_type_model::CubeType;
+use crate::models::performance_model::PerformanceFilter;
+use crate::models::puzzle_shape_model::ShapeKind;
+use crate::models::search_model::SearchHit;
+use crate::models::sort_model::Sort;
+use crate::models::world_record_model::WorldRecord;
+use crate::repository::cube_repository::{CubeRepository, InsertedCubes};
+use crate::repository::repo_error::RepoError;
+
+/// Cubes kept in memory, for the tests of the routes. Filters, sorts and
+/// searches are ignored, and deleted cubes are gone for good.
+#[derive(Default)]
+pub struct MemoryRepo {
+    cubes: Mutex<Vec<Cube>>,
+}
+
+impl MemoryRepo {
+    /// Runs a closure on the cube with the given ID.
+    fn with_cube<T>(&self, id: &str, f: impl FnOnce(&mut Cube) -> T) -> Result<T, RepoError> {
+        let id = ObjectId::parse_str(id)?;
+        let mut cubes = self.cubes.lock().unwrap();
+        let cube = cubes.iter_mut().find(|cube| cube.id == Some(id)).ok_or(RepoError::NotFound)?;
+        Ok(f(cube))
+    }
+
+    fn insert(&self, mut new_cube: Cube) -> Result<ObjectId, RepoError> {
+        let mut cubes = self.cubes.lock().unwrap();
+        if cubes.iter().any(|cube| cube.name == new_cube.name) {
+            return Err(RepoError::DuplicateName(new_cube.name));
+        }
+        let id = ObjectId::new();
+        new_cube.id = Some(id);
+        new_cube.version = 1;
+        cubes.push(new_cube);
+        Ok(id)
+    }
+
+    fn matching(&self, matches: impl Fn(&Cube) -> bool) -> Vec<Cube> {
+        self.cubes.lock().unwrap().iter().filter(|cube| matches(cube)).cloned().collect()
+    }
+}
+
+/// Replaces a cube with its new definition, keeping its ID.
+fn replace(cube: &mut Cube, new_cube: Cube) {
+    let (id, version) = (cube.id, cube.version);
+    *cube = Cube { id, version: version + 1, ..new_cube };
+}
+
+impl CubeRepository for MemoryRepo {
+    fn insert_cube<'a>(&'a self, new_cube: Cube) -> BoxFuture<'a, Result<Bson, RepoError>> {
+        Box::pin(async move { self.insert(new_cube).map(Bson::ObjectId) })
+    }
+
+    fn insert_cubes<'a>(
+        &'a self,
+        new_cubes: Vec<Cube>
+    ) -> BoxFuture<'a, Result<InsertedCubes, RepoError>> {
+        Box::pin(async move {
+            let mut report = InsertedCubes::default();
+            for (index, new_cube) in new_cubes.into_iter().enumerate() {
+                match self.insert(new_cube) {
+                    Ok(id) => {
+                        report.inserted.insert(index, Bson::ObjectId(id));
+                    }
+                    Err(error) => report.failed.push((index, error)),
+                }
+            }
+            Ok(report)
+        })
+    }
+
+    fn get_cube<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<Cube, RepoError>> {
+        Box::pin(async move { self.with_cube(id, |cube| cube.clone()) })
+    }
+
+    fn find_cube<'a>(&'a self, id: &'a ObjectId) -> BoxFuture<'a, Result<Option<Cube>, RepoError>> {
+        Box::pin(async move { Ok(self.matching(|cube| cube.id == Some(*id)).pop()) })
+    }
+
+    fn cube_exists<'a>(&'a self, id: &'a ObjectId) -> BoxFuture<'a, Result<bool, RepoError>> {
+        Box::pin(async move { Ok(self.find_cube(id).await?.is_some()) })
+    }
+
+    fn edit_cube<'a>(
+        &'a self,
+        id: &'a str,
+        new_cube: Cube
+    ) -> BoxFuture<'a, Result<(), RepoError>> {
+        Box::pin(async move { self.with_cube(id, |cube| replace(cube, new_cube)) })
+    }
+
+    fn edit_cube_by_name<'a>(
+        &'a self,
+        name: &'a str,
+        new_cube: Cube
+    ) -> BoxFuture<'a, Result<(), RepoError>> {
+        Box::pin(async move {
+            let mut cubes = self.cubes.lock().unwrap();
+            let cube = cubes.iter_mut().find(|cube| cube.name == name).ok_or(RepoError::NotFound)?;
+            replace(cube, new_cube);
+            Ok(())
+        })
+    }
+
+    fn set_world_record<'a>(
+        &'a self,
+        id: &'a str,
+        wr: WorldRecord
+    ) -> BoxFuture<'a, Result<Cube, RepoError>> {
+        Box::pin(async move {
+            self.with_cube(id, |cube| {
+                cube.wr = wr;
+                cube.version += 1;
+                cube.clone()
+            })
+        })
+    }
+
+    fn delete_cube<'a>(
+        &'a self,
+        id: &'a str,
+        version: Option<u64>
+    ) -> BoxFuture<'a, Result<(), RepoError>> {
+        Box::pin(async move {
+            let id = ObjectId::parse_str(id)?;
+            let mut cubes = self.cubes.lock().unwrap();
+            let before = cubes.len();
+            cubes.retain(|cube| cube.id != Some(id) || version.is_some_and(|version| version != cube.version));
+            match cubes.len() == before {
+                true => Err(RepoError::NotFound),
+                false => Ok(()),
+            }
+        })
+    }
+
+    fn restore_cube<'a>(&'a self, _id: &'a str) -> BoxFuture<'a, Result<Cube, RepoError>> {
+        Box::pin(async { Err(RepoError::NotFound) })
+    }
+
+    fn purge_cube<'a>(&'a self, _id: &'a str) -> BoxFuture<'a, Result<(), RepoError>> {
+        Box::pin(async { Err(RepoError::NotFound) })
+    }
+
+    fn get_versions<'a>(
+        &'a self,
+        _filter: &'a CubeFilter
+    ) -> BoxFuture<'a, Result<Vec<(ObjectId, u64)>, RepoError>> {
+        Box::pin(async move {
+            Ok(self.matching(|_| true).into_iter().filter_map(|cube| Some((cube.id?, cube.version))).collect())
+        })
+    }
+
+    fn delete_versions<'a>(
+        &'a self,
+        versions: &'a [(ObjectId, u64)]
+    ) -> BoxFuture<'a, Result<Vec<ObjectId>, RepoError>> {
+        Box::pin(async move {
+            let mut deleted = Vec::new();
+            self.cubes.lock().unwrap().retain(|cube| match cube.id {
+                Some(id) if versions.contains(&(id, cube.version)) => {
+                    deleted.push(id);
+                    false
+                }
+                _ => true,
+            });
+            Ok(deleted)
+        })
+    }
+
+    fn get_all_cubes<'a>(&'a self) -> BoxFuture<'a, Result<Vec<Cube>, RepoError>> {
+        Box::pin(async move { Ok(self.matching(|_| true)) })
+    }
+
+    fn get_cubes_page<'a>(
+        &'a self,
+        _filter: &'a CubeFilter,
+        _sort: Option<Sort>,
+        skip: u64,
+        limit: i64
+    ) -> BoxFuture<'a, Result<(Vec<Cube>, u64), RepoError>> {
+        Box::pin(async move {
+            let cubes = self.matching(|_| true);
+            let total = cubes.len() as u64;
+            Ok((cubes.into_iter().skip(skip as usize).take(limit as usize).collect(), total))
+        })
+    }
+
+    fn get_cube_by_name<'a>(&'a self, name: &'a str) -> BoxFuture<'a, Result<Cube, RepoError>> {
+        Box::pin(async move { self.find_cube_by_name(name).await?.ok_or(RepoError::NotFound) })
+    }
+
+    fn find_cube_by_name<'a>(
+        &'a self,
+        name: &'a str
+    ) -> BoxFuture<'a, Result<Option<Cube>, RepoError>> {
+        Box::pin(async move { Ok(self.matching(|cube| cube.name == name).pop()) })
+    }
+
+    fn search_cubes<'a>(
+        &'a self,
+        _query: &'a str,
+        _limit: i64
+    ) -> BoxFuture<'a, Result<Vec<SearchHit>, RepoError>> {
+        Box::pin(async { Ok(Vec::new()) })
+    }
+
+    fn get_cube_by_type<'a>(
+        &'a self,
+        type_: &'a CubeType,
+        _sort: Option<Sort>
+    ) -> BoxFuture<'a, Result<Vec<Cube>, RepoError>> {
+        Box::pin(async move { Ok(self.matching(|cube| cube.type_ == *type_)) })
+    }
+
+    fn get_cubes_by_performance<'a>(
+        &'a self,
+        _filter: &'a PerformanceFilter
+    ) -> BoxFuture<'a, Result<Vec<Cube>, RepoError>> {
+        Box::pin(async { Ok(Vec::new()) })
+    }
+
+    fn get_cubes_by_shape<'a>(
+        &'a self,
+        _shape: &'a ShapeKind
+    ) -> BoxFuture<'a, Result<Vec<Cube>, RepoError>> {
+        Box::pin(async { Ok(Vec::new()) })
+    }
+
+    fn get_cubes_by_holder<'a>(
+        &'a self,
+        holder: &'a str
+    ) -> BoxFuture<'a, Result<Vec<Cube>, RepoError>> {
+        Box::pin(async move { Ok(self.matching(|cube| cube.wr.holder == holder)) })
+    }
+
+    fn check_consistency<'a>(
+        &'a self,
+        _fix: bool
+    ) -> BoxFuture<'a, Result<ConsistencyReport, RepoError>> {
+        Box::pin(async move {
+            Ok(ConsistencyReport { scanned: self.cubes.lock().unwrap().len() as u64, issues: Vec::new(), fixed: 0 })
+        })
+    }
+}
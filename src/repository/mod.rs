@@ -1 +1,48 @@
-pub mod mongodb_repo;
\ No newline at end of file
+use std::sync::Arc;
+
+use mongodb::{event::command::CommandEventHandler, options::ClientOptions, Client, Database};
+
+use crate::config::DatabaseConfig;
+
+pub mod cube_repository;
+#[cfg(feature = "cache")]
+pub mod cached_repo;
+pub mod mongodb_repo;
+pub mod repo_error;
+pub mod query_cost;
+pub mod setup_repo;
+pub mod hardware_config_repo;
+pub mod mod_repo;
+pub mod regulation_repo;
+pub mod reconstruction_repo;
+pub mod color_scheme_repo;
+pub mod analytics_repo;
+pub mod contribution_repo;
+pub mod user_repo;
+pub mod api_key_repo;
+pub mod flagged_content_repo;
+pub mod sealed_scramble_repo;
+pub mod image_store;
+#[cfg(test)]
+pub mod memory_repo;
+
+/// Connects to the mongoDB instance with all the info of the database.
+/// 
+/// ## Arguments
+/// * `config` - connection to MongoDB, already checked.
+/// * `sandbox` - whether the server is the sandbox, which has its own
+///   database.
+/// * `commands` - handler told about every command sent to the database,
+///   if any.
+/// 
+/// ## Returns
+/// * The database shared by all the repositories.
+pub async fn connect(
+    config: &DatabaseConfig, sandbox: bool, commands: Option<Arc<dyn CommandEventHandler>>
+) -> Database {
+    let uri = config.uri().unwrap_or_default();
+    let mut options = ClientOptions::parse(uri).await.unwrap_or_else(|error| panic!("Invalid MongoDB URI: {}", error));
+    options.command_event_handler = commands;
+    let client = Client::with_options(options).unwrap();
+    client.database(config.database_name(sandbox))
+}
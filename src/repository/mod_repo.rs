@@ -0,0 +1,97 @@
+use futures::stream::TryStreamExt;
+use mongodb::{
+    bson::{self, oid::ObjectId, doc},
+    error::Error,
+    options::{FindOneAndUpdateOptions, ReturnDocument},
+    results::InsertOneResult,
+    Collection, Database,
+};
+
+use crate::models::mod_model::{Mod, ModKind, ModStatus};
+
+pub struct ModRepo {
+    col: Collection<Mod>,
+}
+
+impl ModRepo {
+    /// Inits the mods repository on top of the given database.
+    /// 
+    /// ## Arguments
+    /// * `db` - the mongoDB database.
+    /// 
+    /// ## Returns
+    /// * An instance of the mods repository.
+    pub fn init(db: &Database) -> Self {
+        let col: Collection<Mod> = db.collection("mods");
+        ModRepo { col }
+    }
+
+    /// Inserts a mod into the database.
+    /// 
+    /// ## Arguments
+    /// * `self` - instance of the mods repository.
+    /// * `new_mod` - mod object to be inserted.
+    /// 
+    /// ## Returns
+    /// * The result of the operation.
+    pub async fn insert_mod(&self, new_mod: Mod) -> Result<InsertOneResult, Error> {
+        self.col.insert_one(new_mod, None).await
+    }
+
+    /// Gets a mod from the database by its ID, whatever its status is.
+    /// 
+    /// ## Arguments
+    /// * `self` - instance of the mods repository.
+    /// * `id` - ID of the mod.
+    /// 
+    /// ## Returns
+    /// * The mod object, if it exists.
+    pub async fn get_mod(&self, id: &ObjectId) -> Result<Option<Mod>, Error> {
+        self.col.find_one(doc! {"_id": id}, None).await
+    }
+
+    /// Gets the mods with the given status, optionally restricted to a cube
+    /// and a kind of mod.
+    /// 
+    /// ## Arguments
+    /// * `self` - instance of the mods repository.
+    /// * `status` - status the mods must have.
+    /// * `cube_id` - cube the mods must apply to, if any.
+    /// * `kind` - kind of the mods, if any.
+    /// 
+    /// ## Returns
+    /// * A vector with the mods that matched.
+    pub async fn get_mods(
+        &self,
+        status: ModStatus,
+        cube_id: Option<&ObjectId>,
+        kind: Option<ModKind>
+    ) -> Result<Vec<Mod>, Error> {
+        let mut filter = doc! {"status": bson::to_bson(&status)?};
+        if let Some(cube_id) = cube_id {
+            filter.insert("cube_ids", cube_id);
+        }
+        if let Some(kind) = kind {
+            filter.insert("kind", bson::to_bson(&kind)?);
+        }
+        self.col.find(filter, None).await?.try_collect().await
+    }
+
+    /// Changes the moderation status of a mod.
+    /// 
+    /// ## Arguments
+    /// * `self` - instance of the mods repository.
+    /// * `id` - ID of the mod.
+    /// * `status` - new status of the mod.
+    /// 
+    /// ## Returns
+    /// * The moderated mod, if it exists.
+    pub async fn set_status(&self, id: &ObjectId, status: ModStatus) -> Result<Option<Mod>, Error> {
+        let filter = doc! {"_id": id};
+        let update = doc! {"$set": {"status": bson::to_bson(&status)?}};
+        let options = FindOneAndUpdateOptions::builder()
+            .return_document(ReturnDocument::After)
+            .build();
+        self.col.find_one_and_update(filter, update, options).await
+    }
+}
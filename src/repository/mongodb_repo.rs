@@ -1,47 +1,260 @@
-use std::env;
-
-extern crate dotenv;
-use dotenv::dotenv;
+use std::collections::{HashMap, HashSet};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use mongodb::{
-    bson::{extjson::de::Error, oid::ObjectId, doc},
+    bson::{oid::ObjectId, bson, doc, Bson, DateTime, Document},
     bson, 
-    results::{InsertOneResult, UpdateResult, DeleteResult},
-    sync::{Client, Collection}
+    error::{BulkWriteFailure, CommandError, ErrorKind, WriteFailure},
+    options::{FindOneAndUpdateOptions, FindOptions, IndexOptions, InsertManyOptions, ReturnDocument},
+    IndexModel,
+    Collection, Database,
 };
 
+use futures::future::BoxFuture;
+use futures::stream::{BoxStream, StreamExt, TryStreamExt};
+
+use crate::duplicates::name_key;
 use crate::models::cube_model::Cube;
+use crate::models::cube_type_model::CubeType;
+use crate::models::performance_model::PerformanceFilter;
+use crate::models::puzzle_shape_model::ShapeKind;
+use crate::models::consistency_report_model::{ConsistencyIssue, ConsistencyReport, IssueKind};
+use crate::models::data_quality_model::DataQuality;
+use crate::models::sort_model::{Sort, SortOrder};
+use crate::models::cube_filter_model::CubeFilter;
+use crate::models::search_model::SearchHit;
+use crate::models::world_record_model::WorldRecord;
+use crate::repository::cube_repository::{CubeRepository, InsertedCubes};
+use crate::repository::query_cost::QueryPolicy;
+use crate::repository::repo_error::RepoError;
+
+/// Current time, in seconds since the Unix epoch.
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0)
+}
+
+/// Field holding when a cube was deleted. Deleted cubes keep their
+/// document, so that they can be restored, and are left out of every read.
+const DELETED_AT: &str = "deleted_at";
+
+/// Collections holding references to cubes, with the field of the
+/// references and the name of their documents in the consistency report.
+const REFERENCES: &[(&str, &str, &str)] = &[
+    ("setups", "cube_id", "setup"),
+    ("mods", "cube_ids", "mod"),
+    ("reconstructions", "cube_id", "reconstruction"),
+    ("hardware_configs", "cube_id", "hardware config"),
+];
+
+/// Restricts a filter to the cubes that are not deleted.
+fn live(mut filter: Document) -> Document {
+    filter.insert(DELETED_AT, Bson::Null);
+    filter
+}
+
+/// Cleans up the aliases of a cube: surrounding whitespace is removed, and
+/// empty or repeated aliases, or aliases equal to the name, are left out.
+fn alias_list(name: &str, aliases: &[String]) -> Vec<String> {
+    let mut list: Vec<String> = Vec::new();
+    for alias in aliases.iter().map(|alias| alias.trim()) {
+        if !alias.is_empty() && alias != name.trim() && !list.iter().any(|known| known == alias) {
+            list.push(alias.to_string());
+        }
+    }
+    list
+}
 
+/// Checks whether an index could not be created because one with the same
+/// name or keys but other options exists.
+fn is_index_conflict(error: &mongodb::error::Error) -> bool {
+    matches!(
+        error.kind.as_ref(),
+        ErrorKind::Command(CommandError { code: 85 | 86, .. })
+    )
+}
+
+/// Code of the errors of writes breaking a unique index.
+const DUPLICATE_KEY: i32 = 11000;
+
+/// Tells apart the writes refused because of the unique index on the name
+/// from the other failures of the database.
+/// 
+/// ## Arguments
+/// * `error` - error of the write.
+/// * `names` - names of the written cubes, by position.
+/// 
+/// ## Returns
+/// * [`RepoError::DuplicateName`] with the offending name, or
+///   [`RepoError::Db`].
+fn write_error(error: mongodb::error::Error, names: &[String]) -> RepoError {
+    let index = match error.kind.as_ref() {
+        ErrorKind::Write(WriteFailure::WriteError(write)) if write.code == DUPLICATE_KEY => Some(0),
+        ErrorKind::BulkWrite(BulkWriteFailure { write_errors: Some(writes), .. }) => writes
+            .iter()
+            .find(|write| write.code == DUPLICATE_KEY)
+            .map(|write| write.index),
+        _ => None,
+    };
+    match index.and_then(|index| names.get(index)) {
+        Some(name) => RepoError::DuplicateName(name.clone()),
+        None => RepoError::Db(error),
+    }
+}
+
+/// Builds the condition matching the cubes at a version. Cubes written
+/// before versions existed have none, and count as version 0.
+fn version_condition(version: u64) -> Bson {
+    if version == 0 {
+        bson!({"$in": [0, Bson::Null]})
+    } else {
+        bson!(version as i64)
+    }
+}
+
+/// Translates the sort asked for by a client into a Mongo sort document.
+/// Insertion order breaks ties, so that pages never overlap.
+fn sort_document(sort: Option<Sort>) -> Document {
+    match sort {
+        Some(sort) => {
+            let direction = match sort.order {
+                SortOrder::Asc => 1,
+                SortOrder::Desc => -1,
+            };
+            doc! {sort.field.key(): direction, "_id": 1}
+        }
+        None => doc! {"_id": 1},
+    }
+}
+
+/// Builds the Mongo condition of an inclusive range, if any of its bounds
+/// is given.
+fn range<T: Into<Bson>>(min: Option<T>, max: Option<T>) -> Option<Document> {
+    let mut condition = Document::new();
+    if let Some(min) = min {
+        condition.insert("$gte", min);
+    }
+    if let Some(max) = max {
+        condition.insert("$lte", max);
+    }
+    (!condition.is_empty()).then_some(condition)
+}
+
+/// Builds the Mongo filter document matching every criterion of a filter,
+/// so that new criteria only need a field in [`CubeFilter`] and a line here.
+fn filter_document(filter: &CubeFilter) -> Result<Document, RepoError> {
+    let mut document = Document::new();
+    if let Some(type_) = &filter.type_ {
+        document.insert("type_", bson::to_bson(type_)?);
+    }
+    if let Some(years) = range(filter.min_year, filter.max_year) {
+        document.insert("year_created", years);
+    }
+    if let Some(pieces) = range(filter.min_pieces, filter.max_pieces) {
+        document.insert("pieces", pieces);
+    }
+    let seconds = |time: Option<u64>| time.map(|time| time as i64);
+    if let Some(created) = range(seconds(filter.min_created_at), seconds(filter.max_created_at)) {
+        document.insert("created_at", created);
+    }
+    if let Some(updated) = range(seconds(filter.min_updated_at), seconds(filter.max_updated_at)) {
+        document.insert("updated_at", updated);
+    }
+    Ok(document)
+}
+
+/// Repository of the cubes stored in the `cubes` collection of MongoDB.
 pub struct MongoRepo {
+    db: Database,
     col: Collection<Cube>,
+    policy: QueryPolicy,
 }
 
 impl MongoRepo {
-    /// Inits a mongoDB instance with all the info of the database
+    /// Inits the cubes repository on top of the given database.
+    /// 
+    /// ## Arguments
+    /// * `db` - the mongoDB database.
+    /// * `collection` - name of the collection of the cubes.
+    /// * `policy` - query cost limits of the configuration.
     /// 
     /// ## Returns
     /// * An instance of a mongoDB repository.
-    pub fn init() -> Self {
-        dotenv().ok();
-        let uri = env::var("MONGOURI")
-            .unwrap_or_else(|_| "MONGOURI not found in .env".to_string());
-        let client = Client::with_uri_str(uri).unwrap();
-        let db = client.database("rubikapi");
-        let col: Collection<Cube> = db.collection("cubes");
-        MongoRepo { col }
+    pub fn init(db: &Database, collection: &str, policy: QueryPolicy) -> Self {
+        let col: Collection<Cube> = db.collection(collection);
+        MongoRepo { db: db.clone(), col, policy }
     }
 
-    /// Inserts a cube into the database.
-    /// 
-    /// ## Arguments
-    /// * `self` - instance of the mongoDB repo.
-    /// * `new_cube` - cube object to be inserted.
-    /// 
-    /// ## Returns
-    /// * The result of the operation.
-    pub fn insert_cube(&self, new_cube: Cube) -> Result<InsertOneResult, Error> {
-        let cube = Cube {
+    /// Checks the cost of a query before it is sent. The size of the
+    /// collection is only read, from its metadata, when the filter uses no
+    /// index and the query has no pagination.
+    async fn check_cost(&self, filter: &Document, paginated: bool) -> Result<(), RepoError> {
+        let (indexed, fields) = self.policy.check_lists(filter)?;
+        if indexed || paginated {
+            return Ok(());
+        }
+        let cubes = self.col.estimated_document_count(None).await?;
+        self.policy.check_scan(fields, cubes)?;
+        Ok(())
+    }
+
+    /// Replaces the known fields of the cube matching a filter, bumping
+    /// its version.
+    async fn edit_where(&self, filter: Document, new_cube: Cube) -> Result<(), RepoError> {
+        let bson_type = bson::to_bson(&new_cube.type_)?;
+        let bson_wr = bson::to_bson(&new_cube.wr)?;
+        let bson_pieces = bson::to_bson(&new_cube.pieces)?;
+        let bson_faces  = bson::to_bson(&new_cube.faces)?;
+        let bson_stickers  = bson::to_bson(&new_cube.stickers)?;
+        let bson_year  = bson::to_bson(&new_cube.year_created)?;
+        let bson_performance = bson::to_bson(&new_cube.performance)?;
+        let bson_adjustability = bson::to_bson(&new_cube.adjustability)?;
+        let bson_shape = bson::to_bson(&new_cube.shape)?;
+        let bson_quality = bson::to_bson(&DataQuality::of(&new_cube))?;
+        let names = [new_cube.name.clone()];
+        let aliases = alias_list(&new_cube.name, &new_cube.aliases);
+        // Only the known fields are set, so that the unknown ones stored
+        // are kept as they are and none sent by the client is written.
+        let fields = doc! {
+            "name": new_cube.name,
+            "aliases": aliases,
+            "type_": bson_type,
+            "pieces": bson_pieces,
+            "faces": bson_faces,
+            "stickers": bson_stickers,
+            "year_created": bson_year,
+            "wr": bson_wr,
+            "performance": bson_performance,
+            "adjustability": bson_adjustability,
+            "electronics": new_cube.electronics,
+            "shape": bson_shape,
+            "quality": bson_quality,
+            "updated_at": now() as i64,
+        };
+        let new_doc = doc! {"$set": fields, "$inc": {"version": 1}};
+        let updated_doc = self
+            .col
+            .update_one(filter, new_doc, None)
+            .await
+            .map_err(|error| write_error(error, &names))?;
+
+        match updated_doc.matched_count {
+            0 => Err(RepoError::NotFound),
+            _ => Ok(()),
+        }
+    }
+
+    /// Prepares a new cube to be stored, without ID, with its data-quality
+    /// score and timestamped now. The unknown fields sent by the client are
+    /// left out.
+    fn prepare_insert(new_cube: Cube) -> Cube {
+        let quality = DataQuality::of(&new_cube);
+        let inserted = now();
+        Cube {
             id: None,
+            aliases: alias_list(&new_cube.name, &new_cube.aliases),
             name: new_cube.name,
             type_: new_cube.type_,
             pieces: new_cube.pieces,
@@ -49,184 +262,710 @@ impl MongoRepo {
             stickers: new_cube.stickers,
             year_created: new_cube.year_created,
             wr: new_cube.wr,
-        };
+            performance: new_cube.performance,
+            adjustability: new_cube.adjustability,
+            electronics: new_cube.electronics,
+            shape: new_cube.shape,
+            quality: Some(quality),
+            created_at: Some(inserted),
+            updated_at: Some(inserted),
+            version: 1,
+            extra: Document::new(),
+        }
+    }
+}
 
-        let cube = self
-            .col
-            .insert_one(cube, None)
-            .expect("Failed to insert document.");
+impl CubeRepository for MongoRepo {
+    fn ping(&self) -> BoxFuture<'_, Result<(), String>> {
+        Box::pin(async move {
+            self.db
+                .run_command(doc! {"ping": 1}, None)
+                .await
+                .map(|_| ())
+                .map_err(|error| error.to_string())
+        })
+    }
+
+    fn create_indexes(&self) -> BoxFuture<'_, Result<(), String>> {
+        Box::pin(async move {
+            let aliases = IndexModel::builder()
+                .keys(doc! {"aliases": 1})
+                .options(IndexOptions::builder().name("cube_aliases".to_string()).build())
+                .build();
+            self.col
+                .create_index(aliases, None)
+                .await
+                .map_err(|error| error.to_string())?;
+
+            let created = IndexModel::builder()
+                .keys(doc! {"created_at": 1})
+                .options(IndexOptions::builder().name("cube_created_at".to_string()).build())
+                .build();
+            self.col
+                .create_index(created, None)
+                .await
+                .map_err(|error| error.to_string())?;
 
-        Ok(cube)
+            let types = IndexModel::builder()
+                .keys(doc! {"type_": 1})
+                .options(IndexOptions::builder().name("cube_type".to_string()).build())
+                .build();
+            self.col
+                .create_index(types, None)
+                .await
+                .map_err(|error| error.to_string())?;
+
+            let text = IndexModel::builder()
+                .keys(doc! {"name": "text", "aliases": "text", "type_": "text"})
+                .options(IndexOptions::builder()
+                    .name("cube_text".to_string())
+                    .weights(doc! {"name": 10, "aliases": 5, "type_": 1})
+                    .build())
+                .build();
+            match self.col.create_index(text.clone(), None).await {
+                Ok(_) => {}
+                // Older deployments have a text index without the aliases,
+                // and a collection can only have one.
+                Err(error) if is_index_conflict(&error) => {
+                    self.col
+                        .drop_index("cube_text", None)
+                        .await
+                        .map_err(|error| error.to_string())?;
+                    self.col
+                        .create_index(text, None)
+                        .await
+                        .map_err(|error| error.to_string())?;
+                }
+                Err(error) => return Err(error.to_string()),
+            }
+
+            // Fails while duplicate names remain, which the consistency
+            // check reports.
+            let names = IndexModel::builder()
+                .keys(doc! {"name": 1})
+                .options(IndexOptions::builder()
+                    .name("cube_name".to_string())
+                    .unique(true)
+                    .build())
+                .build();
+            self.col
+                .create_index(names, None)
+                .await
+                .map(|_| ())
+                .map_err(|error| error.to_string())
+        })
     }
 
-    /// Gets a cube from the database by its ID.
-    /// 
-    /// ## Arguments
-    /// * `self` - instance of the mongoDB repository.
-    /// * `id` - ID of the cube.
-    /// 
-    /// ## Returns
-    /// * The cube object that was retrieved from the database.
-    pub fn get_cube(&self, id: &String) -> Result<Cube, Error> {
-        let obj_id = ObjectId::parse_str(id).unwrap();
-        let filter = doc! {"_id": obj_id};
-        let cube_detail = self
-            .col
-            .find_one(filter, None)
-            .expect("Error getting cube's detail");
-        
-        Ok(cube_detail.unwrap())
+    fn insert_cube<'a>(&'a self, new_cube: Cube) -> BoxFuture<'a, Result<Bson, RepoError>> {
+        Box::pin(async move {
+            let names = [new_cube.name.clone()];
+            let cube = self
+                .col
+                .insert_one(Self::prepare_insert(new_cube), None)
+                .await
+                .map_err(|error| write_error(error, &names))?;
+
+            Ok(cube.inserted_id)
+        })
     }
-    
-    /// Edits a cube from the database given its ID.
-    /// 
-    /// ## Arguments
-    /// * `self` - instance of the mongoDB repository.
-    /// * `new_cube` - new cube object definition.
-    /// 
-    /// ## Returns
-    /// * The result of the operation.
-    pub fn edit_cube(&self, id: &String, new_cube: Cube) -> Result<UpdateResult, Error> {
-        let obj_id = ObjectId::parse_str(id).unwrap();
-        let filter = doc! {"_id": obj_id};
-        let bson_type = bson::to_bson(&new_cube.type_).unwrap();
-        let bson_wr = bson::to_bson(&new_cube.wr).unwrap();
-        let bson_pieces = bson::to_bson(&new_cube.pieces).unwrap();
-        let bson_faces  = bson::to_bson(&new_cube.faces).unwrap();
-        let bson_stickers  = bson::to_bson(&new_cube.stickers).unwrap();
-        let bson_year  = bson::to_bson(&new_cube.year_created).unwrap();
-        let new_doc = doc! {
-            "$set":
-                {
-                    "id": new_cube.id,
-                    "name": new_cube.name,
-                    "type_": bson_type,
-                    "pieces": bson_pieces,
-                    "faces": bson_faces,
-                    "stickers": bson_stickers,
-                    "year_created": bson_year,
-                    "wr": bson_wr,
-                },
-        };
-        let updated_doc = self
-            .col
-            .update_one(filter, new_doc, None)
-            .expect("Error updating the cube");
 
-        Ok(updated_doc)
+    fn insert_cubes<'a>(
+        &'a self,
+        new_cubes: Vec<Cube>
+    ) -> BoxFuture<'a, Result<InsertedCubes, RepoError>> {
+        Box::pin(async move {
+            // The IDs are given here, as those of the cubes inserted are
+            // not reported when others fail.
+            let cubes: Vec<Cube> = new_cubes
+                .into_iter()
+                .map(|cube| Cube { id: Some(ObjectId::new()), ..Self::prepare_insert(cube) })
+                .collect();
+            let mut inserted: HashMap<usize, Bson> = cubes
+                .iter()
+                .enumerate()
+                .filter_map(|(index, cube)| cube.id.map(|id| (index, Bson::ObjectId(id))))
+                .collect();
+            let names: Vec<String> = cubes.iter().map(|cube| cube.name.clone()).collect();
+            let options = InsertManyOptions::builder().ordered(false).build();
+            let error = match self.col.insert_many(cubes, options).await {
+                Ok(_) => return Ok(InsertedCubes { inserted, failed: Vec::new() }),
+                Err(error) => error,
+            };
+            let writes = match error.kind.as_ref() {
+                ErrorKind::BulkWrite(BulkWriteFailure { write_errors: Some(writes), write_concern_error: None, .. }) => {
+                    writes
+                }
+                _ => return Err(RepoError::Db(error)),
+            };
+            let failed = writes
+                .iter()
+                .map(|write| {
+                    inserted.remove(&write.index);
+                    let reason = match names.get(write.index) {
+                        Some(name) if write.code == DUPLICATE_KEY => RepoError::DuplicateName(name.clone()),
+                        _ => RepoError::Refused(write.message.clone()),
+                    };
+                    (write.index, reason)
+                })
+                .collect();
+            Ok(InsertedCubes { inserted, failed })
+        })
     }
-    
-    /// Edits a cube from the database given its name.
-    /// 
-    /// ## Arguments
-    /// * `self` - instance of the mongoDB repository.
-    /// * `new_cube` - new cube object definition.
-    /// 
-    /// ## Returns
-    /// * The result of the operation.
-    pub fn edit_cube_by_name(
-        &self, 
-        name: &String, 
+
+    fn get_cube<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<Cube, RepoError>> {
+        Box::pin(async move {
+            let obj_id = ObjectId::parse_str(id)?;
+            let filter = live(doc! {"_id": obj_id});
+            let cube_detail = self
+                .col
+                .find_one(filter, None)
+                .await?;
+
+            cube_detail.ok_or(RepoError::NotFound)
+        })
+    }
+
+    fn find_cube<'a>(&'a self, id: &'a ObjectId) -> BoxFuture<'a, Result<Option<Cube>, RepoError>> {
+        Box::pin(async move {
+            let cube_detail = self
+                .col
+                .find_one(live(doc! {"_id": id}), None)
+                .await?;
+
+            Ok(cube_detail)
+        })
+    }
+
+    fn cube_exists<'a>(&'a self, id: &'a ObjectId) -> BoxFuture<'a, Result<bool, RepoError>> {
+        Box::pin(async move {
+            let count = self
+                .col
+                .count_documents(live(doc! {"_id": id}), None)
+                .await?;
+
+            Ok(count > 0)
+        })
+    }
+
+    fn edit_cube<'a>(
+        &'a self,
+        id: &'a str,
         new_cube: Cube
-    ) -> Result<UpdateResult, Error> {
-        let filter = doc!{ "name": name };
-        let bson_type = bson::to_bson(&new_cube.type_).unwrap();
-        let bson_wr = bson::to_bson(&new_cube.wr).unwrap();
-        let bson_pieces = bson::to_bson(&new_cube.pieces).unwrap();
-        let bson_faces  = bson::to_bson(&new_cube.faces).unwrap();
-        let bson_stickers  = bson::to_bson(&new_cube.stickers).unwrap();
-        let bson_year  = bson::to_bson(&new_cube.year_created).unwrap();
-        let new_doc = doc! {
-            "$set":
-                {
-                    "id": new_cube.id,
-                    "name": new_cube.name,
-                    "type_": bson_type,
-                    "pieces": bson_pieces,
-                    "faces": bson_faces,
-                    "stickers": bson_stickers,
-                    "year_created": bson_year,
-                    "wr": bson_wr,
-                },
-        };
-        let updated_doc = self
-            .col
-            .update_one(filter, new_doc, None)
-            .expect("Error updating the cube");
+    ) -> BoxFuture<'a, Result<(), RepoError>> {
+        Box::pin(async move {
+            let obj_id = ObjectId::parse_str(id)?;
+            self.edit_where(live(doc! {"_id": obj_id}), new_cube).await
+        })
+    }
 
-        Ok(updated_doc)
+    fn edit_cube_by_name<'a>(
+        &'a self, 
+        name: &'a str, 
+        new_cube: Cube
+    ) -> BoxFuture<'a, Result<(), RepoError>> {
+        Box::pin(self.edit_where(live(doc! {"name": name}), new_cube))
     }
-    
-    /// Deletes a cube from the database given its ID.
-    /// 
-    /// ## Arguments
-    /// * `self` - instance of the mongoDB repository.
-    /// * `id` - ID of the cube to be deleted.
-    /// 
-    /// ## Returns
-    /// * The result of the operation.
-    pub fn delete_cube(&self, id: &String) -> Result<DeleteResult, Error> {
-        let obj_id = ObjectId::parse_str(id).unwrap();
-        let filter = doc! {"_id": obj_id};
-        let cube_detail = self
-            .col
-            .delete_one(filter, None)
-            .expect("Error deleting the cube");
-        
-        Ok(cube_detail)
+
+    fn set_world_record<'a>(
+        &'a self,
+        id: &'a str,
+        wr: WorldRecord
+    ) -> BoxFuture<'a, Result<Cube, RepoError>> {
+        Box::pin(async move {
+            let obj_id = ObjectId::parse_str(id)?;
+            let mut cube = self.get_cube(id).await?;
+            cube.wr = wr;
+            let bson_wr = bson::to_bson(&cube.wr)?;
+            let bson_quality = bson::to_bson(&DataQuality::of(&cube))?;
+            let update = doc! {
+                "$set": {"wr": bson_wr, "quality": bson_quality, "updated_at": now() as i64},
+                "$inc": {"version": 1},
+            };
+            let options = FindOneAndUpdateOptions::builder()
+                .return_document(ReturnDocument::After)
+                .build();
+            let updated_cube = self
+                .col
+                .find_one_and_update(live(doc! {"_id": obj_id}), update, options)
+                .await?;
+
+            updated_cube.ok_or(RepoError::NotFound)
+        })
     }
-    
-    /// Gets all the cubes available from the database.
-    /// 
-    /// ## Arguments
-    /// * `self` - instance of the mongoDB repository.
-    /// 
-    /// ## Returns
-    /// * A vector that contains all the cubes.
-    pub fn get_all_cubes(&self) -> Result<Vec<Cube>, Error> {
-        let cursors = self
-            .col
-            .find(None, None)
-            .expect("Error getting list of cubes!");
-        let cubes = cursors.map(|doc| doc.unwrap()).collect();
 
-        Ok(cubes)
+    fn delete_cube<'a>(
+        &'a self,
+        id: &'a str,
+        version: Option<u64>
+    ) -> BoxFuture<'a, Result<(), RepoError>> {
+        Box::pin(async move {
+            let obj_id = ObjectId::parse_str(id)?;
+            let mut filter = live(doc! {"_id": obj_id});
+            if let Some(version) = version {
+                filter.insert("version", version_condition(version));
+            }
+            let update = doc! {"$set": {DELETED_AT: DateTime::now()}, "$inc": {"version": 1}};
+            let cube_detail = self
+                .col
+                .update_one(filter, update, None)
+                .await?;
+
+            match cube_detail.modified_count {
+                0 => Err(RepoError::NotFound),
+                _ => Ok(()),
+            }
+        })
     }
 
-    /// Gets a cube from the database by its name
-    /// 
-    /// ## Arguments
-    /// * `self` - instance of the mongoDB repository.
-    /// * `name` - name of the cube to be retrieved from the database.
-    /// 
-    /// ## Returns
-    /// * The cube object.
-    pub fn get_cube_by_name(&self, name: &String) -> Result<Cube, Error> {
-        let filter = doc! {"name": name};
-        let cube_detail = self
-            .col
-            .find_one(filter, None)
-            .expect("Error getting cube by name!");
+    fn restore_cube<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<Cube, RepoError>> {
+        Box::pin(async move {
+            let obj_id = ObjectId::parse_str(id)?;
+            let filter = doc! {"_id": obj_id, DELETED_AT: {"$ne": Bson::Null}};
+            let update = doc! {"$unset": {DELETED_AT: ""}, "$inc": {"version": 1}};
+            let options = FindOneAndUpdateOptions::builder()
+                .return_document(ReturnDocument::After)
+                .build();
+            let restored_cube = self
+                .col
+                .find_one_and_update(filter, update, options)
+                .await?;
 
-        Ok(cube_detail.unwrap())
+            restored_cube.ok_or(RepoError::NotFound)
+        })
     }
 
-    /// Gets all the cubes that match the specified type.
-    /// 
-    /// ## Arguments
-    /// * `self` - instance of the mongoDB repository.
-    /// * `type_` - type that the cubes must match.
-    /// 
-    /// ## Returns
-    /// A vector with all the cubes that matched the type.
-    pub fn get_cube_by_type(&self, type_: &String) -> Result<Vec<Cube>, Error> {
-        let filter = doc! {"type_": type_};
-        let cursors = self
-            .col
-            .find(filter, None)
-            .expect("Error getting cube by type!");
-        let cubes = cursors.map(|doc| doc.unwrap()).collect();
+    fn purge_cube<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<(), RepoError>> {
+        Box::pin(async move {
+            let obj_id = ObjectId::parse_str(id)?;
+            let cube_detail = self
+                .col
+                .delete_one(doc! {"_id": obj_id}, None)
+                .await?;
+
+            match cube_detail.deleted_count {
+                0 => Err(RepoError::NotFound),
+                _ => Ok(()),
+            }
+        })
+    }
+
+    fn get_versions<'a>(
+        &'a self,
+        filter: &'a CubeFilter
+    ) -> BoxFuture<'a, Result<Vec<(ObjectId, u64)>, RepoError>> {
+        Box::pin(async move {
+            let options = FindOptions::builder()
+                .projection(doc! {"_id": 1, "version": 1})
+                .build();
+            let filter = filter_document(filter)?;
+            self.check_cost(&filter, false).await?;
+            let cursors = self
+                .col
+                .clone_with_type::<Document>()
+                .find(live(filter), options)
+                .await?;
+            let docs: Vec<Document> = cursors.try_collect().await?;
+            let versions = docs
+                .iter()
+                .filter_map(|doc| {
+                    let version = match doc.get("version") {
+                        Some(Bson::Int32(version)) => *version as u64,
+                        Some(Bson::Int64(version)) => *version as u64,
+                        _ => 0,
+                    };
+                    doc.get_object_id("_id").ok().map(|id| (id, version))
+                })
+                .collect();
+
+            Ok(versions)
+        })
+    }
+
+    fn delete_versions<'a>(
+        &'a self,
+        versions: &'a [(ObjectId, u64)]
+    ) -> BoxFuture<'a, Result<Vec<ObjectId>, RepoError>> {
+        Box::pin(async move {
+            if versions.is_empty() {
+                return Ok(Vec::new());
+            }
+            let conditions: Vec<Document> = versions
+                .iter()
+                .map(|(id, version)| doc! {"_id": id, "version": version_condition(*version)})
+                .collect();
+            let filter = doc! {"$or": conditions};
+            self.check_cost(&filter, false).await?;
+            let deleted_at = DateTime::now();
+            let update = doc! {"$set": {DELETED_AT: deleted_at}, "$inc": {"version": 1}};
+            let deleted = self
+                .col
+                .update_many(live(filter), update, None)
+                .await?;
+            if deleted.modified_count == versions.len() as u64 {
+                return Ok(versions.iter().map(|(id, _)| *id).collect());
+            }
 
-        Ok(cubes)
+            // A cube changed since its version was read: the others are
+            // restored as they were, found by the version and time they
+            // were just deleted with.
+            let restored: Vec<Document> = versions
+                .iter()
+                .map(|(id, version)| doc! {"_id": id, "version": (*version + 1) as i64, DELETED_AT: deleted_at})
+                .collect();
+            self.col
+                .update_many(
+                    doc! {"$or": restored},
+                    doc! {"$unset": {DELETED_AT: ""}, "$inc": {"version": -1}},
+                    None,
+                )
+                .await?;
+            Err(RepoError::Changed)
+        })
     }
-}
\ No newline at end of file
+
+    fn get_all_cubes<'a>(&'a self) -> BoxFuture<'a, Result<Vec<Cube>, RepoError>> {
+        Box::pin(async move {
+            let cursors = self
+                .col
+                .find(live(Document::new()), None)
+                .await?;
+            let cubes = cursors.try_collect().await?;
+
+            Ok(cubes)
+        })
+    }
+
+    fn stream_cubes<'a>(&'a self) -> BoxFuture<'a, Result<BoxStream<'a, Result<Cube, RepoError>>, RepoError>> {
+        Box::pin(async move {
+            let cursor = self.col.find(live(Document::new()), None).await?;
+            Ok(cursor.map(|cube| cube.map_err(RepoError::from)).boxed())
+        })
+    }
+
+    fn get_cubes_page<'a>(
+        &'a self,
+        filter: &'a CubeFilter,
+        sort: Option<Sort>,
+        skip: u64,
+        limit: i64
+    ) -> BoxFuture<'a, Result<(Vec<Cube>, u64), RepoError>> {
+        Box::pin(async move {
+            let options = FindOptions::builder()
+                .sort(sort_document(sort))
+                .skip(skip)
+                .limit(limit)
+                .build();
+            let filter = filter_document(filter)?;
+            self.check_cost(&filter, true).await?;
+            let filter = live(filter);
+            let cursors = self
+                .col
+                .find(filter.clone(), options)
+                .await?;
+            let cubes = cursors.try_collect().await?;
+            let total = self
+                .col
+                .count_documents(filter, None)
+                .await?;
+
+            Ok((cubes, total))
+        })
+    }
+
+    fn get_cube_by_name<'a>(&'a self, name: &'a str) -> BoxFuture<'a, Result<Cube, RepoError>> {
+        Box::pin(async move {
+            let filter = live(doc! {"name": name});
+            let cube_detail = self
+                .col
+                .find_one(filter, None)
+                .await?;
+
+            cube_detail.ok_or(RepoError::NotFound)
+        })
+    }
+
+    fn find_cube_by_name<'a>(
+        &'a self,
+        name: &'a str
+    ) -> BoxFuture<'a, Result<Option<Cube>, RepoError>> {
+        Box::pin(async move {
+            let cube_detail = self
+                .col
+                .find_one(live(doc! {"name": name}), None)
+                .await?;
+            if cube_detail.is_some() {
+                return Ok(cube_detail);
+            }
+            let cube_detail = self
+                .col
+                .find_one(live(doc! {"aliases": name}), None)
+                .await?;
+
+            Ok(cube_detail)
+        })
+    }
+
+    fn search_cubes<'a>(
+        &'a self,
+        query: &'a str,
+        limit: i64
+    ) -> BoxFuture<'a, Result<Vec<SearchHit>, RepoError>> {
+        Box::pin(async move {
+            let pipeline = vec![
+                doc! {"$match": {"$text": {"$search": query}, DELETED_AT: Bson::Null}},
+                doc! {"$sort": {"score": {"$meta": "textScore"}}},
+                doc! {"$limit": limit},
+                doc! {"$project": {"_id": 0, "score": {"$meta": "textScore"}, "cube": "$$ROOT"}},
+            ];
+            let cursors = self
+                .col
+                .aggregate(pipeline, None)
+                .await?;
+            let docs: Vec<Document> = cursors.try_collect().await?;
+            let hits = docs
+                .into_iter()
+                .map(bson::from_document)
+                .collect::<Result<Vec<SearchHit>, _>>()?;
+
+            Ok(hits)
+        })
+    }
+
+    fn get_cube_by_type<'a>(
+        &'a self,
+        type_: &'a CubeType,
+        sort: Option<Sort>
+    ) -> BoxFuture<'a, Result<Vec<Cube>, RepoError>> {
+        Box::pin(async move {
+            let filter = doc! {"type_": bson::to_bson(type_)?};
+            self.check_cost(&filter, false).await?;
+            let options = FindOptions::builder().sort(sort_document(sort)).build();
+            let cursors = self
+                .col
+                .find(live(filter), options)
+                .await?;
+            let cubes: Vec<Cube> = cursors.try_collect().await?;
+            if cubes.is_empty() {
+                return Err(RepoError::NotFound);
+            }
+
+            Ok(cubes)
+        })
+    }
+
+    fn get_cubes_by_performance<'a>(
+        &'a self,
+        filter: &'a PerformanceFilter
+    ) -> BoxFuture<'a, Result<Vec<Cube>, RepoError>> {
+        Box::pin(async move {
+            let mut query = doc! {"performance": {"$type": "object"}};
+            if let Some(min) = filter.min_corner_cutting_forward {
+                query.insert("performance.corner_cutting_forward", doc! {"$gte": min});
+            }
+            if let Some(min) = filter.min_corner_cutting_reverse {
+                query.insert("performance.corner_cutting_reverse", doc! {"$gte": min});
+            }
+            if let Some(min) = filter.min_stability {
+                query.insert("performance.stability", doc! {"$gte": min as i32});
+            }
+            if let Some(min) = filter.min_speed {
+                query.insert("performance.speed", doc! {"$gte": min as i32});
+            }
+            if let Some(one_handed) = filter.one_handed {
+                query.insert("performance.one_handed", one_handed);
+            }
+            self.check_cost(&query, false).await?;
+            let cursors = self
+                .col
+                .find(live(query), None)
+                .await?;
+            let cubes = cursors.try_collect().await?;
+
+            Ok(cubes)
+        })
+    }
+
+    fn get_cubes_by_shape<'a>(
+        &'a self,
+        shape: &'a ShapeKind
+    ) -> BoxFuture<'a, Result<Vec<Cube>, RepoError>> {
+        Box::pin(async move {
+            let filter = doc! {"shape.shape": bson::to_bson(shape)?};
+            self.check_cost(&filter, false).await?;
+            let cursors = self
+                .col
+                .find(live(filter), None)
+                .await?;
+            let cubes = cursors.try_collect().await?;
+
+            Ok(cubes)
+        })
+    }
+
+    fn get_cubes_by_holder<'a>(
+        &'a self,
+        holder: &'a str
+    ) -> BoxFuture<'a, Result<Vec<Cube>, RepoError>> {
+        Box::pin(async move {
+            let filter = doc! {"$or": [{"wr.holder": holder}, {"wr.author": holder}]};
+            self.check_cost(&filter, false).await?;
+            let cursors = self
+                .col
+                .find(live(filter), None)
+                .await?;
+            let cubes = cursors.try_collect().await?;
+
+            Ok(cubes)
+        })
+    }
+
+    fn check_consistency<'a>(
+        &'a self,
+        fix: bool
+    ) -> BoxFuture<'a, Result<ConsistencyReport, RepoError>> {
+        Box::pin(async move {
+            let raw_col = self.col.clone_with_type::<Document>();
+            let mut cursors = raw_col
+                .find(None, None)
+                .await?;
+
+            let mut scanned = 0;
+            let mut issues = Vec::new();
+            let mut names: HashMap<String, Vec<(Option<ObjectId>, String)>> = HashMap::new();
+            let mut live_ids = HashSet::new();
+            let mut deleted_ids = HashSet::new();
+            while let Some(doc) = cursors.next().await {
+                let doc = doc?;
+                scanned += 1;
+                let id = doc.get_object_id("_id").ok();
+                if let Some(id) = id {
+                    if doc.get_datetime(DELETED_AT).is_ok() {
+                        deleted_ids.insert(id);
+                    } else {
+                        live_ids.insert(id);
+                    }
+                }
+
+                if let Err(e) = bson::from_document::<Cube>(doc.clone()) {
+                    issues.push(ConsistencyIssue {
+                        kind: IssueKind::SchemaViolation,
+                        cube_id: id,
+                        detail: e.to_string(),
+                        fixable: false,
+                        fixed: false,
+                        fix_error: None,
+                    });
+                }
+
+                // Older updates stored a redundant `id` field next to `_id`.
+                if doc.contains_key("id") {
+                    issues.push(ConsistencyIssue {
+                        kind: IssueKind::StrayField,
+                        cube_id: id,
+                        detail: "redundant `id` field".to_string(),
+                        fixable: true,
+                        fixed: false,
+                        fix_error: None,
+                    });
+                }
+
+                if let Ok(name) = doc.get_str("name") {
+                    if name.trim() != name {
+                        issues.push(ConsistencyIssue {
+                            kind: IssueKind::UntrimmedName,
+                            cube_id: id,
+                            detail: format!("name `{}` has surrounding whitespace", name),
+                            fixable: true,
+                            fixed: false,
+                            fix_error: None,
+                        });
+                    }
+                    names
+                        .entry(name_key(name))
+                        .or_default()
+                        .push((id, name.to_string()));
+                }
+            }
+
+            for (key, cubes) in names.into_iter().filter(|(_, cubes)| cubes.len() > 1) {
+                let all_names: Vec<String> = cubes.iter().map(|(_, name)| name.clone()).collect();
+                for (id, _) in cubes {
+                    issues.push(ConsistencyIssue {
+                        kind: IssueKind::DuplicateNameKey,
+                        cube_id: id,
+                        detail: format!("name key `{}` shared by {:?}", key, all_names),
+                        fixable: false,
+                        fixed: false,
+                        fix_error: None,
+                    });
+                }
+            }
+
+            // The documents of the other collections must point to live
+            // cubes; those left behind by deletions are reported, but never
+            // fixed, as they belong to their users.
+            for (collection, field, label) in REFERENCES {
+                let options = FindOptions::builder().projection(doc! {*field: 1}).build();
+                let mut references = self
+                    .db
+                    .collection::<Document>(collection)
+                    .find(doc! {*field: {"$exists": true, "$ne": Bson::Null}}, options)
+                    .await?;
+                while let Some(reference) = references.next().await {
+                    let reference = reference?;
+                    let referenced: Vec<ObjectId> = match reference.get(field) {
+                        Some(Bson::ObjectId(id)) => vec![*id],
+                        Some(Bson::Array(ids)) => ids.iter().filter_map(Bson::as_object_id).collect(),
+                        _ => Vec::new(),
+                    };
+                    let owner = reference.get_object_id("_id").map(|id| id.to_hex()).unwrap_or_default();
+                    for cube_id in referenced.into_iter().filter(|id| !live_ids.contains(id)) {
+                        let state = if deleted_ids.contains(&cube_id) { "deleted" } else { "missing" };
+                        issues.push(ConsistencyIssue {
+                            kind: IssueKind::OrphanedReference,
+                            cube_id: Some(cube_id),
+                            detail: format!("{} `{}` points to a {} cube", label, owner, state),
+                            fixable: false,
+                            fixed: false,
+                            fix_error: None,
+                        });
+                    }
+                }
+            }
+
+            let mut fixed = 0;
+            if fix {
+                for issue in issues.iter_mut().filter(|issue| issue.fixable) {
+                    let Some(id) = issue.cube_id else { continue };
+                    let filter = doc! {"_id": id};
+                    let update = match issue.kind {
+                        IssueKind::StrayField => vec![doc! {"$unset": {"id": ""}}],
+                        IssueKind::UntrimmedName => {
+                            vec![doc! {"$set": {"name": {"$trim": {"input": "$name"}}}}]
+                        }
+                        _ => continue,
+                    };
+                    // A fix can fail on its own, such as a trimmed name
+                    // taken by another cube, without stopping the others.
+                    match raw_col.update_one(filter, update, None).await {
+                        Ok(result) if result.modified_count == 1 => {
+                            issue.fixed = true;
+                            fixed += 1;
+                        }
+                        Ok(_) => {}
+                        Err(error) => {
+                            issue.fix_error = Some(match error.kind.as_ref() {
+                                ErrorKind::Write(WriteFailure::WriteError(write)) if write.code == DUPLICATE_KEY => {
+                                    "another cube already has the fixed name".to_string()
+                                }
+                                _ => error.to_string(),
+                            });
+                        }
+                    }
+                }
+            }
+
+            Ok(ConsistencyReport { scanned, issues, fixed })
+        })
+    }
+}
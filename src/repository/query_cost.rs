@@ -0,0 +1,179 @@
+use std::fmt;
+
+use mongodb::bson::{Bson, Document};
+use rocket::serde::Deserialize;
+
+use crate::i18n::Lang;
+
+/// Fields of the cubes with an index, which a filter can select on without
+/// reading the whole collection. Kept in line with
+/// [`MongoRepo::create_indexes`](super::mongodb_repo::MongoRepo).
+const INDEXED_FIELDS: [&str; 5] = ["_id", "name", "aliases", "type_", "created_at"];
+
+/// Limits on the cost of the queries sent to the cubes collection, read
+/// from the `query_cost` key of the Rocket configuration.
+///
+/// ```toml
+/// [default.query_cost]
+/// max_scan = 1000
+/// max_list = 500
+/// ```
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct QueryPolicy {
+    /// Cubes a query without pagination may read when its filter uses no
+    /// index.
+    pub max_scan: u64,
+    /// Values a query may list in an `$in`, `$nin` or `$or`.
+    pub max_list: usize,
+}
+
+impl Default for QueryPolicy {
+    fn default() -> Self {
+        QueryPolicy { max_scan: 1000, max_list: 500 }
+    }
+}
+
+/// Why a query was refused before reaching the database.
+#[derive(Debug)]
+pub enum QueryLimit {
+    /// The filter uses no index, and the query without pagination would
+    /// read more cubes than allowed.
+    Scan { fields: Vec<String>, cubes: u64, max: u64 },
+    /// A list of values of the filter is longer than allowed.
+    List { field: String, values: usize, max: usize },
+}
+
+impl QueryLimit {
+    /// Explains the limit in the language of the client.
+    ///
+    /// ## Arguments
+    /// * `lang` - languages accepted by the client.
+    ///
+    /// ## Returns
+    /// * The localized explanation, with the constraint to follow.
+    pub fn message(&self, lang: &Lang) -> String {
+        match self {
+            QueryLimit::Scan { fields, cubes, max } => lang
+                .message("error.query_cost.scan")
+                .replace("{fields}", &fields.join(", "))
+                .replace("{cubes}", &cubes.to_string())
+                .replace("{max}", &max.to_string()),
+            QueryLimit::List { field, values, max } => lang
+                .message("error.query_cost.list")
+                .replace("{field}", field)
+                .replace("{values}", &values.to_string())
+                .replace("{max}", &max.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for QueryLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueryLimit::Scan { fields, cubes, max } => write!(
+                f,
+                "the filter on {} uses no index and would read {} cubes, more than the {} allowed without pagination",
+                fields.join(", "),
+                cubes,
+                max
+            ),
+            QueryLimit::List { field, values, max } => {
+                write!(f, "the list of `{}` has {} values, more than the {} allowed", field, values, max)
+            }
+        }
+    }
+}
+
+/// What a filter costs: the fields it selects on and its longest list of
+/// values.
+struct FilterCost {
+    fields: Vec<String>,
+    longest_list: Option<(String, usize)>,
+}
+
+impl FilterCost {
+    fn list(&mut self, field: &str, values: usize) {
+        if self.longest_list.as_ref().is_none_or(|(_, longest)| values > *longest) {
+            self.longest_list = Some((field.to_string(), values));
+        }
+    }
+}
+
+/// Walks a filter document. Conditions joined by `$and`, or side by side,
+/// use an index if any of them does, while an `$or` only does if all its
+/// branches do.
+fn estimate(filter: &Document, cost: &mut FilterCost) -> bool {
+    let mut indexed = false;
+    for (key, value) in filter {
+        match (key.as_str(), value) {
+            ("$text", _) => indexed = true,
+            ("$and", Bson::Array(branches)) => {
+                for branch in branches.iter().filter_map(Bson::as_document) {
+                    indexed |= estimate(branch, cost);
+                }
+            }
+            ("$or", Bson::Array(branches)) => {
+                cost.list("$or", branches.len());
+                let mut all = !branches.is_empty();
+                for branch in branches.iter().filter_map(Bson::as_document) {
+                    all &= estimate(branch, cost);
+                }
+                indexed |= all;
+            }
+            (field, condition) => {
+                if !cost.fields.iter().any(|known| known == field) {
+                    cost.fields.push(field.to_string());
+                }
+                if let Bson::Document(operators) = condition {
+                    for (operator, values) in operators {
+                        if let ("$in" | "$nin", Bson::Array(values)) = (operator.as_str(), values) {
+                            cost.list(field, values.len());
+                        }
+                    }
+                }
+                indexed |= INDEXED_FIELDS.contains(&field);
+            }
+        }
+    }
+    indexed
+}
+
+impl QueryPolicy {
+    /// Checks the lists of a filter, which are refused if too long
+    /// whatever the size of the collection.
+    ///
+    /// ## Arguments
+    /// * `filter` - filter document of the query.
+    ///
+    /// ## Returns
+    /// * Whether the filter uses an index, with the fields it selects on,
+    ///   or the limit it breaks.
+    pub fn check_lists(&self, filter: &Document) -> Result<(bool, Vec<String>), QueryLimit> {
+        let mut cost = FilterCost { fields: Vec::new(), longest_list: None };
+        let indexed = estimate(filter, &mut cost);
+        match cost.longest_list {
+            Some((field, values)) if values > self.max_list => {
+                Err(QueryLimit::List { field, values, max: self.max_list })
+            }
+            _ => Ok((indexed, cost.fields)),
+        }
+    }
+
+    /// Checks a query without pagination whose filter uses no index
+    /// against the size of the collection.
+    ///
+    /// ## Arguments
+    /// * `fields` - fields the filter selects on.
+    /// * `cubes` - estimated number of cubes in the collection.
+    ///
+    /// ## Returns
+    /// * The limit the query breaks, if it breaks one.
+    pub fn check_scan(&self, fields: Vec<String>, cubes: u64) -> Result<(), QueryLimit> {
+        if cubes <= self.max_scan {
+            return Ok(());
+        }
+        let fields = fields.into_iter().map(|field| format!("`{}`", field)).collect();
+        Err(QueryLimit::Scan { fields, cubes, max: self.max_scan })
+    }
+}
@@ -0,0 +1,136 @@
+use futures::stream::TryStreamExt;
+use mongodb::{
+    bson::{self, oid::ObjectId, doc, Regex},
+    error::Error,
+    options::{FindOneAndUpdateOptions, ReturnDocument},
+    results::InsertOneResult,
+    Collection, Database,
+};
+
+use crate::models::annotation_model::Annotation;
+use crate::models::reconstruction_model::Reconstruction;
+
+pub struct ReconstructionRepo {
+    col: Collection<Reconstruction>,
+}
+
+impl ReconstructionRepo {
+    /// Inits the reconstructions repository on top of the given database.
+    /// 
+    /// ## Arguments
+    /// * `db` - the mongoDB database.
+    /// 
+    /// ## Returns
+    /// * An instance of the reconstructions repository.
+    pub fn init(db: &Database) -> Self {
+        let col: Collection<Reconstruction> = db.collection("reconstructions");
+        ReconstructionRepo { col }
+    }
+
+    /// Inserts a reconstruction into the database.
+    /// 
+    /// ## Arguments
+    /// * `self` - instance of the reconstructions repository.
+    /// * `new_reconstruction` - reconstruction to be inserted.
+    /// 
+    /// ## Returns
+    /// * The result of the operation.
+    pub async fn insert_reconstruction(
+        &self,
+        new_reconstruction: Reconstruction
+    ) -> Result<InsertOneResult, Error> {
+        self.col.insert_one(new_reconstruction, None).await
+    }
+
+    /// Gets a reconstruction from the database by its ID.
+    /// 
+    /// ## Arguments
+    /// * `self` - instance of the reconstructions repository.
+    /// * `id` - ID of the reconstruction.
+    /// 
+    /// ## Returns
+    /// * The reconstruction, if it exists.
+    pub async fn get_reconstruction(&self, id: &ObjectId) -> Result<Option<Reconstruction>, Error> {
+        self.col.find_one(doc! {"_id": id}, None).await
+    }
+
+    /// Searches reconstructions by solver, event and cube. The solver is
+    /// matched case-insensitively against any part of its name.
+    /// 
+    /// ## Arguments
+    /// * `self` - instance of the reconstructions repository.
+    /// * `solver` - part of the name of the solver, if any.
+    /// * `event` - WCA id of the event, if any.
+    /// * `cube_id` - ID of the cube used, if any.
+    /// 
+    /// ## Returns
+    /// * A vector with the reconstructions that matched.
+    pub async fn search_reconstructions(
+        &self,
+        solver: Option<&str>,
+        event: Option<&str>,
+        cube_id: Option<&ObjectId>
+    ) -> Result<Vec<Reconstruction>, Error> {
+        let mut filter = doc! {};
+        if let Some(solver) = solver {
+            let pattern = Regex { pattern: regex_escape(solver), options: "i".to_string() };
+            filter.insert("solver", pattern);
+        }
+        if let Some(event) = event {
+            filter.insert("event", event);
+        }
+        if let Some(cube_id) = cube_id {
+            filter.insert("cube_id", cube_id);
+        }
+        self.col.find(filter, None).await?.try_collect().await
+    }
+
+    /// Attaches a video to a reconstruction, in place of its current one.
+    /// 
+    /// ## Arguments
+    /// * `self` - instance of the reconstructions repository.
+    /// * `id` - ID of the reconstruction.
+    /// * `video_url` - URL of the video.
+    /// 
+    /// ## Returns
+    /// * The updated reconstruction, if it exists.
+    pub async fn set_video(&self, id: &ObjectId, video_url: &str) -> Result<Option<Reconstruction>, Error> {
+        self.update(id, doc! {"$set": {"video_url": video_url}}).await
+    }
+
+    /// Replaces the annotations of a reconstruction.
+    /// 
+    /// ## Arguments
+    /// * `self` - instance of the reconstructions repository.
+    /// * `id` - ID of the reconstruction.
+    /// * `annotations` - new annotations of the solve.
+    /// 
+    /// ## Returns
+    /// * The updated reconstruction, if it exists.
+    pub async fn set_annotations(
+        &self,
+        id: &ObjectId,
+        annotations: &[Annotation]
+    ) -> Result<Option<Reconstruction>, Error> {
+        self.update(id, doc! {"$set": {"annotations": bson::to_bson(annotations)?}}).await
+    }
+
+    async fn update(&self, id: &ObjectId, update: bson::Document) -> Result<Option<Reconstruction>, Error> {
+        let options = FindOneAndUpdateOptions::builder()
+            .return_document(ReturnDocument::After)
+            .build();
+        self.col.find_one_and_update(doc! {"_id": id}, update, options).await
+    }
+}
+
+/// Escapes the characters with a special meaning in regular expressions.
+fn regex_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if "\\.+*?()|[]{}^$".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
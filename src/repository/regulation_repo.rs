@@ -0,0 +1,55 @@
+use mongodb::{
+    bson::doc,
+    error::Error,
+    options::ReplaceOptions,
+    Collection, Database,
+};
+
+use crate::models::regulation_model::RegulationSet;
+
+pub struct RegulationRepo {
+    col: Collection<RegulationSet>,
+}
+
+impl RegulationRepo {
+    /// Inits the regulations repository on top of the given database.
+    /// 
+    /// ## Arguments
+    /// * `db` - the mongoDB database.
+    /// 
+    /// ## Returns
+    /// * An instance of the regulations repository.
+    pub fn init(db: &Database) -> Self {
+        let col: Collection<RegulationSet> = db.collection("regulations");
+        RegulationRepo { col }
+    }
+
+    /// Gets the regulation set currently in use: the last imported one or,
+    /// if none was imported, the one embedded in the API.
+    /// 
+    /// ## Arguments
+    /// * `self` - instance of the regulations repository.
+    /// 
+    /// ## Returns
+    /// * The regulation set.
+    pub async fn get_regulations(&self) -> Result<RegulationSet, Error> {
+        let imported = self.col.find_one(None, None).await?;
+
+        Ok(imported.unwrap_or_else(RegulationSet::embedded))
+    }
+
+    /// Replaces the stored regulation set with a new one.
+    /// 
+    /// ## Arguments
+    /// * `self` - instance of the regulations repository.
+    /// * `set` - regulation set to be stored.
+    /// 
+    /// ## Returns
+    /// * The result of the operation.
+    pub async fn import_regulations(&self, set: &RegulationSet) -> Result<(), Error> {
+        let options = ReplaceOptions::builder().upsert(true).build();
+        self.col.replace_one(doc! {}, set, options).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,70 @@
+use std::fmt;
+
+use mongodb::bson::{self, oid};
+
+use crate::repository::query_cost::QueryLimit;
+
+/// Why an operation of the cube repository failed.
+#[derive(Debug)]
+pub enum RepoError {
+    /// No cube matched.
+    NotFound,
+    /// The given ID is not a valid ObjectId.
+    InvalidId,
+    /// Another cube already has the given name.
+    DuplicateName(String),
+    /// The database refused to write a cube, with its reason.
+    Refused(String),
+    /// A cube changed since its version was read, and nothing was written.
+    Changed,
+    /// The query would cost the database too much, and was not sent.
+    TooExpensive(QueryLimit),
+    /// The database could not complete the operation.
+    Db(mongodb::error::Error),
+}
+
+impl fmt::Display for RepoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RepoError::NotFound => write!(f, "no cube matched"),
+            RepoError::InvalidId => write!(f, "invalid cube ID"),
+            RepoError::DuplicateName(name) => write!(f, "a cube named `{}` already exists", name),
+            RepoError::Refused(reason) => write!(f, "write refused: {}", reason),
+            RepoError::Changed => write!(f, "a cube changed since it was read"),
+            RepoError::TooExpensive(limit) => write!(f, "query refused: {}", limit),
+            RepoError::Db(error) => write!(f, "database error: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for RepoError {}
+
+impl From<mongodb::error::Error> for RepoError {
+    fn from(error: mongodb::error::Error) -> Self {
+        RepoError::Db(error)
+    }
+}
+
+impl From<QueryLimit> for RepoError {
+    fn from(limit: QueryLimit) -> Self {
+        RepoError::TooExpensive(limit)
+    }
+}
+
+impl From<oid::Error> for RepoError {
+    fn from(_: oid::Error) -> Self {
+        RepoError::InvalidId
+    }
+}
+
+impl From<bson::ser::Error> for RepoError {
+    fn from(error: bson::ser::Error) -> Self {
+        RepoError::Db(error.into())
+    }
+}
+
+impl From<bson::de::Error> for RepoError {
+    fn from(error: bson::de::Error) -> Self {
+        RepoError::Db(error.into())
+    }
+}
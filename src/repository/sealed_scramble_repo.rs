@@ -0,0 +1,51 @@
+use mongodb::{
+    bson::{doc, oid::ObjectId},
+    error::Error,
+    results::InsertOneResult,
+    Collection, Database,
+};
+
+use crate::models::scramble_model::SealedScrambles;
+
+pub struct SealedScrambleRepo {
+    col: Collection<SealedScrambles>,
+}
+
+impl SealedScrambleRepo {
+    /// Inits the repository of the sealed scramble sets on top of the given
+    /// database.
+    /// 
+    /// ## Arguments
+    /// * `db` - the mongoDB database.
+    /// 
+    /// ## Returns
+    /// * An instance of the sealed scramble sets repository.
+    pub fn init(db: &Database) -> Self {
+        let col: Collection<SealedScrambles> = db.collection("sealed_scrambles");
+        SealedScrambleRepo { col }
+    }
+
+    /// Stores a sealed scramble set.
+    /// 
+    /// ## Arguments
+    /// * `self` - instance of the sealed scramble sets repository.
+    /// * `sealed` - the set, with its commitment.
+    /// 
+    /// ## Returns
+    /// * The result of the operation.
+    pub async fn insert_sealed(&self, sealed: &SealedScrambles) -> Result<InsertOneResult, Error> {
+        self.col.insert_one(sealed, None).await
+    }
+
+    /// Gets a sealed scramble set by its ID.
+    /// 
+    /// ## Arguments
+    /// * `self` - instance of the sealed scramble sets repository.
+    /// * `id` - ID of the set.
+    /// 
+    /// ## Returns
+    /// * The set, if there is one with the ID.
+    pub async fn get_sealed(&self, id: &ObjectId) -> Result<Option<SealedScrambles>, Error> {
+        self.col.find_one(doc! {"_id": id}, None).await
+    }
+}
@@ -0,0 +1,80 @@
+use futures::stream::TryStreamExt;
+use mongodb::{
+    bson::{oid::ObjectId, doc},
+    error::Error,
+    options::{FindOneAndUpdateOptions, FindOptions, ReturnDocument},
+    results::InsertOneResult,
+    Collection, Database,
+};
+
+use crate::models::setup_model::Setup;
+
+pub struct SetupRepo {
+    col: Collection<Setup>,
+}
+
+impl SetupRepo {
+    /// Inits the setups repository on top of the given database.
+    /// 
+    /// ## Arguments
+    /// * `db` - the mongoDB database.
+    /// 
+    /// ## Returns
+    /// * An instance of the setups repository.
+    pub fn init(db: &Database) -> Self {
+        let col: Collection<Setup> = db.collection("setups");
+        SetupRepo { col }
+    }
+
+    /// Inserts a setup into the database.
+    /// 
+    /// ## Arguments
+    /// * `self` - instance of the setups repository.
+    /// * `new_setup` - setup object to be inserted.
+    /// 
+    /// ## Returns
+    /// * The result of the operation.
+    pub async fn insert_setup(&self, new_setup: Setup) -> Result<InsertOneResult, Error> {
+        self.col.insert_one(new_setup, None).await
+    }
+
+    /// Gets the setups of a cube, the most voted first.
+    /// 
+    /// ## Arguments
+    /// * `self` - instance of the setups repository.
+    /// * `cube_id` - ID of the cube.
+    /// * `limit` - maximum number of setups to return, if any.
+    /// 
+    /// ## Returns
+    /// * A vector with the setups of the cube.
+    pub async fn get_setups_by_cube(
+        &self,
+        cube_id: &ObjectId,
+        limit: Option<i64>
+    ) -> Result<Vec<Setup>, Error> {
+        let filter = doc! {"cube_id": cube_id};
+        let options = FindOptions::builder()
+            .sort(doc! {"votes": -1})
+            .limit(limit)
+            .build();
+        self.col.find(filter, options).await?.try_collect().await
+    }
+
+    /// Adds a vote to a setup.
+    /// 
+    /// ## Arguments
+    /// * `self` - instance of the setups repository.
+    /// * `id` - ID of the setup.
+    /// * `delta` - `1` for an upvote, `-1` for a downvote.
+    /// 
+    /// ## Returns
+    /// * The setup after the vote, if it exists.
+    pub async fn vote_setup(&self, id: &ObjectId, delta: i64) -> Result<Option<Setup>, Error> {
+        let filter = doc! {"_id": id};
+        let update = doc! {"$inc": {"votes": delta}};
+        let options = FindOneAndUpdateOptions::builder()
+            .return_document(ReturnDocument::After)
+            .build();
+        self.col.find_one_and_update(filter, update, options).await
+    }
+}
@@ -0,0 +1,133 @@
+use futures::stream::TryStreamExt;
+use mongodb::{
+    bson::{self, doc},
+    error::{Error, ErrorKind, WriteFailure},
+    options::{FindOneAndUpdateOptions, FindOptions, IndexOptions, ReturnDocument, UpdateOptions},
+    results::{InsertOneResult, UpdateResult},
+    Collection, Database, IndexModel,
+};
+
+use crate::models::user_model::{Role, User};
+
+/// Code of the writes refused by a unique index.
+const DUPLICATE_KEY: i32 = 11000;
+
+pub struct UserRepo {
+    col: Collection<User>,
+}
+
+impl UserRepo {
+    /// Inits the users repository on top of the given database.
+    /// 
+    /// ## Arguments
+    /// * `db` - the mongoDB database.
+    /// 
+    /// ## Returns
+    /// * An instance of the users repository.
+    pub fn init(db: &Database) -> Self {
+        let col: Collection<User> = db.collection("users");
+        UserRepo { col }
+    }
+
+    /// Creates the unique index on the usernames, so that two users
+    /// registering at once cannot take the same name.
+    /// 
+    /// ## Arguments
+    /// * `self` - instance of the users repository.
+    /// 
+    /// ## Returns
+    /// * The result of the operation.
+    pub async fn create_indexes(&self) -> Result<(), Error> {
+        let usernames = IndexModel::builder()
+            .keys(doc! {"username": 1})
+            .options(IndexOptions::builder()
+                .name("user_username".to_string())
+                .unique(true)
+                .build())
+            .build();
+        self.col.create_index(usernames, None).await.map(|_| ())
+    }
+
+    /// Inserts a user into the database.
+    /// 
+    /// ## Arguments
+    /// * `self` - instance of the users repository.
+    /// * `user` - user to be inserted, with its password already hashed.
+    /// 
+    /// ## Returns
+    /// * The result of the operation, or `None` if the username is taken.
+    pub async fn insert_user(&self, user: User) -> Result<Option<InsertOneResult>, Error> {
+        match self.col.insert_one(user, None).await {
+            Ok(result) => Ok(Some(result)),
+            Err(error) => match error.kind.as_ref() {
+                ErrorKind::Write(WriteFailure::WriteError(write)) if write.code == DUPLICATE_KEY => Ok(None),
+                _ => Err(error),
+            },
+        }
+    }
+
+    /// Gets a user by its username.
+    /// 
+    /// ## Arguments
+    /// * `self` - instance of the users repository.
+    /// * `username` - name of the user, in lowercase.
+    /// 
+    /// ## Returns
+    /// * The user, if it exists.
+    pub async fn get_user(&self, username: &str) -> Result<Option<User>, Error> {
+        self.col.find_one(doc! {"username": username}, None).await
+    }
+
+    /// Makes sure the bootstrap admin exists with the given password,
+    /// creating it if needed. An existing user with that name is made an
+    /// admin and gets the password, so that nobody can take the name first.
+    /// 
+    /// ## Arguments
+    /// * `self` - instance of the users repository.
+    /// * `username` - name of the admin, in lowercase.
+    /// * `password_hash` - hash of the password of the admin.
+    /// 
+    /// ## Returns
+    /// * The result of the operation.
+    pub async fn ensure_admin(&self, username: &str, password_hash: &str) -> Result<UpdateResult, Error> {
+        let options = UpdateOptions::builder().upsert(true).build();
+        let update = doc! {"$set": {
+            "password_hash": password_hash,
+            "role": bson::to_bson(&Role::Admin)?,
+        }};
+        self.col.update_one(doc! {"username": username}, update, options).await
+    }
+
+    /// Gets every user with its role, without the password hashes.
+    /// 
+    /// ## Arguments
+    /// * `self` - instance of the users repository.
+    /// 
+    /// ## Returns
+    /// * A vector with the users.
+    pub async fn get_users(&self) -> Result<Vec<User>, Error> {
+        let options = FindOptions::builder()
+            .projection(doc! {"password_hash": 0})
+            .sort(doc! {"username": 1})
+            .build();
+        self.col.find(None, options).await?.try_collect().await
+    }
+
+    /// Sets the role of a user.
+    /// 
+    /// ## Arguments
+    /// * `self` - instance of the users repository.
+    /// * `username` - name of the user, in lowercase.
+    /// * `role` - new role of the user.
+    /// 
+    /// ## Returns
+    /// * The updated user, without its password hash, if it exists.
+    pub async fn set_role(&self, username: &str, role: Role) -> Result<Option<User>, Error> {
+        let options = FindOneAndUpdateOptions::builder()
+            .projection(doc! {"password_hash": 0})
+            .return_document(ReturnDocument::After)
+            .build();
+        let update = doc! {"$set": {"role": bson::to_bson(&role)?}};
+        self.col.find_one_and_update(doc! {"username": username}, update, options).await
+    }
+}
@@ -0,0 +1,141 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::serde::{Deserialize, Serialize};
+use rocket::{Orbit, Rocket};
+use schemars::JsonSchema;
+
+use crate::models::retention_model::{RetentionReport, RetentionStatus};
+use crate::repository::analytics_repo::AnalyticsRepo;
+
+/// How long the records of a collection are kept. Without `max_age_days`
+/// nor `max_records` they are kept forever.
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(default)]
+pub struct CollectionRetention {
+    /// Days a record is kept, counted from the day it was written.
+    pub max_age_days: Option<u32>,
+    /// Records kept at most, the oldest ones expiring first.
+    pub max_records: Option<u64>,
+    /// Whether the expired records are downsampled into the archive of the
+    /// collection, or dropped.
+    pub archive: bool,
+}
+
+impl Default for CollectionRetention {
+    fn default() -> Self {
+        CollectionRetention { max_age_days: None, max_records: None, archive: true }
+    }
+}
+
+impl CollectionRetention {
+    fn is_limited(&self) -> bool {
+        self.max_age_days.is_some() || self.max_records.is_some()
+    }
+}
+
+/// Retention of the telemetry collections, read from the `retention` key
+/// of the Rocket configuration.
+///
+/// ```toml
+/// [default.retention]
+/// interval_hours = 24
+///
+/// [default.retention.analytics]
+/// max_age_days = 90
+/// max_records = 1000000
+/// archive = true
+/// ```
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct RetentionPolicy {
+    /// Hours between two runs of the retention.
+    pub interval_hours: u64,
+    /// Retention of the sampled catalog lookups.
+    pub analytics: CollectionRetention,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        RetentionPolicy { interval_hours: 24, analytics: CollectionRetention::default() }
+    }
+}
+
+impl RetentionPolicy {
+    /// Applies the policy to every telemetry collection.
+    ///
+    /// ## Arguments
+    /// * `analytics` - instance of the query analytics repository.
+    ///
+    /// ## Returns
+    /// * What was expired from each collection.
+    pub async fn apply(&self, analytics: &AnalyticsRepo) -> mongodb::error::Result<Vec<RetentionReport>> {
+        Ok(vec![analytics.apply_retention(&self.analytics).await?])
+    }
+
+    /// Gets the size of every telemetry collection.
+    ///
+    /// ## Arguments
+    /// * `analytics` - instance of the query analytics repository.
+    ///
+    /// ## Returns
+    /// * The size of each collection, with its policy.
+    pub async fn status(&self, analytics: &AnalyticsRepo) -> mongodb::error::Result<Vec<RetentionStatus>> {
+        Ok(vec![analytics.retention_status(&self.analytics).await?])
+    }
+}
+
+/// Applies the [`RetentionPolicy`] every `interval_hours` once the server
+/// has launched, if it limits any collection.
+pub struct RetentionFairing {
+    policy: RetentionPolicy,
+}
+
+impl RetentionFairing {
+    pub fn new(policy: RetentionPolicy) -> Self {
+        RetentionFairing { policy }
+    }
+}
+
+impl Fairing for RetentionFairing {
+    fn info(&self) -> Info {
+        Info {
+            name: "Retention Fairing",
+            kind: Kind::Liftoff,
+        }
+    }
+
+    fn on_liftoff<'life0, 'life1, 'async_trait>(
+        &'life0 self,
+        rocket: &'life1 Rocket<Orbit>
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'async_trait>>
+    where
+        Self: 'async_trait,
+        'life0: 'async_trait,
+        'life1: 'async_trait,
+    {
+        let policy = self.policy.clone();
+        let analytics = rocket.state::<AnalyticsRepo>().cloned();
+        Box::pin(async move {
+            let Some(analytics) = analytics.filter(|_| policy.analytics.is_limited()) else {
+                return;
+            };
+            let interval = Duration::from_secs(policy.interval_hours.max(1) * 60 * 60);
+            rocket::tokio::spawn(async move {
+                loop {
+                    rocket::tokio::time::sleep(interval).await;
+                    match policy.apply(&analytics).await {
+                        Ok(reports) => {
+                            for report in reports {
+                                info!("Retention expired {} records of {}", report.expired, report.collection);
+                            }
+                        }
+                        Err(error) => error!("Could not apply the retention: {}", error),
+                    }
+                }
+            });
+        })
+    }
+}
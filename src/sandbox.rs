@@ -0,0 +1,229 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use mongodb::{bson::doc, Database};
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::{uri::Origin, Header, Method};
+use rocket::request::{FromRequest, Outcome};
+use rocket::serde::Deserialize;
+use rocket::{Data, Orbit, Request, Response, Rocket};
+
+use crate::import;
+use crate::repository::cube_repository::CubeRepository;
+
+/// Header asking for the sandbox, and telling that a response comes from it.
+pub const SANDBOX_HEADER: &str = "X-Sandbox";
+/// Route the requests for the sandbox are sent to when this server is not
+/// the sandbox.
+pub const REDIRECT_PATH: &str = "/sandbox/redirect";
+
+/// Sandbox mode, read from the `sandbox` key of the Rocket configuration.
+/// A sandbox server keeps its data in its own database, lets anonymous
+/// requests act as an admin and empties its data every night. Live servers
+/// with a sandbox `url` send the requests with `X-Sandbox: true` there.
+///
+/// ```toml
+/// [default.sandbox]
+/// enabled = true
+/// reset_hour = 3
+/// seed = "sandbox/cubes.json"
+/// url = "https://sandbox.example.com"
+/// ```
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct SandboxPolicy {
+    /// Whether this server is the sandbox.
+    pub enabled: bool,
+    /// Hour of the day, in UTC, the sandbox data are reset at.
+    pub reset_hour: u8,
+    /// JSON file with the cubes the sandbox starts with after every reset,
+    /// as sent to `/cubes/import`.
+    pub seed: Option<String>,
+    /// Base URL of the sandbox, where live servers redirect the requests
+    /// asking for it.
+    pub url: Option<String>,
+}
+
+impl Default for SandboxPolicy {
+    fn default() -> Self {
+        SandboxPolicy { enabled: false, reset_hour: 3, seed: None, url: None }
+    }
+}
+
+/// Sandbox of the server, managed as state: its policy and, on the
+/// sandbox, the database reset every night with the cube repository the
+/// seed cubes are inserted through.
+#[derive(Clone)]
+pub struct Sandbox {
+    pub policy: SandboxPolicy,
+    pub database: Option<Database>,
+    /// The cube repository of the server, sharing its cache.
+    pub cubes: Arc<dyn CubeRepository>,
+}
+
+impl Sandbox {
+    /// Empties every collection of the sandbox, keeping their indexes, and
+    /// inserts the seed cubes again.
+    ///
+    /// ## Returns
+    /// * The number of seed cubes inserted, or why the reset failed.
+    pub async fn reset(&self) -> Result<usize, String> {
+        let Some(database) = &self.database else {
+            return Err("this server is not the sandbox".to_string());
+        };
+        let names = database.list_collection_names(None).await.map_err(|error| error.to_string())?;
+        for name in names.iter().filter(|name| !name.starts_with("system.")) {
+            database
+                .collection::<mongodb::bson::Document>(name)
+                .delete_many(doc! {}, None)
+                .await
+                .map_err(|error| error.to_string())?;
+        }
+        self.cubes.invalidate();
+
+        let Some(seed) = &self.policy.seed else {
+            return Ok(0);
+        };
+        let text = rocket::tokio::fs::read_to_string(seed)
+            .await
+            .map_err(|error| format!("cannot read the seed {}: {}", seed, error))?;
+        let cubes: Vec<_> = import::read_json(&text)?.into_iter().filter_map(Result::ok).collect();
+        if cubes.is_empty() {
+            return Ok(0);
+        }
+        let inserted = self.cubes.insert_cubes(cubes).await.map_err(|error| error.to_string())?;
+        Ok(inserted.inserted.len())
+    }
+}
+
+/// Seconds until the next reset of the sandbox.
+fn until_reset(reset_hour: u8) -> u64 {
+    const DAY: u64 = 24 * 60 * 60;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0);
+    let reset = u64::from(reset_hour % 24) * 60 * 60;
+    match (reset + DAY - now % DAY) % DAY {
+        0 => DAY,
+        seconds => seconds,
+    }
+}
+
+/// Runs the sandbox mode: tags the responses of the sandbox and resets
+/// its data every night, and, on live servers, sends the requests asking
+/// for the sandbox to [`REDIRECT_PATH`].
+pub struct SandboxFairing {
+    sandbox: Sandbox,
+}
+
+impl SandboxFairing {
+    pub fn new(sandbox: Sandbox) -> Self {
+        SandboxFairing { sandbox }
+    }
+}
+
+impl Fairing for SandboxFairing {
+    fn info(&self) -> Info {
+        Info {
+            name: "Sandbox Fairing",
+            kind: Kind::Liftoff | Kind::Request | Kind::Response,
+        }
+    }
+
+    fn on_liftoff<'life0, 'life1, 'async_trait>(
+        &'life0 self,
+        _rocket: &'life1 Rocket<Orbit>
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'async_trait>>
+    where
+        Self: 'async_trait,
+        'life0: 'async_trait,
+        'life1: 'async_trait,
+    {
+        let sandbox = self.sandbox.clone();
+        Box::pin(async move {
+            if !sandbox.policy.enabled {
+                return;
+            }
+            warn!("Running as the sandbox: anonymous requests act as an admin");
+            rocket::tokio::spawn(async move {
+                loop {
+                    rocket::tokio::time::sleep(Duration::from_secs(until_reset(sandbox.policy.reset_hour))).await;
+                    match sandbox.reset().await {
+                        Ok(seeded) => info!("Sandbox reset, {} seed cubes inserted", seeded),
+                        Err(error) => error!("Could not reset the sandbox: {}", error),
+                    }
+                }
+            });
+        })
+    }
+
+    fn on_request<'life0, 'life1, 'life2, 'life3, 'life4, 'async_trait>(
+        &'life0 self,
+        request: &'life1 mut Request<'life2>,
+        _data: &'life3 mut Data<'life4>
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'async_trait>>
+    where
+        Self: 'async_trait,
+        'life0: 'async_trait,
+        'life1: 'async_trait,
+        'life2: 'async_trait,
+        'life3: 'async_trait,
+        'life4: 'async_trait,
+    {
+        let asked = request
+            .headers()
+            .get_one(SANDBOX_HEADER)
+            .is_some_and(|value| value.trim().eq_ignore_ascii_case("true"));
+        if asked && !self.sandbox.policy.enabled && self.sandbox.policy.url.is_some() {
+            // The original target is kept for the redirect, and the request
+            // never reaches the live routes.
+            let target = request.uri().to_string();
+            request.local_cache(|| SandboxTarget(target));
+            request.set_method(Method::Get);
+            request.set_uri(Origin::parse(REDIRECT_PATH).expect("the redirect path is valid"));
+        }
+        Box::pin(async move {})
+    }
+
+    fn on_response<'r, 'life0, 'life1, 'life2, 'async_trait>(
+        &'life0 self,
+        _request: &'r Request<'life1>,
+        response: &'life2 mut Response<'r>
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'async_trait>>
+    where
+        Self: 'async_trait,
+        'r: 'async_trait,
+        'life0: 'async_trait,
+        'life1: 'async_trait,
+        'life2: 'async_trait,
+    {
+        if self.sandbox.policy.enabled {
+            response.set_header(Header::new(SANDBOX_HEADER, "true"));
+        }
+        Box::pin(async move {})
+    }
+}
+
+/// Path and query a request asking for the sandbox was sent to, `/` if it
+/// did not ask for it.
+#[derive(Clone)]
+pub struct SandboxTarget(pub String);
+
+impl<'r> FromRequest<'r> for SandboxTarget {
+    type Error = ();
+
+    fn from_request<'life0, 'async_trait>(
+        request: &'r Request<'life0>
+    ) -> Pin<Box<dyn Future<Output = Outcome<Self, Self::Error>> + Send + 'async_trait>>
+    where
+        'r: 'async_trait,
+        'life0: 'async_trait,
+        Self: 'async_trait,
+    {
+        let target = request.local_cache(|| SandboxTarget("/".to_string())).clone();
+        Box::pin(async move { Outcome::Success(target) })
+    }
+}
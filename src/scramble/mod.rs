@@ -0,0 +1,176 @@
+use sha2::{Digest, Sha256};
+
+use crate::cube_state::{Face, Move};
+use random::Rng;
+
+pub mod random;
+
+/// Version of the scramble generation. It is increased whenever a change
+/// makes the same seed give different scrambles, so that published seeds
+/// can be reproduced with the version they were drawn with.
+pub const SCRAMBLE_VERSION: u32 = 1;
+/// Name of the pseudo-random generator scrambles are drawn with.
+pub const RNG_ALGORITHM: &str = "splitmix64";
+
+/// Number of layers of the cube of a WCA event, if it is an NxNxN one.
+fn event_size(event: &str) -> Option<usize> {
+    let size = match event {
+        "222" => 2,
+        "333" | "333bf" | "333oh" | "333fm" => 3,
+        "444" | "444bf" => 4,
+        "555" | "555bf" => 5,
+        "666" => 6,
+        "777" => 7,
+        _ => return None,
+    };
+    Some(size)
+}
+
+/// Number of moves of the random-move scrambles of a cube.
+fn cube_length(size: usize) -> usize {
+    match size {
+        2 => 11,
+        3 => 25,
+        4 => 40,
+        _ => 20 * (size - 2),
+    }
+}
+
+/// Generates a random-move scramble for a cube, with outer turns and, on
+/// big cubes, wide turns of up to half of the layers. The same face is never
+/// turned twice in a row, nor both faces of an axis around a turn of the
+/// other one.
+///
+/// ## Arguments
+/// * `rng` - the random generator to pick moves with.
+/// * `size` - the number of layers of the cube.
+///
+/// ## Returns
+/// * The moves of the scramble.
+pub fn cube_scramble(rng: &mut Rng, size: usize) -> Vec<Move> {
+    let depths = (size / 2).max(1);
+    let mut moves: Vec<Move> = Vec::new();
+    while moves.len() < cube_length(size) {
+        let face = Face::ALL[rng.below(Face::ALL.len())];
+        let depth = rng.below(depths) as u8 + 1;
+        let turns = rng.below(3) as u8 + 1;
+        let repeated = match moves.as_slice() {
+            [.., last] if last.face == face => true,
+            [.., before, last] => before.face == face && last.face.axis().0 == face.axis().0,
+            _ => false,
+        };
+        if !repeated {
+            moves.push(Move { face, depth, wide: depth > 1, turns });
+        }
+    }
+    moves
+}
+
+/// Generates a megaminx scramble in Pochmann notation: seven lines of ten
+/// `R` and `D` moves, each line ending with the `U` turn that matches its
+/// last `D` move.
+fn megaminx_scramble(rng: &mut Rng) -> String {
+    let lines: Vec<String> = (0..7)
+        .map(|_| {
+            let mut moves: Vec<&str> = Vec::new();
+            let mut clockwise = true;
+            for i in 0..10 {
+                clockwise = rng.below(2) == 0;
+                moves.push(match (i % 2, clockwise) {
+                    (0, true) => "R++",
+                    (0, false) => "R--",
+                    (_, true) => "D++",
+                    (_, false) => "D--",
+                });
+            }
+            moves.push(if clockwise { "U" } else { "U'" });
+            moves.join(" ")
+        })
+        .collect();
+    lines.join("\n")
+}
+
+/// Generates a random-move scramble of the given faces, never turning the
+/// same face twice in a row.
+fn corner_turn_scramble(rng: &mut Rng, faces: &[char], length: usize) -> Vec<String> {
+    let mut moves: Vec<String> = Vec::new();
+    let mut last = None;
+    while moves.len() < length {
+        let face = faces[rng.below(faces.len())];
+        if last != Some(face) {
+            let suffix = if rng.below(2) == 0 { "" } else { "'" };
+            moves.push(format!("{}{}", face, suffix));
+            last = Some(face);
+        }
+    }
+    moves
+}
+
+/// Generates a pyraminx scramble: eleven turns followed by random tips.
+fn pyraminx_scramble(rng: &mut Rng) -> String {
+    let mut moves = corner_turn_scramble(rng, &['U', 'L', 'R', 'B'], 11);
+    for tip in ['u', 'l', 'r', 'b'] {
+        match rng.below(3) {
+            1 => moves.push(tip.to_string()),
+            2 => moves.push(format!("{}'", tip)),
+            _ => {}
+        }
+    }
+    moves.join(" ")
+}
+
+/// Generates a scramble for an event.
+///
+/// ## Arguments
+/// * `rng` - the random generator to pick moves with.
+/// * `event` - WCA id of the event, e.g. `333`.
+///
+/// ## Returns
+/// * The scramble, if scrambles of the event are supported.
+pub fn event_scramble(rng: &mut Rng, event: &str) -> Option<String> {
+    if let Some(size) = event_size(event) {
+        let moves = cube_scramble(rng, size);
+        return Some(moves.iter().map(Move::to_string).collect::<Vec<_>>().join(" "));
+    }
+    match event {
+        "minx" => Some(megaminx_scramble(rng)),
+        "pyram" => Some(pyraminx_scramble(rng)),
+        "skewb" => Some(corner_turn_scramble(rng, &['R', 'U', 'L', 'B'], 11).join(" ")),
+        _ => None,
+    }
+}
+
+/// Draws a set of scrambles for an event from a seed.
+///
+/// ## Arguments
+/// * `event` - WCA id of the event, e.g. `333`.
+/// * `seed` - seed of the set.
+/// * `count` - number of scrambles.
+///
+/// ## Returns
+/// * The scrambles, if scrambles of the event are supported.
+pub fn draw_set(event: &str, seed: &str, count: usize) -> Option<Vec<String>> {
+    let mut rng = Rng::from_seed(random::seed_from_str(seed));
+    (0..count).map(|_| event_scramble(&mut rng, event)).collect()
+}
+
+/// Commits to a scramble set: the SHA-256, in hexadecimal, of the version,
+/// the event, the seed and the scrambles, one per line. The seed is drawn
+/// at random when a set is sealed, so the commitment tells nothing of the
+/// scrambles until they are revealed, and no other set has the same one.
+///
+/// ## Arguments
+/// * `version` - scramble version of the set.
+/// * `event` - WCA id of the event.
+/// * `seed` - seed of the set.
+/// * `scrambles` - scrambles of the set.
+///
+/// ## Returns
+/// * The commitment of the set.
+pub fn commitment(version: u32, event: &str, seed: &str, scrambles: &[String]) -> String {
+    let content = format!("{}\n{}\n{}\n{}", version, event, seed, scrambles.join("\n"));
+    Sha256::digest(content.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
@@ -0,0 +1,55 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// SplitMix64 pseudo-random generator, as described by Steele, Lea and
+/// Flood in "Fast splittable pseudorandom number generators" (2014). Every
+/// step adds `0x9E3779B97F4A7C15` to the state and mixes the result, so the
+/// same seed always gives the same numbers on every platform. It is good
+/// enough to pick scramble moves, not for anything security related.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// Builds a generator with the given seed.
+    pub fn from_seed(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    /// Builds a generator seeded with the current time.
+    pub fn from_time() -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos() as u64);
+        Rng { state: nanos }
+    }
+
+    /// Gets the next pseudo-random number.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Gets a pseudo-random number between 0 and `bound`, excluded, as the
+    /// remainder of the next number divided by `bound`.
+    pub fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+}
+
+/// Hashes a seed given as text into the seed of a generator, with 64-bit
+/// FNV-1a over its UTF-8 bytes.
+///
+/// ## Arguments
+/// * `seed` - the seed as text, e.g. `open-2026-round-1`.
+///
+/// ## Returns
+/// * The numeric seed.
+pub fn seed_from_str(seed: &str) -> u64 {
+    seed.bytes().fold(0xCBF2_9CE4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01B3)
+    })
+}
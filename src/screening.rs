@@ -0,0 +1,166 @@
+use rocket::serde::Deserialize;
+
+use crate::models::hardware_config_model::HardwareConfig;
+use crate::models::mod_model::Mod;
+use crate::models::reconstruction_model::Reconstruction;
+use crate::models::setup_model::Setup;
+
+/// Check run on the text of every community submission before it is
+/// published. Screens can be added with
+/// [`RubikApiBuilder::add_screen`](crate::RubikApiBuilder::add_screen), for
+/// instance one calling an external moderation service.
+pub trait ContentScreen: Send + Sync {
+    /// Screens the text fields of a submission.
+    ///
+    /// ## Arguments
+    /// * `self` - instance of the screen.
+    /// * `texts` - free-text fields of the submission.
+    ///
+    /// ## Returns
+    /// * Why the submission must be reviewed first, if it must.
+    fn screen(&self, texts: &[&str]) -> Option<String>;
+}
+
+/// Built-in rules of the screening, read from the `screening` key of the
+/// Rocket configuration.
+///
+/// ```toml
+/// [default.screening]
+/// blocked_words = ["casino", "free followers"]
+/// max_links = 2
+/// ```
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct ScreeningPolicy {
+    /// Words, or phrases, that send a submission to review, in any case.
+    pub blocked_words: Vec<String>,
+    /// Links a submission can have before it is sent to review.
+    pub max_links: usize,
+}
+
+impl Default for ScreeningPolicy {
+    fn default() -> Self {
+        ScreeningPolicy { blocked_words: Vec::new(), max_links: 2 }
+    }
+}
+
+/// Flags the texts containing a blocked word or phrase. Single words only
+/// match whole words, so that `ass` does not flag `glass`.
+struct KeywordScreen(Vec<String>);
+
+impl ContentScreen for KeywordScreen {
+    fn screen(&self, texts: &[&str]) -> Option<String> {
+        let texts: Vec<String> = texts.iter().map(|text| text.to_lowercase()).collect();
+        let found = self.0.iter().find(|blocked| {
+            texts.iter().any(|text| match blocked.contains(' ') {
+                true => text.contains(blocked.as_str()),
+                false => text.split(|c: char| !c.is_alphanumeric()).any(|word| word == blocked.as_str()),
+            })
+        })?;
+        Some(format!("contains the blocked term `{}`", found))
+    }
+}
+
+/// Flags the submissions with more links than allowed.
+struct LinkScreen(usize);
+
+impl ContentScreen for LinkScreen {
+    fn screen(&self, texts: &[&str]) -> Option<String> {
+        let links: usize = texts
+            .iter()
+            .map(|text| {
+                text.split_whitespace()
+                    .filter(|word| {
+                        let word = word.to_lowercase();
+                        word.contains("http://") || word.contains("https://") || word.starts_with("www.")
+                    })
+                    .count()
+            })
+            .sum();
+        (links > self.0).then(|| format!("has {} links, more than the {} allowed", links, self.0))
+    }
+}
+
+/// Screens run on the community submissions: the built-in rules of the
+/// configuration, then the ones added by the embedding application.
+pub struct ContentScreening {
+    screens: Vec<Box<dyn ContentScreen>>,
+}
+
+impl ContentScreening {
+    /// Builds the screening with the rules of the configuration and the
+    /// given extra screens.
+    ///
+    /// ## Arguments
+    /// * `policy` - rules of the built-in screens.
+    /// * `extra` - screens added to the server builder.
+    ///
+    /// ## Returns
+    /// * The screening of the server.
+    pub fn new(policy: &ScreeningPolicy, extra: Vec<Box<dyn ContentScreen>>) -> Self {
+        let blocked = policy
+            .blocked_words
+            .iter()
+            .map(|word| word.trim().to_lowercase())
+            .filter(|word| !word.is_empty())
+            .collect();
+        let mut screens: Vec<Box<dyn ContentScreen>> =
+            vec![Box::new(KeywordScreen(blocked)), Box::new(LinkScreen(policy.max_links))];
+        screens.extend(extra);
+        ContentScreening { screens }
+    }
+
+    /// Screens a submission.
+    ///
+    /// ## Arguments
+    /// * `submission` - the submission sent by the client.
+    ///
+    /// ## Returns
+    /// * Why the submission must be reviewed first, empty if it can be
+    ///   published.
+    pub fn screen(&self, submission: &impl Screened) -> Vec<String> {
+        let texts = submission.texts();
+        self.screens.iter().filter_map(|screen| screen.screen(&texts)).collect()
+    }
+}
+
+/// Submission whose free-text fields are screened.
+pub trait Screened {
+    /// Gets the free-text fields of the submission.
+    fn texts(&self) -> Vec<&str>;
+}
+
+impl Screened for Setup {
+    fn texts(&self) -> Vec<&str> {
+        let mut texts = vec![self.author.as_str(), &self.tension, &self.feel];
+        for lube in &self.lubes {
+            texts.extend([lube.lube.as_str(), &lube.amount, &lube.applied_to]);
+        }
+        texts
+    }
+}
+
+impl Screened for HardwareConfig {
+    fn texts(&self) -> Vec<&str> {
+        [&self.author, &self.notes].into_iter().flatten().map(String::as_str).collect()
+    }
+}
+
+impl Screened for Mod {
+    fn texts(&self) -> Vec<&str> {
+        let mut texts = vec![self.title.as_str(), &self.author];
+        texts.extend(self.steps.iter().map(String::as_str));
+        texts.extend(self.materials.iter().map(String::as_str));
+        texts
+    }
+}
+
+/// The video link, scramble and solution are not free text, so only the
+/// names are screened.
+impl Screened for Reconstruction {
+    fn texts(&self) -> Vec<&str> {
+        let mut texts = vec![self.solver.as_str()];
+        texts.extend(self.competition.as_deref());
+        texts
+    }
+}
@@ -0,0 +1,452 @@
+use std::sync::Arc;
+use dotenv::dotenv;
+use rocket::fairing::AdHoc;
+use rocket::{Build, Rocket, Route};
+use crate::api::cube_api::*;
+use crate::api::admin_api::*;
+use crate::api::manifest_api::*;
+use crate::api::setup_api::*;
+use crate::api::hardware_config_api::*;
+use crate::api::mod_api::*;
+use crate::api::regulation_api::*;
+use crate::api::scramble_api::*;
+use crate::api::reconstruction_api::*;
+use crate::api::stats_api::*;
+use crate::api::simulation_api::*;
+use crate::api::color_scheme_api::*;
+use crate::api::training_api::*;
+use crate::api::notation_api::*;
+use crate::api::auth_api::*;
+use crate::api::api_key_api::*;
+use crate::api::graphql_api::{self, *};
+use crate::api::sandbox_api::*;
+use crate::api::image_api::*;
+use crate::api::health_api::*;
+use crate::api::metrics_api::*;
+use crate::api::moderation_api::*;
+use crate::api::error_api::*;
+use crate::api::contribution_api::*;
+#[cfg(feature = "cache")]
+use crate::repository::cached_repo::CachedRepo;
+use crate::repository::cube_repository::CubeRepository;
+use crate::repository::mongodb_repo::MongoRepo;
+use crate::repository::image_store::{GridFsImageStore, ImageStore};
+use crate::repository::setup_repo::SetupRepo;
+use crate::repository::hardware_config_repo::HardwareConfigRepo;
+use crate::repository::mod_repo::ModRepo;
+use crate::repository::regulation_repo::RegulationRepo;
+use crate::repository::reconstruction_repo::ReconstructionRepo;
+use crate::repository::color_scheme_repo::ColorSchemeRepo;
+use crate::repository::analytics_repo::AnalyticsRepo;
+use crate::repository::contribution_repo::ContributionRepo;
+use crate::repository::user_repo::UserRepo;
+use crate::repository::api_key_repo::ApiKeyRepo;
+use crate::repository::flagged_content_repo::FlaggedContentRepo;
+use crate::repository::sealed_scramble_repo::SealedScrambleRepo;
+use crate::events::{EventBus, EventListener};
+use crate::manifest::{self, RouteDescriptor, RouteManifest, ROUTES};
+use crate::auth::{hash_password, AuthKeys};
+use crate::compute::ComputePool;
+use crate::config::ServerConfig;
+use crate::screening::{ContentScreen, ContentScreening};
+use crate::metrics::{Metrics, MetricsFairing};
+use crate::proxy::TrustedProxies;
+use crate::retention::RetentionFairing;
+use crate::sandbox::{Sandbox, SandboxFairing};
+use crate::warmup::{Readiness, Warmup, WarmupFairing};
+use crate::versioning::{self, ApiVersion, VersionFairing};
+use crate::{access_log, cors, deprecation, envelope, logging, repository};
+
+/// Extra functionality mounted on the server by a downstream crate.
+pub trait Extension: Send + Sync + 'static {
+    /// Name of the extension, shown in the launch logs.
+    fn name(&self) -> &'static str;
+
+    /// Routes of the extension, mounted under the prefix of the current
+    /// version of the API, and at `/` like the built-in ones.
+    fn routes(&self) -> Vec<Route> {
+        Vec::new()
+    }
+
+    /// Descriptions of the routes of the extension. Every route must be
+    /// described, or the route manifest check refuses to launch.
+    fn manifest(&self) -> Vec<RouteDescriptor> {
+        Vec::new()
+    }
+
+    /// Lets the extension add what its routes need before launch, such as
+    /// its own repositories as managed state, fairings or catchers.
+    fn configure(&self, rocket: Rocket<Build>) -> Rocket<Build> {
+        rocket
+    }
+}
+
+/// Entry point to run the API as a library.
+pub struct RubikApi;
+
+impl RubikApi {
+    /// Starts building a server with every built-in route.
+    /// 
+    /// ## Returns
+    /// * A builder to register extensions and event listeners with.
+    pub fn builder() -> RubikApiBuilder {
+        RubikApiBuilder::default()
+    }
+}
+
+#[derive(Default)]
+pub struct RubikApiBuilder {
+    extensions: Vec<Box<dyn Extension>>,
+    listeners: Vec<Box<dyn EventListener>>,
+    cubes: Option<Box<dyn CubeRepository>>,
+    images: Option<Box<dyn ImageStore>>,
+    screens: Vec<Box<dyn ContentScreen>>,
+}
+
+impl RubikApiBuilder {
+    /// Registers an extension, whose routes are mounted next to the
+    /// built-in ones.
+    /// 
+    /// ## Arguments
+    /// * `extension` - the extension to mount.
+    /// 
+    /// ## Returns
+    /// * The builder.
+    pub fn mount_extension(mut self, extension: impl Extension) -> Self {
+        self.extensions.push(Box::new(extension));
+        self
+    }
+
+    /// Registers a listener of the events of the catalog, such as cubes
+    /// being created or deleted.
+    /// 
+    /// ## Arguments
+    /// * `listener` - the listener to notify.
+    /// 
+    /// ## Returns
+    /// * The builder.
+    pub fn add_listener(mut self, listener: impl EventListener + 'static) -> Self {
+        self.listeners.push(Box::new(listener));
+        self
+    }
+
+    /// Adds a check run on every community submission after the rules of
+    /// the `screening` configuration, such as a call to an external
+    /// moderation service. Flagged submissions are held for review.
+    /// 
+    /// ## Arguments
+    /// * `screen` - the check to run.
+    /// 
+    /// ## Returns
+    /// * The builder.
+    pub fn add_screen(mut self, screen: impl ContentScreen + 'static) -> Self {
+        self.screens.push(Box::new(screen));
+        self
+    }
+
+    /// Stores the cubes in the given repository instead of the MongoDB
+    /// collection, for instance an in-memory one in tests.
+    /// 
+    /// ## Arguments
+    /// * `repository` - the storage of the cubes.
+    /// 
+    /// ## Returns
+    /// * The builder.
+    pub fn cube_repository(mut self, repository: impl CubeRepository + 'static) -> Self {
+        self.cubes = Some(Box::new(repository));
+        self
+    }
+
+    /// Stores the pictures of the cubes in the given store instead of the
+    /// MongoDB GridFS bucket, for instance an object storage service.
+    /// 
+    /// ## Arguments
+    /// * `store` - the storage of the pictures.
+    /// 
+    /// ## Returns
+    /// * The builder.
+    pub fn image_store(mut self, store: impl ImageStore + 'static) -> Self {
+        self.images = Some(Box::new(store));
+        self
+    }
+
+    /// Builds the server, reading its configuration from the environment
+    /// and connecting to MongoDB.
+    /// 
+    /// ## Returns
+    /// * The rocket instance, ready to be launched.
+    pub async fn build(self) -> Rocket<Build> {
+        dotenv().ok();
+        logging::init();
+        let config = ServerConfig::load()
+            .unwrap_or_else(|errors| panic!("Invalid configuration:\n- {}", errors.join("\n- ")));
+        let mut figment = rocket::Config::figment();
+        if let Some(port) = config.port {
+            figment = figment.merge(("port", port));
+        }
+        if let Some(address) = config.address {
+            figment = figment.merge(("address", address));
+        }
+        let features = config.features;
+        let admin = config.admin;
+        let policy = config.sandbox;
+        let collection = config.database.cubes_collection.clone();
+        let query_cost = config.query_cost;
+        let proxies = TrustedProxies::new(config.trusted_proxies);
+        let metrics = Metrics::default();
+        let commands = features.metrics.then(|| Arc::new(metrics.clone()) as _);
+        let database = repository::connect(&config.database, policy.enabled, commands).await;
+        let readiness = Readiness::default();
+        let retention = config.retention;
+        let custom = self.cubes.is_some();
+        let db: Arc<dyn CubeRepository> = match self.cubes {
+            Some(cubes) => Arc::from(cubes),
+            None => Arc::new(MongoRepo::init(&database, &collection, query_cost)),
+        };
+        // The routes, the warmup and the sandbox resets share the same
+        // repository, custom or cached, so that the warmup fills the cache
+        // and the resets empty it.
+        #[cfg(feature = "cache")]
+        let (db, cached): (Arc<dyn CubeRepository>, _) = match config.cache {
+            cache if cache.enabled => (Arc::new(CachedRepo::new(db, cache)), true),
+            _ => (db, false),
+        };
+        #[cfg(not(feature = "cache"))]
+        let cached = false;
+        let sandbox = Sandbox {
+            database: policy.enabled.then(|| database.clone()),
+            cubes: db.clone(),
+            policy,
+        };
+        // Custom repositories have no connections to open, nor, without a
+        // cache, anything to fill.
+        let warmup = Warmup {
+            database: database.clone(),
+            cubes: (cached || !custom).then(|| db.clone()),
+            page_size: config.pagination.default_limit,
+        };
+        let images: Box<dyn ImageStore> = match self.images {
+            Some(images) => images,
+            None => Box::new(GridFsImageStore::init(&database)),
+        };
+        let setups = SetupRepo::init(&database);
+        let configs = HardwareConfigRepo::init(&database);
+        let mods = ModRepo::init(&database);
+        let regulations = RegulationRepo::init(&database);
+        let reconstructions = ReconstructionRepo::init(&database);
+        let schemes = ColorSchemeRepo::init(&database);
+        let analytics = AnalyticsRepo::init(&database, config.analytics_sample_rate);
+        let contributions = ContributionRepo::init(&database);
+        let users = UserRepo::init(&database);
+        let api_keys = ApiKeyRepo::init(&database);
+        let flagged = FlaggedContentRepo::init(&database);
+        let sealed = SealedScrambleRepo::init(&database);
+        let mut descriptors: Vec<RouteDescriptor> =
+            ROUTES.iter().filter(|descriptor| features.serves(descriptor.name)).cloned().collect();
+        for extension in &self.extensions {
+            descriptors.extend(extension.manifest());
+        }
+
+        let events = EventBus::new(self.listeners);
+        let graphql = features
+            .graphql
+            .then(|| graphql_api::schema(db.clone(), events.clone(), contributions.clone(), config.pagination.clone()));
+
+        let mut rocket = rocket::build()
+            .configure(figment)
+            .manage(Box::new(db) as Box<dyn CubeRepository>)
+            .manage(images)
+            .manage(setups)
+            .manage(configs)
+            .manage(mods)
+            .manage(regulations)
+            .manage(reconstructions)
+            .manage(schemes)
+            .manage(analytics)
+            .manage(contributions)
+            .manage(users)
+            .manage(api_keys)
+            .manage(flagged)
+            .manage(sealed)
+            .manage(ContentScreening::new(&config.screening, self.screens))
+            .manage(AuthKeys::new(&config.auth))
+            .manage(config.pagination)
+            .manage(config.images)
+            .manage(retention.clone())
+            .manage(ComputePool::new(&config.compute, proxies.clone()))
+            .manage(RouteManifest(descriptors))
+            .manage(events)
+            .manage(sandbox.clone())
+            .manage(readiness.clone())
+            .manage(metrics.clone())
+            .mount(ApiVersion::CURRENT.base(), routes![insert_cube])
+            .mount(ApiVersion::CURRENT.base(), routes![insert_cubes])
+            .mount(ApiVersion::CURRENT.base(), routes![get_cube])
+            .mount(ApiVersion::CURRENT.base(), routes![update_cube])
+            .mount(ApiVersion::CURRENT.base(), routes![delete_cube])
+            .mount(ApiVersion::CURRENT.base(), routes![delete_cubes])
+            .mount(ApiVersion::CURRENT.base(), routes![restore_cube])
+            .mount(ApiVersion::CURRENT.base(), routes![purge_cube])
+            .mount(ApiVersion::CURRENT.base(), routes![get_all_cubes])
+            .mount(ApiVersion::CURRENT.base(), routes![get_cube_by_name])
+            .mount(ApiVersion::CURRENT.base(), routes![get_cube_by_type])
+            .mount(ApiVersion::CURRENT.base(), routes![search_cubes])
+            .mount(ApiVersion::CURRENT.base(), routes![get_cube_by_performance])
+            .mount(ApiVersion::CURRENT.base(), routes![get_cube_by_shape])
+            .mount(ApiVersion::CURRENT.base(), routes![get_cube_by_holder])
+            .mount(ApiVersion::CURRENT.base(), routes![export_cubes])
+            .mount(ApiVersion::CURRENT.base(), routes![import_cubes])
+            .mount(ApiVersion::CURRENT.base(), routes![update_cube_by_name])
+            .mount(ApiVersion::CURRENT.base(), routes![update_world_record])
+            .mount(ApiVersion::CURRENT.base(), routes![upload_cube_image])
+            .mount(ApiVersion::CURRENT.base(), routes![get_cube_image])
+            .mount(ApiVersion::CURRENT.base(), routes![check_consistency])
+            .mount(ApiVersion::CURRENT.base(), routes![get_data_quality])
+            .mount(ApiVersion::CURRENT.base(), routes![get_top_queries])
+            .mount(ApiVersion::CURRENT.base(), routes![get_zero_result_queries])
+            .mount(ApiVersion::CURRENT.base(), routes![get_retention])
+            .mount(ApiVersion::CURRENT.base(), routes![run_retention])
+            .mount(ApiVersion::CURRENT.base(), routes![get_top_contributors])
+            .mount(ApiVersion::CURRENT.base(), routes![get_contributor])
+            .mount(ApiVersion::CURRENT.base(), routes![get_routes_manifest])
+            .mount(ApiVersion::CURRENT.base(), routes![get_openapi])
+            .mount(ApiVersion::CURRENT.base(), routes![get_docs])
+            .mount(ApiVersion::CURRENT.base(), routes![insert_setup])
+            .mount(ApiVersion::CURRENT.base(), routes![get_cube_setups])
+            .mount(ApiVersion::CURRENT.base(), routes![get_top_cube_setups])
+            .mount(ApiVersion::CURRENT.base(), routes![upvote_setup])
+            .mount(ApiVersion::CURRENT.base(), routes![downvote_setup])
+            .mount(ApiVersion::CURRENT.base(), routes![insert_hardware_config])
+            .mount(ApiVersion::CURRENT.base(), routes![get_hardware_configs])
+            .mount(ApiVersion::CURRENT.base(), routes![insert_mod])
+            .mount(ApiVersion::CURRENT.base(), routes![get_mods])
+            .mount(ApiVersion::CURRENT.base(), routes![get_mod])
+            .mount(ApiVersion::CURRENT.base(), routes![get_cube_mods])
+            .mount(ApiVersion::CURRENT.base(), routes![get_pending_mods])
+            .mount(ApiVersion::CURRENT.base(), routes![approve_mod])
+            .mount(ApiVersion::CURRENT.base(), routes![reject_mod])
+            .mount(ApiVersion::CURRENT.base(), routes![get_regulations])
+            .mount(ApiVersion::CURRENT.base(), routes![get_regulation])
+            .mount(ApiVersion::CURRENT.base(), routes![get_event_regulations])
+            .mount(ApiVersion::CURRENT.base(), routes![get_cube_legality])
+            .mount(ApiVersion::CURRENT.base(), routes![import_regulations])
+            .mount(ApiVersion::CURRENT.base(), routes![analyze_scramble])
+            .mount(ApiVersion::CURRENT.base(), routes![get_scrambles])
+            .mount(ApiVersion::CURRENT.base(), routes![seal_scrambles])
+            .mount(ApiVersion::CURRENT.base(), routes![get_sealed_scrambles])
+            .mount(ApiVersion::CURRENT.base(), routes![verify_scrambles])
+            .mount(ApiVersion::CURRENT.base(), routes![insert_reconstruction])
+            .mount(ApiVersion::CURRENT.base(), routes![search_reconstructions])
+            .mount(ApiVersion::CURRENT.base(), routes![get_reconstruction])
+            .mount(ApiVersion::CURRENT.base(), routes![export_reconstruction_alg])
+            .mount(ApiVersion::CURRENT.base(), routes![get_cube_reconstructions])
+            .mount(ApiVersion::CURRENT.base(), routes![attach_reconstruction_video])
+            .mount(ApiVersion::CURRENT.base(), routes![annotate_reconstruction])
+            .mount(ApiVersion::CURRENT.base(), routes![delete_reconstruction_annotation])
+            .mount(ApiVersion::CURRENT.base(), routes![get_reconstruction_splits])
+            .mount(ApiVersion::CURRENT.base(), routes![get_phase_stats])
+            .mount(ApiVersion::CURRENT.base(), routes![get_cube_records])
+            .mount(ApiVersion::CURRENT.base(), routes![compute_average])
+            .mount(ApiVersion::CURRENT.base(), routes![simulate_playback])
+            .mount(ApiVersion::CURRENT.base(), routes![insert_color_scheme])
+            .mount(ApiVersion::CURRENT.base(), routes![get_color_schemes])
+            .mount(ApiVersion::CURRENT.base(), routes![get_color_scheme])
+            .mount(ApiVersion::CURRENT.base(), routes![get_cross_case])
+            .mount(ApiVersion::CURRENT.base(), routes![get_f2l_case])
+            .mount(ApiVersion::CURRENT.base(), routes![get_last_layer_case])
+            .mount(ApiVersion::CURRENT.base(), routes![convert_notation])
+            .mount(ApiVersion::CURRENT.base(), routes![register])
+            .mount(ApiVersion::CURRENT.base(), routes![login])
+            .mount(ApiVersion::CURRENT.base(), routes![get_users])
+            .mount(ApiVersion::CURRENT.base(), routes![set_user_role])
+            .mount(ApiVersion::CURRENT.base(), routes![insert_api_key])
+            .mount(ApiVersion::CURRENT.base(), routes![get_api_keys])
+            .mount(ApiVersion::CURRENT.base(), routes![revoke_api_key])
+            .mount(ApiVersion::CURRENT.base(), routes![get_flagged_content])
+            .mount(ApiVersion::CURRENT.base(), routes![approve_flagged_content])
+            .mount(ApiVersion::CURRENT.base(), routes![reject_flagged_content])
+            .mount(ApiVersion::CURRENT.base(), routes![redirect_to_sandbox])
+            .mount(ApiVersion::CURRENT.base(), routes![reset_sandbox])
+            .mount(ApiVersion::CURRENT.base(), routes![get_ready])
+            .mount(ApiVersion::CURRENT.base(), routes![get_liveness])
+            .mount(ApiVersion::CURRENT.base(), routes![get_health_readiness])
+            .register("/", catchers![localized_error])
+            .attach(cors::CorsFairing::new(config.cors))
+            .attach(access_log::AccessLogFairing::new(proxies))
+            .attach(deprecation::DeprecationFairing::new(config.deprecations))
+            .attach(envelope::EnvelopeFairing)
+            .attach(VersionFairing)
+            .attach(SandboxFairing::new(sandbox))
+            .attach(WarmupFairing::new(warmup, readiness))
+            .attach(RetentionFairing::new(retention))
+            .attach(AdHoc::on_ignite("Cube Indexes", |rocket| async {
+                if let Some(db) = rocket.state::<Box<dyn CubeRepository>>() {
+                    if let Err(error) = db.create_indexes().await {
+                        warn!("Could not create the indexes of the cubes: {}", error);
+                    }
+                }
+                rocket
+            }))
+            .attach(AdHoc::on_ignite("API Key Indexes", |rocket| async {
+                if let Some(keys) = rocket.state::<ApiKeyRepo>() {
+                    if let Err(error) = keys.create_indexes().await {
+                        warn!("Could not create the indexes of the API keys: {}", error);
+                    }
+                }
+                rocket
+            }))
+            .attach(AdHoc::on_ignite("User Indexes", |rocket| async {
+                if let Some(users) = rocket.state::<UserRepo>() {
+                    if let Err(error) = users.create_indexes().await {
+                        warn!("Could not create the indexes of the users: {}", error);
+                    }
+                }
+                rocket
+            }))
+            .attach(AdHoc::on_ignite("Bootstrap Admin", |rocket| async move {
+                let (Some(admin), Some(users)) = (admin, rocket.state::<UserRepo>()) else {
+                    return rocket;
+                };
+                let password = admin.password;
+                let ensured = match rocket::tokio::task::spawn_blocking(move || hash_password(&password)).await {
+                    Ok(password_hash) => users.ensure_admin(&admin.username, &password_hash).await.map_err(|e| e.to_string()),
+                    Err(error) => Err(error.to_string()),
+                };
+                match ensured {
+                    Ok(_) => info!("Admin `{}` is ready", admin.username),
+                    Err(error) => warn!("Could not create the admin `{}`: {}", admin.username, error),
+                }
+                rocket
+            }))
+            .attach(AdHoc::try_on_ignite("Route Manifest", |rocket| async {
+                let checked = match rocket.state::<RouteManifest>() {
+                    Some(manifest) => manifest::check_routes(&rocket, &manifest.0),
+                    None => Err(vec!["the route manifest is not managed".to_string()]),
+                };
+                match checked {
+                    Ok(()) => Ok(rocket),
+                    Err(errors) => {
+                        for error in errors {
+                            error!("{}", error);
+                        }
+                        Err(rocket)
+                    }
+                }
+            }));
+
+        if let Some(schema) = graphql {
+            rocket = rocket.manage(schema).mount(ApiVersion::CURRENT.base(), routes![graphql_endpoint]);
+        }
+        if features.metrics {
+            rocket = rocket
+                .mount(ApiVersion::CURRENT.base(), routes![get_metrics])
+                .attach(MetricsFairing::new(metrics));
+        }
+
+        for extension in self.extensions {
+            info!("Mounting extension {}", extension.name());
+            rocket = extension.configure(rocket.mount(ApiVersion::CURRENT.base(), extension.routes()));
+        }
+        versioning::mount_unprefixed(rocket)
+    }
+}
@@ -0,0 +1,152 @@
+use std::collections::BTreeMap;
+
+use crate::models::annotation_model::{Mark, PhaseSplit, PhaseStats};
+use crate::models::solve_time_model::SolveTime;
+
+fn rounded_mean(times: &[u32]) -> u32 {
+    let count = times.len() as u64;
+    let sum: u64 = times.iter().map(|t| *t as u64).sum();
+    ((sum + count / 2) / count) as u32
+}
+
+/// Computes the mean of all the solves. Any DNF or DNS makes the mean a DNF.
+pub fn mean(solves: &[SolveTime]) -> SolveTime {
+    let results: Option<Vec<u32>> = solves.iter().map(SolveTime::result).collect();
+    SolveTime::from_result(results.filter(|r| !r.is_empty()).map(|r| rounded_mean(&r)))
+}
+
+/// Number of best and worst solves removed from an average of `size`: 5%
+/// of the solves rounded up, or none for averages of less than 5 solves,
+/// which are plain means.
+pub fn trimmed_count(size: usize) -> usize {
+    if size < 5 {
+        0
+    } else {
+        size.div_ceil(20)
+    }
+}
+
+/// Computes the trimmed average of the solves, following the WCA rules for
+/// averages of 5: the best and worst solves are removed, and the average is
+/// a DNF if there are more DNFs than removed solves.
+pub fn average(solves: &[SolveTime]) -> SolveTime {
+    let trimmed = trimmed_count(solves.len());
+    if trimmed == 0 {
+        return mean(solves);
+    }
+    let mut sorted = solves.to_vec();
+    sorted.sort_by_key(SolveTime::rank);
+    mean(&sorted[trimmed..sorted.len() - trimmed])
+}
+
+/// Gets the best solve of the list.
+pub fn best(solves: &[SolveTime]) -> Option<SolveTime> {
+    solves.iter().copied().min_by_key(SolveTime::rank)
+}
+
+/// Gets the worst solve of the list.
+pub fn worst(solves: &[SolveTime]) -> Option<SolveTime> {
+    solves.iter().copied().max_by_key(SolveTime::rank)
+}
+
+/// Computes the average of every run of `size` consecutive solves.
+pub fn rolling_averages(solves: &[SolveTime], size: usize) -> Vec<SolveTime> {
+    if size == 0 {
+        return Vec::new();
+    }
+    solves.windows(size).map(average).collect()
+}
+
+/// Computes the mean and best split of every phase over several solves.
+/// Phases only count the solves where their two marks are next to each
+/// other, so that merged phases are not mixed with the others.
+pub fn phase_stats(solves: &[Vec<PhaseSplit>]) -> Vec<PhaseStats> {
+    let mut phases: BTreeMap<(Mark, Mark), Vec<u64>> = BTreeMap::new();
+    for split in solves.iter().flatten() {
+        phases.entry((split.from, split.to)).or_default().push(split.duration_ms);
+    }
+    phases
+        .into_iter()
+        .map(|((from, to), durations)| {
+            let count = durations.len() as u64;
+            let sum: u64 = durations.iter().sum();
+            PhaseStats {
+                from,
+                to,
+                solves: durations.len(),
+                mean_ms: (sum + count / 2) / count,
+                best_ms: durations.iter().copied().min().unwrap_or(0),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solves(times: &[&str]) -> Vec<SolveTime> {
+        times.iter().map(|time| time.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn means_round_to_the_centisecond() {
+        assert_eq!(mean(&solves(&["10.00", "10.01", "10.01"])), SolveTime::new(1001));
+        assert_eq!(mean(&solves(&["10.00", "10.00", "10.01"])), SolveTime::new(1000));
+        assert_eq!(mean(&solves(&["10.00", "12.00+"])), SolveTime::new(1100));
+        assert_eq!(mean(&solves(&["10.00", "DNF(9.00)", "11.00"])), SolveTime::dnf());
+        assert_eq!(mean(&[]), SolveTime::dnf());
+    }
+
+    #[test]
+    fn averages_of_five_drop_the_best_and_the_worst() {
+        assert_eq!(average(&solves(&["10.00", "12.00", "11.00", "9.00", "20.00"])), SolveTime::new(1100));
+        assert_eq!(average(&solves(&["10.00", "12.00", "DNF", "9.00", "11.00"])), SolveTime::new(1100));
+        assert_eq!(average(&solves(&["10.00", "DNS", "DNF", "9.00", "11.00"])), SolveTime::dnf());
+        assert_eq!(average(&solves(&["10.00", "11.00", "12.00"])), SolveTime::new(1100));
+    }
+
+    #[test]
+    fn bigger_averages_drop_five_percent() {
+        assert_eq!(trimmed_count(3), 0);
+        assert_eq!(trimmed_count(5), 1);
+        assert_eq!(trimmed_count(12), 1);
+        assert_eq!(trimmed_count(50), 3);
+        assert_eq!(trimmed_count(100), 5);
+        let mut times: Vec<String> = (1..=100).map(|s| format!("{}.00", s)).collect();
+        times[0] = "DNF".to_string();
+        let times: Vec<&str> = times.iter().map(String::as_str).collect();
+        // 7 to 96 are kept: 2 to 6 are dropped, and 97 to 100 with the DNF.
+        assert_eq!(average(&solves(&times)), SolveTime::new(5150));
+    }
+
+    #[test]
+    fn best_and_worst_rank_penalties() {
+        let list = solves(&["10.00", "11.00+", "DNF(8.00)", "12.00"]);
+        assert_eq!(best(&list), Some(SolveTime::new(1000)));
+        assert_eq!(worst(&list), Some("DNF(8.00)".parse().unwrap()));
+        assert_eq!(best(&solves(&["12.00", "11.99+"])), Some("11.99+".parse().unwrap()));
+        assert_eq!(best(&[]), None);
+    }
+
+    #[test]
+    fn rolling_averages_cover_every_window() {
+        let list = solves(&["1.00", "2.00", "3.00", "4.00"]);
+        assert_eq!(rolling_averages(&list, 3), vec![SolveTime::new(200), SolveTime::new(300)]);
+        assert_eq!(rolling_averages(&list, 5), Vec::<SolveTime>::new());
+        assert_eq!(rolling_averages(&list, 0), Vec::<SolveTime>::new());
+    }
+
+    #[test]
+    fn phases_are_grouped_by_their_marks() {
+        let split = |from, to, duration_ms| PhaseSplit { from, to, duration_ms };
+        let stats = phase_stats(&[
+            vec![split(Mark::InspectionEnd, Mark::CrossDone, 2000), split(Mark::CrossDone, Mark::F2lDone, 7000)],
+            vec![split(Mark::InspectionEnd, Mark::CrossDone, 3001), split(Mark::CrossDone, Mark::OllStart, 9000)],
+        ]);
+        assert_eq!(stats.len(), 3);
+        assert_eq!((stats[0].from, stats[0].to), (Mark::InspectionEnd, Mark::CrossDone));
+        assert_eq!((stats[0].solves, stats[0].mean_ms, stats[0].best_ms), (2, 2501, 2000));
+        assert_eq!(stats[1].solves, 1);
+    }
+}
@@ -0,0 +1,132 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rocket::serde::Serialize;
+use schemars::JsonSchema;
+
+use crate::models::cube_model::Cube;
+use crate::models::user_model::Credentials;
+use crate::models::world_record_model::WorldRecord;
+
+/// Year of the oldest puzzles worth cataloguing, well before the 3x3.
+const FIRST_YEAR: u32 = 1800;
+
+/// Shortest password accepted on registration.
+const MIN_PASSWORD_LENGTH: usize = 8;
+
+/// What is wrong with one field of a payload.
+#[derive(Debug, Serialize, Clone, PartialEq, JsonSchema)]
+pub struct FieldError {
+    /// Name of the field, as written in the payload.
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub(crate) fn new(field: &str, message: impl Into<String>) -> Self {
+        FieldError { field: field.to_string(), message: message.into() }
+    }
+}
+
+/// Gets the current year, from the system clock.
+fn current_year() -> u32 {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0);
+    // Average length of a Gregorian year, in seconds.
+    1970 + (seconds / 31_556_952) as u32
+}
+
+/// Checks the fields of a cube sent to be inserted or updated. Types that
+/// are not recognized are already refused when the payload is read.
+///
+/// ## Arguments
+/// * `cube` - the cube sent by the client.
+///
+/// ## Returns
+/// * Every problem found, by field.
+pub fn validate_cube(cube: &Cube) -> Result<(), Vec<FieldError>> {
+    let mut errors = Vec::new();
+    if cube.name.trim().is_empty() {
+        errors.push(FieldError::new("name", "name must not be empty"));
+    }
+    for (field, value) in [("pieces", cube.pieces), ("faces", cube.faces), ("stickers", cube.stickers)] {
+        if value == 0 {
+            errors.push(FieldError::new(field, format!("{} must be positive", field)));
+        }
+    }
+    let last_year = current_year();
+    if !(FIRST_YEAR..=last_year).contains(&cube.year_created) {
+        errors.push(FieldError::new(
+            "year_created",
+            format!("year_created must be between {} and {}", FIRST_YEAR, last_year),
+        ));
+    }
+    if let Some(Err(performance)) = cube.performance.as_ref().map(|p| p.validate()) {
+        errors.extend(performance.into_iter().map(|message| FieldError::new("performance", message)));
+    }
+    if let Some(Err(shape)) = cube.shape.as_ref().map(|s| s.validate(cube.faces, cube.pieces)) {
+        errors.extend(shape.into_iter().map(|message| FieldError::new("shape", message)));
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Checks a world record sent on its own to replace the one of a cube.
+///
+/// ## Arguments
+/// * `wr` - the record sent by the client.
+///
+/// ## Returns
+/// * Every problem found, by field.
+pub fn validate_world_record(wr: &WorldRecord) -> Result<(), Vec<FieldError>> {
+    let mut errors = Vec::new();
+    if wr.holder.trim().is_empty() {
+        errors.push(FieldError::new("holder", "holder must not be empty"));
+    }
+    if wr.time_ms == Some(0) {
+        errors.push(FieldError::new("time_ms", "time_ms must be positive"));
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Checks the credentials of a new user. Usernames are compared in
+/// lowercase, so they are checked that way.
+///
+/// ## Arguments
+/// * `credentials` - the username and the password sent by the client.
+///
+/// ## Returns
+/// * Every problem found, by field.
+pub fn validate_credentials(credentials: &Credentials) -> Result<(), Vec<FieldError>> {
+    let mut errors = Vec::new();
+    let username = credentials.username.trim().to_lowercase();
+    let allowed = |c: char| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.');
+    if !(3..=32).contains(&username.len()) || !username.chars().all(allowed) {
+        errors.push(FieldError::new(
+            "username",
+            "username must have 3 to 32 letters, digits, `_`, `-` or `.`",
+        ));
+    }
+    if credentials.password.chars().count() < MIN_PASSWORD_LENGTH {
+        errors.push(FieldError::new(
+            "password",
+            format!("password must have at least {} characters", MIN_PASSWORD_LENGTH),
+        ));
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
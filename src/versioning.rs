@@ -0,0 +1,126 @@
+use std::future::Future;
+use std::pin::Pin;
+
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::Header;
+use rocket::{Build, Request, Response, Rocket, Route};
+
+/// Header telling the version of the API that served a response.
+pub const VERSION_HEADER: &str = "Api-Version";
+
+/// Version of the API, mounted under its own prefix so that a version with
+/// breaking changes can be served next to the previous ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiVersion {
+    V1,
+}
+
+impl ApiVersion {
+    /// Every version served, from the oldest to the newest.
+    pub const ALL: [ApiVersion; 1] = [ApiVersion::V1];
+    /// Version the built-in routes and those of the extensions are mounted
+    /// under, and the one the manifest describes.
+    pub const CURRENT: ApiVersion = ApiVersion::V1;
+    /// Version served on the paths without prefix, the ones of the API
+    /// before it was versioned, kept so that older clients go on working.
+    pub const UNPREFIXED: ApiVersion = ApiVersion::V1;
+
+    /// Number of the version, as sent in [`VERSION_HEADER`].
+    pub fn number(&self) -> u32 {
+        match self {
+            ApiVersion::V1 => 1,
+        }
+    }
+
+    /// Prefix the routes of the version are mounted under.
+    pub fn base(&self) -> &'static str {
+        match self {
+            ApiVersion::V1 => "/api/v1",
+        }
+    }
+
+    /// Finds the version a route was mounted for.
+    ///
+    /// ## Arguments
+    /// * `base` - base the route is mounted at.
+    ///
+    /// ## Returns
+    /// * The version of the base, if it is the one of a version or `/`.
+    pub fn of_base(base: &str) -> Option<ApiVersion> {
+        if base == "/" {
+            return Some(ApiVersion::UNPREFIXED);
+        }
+        ApiVersion::ALL.into_iter().find(|version| version.base() == base)
+    }
+}
+
+/// Removes the prefix of a version from the path of a route, giving it as
+/// the manifest describes it.
+///
+/// ## Arguments
+/// * `path` - path of the route as it is mounted.
+///
+/// ## Returns
+/// * The version the path is under, if any, and the path without its prefix.
+pub fn unversioned(path: &str) -> (Option<ApiVersion>, &str) {
+    for version in ApiVersion::ALL {
+        match path.strip_prefix(version.base()) {
+            Some("") => return (Some(version), "/"),
+            Some(rest) if rest.starts_with('/') => return (Some(version), rest),
+            _ => {}
+        }
+    }
+    (None, path)
+}
+
+/// Mounts the routes of [`ApiVersion::UNPREFIXED`] once more at `/`, so
+/// that the paths used before the API was versioned keep working. Called
+/// once every route of the version is mounted.
+///
+/// ## Arguments
+/// * `rocket` - the rocket instance with the routes of every version.
+///
+/// ## Returns
+/// * The rocket instance, with the routes without prefix.
+pub fn mount_unprefixed(rocket: Rocket<Build>) -> Rocket<Build> {
+    let base = ApiVersion::UNPREFIXED.base();
+    let routes: Vec<Route> = rocket
+        .routes()
+        .filter(|route| route.uri.base() == base)
+        .cloned()
+        .filter_map(|route| route.map_base(|_| "/".to_string()).ok())
+        .collect();
+    rocket.mount("/", routes)
+}
+
+/// Tags the responses of the routes with the [`VERSION_HEADER`] of the
+/// version they were mounted for.
+pub struct VersionFairing;
+
+impl Fairing for VersionFairing {
+    fn info(&self) -> Info {
+        Info {
+            name: "Version Fairing",
+            kind: Kind::Response,
+        }
+    }
+
+    fn on_response<'r, 'life0, 'life1, 'life2, 'async_trait>(
+        &'life0 self,
+        request: &'r Request<'life1>,
+        response: &'life2 mut Response<'r>
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'async_trait>>
+    where
+        Self: 'async_trait,
+        'r: 'async_trait,
+        'life0: 'async_trait,
+        'life1: 'async_trait,
+        'life2: 'async_trait,
+    {
+        let version = request.route().and_then(|route| ApiVersion::of_base(route.uri.base()));
+        if let Some(version) = version {
+            response.set_header(Header::new(VERSION_HEADER, version.number().to_string()));
+        }
+        Box::pin(async move {})
+    }
+}
@@ -0,0 +1,126 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use mongodb::{bson::doc, Database};
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::{Orbit, Rocket};
+
+use crate::cube_state::analysis;
+use crate::models::{cube_filter_model::CubeFilter, cube_type_model::CubeType};
+use crate::repository::cube_repository::CubeRepository;
+
+/// Connections opened to MongoDB before the server is ready, so that the
+/// first requests do not wait for the handshakes.
+const CONNECTIONS: usize = 4;
+/// Wait between two attempts to reach MongoDB.
+const RETRY: Duration = Duration::from_secs(2);
+
+/// Whether the warmup is over, managed as state and shared with the
+/// [`WarmupFairing`].
+#[derive(Clone, Default)]
+pub struct Readiness(Arc<AtomicBool>);
+
+impl Readiness {
+    pub fn is_ready(&self) -> bool {
+        self.0.load(Ordering::Acquire)
+    }
+}
+
+/// What the warmup goes through: the database, and the cube repository
+/// of the server, whose hot listings are read once so that its cache has
+/// them, unless a custom repository is used without a cache.
+pub struct Warmup {
+    pub database: Database,
+    pub cubes: Option<Arc<dyn CubeRepository>>,
+    /// Page size of the listings read, the default one of `/cubes`.
+    pub page_size: u64,
+}
+
+impl Warmup {
+    /// Opens the connections to MongoDB, trying again until it answers.
+    async fn connect(&self) {
+        loop {
+            let pings = (0..CONNECTIONS).map(|_| self.database.run_command(doc! {"ping": 1}, None));
+            match futures::future::try_join_all(pings).await {
+                Ok(_) => return,
+                Err(error) => {
+                    warn!("Warmup could not reach the database, trying again: {}", error);
+                    rocket::tokio::time::sleep(RETRY).await;
+                }
+            }
+        }
+    }
+
+    /// Reads the first page of the catalog and of every type, so that the
+    /// database has them in memory for the first clients.
+    async fn prime(&self, cubes: &dyn CubeRepository) {
+        let types = [None, Some(CubeType::Cubic), Some(CubeType::Cuboid), Some(CubeType::Minx), Some(CubeType::Other)];
+        for type_ in types {
+            let filter = CubeFilter { type_, ..CubeFilter::default() };
+            if let Err(error) = cubes.get_cubes_page(&filter, None, 0, self.page_size as i64).await {
+                warn!("Warmup could not read the cubes: {}", error);
+            }
+        }
+    }
+
+    /// Runs every step of the warmup.
+    async fn run(&self) {
+        let started = Instant::now();
+        let tables = rocket::tokio::task::spawn_blocking(analysis::cross_tables);
+        self.connect().await;
+        if let Some(cubes) = &self.cubes {
+            self.prime(cubes.as_ref()).await;
+        }
+        if tables.await.is_err() {
+            warn!("Warmup could not build the cross tables");
+        }
+        info!("Warmup done in {} ms", started.elapsed().as_millis());
+    }
+}
+
+/// Runs the [`Warmup`] once the server has launched, and marks it as ready
+/// when it is over. Requests are served meanwhile, but `/ready` answers
+/// them with a 503 so that load balancers wait.
+pub struct WarmupFairing {
+    warmup: Arc<Warmup>,
+    readiness: Readiness,
+}
+
+impl WarmupFairing {
+    pub fn new(warmup: Warmup, readiness: Readiness) -> Self {
+        WarmupFairing { warmup: Arc::new(warmup), readiness }
+    }
+}
+
+impl Fairing for WarmupFairing {
+    fn info(&self) -> Info {
+        Info {
+            name: "Warmup Fairing",
+            kind: Kind::Liftoff,
+        }
+    }
+
+    fn on_liftoff<'life0, 'life1, 'async_trait>(
+        &'life0 self,
+        _rocket: &'life1 Rocket<Orbit>
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'async_trait>>
+    where
+        Self: 'async_trait,
+        'life0: 'async_trait,
+        'life1: 'async_trait,
+    {
+        let warmup = self.warmup.clone();
+        let readiness = self.readiness.clone();
+        Box::pin(async move {
+            // Liftoff fairings run before the first connection is accepted,
+            // so the warmup goes on in the background.
+            rocket::tokio::spawn(async move {
+                warmup.run().await;
+                readiness.0.store(true, Ordering::Release);
+            });
+        })
+    }
+}